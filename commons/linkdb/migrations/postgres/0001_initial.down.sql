@@ -0,0 +1,17 @@
+DROP TABLE linknova_alert_rule;
+DROP TABLE linknova_notification;
+DROP TABLE linknova_hn_watch;
+DROP TABLE linknova_hn_ingest_state;
+DROP TABLE linknova_hn_score;
+DROP TABLE linknova_hn_item;
+DROP TABLE linknova_hn_bookmark;
+DROP TABLE linknova_membership;
+DROP TABLE linknova_share_access;
+DROP TABLE linknova_share_link;
+DROP TABLE linknova_feed_entry;
+DROP TABLE linknova_feed;
+DROP TABLE linknova_bookmark_category_map;
+DROP TABLE linknova_bookmark;
+DROP TABLE linknova_topic_category_map;
+DROP TABLE linknova_category;
+DROP TABLE linknova_topic;
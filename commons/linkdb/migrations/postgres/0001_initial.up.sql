@@ -0,0 +1,277 @@
+-- Schema as left by the Django project's migrations 0001 to 0010.
+-- Foreign keys have no ON DELETE action, the service deletes dependent rows itself.
+
+CREATE TABLE linknova_topic (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    name varchar(255) NOT NULL,
+    display_name varchar(512),
+    description varchar(1024),
+    about text,
+    priority integer NOT NULL,
+    active boolean NOT NULL,
+    public boolean NOT NULL,
+    user_id varchar(255) NOT NULL,
+    UNIQUE (user_id, name)
+);
+CREATE INDEX linknova_topic_name ON linknova_topic (name);
+
+-- categories/labels/tags, other users get access through `linknova_membership`
+CREATE TABLE linknova_category (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    name varchar(255) NOT NULL,
+    display_name varchar(255),
+    description varchar(1024),
+    about text,
+    priority integer NOT NULL,
+    user_id varchar(255) NOT NULL,
+    active boolean NOT NULL,
+    -- any logged-in user can see the labeled content
+    public boolean NOT NULL,
+    UNIQUE (user_id, name)
+);
+CREATE INDEX linknova_category_name ON linknova_category (name);
+
+CREATE TABLE linknova_topic_category_map (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    topic_id bigint NOT NULL REFERENCES linknova_topic (id) DEFERRABLE INITIALLY DEFERRED,
+    category_id bigint NOT NULL REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (category_id, topic_id)
+);
+CREATE INDEX linknova_topic_category_map_topic_id ON linknova_topic_category_map (topic_id);
+CREATE INDEX linknova_topic_category_map_category_id ON linknova_topic_category_map (category_id);
+
+CREATE TABLE linknova_bookmark (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    url varchar(4096) NOT NULL,
+    user_id varchar(255) NOT NULL,
+    title varchar(1024),
+    content text,
+    referrer varchar(1024),
+    -- UN: Unread, RD: Read, AR: Archived
+    status varchar(2) NOT NULL
+);
+CREATE INDEX linknova_bookmark_user_id ON linknova_bookmark (user_id);
+
+CREATE TABLE linknova_bookmark_category_map (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    bookmark_id bigint NOT NULL REFERENCES linknova_bookmark (id) DEFERRABLE INITIALLY DEFERRED,
+    category_id bigint NOT NULL REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (category_id, bookmark_id)
+);
+CREATE INDEX linknova_bookmark_category_map_bookmark_id ON linknova_bookmark_category_map (bookmark_id);
+CREATE INDEX linknova_bookmark_category_map_category_id ON linknova_bookmark_category_map (category_id);
+
+-- RSS/Atom subscription, new entries are saved as bookmarks into `category_id`
+CREATE TABLE linknova_feed (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    url varchar(4096) NOT NULL,
+    user_id varchar(255) NOT NULL,
+    title varchar(1024),
+    site_url varchar(4096),
+    -- conditional GET validators returned by the feed server
+    etag varchar(1024),
+    last_modified varchar(255),
+    last_polled_on timestamptz,
+    last_error text,
+    active boolean NOT NULL,
+    category_id bigint NOT NULL REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, url)
+);
+CREATE INDEX linknova_feed_user_id ON linknova_feed (user_id);
+CREATE INDEX linknova_feed_category_id ON linknova_feed (category_id);
+
+-- entries already seen, `bookmark_id` is a plain id so deleting the bookmark
+-- doesn't bring the entry back on the next poll
+CREATE TABLE linknova_feed_entry (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    guid varchar(2048) NOT NULL,
+    bookmark_id bigint,
+    created_on timestamptz NOT NULL,
+    feed_id bigint NOT NULL REFERENCES linknova_feed (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (feed_id, guid)
+);
+CREATE INDEX linknova_feed_entry_feed_id ON linknova_feed_entry (feed_id);
+
+-- read-only link to a bookmark (BM), category (CT) or topic (TP), only the
+-- sha256 of the token is kept
+CREATE TABLE linknova_share_link (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    kind varchar(2) NOT NULL,
+    target_id bigint NOT NULL,
+    user_id varchar(255) NOT NULL,
+    token_hash varchar(64) NOT NULL UNIQUE,
+    token_prefix varchar(8) NOT NULL,
+    password_hash varchar(255),
+    expires_on timestamptz,
+    max_views integer,
+    view_count integer NOT NULL,
+    revoked_on timestamptz
+);
+CREATE INDEX linknova_share_link_user_id ON linknova_share_link (user_id);
+
+CREATE TABLE linknova_share_access (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    accessed_on timestamptz NOT NULL,
+    granted boolean NOT NULL,
+    -- ok, expired, revoked, exhausted, password-required, bad-password
+    reason varchar(32) NOT NULL,
+    ip varchar(64),
+    user_agent varchar(1024),
+    share_link_id bigint NOT NULL REFERENCES linknova_share_link (id) DEFERRABLE INITIALLY DEFERRED
+);
+CREATE INDEX linknova_share_access_share_link_id ON linknova_share_access (share_link_id);
+
+-- another user's access to exactly one category or topic, a topic membership
+-- covers the owner's categories of the topic as well
+CREATE TABLE linknova_membership (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    user_id varchar(255) NOT NULL,
+    -- VW: Viewer, CB: Contributor, ED: Editor, OW: Owner
+    role varchar(2) NOT NULL,
+    invited_by varchar(255) NOT NULL,
+    -- pending invitation until accepted
+    accepted_on timestamptz,
+    category_id bigint REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    topic_id bigint REFERENCES linknova_topic (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (category_id, user_id),
+    UNIQUE (topic_id, user_id),
+    CONSTRAINT linknova_membership_one_target CHECK (
+        (category_id IS NOT NULL AND topic_id IS NULL)
+        OR (category_id IS NULL AND topic_id IS NOT NULL)
+    )
+);
+CREATE INDEX linknova_membership_user_id ON linknova_membership (user_id);
+CREATE INDEX linknova_membership_category_id ON linknova_membership (category_id);
+CREATE INDEX linknova_membership_topic_id ON linknova_membership (topic_id);
+
+-- HN item saved as a bookmark, refreshed on every re-save
+CREATE TABLE linknova_hn_bookmark (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    user_id varchar(255) NOT NULL,
+    hn_id bigint NOT NULL,
+    -- story, job, poll, comment ...
+    item_type varchar(16) NOT NULL,
+    by varchar(255),
+    score integer NOT NULL,
+    -- comment count
+    descendants integer NOT NULL,
+    posted_on timestamptz,
+    bookmark_id bigint NOT NULL REFERENCES linknova_bookmark (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, hn_id)
+);
+CREATE INDEX linknova_hn_bookmark_bookmark_id ON linknova_hn_bookmark (bookmark_id);
+
+-- HN item kept by the ingestion job, `id` is HN's own item id
+CREATE TABLE linknova_hn_item (
+    id bigint PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    item_type varchar(16) NOT NULL,
+    by varchar(255),
+    title text,
+    url text,
+    text text,
+    parent bigint,
+    score integer NOT NULL,
+    descendants integer NOT NULL,
+    deleted boolean NOT NULL,
+    dead boolean NOT NULL,
+    posted_on timestamptz
+);
+CREATE INDEX linknova_hn_item_by ON linknova_hn_item (by);
+CREATE INDEX linknova_hn_item_posted_on ON linknova_hn_item (posted_on);
+CREATE INDEX linknova_hn_item_score ON linknova_hn_item (score);
+-- must stay in sync with `hn::search::DOCUMENT`
+CREATE INDEX linknova_hn_item_search ON linknova_hn_item USING GIN (
+    to_tsvector('english', coalesce(title, '') || ' ' || coalesce(text, '') || ' ' || coalesce(url, ''))
+);
+
+-- score and comment count of an item each time a story list was snapshotted
+CREATE TABLE linknova_hn_score (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    score integer NOT NULL,
+    descendants integer NOT NULL,
+    taken_on timestamptz NOT NULL,
+    item_id bigint NOT NULL REFERENCES linknova_hn_item (id) DEFERRABLE INITIALLY DEFERRED
+);
+CREATE INDEX linknova_hn_score_taken_on ON linknova_hn_score (taken_on);
+CREATE INDEX linknova_hn_score_item_id ON linknova_hn_score (item_id);
+CREATE INDEX linknova_hn_score_item_id_taken_on ON linknova_hn_score (item_id, taken_on);
+
+-- where the incremental item ingestion stopped, one row per job
+CREATE TABLE linknova_hn_ingest_state (
+    name varchar(32) PRIMARY KEY,
+    last_item_id bigint NOT NULL,
+    updated_on timestamptz NOT NULL
+);
+
+-- HN username followed by a user, `last_seen_id` is the newest submission
+-- already reported
+CREATE TABLE linknova_hn_watch (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    user_id varchar(255) NOT NULL,
+    username varchar(255) NOT NULL,
+    last_seen_id bigint,
+    last_polled_on timestamptz,
+    last_error text,
+    -- auto-save target, nothing is saved when empty
+    category_id bigint REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, username)
+);
+CREATE INDEX linknova_hn_watch_user_id ON linknova_hn_watch (user_id);
+CREATE INDEX linknova_hn_watch_username ON linknova_hn_watch (username);
+CREATE INDEX linknova_hn_watch_category_id ON linknova_hn_watch (category_id);
+
+-- in-app notification, HW: HN watch, HA: HN alert rule
+CREATE TABLE linknova_notification (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    user_id varchar(255) NOT NULL,
+    kind varchar(2) NOT NULL,
+    title varchar(1024) NOT NULL,
+    url varchar(4096),
+    body text,
+    hn_id bigint,
+    -- plain id, the bookmark may be deleted while the notification stays
+    bookmark_id bigint,
+    read_on timestamptz,
+    created_on timestamptz NOT NULL,
+    UNIQUE (user_id, kind, hn_id)
+);
+CREATE INDEX linknova_notification_user_id_created_on ON linknova_notification (user_id, created_on);
+
+-- conditions on new HN stories, every one which is set must hold
+CREATE TABLE linknova_alert_rule (
+    id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_on timestamptz NOT NULL,
+    updated_on timestamptz NOT NULL,
+    user_id varchar(255) NOT NULL,
+    name varchar(255) NOT NULL,
+    -- case-insensitive substring of the title
+    title_contains varchar(255),
+    -- host of the story url, subdomains included
+    domain varchar(255),
+    min_score integer,
+    active boolean NOT NULL,
+    last_matched_on timestamptz,
+    -- matches are also saved as bookmarks here when set
+    category_id bigint REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, name)
+);
+CREATE INDEX linknova_alert_rule_user_id ON linknova_alert_rule (user_id);
+CREATE INDEX linknova_alert_rule_category_id ON linknova_alert_rule (category_id);
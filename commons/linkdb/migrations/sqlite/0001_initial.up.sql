@@ -0,0 +1,300 @@
+-- Same schema as `migrations/postgres/0001_initial.up.sql` for SQLite, keep
+-- the two in step. Timestamps are stored as RFC 3339 text in UTC, which sorts
+-- and compares in time order.
+-- Foreign keys have no ON DELETE action, the service deletes dependent rows itself.
+
+CREATE TABLE linknova_topic (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    name TEXT NOT NULL,
+    display_name TEXT,
+    description TEXT,
+    about TEXT,
+    priority INTEGER NOT NULL,
+    active BOOLEAN NOT NULL,
+    public BOOLEAN NOT NULL,
+    user_id TEXT NOT NULL,
+    UNIQUE (user_id, name)
+);
+CREATE INDEX linknova_topic_name ON linknova_topic (name);
+
+-- categories/labels/tags, other users get access through `linknova_membership`
+CREATE TABLE linknova_category (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    name TEXT NOT NULL,
+    display_name TEXT,
+    description TEXT,
+    about TEXT,
+    priority INTEGER NOT NULL,
+    user_id TEXT NOT NULL,
+    active BOOLEAN NOT NULL,
+    -- any logged-in user can see the labeled content
+    public BOOLEAN NOT NULL,
+    UNIQUE (user_id, name)
+);
+CREATE INDEX linknova_category_name ON linknova_category (name);
+
+CREATE TABLE linknova_topic_category_map (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    topic_id INTEGER NOT NULL REFERENCES linknova_topic (id) DEFERRABLE INITIALLY DEFERRED,
+    category_id INTEGER NOT NULL REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (category_id, topic_id)
+);
+CREATE INDEX linknova_topic_category_map_topic_id ON linknova_topic_category_map (topic_id);
+CREATE INDEX linknova_topic_category_map_category_id ON linknova_topic_category_map (category_id);
+
+CREATE TABLE linknova_bookmark (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    url TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    title TEXT,
+    content TEXT,
+    referrer TEXT,
+    -- UN: Unread, RD: Read, AR: Archived
+    status TEXT NOT NULL
+);
+CREATE INDEX linknova_bookmark_user_id ON linknova_bookmark (user_id);
+
+CREATE TABLE linknova_bookmark_category_map (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bookmark_id INTEGER NOT NULL REFERENCES linknova_bookmark (id) DEFERRABLE INITIALLY DEFERRED,
+    category_id INTEGER NOT NULL REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (category_id, bookmark_id)
+);
+CREATE INDEX linknova_bookmark_category_map_bookmark_id ON linknova_bookmark_category_map (bookmark_id);
+CREATE INDEX linknova_bookmark_category_map_category_id ON linknova_bookmark_category_map (category_id);
+
+-- RSS/Atom subscription, new entries are saved as bookmarks into `category_id`
+CREATE TABLE linknova_feed (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    url TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    title TEXT,
+    site_url TEXT,
+    -- conditional GET validators returned by the feed server
+    etag TEXT,
+    last_modified TEXT,
+    last_polled_on DATETIME,
+    last_error TEXT,
+    active BOOLEAN NOT NULL,
+    category_id INTEGER NOT NULL REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, url)
+);
+CREATE INDEX linknova_feed_user_id ON linknova_feed (user_id);
+CREATE INDEX linknova_feed_category_id ON linknova_feed (category_id);
+
+-- entries already seen, `bookmark_id` is a plain id so deleting the bookmark
+-- doesn't bring the entry back on the next poll
+CREATE TABLE linknova_feed_entry (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guid TEXT NOT NULL,
+    bookmark_id INTEGER,
+    created_on DATETIME NOT NULL,
+    feed_id INTEGER NOT NULL REFERENCES linknova_feed (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (feed_id, guid)
+);
+CREATE INDEX linknova_feed_entry_feed_id ON linknova_feed_entry (feed_id);
+
+-- read-only link to a bookmark (BM), category (CT) or topic (TP), only the
+-- sha256 of the token is kept
+CREATE TABLE linknova_share_link (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    kind TEXT NOT NULL,
+    target_id INTEGER NOT NULL,
+    user_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    token_prefix TEXT NOT NULL,
+    password_hash TEXT,
+    expires_on DATETIME,
+    max_views INTEGER,
+    view_count INTEGER NOT NULL,
+    revoked_on DATETIME
+);
+CREATE INDEX linknova_share_link_user_id ON linknova_share_link (user_id);
+
+CREATE TABLE linknova_share_access (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    accessed_on DATETIME NOT NULL,
+    granted BOOLEAN NOT NULL,
+    -- ok, expired, revoked, exhausted, password-required, bad-password
+    reason TEXT NOT NULL,
+    ip TEXT,
+    user_agent TEXT,
+    share_link_id INTEGER NOT NULL REFERENCES linknova_share_link (id) DEFERRABLE INITIALLY DEFERRED
+);
+CREATE INDEX linknova_share_access_share_link_id ON linknova_share_access (share_link_id);
+
+-- another user's access to exactly one category or topic, a topic membership
+-- covers the owner's categories of the topic as well
+CREATE TABLE linknova_membership (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    user_id TEXT NOT NULL,
+    -- VW: Viewer, CB: Contributor, ED: Editor, OW: Owner
+    role TEXT NOT NULL,
+    invited_by TEXT NOT NULL,
+    -- pending invitation until accepted
+    accepted_on DATETIME,
+    category_id INTEGER REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    topic_id INTEGER REFERENCES linknova_topic (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (category_id, user_id),
+    UNIQUE (topic_id, user_id),
+    CONSTRAINT linknova_membership_one_target CHECK (
+        (category_id IS NOT NULL AND topic_id IS NULL)
+        OR (category_id IS NULL AND topic_id IS NOT NULL)
+    )
+);
+CREATE INDEX linknova_membership_user_id ON linknova_membership (user_id);
+CREATE INDEX linknova_membership_category_id ON linknova_membership (category_id);
+CREATE INDEX linknova_membership_topic_id ON linknova_membership (topic_id);
+
+-- HN item saved as a bookmark, refreshed on every re-save
+CREATE TABLE linknova_hn_bookmark (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    user_id TEXT NOT NULL,
+    hn_id INTEGER NOT NULL,
+    -- story, job, poll, comment ...
+    item_type TEXT NOT NULL,
+    by TEXT,
+    score INTEGER NOT NULL,
+    -- comment count
+    descendants INTEGER NOT NULL,
+    posted_on DATETIME,
+    bookmark_id INTEGER NOT NULL REFERENCES linknova_bookmark (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, hn_id)
+);
+CREATE INDEX linknova_hn_bookmark_bookmark_id ON linknova_hn_bookmark (bookmark_id);
+
+-- HN item kept by the ingestion job, `id` is HN's own item id
+CREATE TABLE linknova_hn_item (
+    id INTEGER PRIMARY KEY,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    item_type TEXT NOT NULL,
+    by TEXT,
+    title TEXT,
+    url TEXT,
+    text TEXT,
+    parent INTEGER,
+    score INTEGER NOT NULL,
+    descendants INTEGER NOT NULL,
+    deleted BOOLEAN NOT NULL,
+    dead BOOLEAN NOT NULL,
+    posted_on DATETIME
+);
+CREATE INDEX linknova_hn_item_by ON linknova_hn_item (by);
+CREATE INDEX linknova_hn_item_posted_on ON linknova_hn_item (posted_on);
+CREATE INDEX linknova_hn_item_score ON linknova_hn_item (score);
+
+-- full text index over the items for `hn::search`, the triggers keep it in step
+-- with `linknova_hn_item`
+CREATE VIRTUAL TABLE linknova_hn_item_search USING fts5 (
+    title,
+    text,
+    url,
+    content = 'linknova_hn_item',
+    content_rowid = 'id',
+    tokenize = 'porter unicode61'
+);
+CREATE TRIGGER linknova_hn_item_search_insert AFTER INSERT ON linknova_hn_item BEGIN
+    INSERT INTO linknova_hn_item_search (rowid, title, text, url)
+    VALUES (new.id, new.title, new.text, new.url);
+END;
+CREATE TRIGGER linknova_hn_item_search_delete AFTER DELETE ON linknova_hn_item BEGIN
+    INSERT INTO linknova_hn_item_search (linknova_hn_item_search, rowid, title, text, url)
+    VALUES ('delete', old.id, old.title, old.text, old.url);
+END;
+CREATE TRIGGER linknova_hn_item_search_update AFTER UPDATE OF title, text, url ON linknova_hn_item BEGIN
+    INSERT INTO linknova_hn_item_search (linknova_hn_item_search, rowid, title, text, url)
+    VALUES ('delete', old.id, old.title, old.text, old.url);
+    INSERT INTO linknova_hn_item_search (rowid, title, text, url)
+    VALUES (new.id, new.title, new.text, new.url);
+END;
+
+-- score and comment count of an item each time a story list was snapshotted
+CREATE TABLE linknova_hn_score (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    score INTEGER NOT NULL,
+    descendants INTEGER NOT NULL,
+    taken_on DATETIME NOT NULL,
+    item_id INTEGER NOT NULL REFERENCES linknova_hn_item (id) DEFERRABLE INITIALLY DEFERRED
+);
+CREATE INDEX linknova_hn_score_taken_on ON linknova_hn_score (taken_on);
+CREATE INDEX linknova_hn_score_item_id ON linknova_hn_score (item_id);
+CREATE INDEX linknova_hn_score_item_id_taken_on ON linknova_hn_score (item_id, taken_on);
+
+-- where the incremental item ingestion stopped, one row per job
+CREATE TABLE linknova_hn_ingest_state (
+    name TEXT PRIMARY KEY,
+    last_item_id INTEGER NOT NULL,
+    updated_on DATETIME NOT NULL
+);
+
+-- HN username followed by a user, `last_seen_id` is the newest submission
+-- already reported
+CREATE TABLE linknova_hn_watch (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    user_id TEXT NOT NULL,
+    username TEXT NOT NULL,
+    last_seen_id INTEGER,
+    last_polled_on DATETIME,
+    last_error TEXT,
+    -- auto-save target, nothing is saved when empty
+    category_id INTEGER REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, username)
+);
+CREATE INDEX linknova_hn_watch_user_id ON linknova_hn_watch (user_id);
+CREATE INDEX linknova_hn_watch_username ON linknova_hn_watch (username);
+CREATE INDEX linknova_hn_watch_category_id ON linknova_hn_watch (category_id);
+
+-- in-app notification, HW: HN watch, HA: HN alert rule
+CREATE TABLE linknova_notification (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    url TEXT,
+    body TEXT,
+    hn_id INTEGER,
+    -- plain id, the bookmark may be deleted while the notification stays
+    bookmark_id INTEGER,
+    read_on DATETIME,
+    created_on DATETIME NOT NULL,
+    UNIQUE (user_id, kind, hn_id)
+);
+CREATE INDEX linknova_notification_user_id_created_on ON linknova_notification (user_id, created_on);
+
+-- conditions on new HN stories, every one which is set must hold
+CREATE TABLE linknova_alert_rule (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_on DATETIME NOT NULL,
+    updated_on DATETIME NOT NULL,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    -- case-insensitive substring of the title
+    title_contains TEXT,
+    -- host of the story url, subdomains included
+    domain TEXT,
+    min_score INTEGER,
+    active BOOLEAN NOT NULL,
+    last_matched_on DATETIME,
+    -- matches are also saved as bookmarks here when set
+    category_id INTEGER REFERENCES linknova_category (id) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (user_id, name)
+);
+CREATE INDEX linknova_alert_rule_user_id ON linknova_alert_rule (user_id);
+CREATE INDEX linknova_alert_rule_category_id ON linknova_alert_rule (category_id);
@@ -0,0 +1,8 @@
+pub mod query;
+pub mod types;
+
+pub use types::{AlertRuleI, AlertRuleRow};
+
+pub use query::{
+    clear_category, delete, get_by_id, insert, list_active, list_all, set_last_matched, update,
+};
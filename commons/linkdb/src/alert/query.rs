@@ -0,0 +1,165 @@
+use crate::alert::{AlertRuleI, AlertRuleRow};
+use crate::db::{on_pool, on_tx};
+use sqlx::types::chrono;
+
+const RULE_SELECT: &str = r#"
+    SELECT
+        r.id,
+        r.user_id,
+        r.name,
+        r.title_contains,
+        r.domain,
+        r.min_score,
+        r.category_id,
+        cat.name AS category,
+        r.active,
+        r.last_matched_on,
+        r.created_on,
+        r.updated_on
+    FROM linknova_alert_rule as r
+    LEFT JOIN linknova_category as cat ON r.category_id = cat.id
+"#;
+
+#[tracing::instrument(name = "linkdb::alert::insert", skip_all, err)]
+pub async fn insert(
+    tx: &mut crate::Tx<'_>,
+    row: AlertRuleI,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<i64, sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_alert_rule(
+            user_id,
+            name,
+            title_contains,
+            domain,
+            min_score,
+            category_id,
+            active,
+            created_on,
+            updated_on
+        ) VALUES($1, $2, $3, $4, $5, $6, $7, $8, $8)
+        RETURNING id
+    "#;
+
+    on_tx!(tx, |tx| sqlx::query_scalar(query)
+        .bind(row.user_id)
+        .bind(row.name)
+        .bind(row.title_contains)
+        .bind(row.domain)
+        .bind(row.min_score)
+        .bind(row.category_id)
+        .bind(row.active)
+        .bind(now)
+        .fetch_one(&mut **tx)
+        .await)
+}
+
+/// Replaces every field of the user's rule, returns whether it exists.
+#[tracing::instrument(name = "linkdb::alert::update", skip_all, err)]
+pub async fn update(
+    tx: &mut crate::Tx<'_>,
+    id: i64,
+    row: AlertRuleI,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_alert_rule SET
+            name = $3,
+            title_contains = $4,
+            domain = $5,
+            min_score = $6,
+            category_id = $7,
+            active = $8,
+            updated_on = $9
+        WHERE id = $1 AND user_id = $2
+    "#;
+
+    let rows = on_tx!(tx, |tx| sqlx::query(query)
+        .bind(id)
+        .bind(row.user_id)
+        .bind(row.name)
+        .bind(row.title_contains)
+        .bind(row.domain)
+        .bind(row.min_score)
+        .bind(row.category_id)
+        .bind(row.active)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(rows > 0)
+}
+
+#[tracing::instrument(name = "linkdb::alert::get-by-id", skip_all, err)]
+pub async fn get_by_id(
+    db: &crate::Db,
+    user_id: &str,
+    id: i64,
+) -> Result<Option<AlertRuleRow>, sqlx::Error> {
+    let query = format!("{RULE_SELECT} WHERE r.id = $1 AND r.user_id = $2");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::alert::list-all", skip_all, err)]
+pub async fn list_all(db: &crate::Db, user_id: &str) -> Result<Vec<AlertRuleRow>, sqlx::Error> {
+    let query = format!("{RULE_SELECT} WHERE r.user_id = $1 ORDER BY r.name");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Active rules of every user.
+#[tracing::instrument(name = "linkdb::alert::list-active", skip_all, err)]
+pub async fn list_active(db: &crate::Db) -> Result<Vec<AlertRuleRow>, sqlx::Error> {
+    let query = format!("{RULE_SELECT} WHERE r.active ORDER BY r.user_id, r.id");
+    on_pool!(db, |pool| sqlx::query_as(&query).fetch_all(pool).await)
+}
+
+#[tracing::instrument(name = "linkdb::alert::set-last-matched", skip_all, err)]
+pub async fn set_last_matched(
+    db: &crate::Db,
+    id: i64,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query(
+        "UPDATE linknova_alert_rule SET last_matched_on = $2 WHERE id = $1"
+    )
+    .bind(id)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+/// Returns whether a rule was removed.
+#[tracing::instrument(name = "linkdb::alert::delete", skip_all, err)]
+pub async fn delete(db: &crate::Db, user_id: &str, id: i64) -> Result<bool, sqlx::Error> {
+    let rows = on_pool!(db, |pool| sqlx::query(
+        "DELETE FROM linknova_alert_rule WHERE id = $1 AND user_id = $2"
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(rows > 0)
+}
+
+/// Stops auto-saving into a category which is about to be deleted.
+#[tracing::instrument(name = "linkdb::alert::clear-category", skip_all, err)]
+pub async fn clear_category(db: &crate::Db, category_id: i64) -> Result<(), sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query(
+        "UPDATE linknova_alert_rule SET category_id = NULL WHERE category_id = $1"
+    )
+    .bind(category_id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use sqlx::types::chrono;
+
+#[derive(Debug)]
+pub struct AlertRuleI {
+    pub user_id: String,
+    pub name: String,
+    pub title_contains: Option<String>,
+    pub domain: Option<String>,
+    pub min_score: Option<i32>,
+    pub category_id: Option<i64>,
+    pub active: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct AlertRuleRow {
+    pub id: i64,
+    pub user_id: String,
+    pub name: String,
+    pub title_contains: Option<String>,
+    pub domain: Option<String>,
+    pub min_score: Option<i32>,
+    pub category_id: Option<i64>,
+    pub category: Option<String>,
+    pub active: bool,
+    pub last_matched_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
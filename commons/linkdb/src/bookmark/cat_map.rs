@@ -1,60 +1,61 @@
+use crate::db::{on_pool, on_tx, push_in};
+
 #[tracing::instrument(name = "linkdb::bookmark::cat-map::add-categories", skip_all, err)]
 pub async fn add_categories(
-    tx: &mut sqlx::PgTransaction<'_>,
+    tx: &mut crate::Tx<'_>,
     bookmark_id: i64,
     categories: &[i64],
 ) -> Result<(), sqlx::Error> {
-    // Build a Vec with topic_id repeated for each category
-    let bookmark_ids: Vec<i64> = std::iter::repeat(bookmark_id)
-        .take(categories.len())
-        .collect();
-
-    let query = r#"
-        INSERT INTO linknova_bookmark_category_map (bookmark_id, category_id)
-        SELECT * FROM
-            unnest($1::bigint[], $2::bigint[])
-        ON CONFLICT DO NOTHING
-    "#;
+    if categories.is_empty() {
+        return Ok(());
+    }
 
-    sqlx::query(query)
-        .bind(&bookmark_ids)
-        .bind(categories)
-        .execute(&mut **tx)
-        .await?;
+    on_tx!(tx, |tx| {
+        let mut qb = sqlx::QueryBuilder::new(
+            "INSERT INTO linknova_bookmark_category_map (bookmark_id, category_id) ",
+        );
+        qb.push_values(categories, |mut b, category_id| {
+            b.push_bind(bookmark_id).push_bind(category_id);
+        });
+        qb.push(" ON CONFLICT DO NOTHING");
+        qb.build()
+            .execute(&mut **tx)
+            .await
+            .map(|r| r.rows_affected())
+    })?;
     Ok(())
 }
 
 #[tracing::instrument(name = "linkdb::bookmark::cat-map::remove-categories", skip_all, err)]
 pub async fn remove_categories(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     bm_id: i64,
-    categories: &[String],
+    category_ids: &[i64],
 ) -> Result<(), sqlx::Error> {
-    let query = r#"
-        DELETE from linknova_bookmark_category_map as mapping
-        USING linknova_category as cat
-        WHERE
-            cat.id = mapping.category_id
-            AND mapping.bookmark_id = $1
-            AND cat.name = ANY($2)
-    "#;
-
-    sqlx::query(query)
-        .bind(bm_id)
-        .bind(categories)
-        .execute(pool)
-        .await?;
+    on_pool!(db, |pool| {
+        let mut qb = sqlx::QueryBuilder::new(
+            "DELETE from linknova_bookmark_category_map WHERE bookmark_id = ",
+        );
+        qb.push_bind(bm_id);
+        qb.push(" AND category_id IN ");
+        push_in(&mut qb, category_ids);
+        qb.build().execute(pool).await.map(|r| r.rows_affected())
+    })?;
 
     Ok(())
 }
 
 #[tracing::instrument(name = "linkdb::bookmark::cat-map::delete-by-bm-id", skip_all, err)]
 pub async fn delete_by_bookmark_id(
-    tx: &mut sqlx::PgTransaction<'_>,
+    tx: &mut crate::Tx<'_>,
     topic_id: i64,
 ) -> Result<(), sqlx::Error> {
     let query = r#"delete from linknova_bookmark_category_map where bookmark_id = $1"#;
-    sqlx::query(query).bind(topic_id).execute(&mut **tx).await?;
+    on_tx!(tx, |tx| sqlx::query(query)
+        .bind(topic_id)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected()))?;
 
     Ok(())
 }
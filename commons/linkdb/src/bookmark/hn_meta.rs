@@ -0,0 +1,120 @@
+use crate::bookmark::{HnMetaI, HnMetaRow};
+use crate::db::{on_pool, on_tx};
+use sqlx::types::chrono;
+
+/// Inserts or refreshes the HN details of a user's saved item, pointing it at
+/// `bookmark_id` in both cases.
+#[tracing::instrument(name = "linkdb::bookmark::hn-meta::upsert", skip_all, err)]
+pub async fn upsert(
+    tx: &mut crate::Tx<'_>,
+    row: HnMetaI,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_hn_bookmark(
+            bookmark_id,
+            user_id,
+            hn_id,
+            item_type,
+            by,
+            score,
+            descendants,
+            posted_on,
+            created_on,
+            updated_on
+        ) VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $9)
+        ON CONFLICT (user_id, hn_id) DO UPDATE SET
+            bookmark_id = EXCLUDED.bookmark_id,
+            item_type = EXCLUDED.item_type,
+            by = EXCLUDED.by,
+            score = EXCLUDED.score,
+            descendants = EXCLUDED.descendants,
+            posted_on = EXCLUDED.posted_on,
+            updated_on = EXCLUDED.updated_on
+    "#;
+
+    on_tx!(tx, |tx| sqlx::query(query)
+        .bind(row.bookmark_id)
+        .bind(row.user_id)
+        .bind(row.hn_id)
+        .bind(row.item_type)
+        .bind(row.by)
+        .bind(row.score)
+        .bind(row.descendants)
+        .bind(row.posted_on)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+/// The bookmark a user's saved item points at, if it still exists.
+#[tracing::instrument(name = "linkdb::bookmark::hn-meta::get-bookmark-id", skip_all, err)]
+pub async fn get_bookmark_id(
+    tx: &mut crate::Tx<'_>,
+    user_id: &str,
+    hn_id: i64,
+) -> Result<Option<i64>, sqlx::Error> {
+    let query = r#"
+        SELECT b.id
+        FROM linknova_hn_bookmark m
+        JOIN linknova_bookmark b ON b.id = m.bookmark_id
+        WHERE m.user_id = $1 AND m.hn_id = $2
+    "#;
+
+    on_tx!(tx, |tx| sqlx::query_scalar(query)
+        .bind(user_id)
+        .bind(hn_id)
+        .fetch_optional(&mut **tx)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::bookmark::hn-meta::get-by-hn-id", skip_all, err)]
+pub async fn get_by_hn_id(
+    db: &crate::Db,
+    user_id: &str,
+    hn_id: i64,
+) -> Result<Option<HnMetaRow>, sqlx::Error> {
+    let query = r#"
+        SELECT
+            id,
+            bookmark_id,
+            user_id,
+            hn_id,
+            item_type,
+            by,
+            score,
+            descendants,
+            posted_on,
+            created_on,
+            updated_on
+        FROM linknova_hn_bookmark
+        WHERE user_id = $1 AND hn_id = $2
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(user_id)
+        .bind(hn_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(
+    name = "linkdb::bookmark::hn-meta::delete-by-bookmark-id",
+    skip_all,
+    err
+)]
+pub async fn delete_by_bookmark_id(
+    tx: &mut crate::Tx<'_>,
+    bookmark_id: i64,
+) -> Result<(), sqlx::Error> {
+    on_tx!(tx, |tx| sqlx::query(
+        "DELETE FROM linknova_hn_bookmark WHERE bookmark_id = $1"
+    )
+    .bind(bookmark_id)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
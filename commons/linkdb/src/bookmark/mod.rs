@@ -1,7 +1,11 @@
 pub mod cat_map;
+pub mod hn_meta;
 pub mod query;
 pub mod types;
 
-pub use types::{BookmarkI, BookmarkRow};
+pub use types::{BookmarkI, BookmarkRow, BookmarkUpdate, HnMetaI, HnMetaRow};
 
-pub use query::{filter, filter_by_topic, get_by_id, insert};
+pub use query::{
+    filter, filter_by_topic, get_by_id, get_id_by_url, get_visible, insert, list_by_category_ids,
+    list_public, update,
+};
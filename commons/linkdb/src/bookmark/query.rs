@@ -1,7 +1,11 @@
-use crate::bookmark::{BookmarkI, BookmarkRow};
+use crate::Db;
+use crate::bookmark::{BookmarkI, BookmarkRow, BookmarkUpdate};
+use crate::db::{on_pool, on_tx, push_in};
+use sqlx::types::chrono;
+use std::collections::HashMap;
 
 #[tracing::instrument(name = "linkdb::bookmark::insert", skip_all, err)]
-pub async fn insert(tx: &mut sqlx::PgTransaction<'_>, row: BookmarkI) -> Result<i64, sqlx::Error> {
+pub async fn insert(tx: &mut crate::Tx<'_>, row: BookmarkI) -> Result<i64, sqlx::Error> {
     let query = r#"
         INSERT INTO linknova_bookmark(
             url,
@@ -16,7 +20,7 @@ pub async fn insert(tx: &mut sqlx::PgTransaction<'_>, row: BookmarkI) -> Result<
         RETURNING id;
     "#;
 
-    let (id,): (i64,) = sqlx::query_as(query)
+    let (id,): (i64,) = on_tx!(tx, |tx| sqlx::query_as(query)
         .bind(row.url)
         .bind(row.user_id)
         .bind(row.title)
@@ -26,161 +30,412 @@ pub async fn insert(tx: &mut sqlx::PgTransaction<'_>, row: BookmarkI) -> Result<
         .bind(row.created_on)
         .bind(row.updated_on)
         .fetch_one(&mut **tx)
-        .await?;
+        .await)?;
 
     Ok(id)
 }
 
-#[tracing::instrument(name = "linkdb::bookmark::get", skip_all, err)]
-pub async fn get_by_id(pool: &sqlx::PgPool, id: i64) -> Result<BookmarkRow, sqlx::Error> {
+/// Oldest of the user's own bookmarks of `url`.
+#[tracing::instrument(name = "linkdb::bookmark::get-id-by-url", skip_all, err)]
+pub async fn get_id_by_url(
+    tx: &mut crate::Tx<'_>,
+    user_id: &str,
+    url: &str,
+) -> Result<Option<i64>, sqlx::Error> {
     let query = r#"
-        SELECT
-            b.id,
-            b.url,
-            b.user_id,
-            b.title,
-            b.content,
-            b.referrer,
-            b.status,
-            b.created_on,
-            b.updated_on,
-            COALESCE(
-                ARRAY_AGG(cat.name ORDER BY cat.name) FILTER (WHERE cat.name IS NOT NULL),
-                '{}'
-            ) AS categories
-        FROM linknova_bookmark as b
-        LEFT JOIN linknova_bookmark_category_map as mapping ON b.id = mapping.bookmark_id
-        LEFT JOIN linknova_category as cat ON mapping.category_id = cat.id
-        WHERE b.id = $1
-        GROUP by b.id
+        SELECT id FROM linknova_bookmark WHERE user_id = $1 AND url = $2 ORDER BY id LIMIT 1
     "#;
+    on_tx!(tx, |tx| sqlx::query_scalar(query)
+        .bind(user_id)
+        .bind(url)
+        .fetch_optional(&mut **tx)
+        .await)
+}
+
+const BOOKMARK_SELECT: &str = r#"
+    SELECT
+        b.id,
+        b.url,
+        b.user_id,
+        b.title,
+        b.content,
+        b.referrer,
+        b.status,
+        b.created_on,
+        b.updated_on
+    FROM linknova_bookmark as b
+"#;
+
+/// Which of a bookmark's categories its `categories` lists.
+enum Listed<'a> {
+    All,
+    /// The ones visible to the user, other users' categories named `@owner/name`,
+    /// only those mapped to the topic when there is one.
+    Visible {
+        user_id: &'a str,
+        topic_id: Option<i64>,
+    },
+    Public,
+    Among(&'a [i64]),
+}
+
+/// Starts a query with the `visible_cat` CTE: ids of the categories owned by or
+/// shared with `user_id`, which is bound as `$1`.
+fn with_visible_categories<'a, DB>(user_id: &'a str) -> sqlx::QueryBuilder<'a, DB>
+where
+    DB: sqlx::Database,
+    DB::Arguments<'a>: Default,
+    &'a str: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    let mut qb = sqlx::QueryBuilder::new(
+        "WITH visible_cat AS (SELECT id FROM linknova_category WHERE user_id = ",
+    );
+    qb.push_bind(user_id);
+    qb.push(" UNION ");
+    qb.push(crate::member::SHARED_CATEGORY_IDS);
+    qb.push(")");
+    qb
+}
+
+/// Fills in `categories` of the rows, sorted by name, with a second query
+/// rather than aggregating them into an array, which only Postgres has.
+async fn list_categories(
+    db: &Db,
+    rows: &mut [BookmarkRow],
+    listed: Listed<'_>,
+) -> Result<(), sqlx::Error> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<i64> = rows.iter().map(|r| r.id).collect();
+
+    let labels: Vec<(i64, String)> = on_pool!(db, |pool| {
+        let mut qb = match listed {
+            Listed::Visible { user_id, .. } => {
+                let mut qb = with_visible_categories(user_id);
+                qb.push(format!(
+                    " SELECT m.bookmark_id, {} AS label",
+                    crate::member::CATEGORY_LABEL
+                ));
+                qb
+            }
+            _ => sqlx::QueryBuilder::new("SELECT m.bookmark_id, cat.name AS label"),
+        };
+        qb.push(
+            r#"
+            FROM linknova_bookmark_category_map as m
+            JOIN linknova_category as cat ON m.category_id = cat.id
+            WHERE m.bookmark_id IN "#,
+        );
+        push_in(&mut qb, &ids);
+        match listed {
+            Listed::All => {}
+            Listed::Visible { topic_id, .. } => {
+                qb.push(" AND cat.id IN (SELECT id FROM visible_cat)");
+                if let Some(topic_id) = topic_id {
+                    qb.push(" AND EXISTS (SELECT 1 FROM linknova_topic_category_map tcm WHERE tcm.category_id = cat.id AND tcm.topic_id = ");
+                    qb.push_bind(topic_id);
+                    qb.push(")");
+                }
+            }
+            Listed::Public => {
+                qb.push(" AND cat.public");
+            }
+            Listed::Among(category_ids) => {
+                qb.push(" AND cat.id IN ");
+                push_in(&mut qb, category_ids);
+            }
+        }
+        qb.push(" ORDER BY label");
+        qb.build_query_as().fetch_all(pool).await
+    })?;
+
+    let mut by_bookmark: HashMap<i64, Vec<String>> = HashMap::new();
+    for (id, label) in labels {
+        by_bookmark.entry(id).or_default().push(label);
+    }
+    for row in rows {
+        row.categories = by_bookmark.remove(&row.id).unwrap_or_default();
+    }
+    Ok(())
+}
 
-    let row = sqlx::query_as(query).bind(id).fetch_one(pool).await?;
+#[tracing::instrument(name = "linkdb::bookmark::get", skip_all, err)]
+pub async fn get_by_id(db: &Db, id: i64) -> Result<BookmarkRow, sqlx::Error> {
+    let query = format!("{BOOKMARK_SELECT} WHERE b.id = $1");
+
+    let row = on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(id)
+        .fetch_one(pool)
+        .await)?;
+    let mut rows = [row];
+    list_categories(db, &mut rows, Listed::All).await?;
+    let [row] = rows;
     Ok(row)
 }
 
+/// Own bookmarks along with everyone's bookmarks in the categories shared with
+/// the user. `categories` of a row only lists the ones visible to the user,
+/// other users' categories named `@owner/name`.
 #[tracing::instrument(name = "linkdb::bookmark::filter", skip_all, err)]
 pub async fn filter(
-    pool: &sqlx::PgPool,
+    db: &Db,
     user_id: &str,
     categories: Option<&[String]>,
     status: &Option<String>,
 ) -> Result<Vec<BookmarkRow>, sqlx::Error> {
-    let mut qb = sqlx::query_builder::QueryBuilder::new(
-        r#"
-        SELECT
-            b.id,
-            b.url,
-            b.user_id,
-            b.title,
-            b.content,
-            b.referrer,
-            b.status,
-            b.created_on,
-            b.updated_on,
-            COALESCE(
-                ARRAY_AGG(cat.name ORDER BY cat.name) FILTER (WHERE cat.name IS NOT NULL),
-                '{}'
-            ) AS categories
-
-        FROM linknova_bookmark as b
-        LEFT JOIN linknova_bookmark_category_map as mapping ON b.id = mapping.bookmark_id
-        LEFT JOIN linknova_category as cat ON mapping.category_id = cat.id
-        WHERE
-    "#,
-    );
-
-    qb.push(" b.user_id = ");
-    qb.push_bind(user_id);
-
-    if let Some(status) = status {
-        qb.push(" AND b.status = ");
-        qb.push_bind(status);
-    }
+    let mut rows = on_pool!(db, |pool| {
+        let mut qb = with_visible_categories(user_id);
+        qb.push(BOOKMARK_SELECT);
+        qb.push(
+            r#"
+            WHERE
+                (b.user_id = $1 OR EXISTS (
+                    SELECT 1 FROM linknova_bookmark_category_map as m
+                    WHERE m.bookmark_id = b.id AND m.category_id IN (SELECT id FROM visible_cat)
+                ))
+        "#,
+        );
 
-    qb.push(" GROUP by b.id");
+        if let Some(status) = status {
+            qb.push(" AND b.status = ");
+            qb.push_bind(status);
+        }
 
-    // Use a HAVING clause to filter based on the aggregated categories.
-    if let Some(categories) = categories {
-        if !categories.is_empty() {
-            // This checks if any of the bookmark's categories are in the provided list.
-            qb.push(" HAVING bool_or(cat.name = ANY(");
-            qb.push_bind(categories);
-            qb.push("))");
+        // Any of the bookmark's visible categories has to be in the provided list.
+        if let Some(categories) = categories.filter(|c| !c.is_empty()) {
+            qb.push(format!(
+                r#"
+                AND EXISTS (
+                    SELECT 1 FROM linknova_bookmark_category_map as m
+                    JOIN linknova_category as cat ON m.category_id = cat.id
+                    WHERE
+                        m.bookmark_id = b.id
+                        AND cat.id IN (SELECT id FROM visible_cat)
+                        AND {} IN "#,
+                crate::member::CATEGORY_LABEL
+            ));
+            push_in(&mut qb, categories);
+            qb.push(")");
         }
-    }
 
-    let row = qb.build_query_as().fetch_all(pool).await?;
-    Ok(row)
+        qb.build_query_as().fetch_all(pool).await
+    })?;
+
+    let listed = Listed::Visible {
+        user_id,
+        topic_id: None,
+    };
+    list_categories(db, &mut rows, listed).await?;
+    Ok(rows)
 }
 
-#[tracing::instrument(name = "linkdb::bookmark::filter", skip_all, err)]
+/// Bookmarks of anyone in the topic's categories visible to the user, the topic
+/// itself is resolved, and access to it checked, by the caller.
+#[tracing::instrument(name = "linkdb::bookmark::filter-by-topic", skip_all, err)]
 pub async fn filter_by_topic(
-    pool: &sqlx::PgPool,
+    db: &Db,
     user_id: &str,
-    topic_name: &str,
+    topic_id: i64,
     categories: Option<&[String]>,
     status: &Option<String>,
 ) -> Result<Vec<BookmarkRow>, sqlx::Error> {
-    // This is the base query for selecting bookmark data and ALL its categories.
-    // The filtering is handled separately below.
-    let mut qb = sqlx::query_builder::QueryBuilder::new(
-        r#"
-        SELECT
-            b.id,
-            b.url,
-            b.user_id,
-            b.title,
-            b.content,
-            b.referrer,
-            b.status,
-            b.created_on,
-            b.updated_on,
-            COALESCE(
-                ARRAY_AGG(cat.name ORDER BY cat.name) FILTER (WHERE cat.name IS NOT NULL),
-                '{}'
-            ) AS categories
-        FROM linknova_bookmark as b
-        LEFT JOIN linknova_bookmark_category_map as bcm ON b.id = bcm.bookmark_id
-        LEFT JOIN linknova_category as cat ON bcm.category_id = cat.id
-        WHERE
-    "#,
-    );
+    let mut rows = on_pool!(db, |pool| {
+        let mut qb = with_visible_categories(user_id);
+        qb.push(BOOKMARK_SELECT);
+        qb.push(
+            r#"
+            WHERE EXISTS (
+                SELECT 1 FROM linknova_bookmark_category_map as bcm
+                JOIN linknova_category as cat ON bcm.category_id = cat.id
+                JOIN linknova_topic_category_map as tcm ON tcm.category_id = cat.id
+                WHERE
+                    bcm.bookmark_id = b.id
+                    AND cat.id IN (SELECT id FROM visible_cat)
+                    AND tcm.topic_id = "#,
+        );
+        qb.push_bind(topic_id);
 
-    qb.push(" b.user_id = ");
-    qb.push_bind(user_id);
+        // Any of the bookmark's categories in the topic has to be in the provided list.
+        if let Some(categories) = categories.filter(|c| !c.is_empty()) {
+            qb.push(format!(" AND {} IN ", crate::member::CATEGORY_LABEL));
+            push_in(&mut qb, categories);
+        }
+        qb.push(")");
 
-    // Use an EXISTS subquery to check for a path to the topic without affecting the main JOINs.
-    qb.push(" AND EXISTS (SELECT 1 FROM linknova_topic_category_map tcm JOIN linknova_topic t ON tcm.topic_id = t.id WHERE tcm.category_id = bcm.category_id AND t.name = ");
-    qb.push_bind(topic_name);
-    qb.push(")");
+        if let Some(status) = status {
+            qb.push(" AND b.status = ");
+            qb.push_bind(status);
+        }
 
-    // The optional status filter is a simple WHERE condition.
-    if let Some(status) = status {
-        qb.push(" AND b.status = ");
-        qb.push_bind(status);
-    }
+        qb.build_query_as().fetch_all(pool).await
+    })?;
+
+    // Only the topic's categories are listed.
+    let listed = Listed::Visible {
+        user_id,
+        topic_id: Some(topic_id),
+    };
+    list_categories(db, &mut rows, listed).await?;
+    Ok(rows)
+}
+
+/// Same as `get_by_id` as seen by `user_id`: `None` unless the bookmark is theirs
+/// or in one of the categories shared with them.
+#[tracing::instrument(name = "linkdb::bookmark::get-visible", skip_all, err)]
+pub async fn get_visible(
+    db: &Db,
+    user_id: &str,
+    id: i64,
+) -> Result<Option<BookmarkRow>, sqlx::Error> {
+    let row = on_pool!(db, |pool| {
+        let mut qb = with_visible_categories(user_id);
+        qb.push(BOOKMARK_SELECT);
+        qb.push(" WHERE b.id = ");
+        qb.push_bind(id);
+        qb.push(
+            r#"
+                AND (b.user_id = $1 OR EXISTS (
+                    SELECT 1 FROM linknova_bookmark_category_map as m
+                    WHERE m.bookmark_id = b.id AND m.category_id IN (SELECT id FROM visible_cat)
+                ))
+        "#,
+        );
+        qb.build_query_as().fetch_optional(pool).await
+    })?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let mut rows = [row];
+    let listed = Listed::Visible {
+        user_id,
+        topic_id: None,
+    };
+    list_categories(db, &mut rows, listed).await?;
+    let [row] = rows;
+    Ok(Some(row))
+}
 
-    qb.push(" GROUP BY b.id");
+/// Updates the given fields of the user's own bookmark, `false` if they have none with that id.
+#[tracing::instrument(name = "linkdb::bookmark::update", skip_all, err)]
+pub async fn update(
+    db: &Db,
+    user_id: &str,
+    id: i64,
+    fields: BookmarkUpdate,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, sqlx::Error> {
+    let rows = on_pool!(db, |pool| {
+        // Build dynamic query with only the fields that are provided
+        let mut qb = sqlx::QueryBuilder::new("UPDATE linknova_bookmark SET updated_on = ");
+        qb.push_bind(now);
 
-    // Use a HAVING clause to filter based on the aggregated categories.
-    if let Some(categories) = categories {
-        if !categories.is_empty() {
-            // This checks if any of the bookmark's categories are in the provided list.
-            qb.push(" HAVING bool_or(cat.name = ANY(");
-            qb.push_bind(categories);
-            qb.push("))");
+        if let Some(title) = &fields.title {
+            qb.push(", title = ");
+            qb.push_bind(title);
         }
-    }
 
-    let row = qb.build_query_as().fetch_all(pool).await?;
-    Ok(row)
+        if let Some(url) = &fields.url {
+            qb.push(", url = ");
+            qb.push_bind(url);
+        }
+
+        if let Some(content) = &fields.content {
+            qb.push(", content = ");
+            qb.push_bind(content);
+        }
+
+        if let Some(referrer) = &fields.referrer {
+            qb.push(", referrer = ");
+            qb.push_bind(referrer);
+        }
+
+        if let Some(status) = &fields.status {
+            qb.push(", status = ");
+            qb.push_bind(status);
+        }
+
+        qb.push(" WHERE id = ");
+        qb.push_bind(id);
+        qb.push(" AND user_id = ");
+        qb.push_bind(user_id);
+
+        qb.build().execute(pool).await.map(|r| r.rows_affected())
+    })?;
+    Ok(rows == 1)
 }
 
-pub async fn delete_by_id(tx: &mut sqlx::PgTransaction<'_>, id: i64) -> Result<(), sqlx::Error> {
-    sqlx::query("DELETE FROM linknova_bookmark WHERE id = $1")
-        .bind(id)
-        .execute(&mut **tx)
-        .await?;
-    Ok(())
+/// `false` unless the user owns a bookmark with that id.
+pub async fn delete_by_id(
+    tx: &mut crate::Tx<'_>,
+    user_id: &str,
+    id: i64,
+) -> Result<bool, sqlx::Error> {
+    let rows = on_tx!(tx, |tx| sqlx::query(
+        "DELETE FROM linknova_bookmark WHERE id = $1 AND user_id = $2"
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(rows == 1)
+}
+
+/// Most recent bookmarks in any of the given categories, `categories` of a row
+/// only lists the public ones so private category names never show up.
+#[tracing::instrument(name = "linkdb::bookmark::list-public", skip_all, err)]
+pub async fn list_public(
+    db: &Db,
+    user_id: &str,
+    category_ids: &[i64],
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<BookmarkRow>, sqlx::Error> {
+    let mut rows = list_in_categories(db, user_id, category_ids, limit, offset).await?;
+    list_categories(db, &mut rows, Listed::Public).await?;
+    Ok(rows)
+}
+
+/// Like `list_public` for a shared topic or category, `categories` of a row only
+/// lists the given categories, whether public or not.
+#[tracing::instrument(name = "linkdb::bookmark::list-by-category-ids", skip_all, err)]
+pub async fn list_by_category_ids(
+    db: &Db,
+    user_id: &str,
+    category_ids: &[i64],
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<BookmarkRow>, sqlx::Error> {
+    let mut rows = list_in_categories(db, user_id, category_ids, limit, offset).await?;
+    list_categories(db, &mut rows, Listed::Among(category_ids)).await?;
+    Ok(rows)
+}
+
+/// The user's bookmarks in any of the categories, newest first.
+async fn list_in_categories(
+    db: &Db,
+    user_id: &str,
+    category_ids: &[i64],
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<BookmarkRow>, sqlx::Error> {
+    on_pool!(db, |pool| {
+        let mut qb = sqlx::QueryBuilder::new(BOOKMARK_SELECT);
+        qb.push(" WHERE b.user_id = ");
+        qb.push_bind(user_id);
+        qb.push(
+            r#"
+            AND EXISTS (
+                SELECT 1 FROM linknova_bookmark_category_map as m
+                WHERE m.bookmark_id = b.id AND m.category_id IN "#,
+        );
+        push_in(&mut qb, category_ids);
+        qb.push(") ORDER BY b.created_on DESC, b.id DESC LIMIT ");
+        qb.push_bind(limit);
+        qb.push(" OFFSET ");
+        qb.push_bind(offset);
+        qb.build_query_as().fetch_all(pool).await
+    })
 }
@@ -1,6 +1,6 @@
 use sqlx::types::chrono;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BookmarkI {
     pub url: String,
     pub user_id: String,
@@ -12,6 +12,16 @@ pub struct BookmarkI {
     pub updated_on: chrono::DateTime<chrono::Utc>,
 }
 
+/// Fields of a bookmark to change, `None` ones are left as they are.
+#[derive(Debug, Default)]
+pub struct BookmarkUpdate {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub content: Option<String>,
+    pub referrer: Option<String>,
+    pub status: Option<String>,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct BookmarkRow {
     pub id: i64,
@@ -23,5 +33,35 @@ pub struct BookmarkRow {
     pub status: String,
     pub created_on: chrono::DateTime<chrono::Utc>,
     pub updated_on: chrono::DateTime<chrono::Utc>,
+    /// Names of its categories, read by a query of their own.
+    #[sqlx(skip)]
     pub categories: Vec<String>,
 }
+
+/// Hacker News details of a bookmark saved from HN.
+#[derive(Debug)]
+pub struct HnMetaI {
+    pub bookmark_id: i64,
+    pub user_id: String,
+    pub hn_id: i64,
+    pub item_type: String,
+    pub by: Option<String>,
+    pub score: i32,
+    pub descendants: i32,
+    pub posted_on: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct HnMetaRow {
+    pub id: i64,
+    pub bookmark_id: i64,
+    pub user_id: String,
+    pub hn_id: i64,
+    pub item_type: String,
+    pub by: Option<String>,
+    pub score: i32,
+    pub descendants: i32,
+    pub posted_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
@@ -2,6 +2,7 @@ pub mod query;
 pub mod types;
 
 pub use query::{
-    delete, get_by_name, get_id_by_name, insert, list_all, list_by_topic_name, update, upsert,
+    delete, get_by_id, get_by_name, get_id_by_name, get_public, insert, list_all, list_by_topic_id,
+    list_by_topic_name, list_public_by_topic_id, update, upsert,
 };
 pub use types::{CatRow, CatRowI, CategoryRowView};
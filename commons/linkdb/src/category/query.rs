@@ -1,29 +1,17 @@
+use crate::db::{on_pool, on_tx, push_in};
 use sqlx::types::chrono;
 
-/*
-INSERT INTO your_table (col1, col2)
-SELECT *
-FROM unnest($1::text[], $2::int[])
-ON CONFLICT (col1) DO NOTHING;
- */
-
 #[tracing::instrument(name = "linkdb::category::upsert", skip_all, err)]
 pub async fn upsert(
-    tx: &mut sqlx::PgTransaction<'_>,
+    tx: &mut crate::Tx<'_>,
     rows: Vec<crate::CatRowI>,
     now: chrono::DateTime<chrono::Utc>,
 ) -> Result<Vec<i64>, sqlx::Error> {
-    let names: Vec<_> = rows.iter().map(|r| &r.name).collect();
-    let display_name: Vec<_> = rows.iter().map(|r| &r.display_name).collect();
-    let description: Vec<_> = rows.iter().map(|r| &r.description).collect();
-    let about: Vec<_> = rows.iter().map(|r| &r.about).collect();
-    let priority: Vec<_> = rows.iter().map(|r| &r.priority).collect();
-    let active: Vec<_> = rows.iter().map(|r| &r.active).collect();
-    let public: Vec<_> = rows.iter().map(|r| &r.public).collect();
-    let user_id: Vec<_> = rows.iter().map(|r| &r.user_id).collect();
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    let query = r#"
-    WITH ins AS (
+    let insert = r#"
         INSERT INTO linknova_category (
             name,
             display_name,
@@ -36,74 +24,35 @@ pub async fn upsert(
             created_on,
             updated_on
         )
-        SELECT
-            name,
-            display_name,
-            description,
-            about,
-            priority,
-            active,
-            public,
-            user_id,
-            $9,
-            $10
-        FROM unnest(
-            $1::text[],
-            $2::text[],
-            $3::text[],
-            $4::text[],
-            $5::int[],
-            $6::bool[],
-            $7::bool[],
-            $8::text[]
-        ) AS t(
-            name,
-            display_name,
-            description,
-            about,
-            priority,
-            active,
-            public,
-            user_id
-        )
+    "#;
+    // A benign update of `updated_on` so RETURNING has the existing rows as well.
+    let conflict = r#"
         ON CONFLICT (name, user_id)
-
-        DO UPDATE SET
-        -- Perform a benign update to ensure RETURNING works for existing rows.
-        -- Updating the 'updated_on' timestamp is a common and useful practice.
-        updated_on = EXCLUDED.updated_on
+        DO UPDATE SET updated_on = EXCLUDED.updated_on
         RETURNING id
-    )
-    SELECT id FROM ins;
     "#;
 
-    #[derive(sqlx::FromRow, Debug)]
-    struct TempCat {
-        id: i64,
-    }
-
-    let categories: Vec<TempCat> = sqlx::query_as(query)
-        .bind(names)
-        .bind(display_name)
-        .bind(description)
-        .bind(about)
-        .bind(priority)
-        .bind(active)
-        .bind(public)
-        .bind(user_id)
-        .bind(now)
-        .bind(now)
-        .fetch_all(&mut **tx)
-        .await?;
-
-    Ok(categories.into_iter().map(|c| c.id).collect())
+    on_tx!(tx, |tx| {
+        let mut qb = sqlx::QueryBuilder::new(insert);
+        qb.push_values(&rows, |mut b, r| {
+            b.push_bind(&r.name)
+                .push_bind(&r.display_name)
+                .push_bind(&r.description)
+                .push_bind(&r.about)
+                .push_bind(r.priority)
+                .push_bind(r.active)
+                .push_bind(r.public)
+                .push_bind(&r.user_id)
+                .push_bind(now)
+                .push_bind(now);
+        });
+        qb.push(conflict);
+        qb.build_query_scalar().fetch_all(&mut **tx).await
+    })
 }
 
 #[tracing::instrument(name = "linkdb::category::insert", skip_all, err)]
-pub async fn insert(
-    pool: &sqlx::PgPool,
-    row: crate::CatRowI,
-) -> Result<(i64, String), sqlx::Error> {
+pub async fn insert(db: &crate::Db, row: crate::CatRowI) -> Result<(i64, String), sqlx::Error> {
     let query = r#"
         INSERT INTO linknova_category(
             name,
@@ -123,7 +72,7 @@ pub async fn insert(
 
     let now = chrono::Utc::now();
 
-    let (id, name): (i64, String) = sqlx::query_as(query)
+    let (id, name): (i64, String) = on_pool!(db, |pool| sqlx::query_as(query)
         .bind(row.name)
         .bind(row.display_name)
         .bind(row.description)
@@ -135,14 +84,14 @@ pub async fn insert(
         .bind(now)
         .bind(now)
         .fetch_one(pool)
-        .await?;
+        .await)?;
 
     Ok((id, name))
 }
 
 #[tracing::instrument(name = "linkdb::category::get-by-name", skip_all, err)]
 pub async fn get_by_name(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     user_id: &str,
     cat_name: &str,
 ) -> Result<Option<crate::CatRow>, sqlx::Error> {
@@ -163,16 +112,16 @@ pub async fn get_by_name(
         WHERE name = $1 and user_id = $2
     "#;
 
-    sqlx::query_as(query)
+    on_pool!(db, |pool| sqlx::query_as(query)
         .bind(cat_name)
         .bind(user_id)
         .fetch_optional(pool)
-        .await
+        .await)
 }
 
 #[tracing::instrument(name = "linkdb::category::get-id-by-name", skip_all, err)]
 pub async fn get_id_by_name(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     user_id: &str,
     cat_name: &str,
 ) -> Result<Option<i64>, sqlx::Error> {
@@ -181,23 +130,26 @@ pub async fn get_id_by_name(
         WHERE name = $1 and user_id = $2
     "#;
 
-    let id: Option<(i64,)> = sqlx::query_as(query)
+    let id: Option<(i64,)> = on_pool!(db, |pool| sqlx::query_as(query)
         .bind(cat_name)
         .bind(user_id)
         .fetch_optional(pool)
-        .await?;
+        .await)?;
     Ok(id.map(|(x,)| x))
 }
 
+/// Own categories along with the ones shared with the user.
 #[tracing::instrument(name = "linkdb::category::list-all", skip_all, err)]
 pub async fn list_all(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     user_id: &str,
 ) -> Result<Vec<crate::CategoryRowView>, sqlx::Error> {
-    let query = r#"
+    let query = format!(
+        r#"
         select
             id,
             name,
+            user_id,
             display_name,
             description,
             priority,
@@ -206,15 +158,21 @@ pub async fn list_all(
             created_on,
             updated_on
         FROM linknova_category
-        WHERE user_id = $1
-    "#;
+        WHERE user_id = $1 OR id IN ({})
+        ORDER BY user_id = $1 DESC, user_id, name
+    "#,
+        crate::member::SHARED_CATEGORY_IDS
+    );
 
-    sqlx::query_as(query).bind(user_id).fetch_all(pool).await
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
 }
 
 #[tracing::instrument(name = "linkdb::category::list-by-topic-name", skip_all, err)]
 pub async fn list_by_topic_name(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     user_id: &str,
     topic_names: &[String],
 ) -> Result<Vec<crate::CategoryRowView>, sqlx::Error> {
@@ -222,6 +180,7 @@ pub async fn list_by_topic_name(
         select
             cat.id,
             cat.name,
+            cat.user_id,
             cat.display_name,
             cat.description,
             cat.priority,
@@ -234,23 +193,21 @@ pub async fn list_by_topic_name(
             ON cat.id = mapping.category_id
         JOIN linknova_topic as topic
             ON mapping.topic_id = topic.id
-        WHERE
-            topic.name = ANY($1) AND
-            topic.user_id = $2 AND
-            cat.user_id = $3
-    "#;
+        WHERE topic.user_id = "#;
 
-    sqlx::query_as(query)
-        .bind(topic_names)
-        .bind(user_id)
-        .bind(user_id)
-        .fetch_all(pool)
-        .await
+    on_pool!(db, |pool| {
+        let mut qb = sqlx::QueryBuilder::new(query);
+        qb.push_bind(user_id);
+        qb.push(" AND cat.user_id = $1 AND topic.name IN ");
+        push_in(&mut qb, topic_names);
+        qb.build_query_as().fetch_all(pool).await
+    })
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(name = "linkdb::category::update", skip_all, err)]
 pub async fn update(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     user_id: &str,
     cat_name: &str,
     display_name: Option<String>,
@@ -272,7 +229,7 @@ pub async fn update(
         WHERE name = $1 AND user_id = $2
     "#;
 
-    sqlx::query(query)
+    on_pool!(db, |pool| sqlx::query(query)
         .bind(cat_name)
         .bind(user_id)
         .bind(display_name)
@@ -282,20 +239,154 @@ pub async fn update(
         .bind(priority)
         .bind(now)
         .execute(pool)
-        .await?;
+        .await
+        .map(|r| r.rows_affected()))?;
 
     Ok(())
 }
 
 #[tracing::instrument(name = "linkdb::category::delete", skip_all, err)]
-pub async fn delete(pool: &sqlx::PgPool, user_id: &str, cat_name: &str) -> Result<(), sqlx::Error> {
+pub async fn delete(db: &crate::Db, user_id: &str, cat_name: &str) -> Result<(), sqlx::Error> {
     let query = r#"
         DELETE FROM linknova_category WHERE name = $1 and user_id = $2 returning id
     "#;
-    let (_id,): (i64,) = sqlx::query_as(query)
+    let (_id,): (i64,) = on_pool!(db, |pool| sqlx::query_as(query)
         .bind(cat_name)
         .bind(user_id)
         .fetch_one(pool)
-        .await?;
+        .await)?;
     Ok(())
 }
+
+#[tracing::instrument(name = "linkdb::category::get-public", skip_all, err)]
+pub async fn get_public(
+    db: &crate::Db,
+    user_id: &str,
+    cat_name: &str,
+) -> Result<Option<crate::CatRow>, sqlx::Error> {
+    let query = r#"
+        select
+            id,
+            name,
+            display_name,
+            description,
+            about,
+            priority,
+            active,
+            public,
+            user_id,
+            created_on,
+            updated_on
+        FROM linknova_category
+        WHERE name = $1 and user_id = $2 and public and active
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(cat_name)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+/// Public, active categories mapped to the topic, highest priority first.
+#[tracing::instrument(name = "linkdb::category::list-public-by-topic-id", skip_all, err)]
+pub async fn list_public_by_topic_id(
+    db: &crate::Db,
+    user_id: &str,
+    topic_id: i64,
+) -> Result<Vec<crate::CategoryRowView>, sqlx::Error> {
+    let query = r#"
+        select
+            cat.id,
+            cat.name,
+            cat.user_id,
+            cat.display_name,
+            cat.description,
+            cat.priority,
+            cat.active,
+            cat.public,
+            cat.created_on,
+            cat.updated_on
+        FROM linknova_category as cat
+        JOIN linknova_topic_category_map as mapping
+            ON cat.id = mapping.category_id
+        WHERE
+            mapping.topic_id = $1 AND
+            cat.user_id = $2 AND
+            cat.public AND
+            cat.active
+        ORDER BY cat.priority DESC, cat.name
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(topic_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::category::get-by-id", skip_all, err)]
+pub async fn get_by_id(
+    db: &crate::Db,
+    user_id: &str,
+    id: i64,
+) -> Result<Option<crate::CatRow>, sqlx::Error> {
+    let query = r#"
+        select
+            id,
+            name,
+            display_name,
+            description,
+            about,
+            priority,
+            active,
+            public,
+            user_id,
+            created_on,
+            updated_on
+        FROM linknova_category
+        WHERE id = $1 and user_id = $2
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+/// Active categories mapped to the topic, public or not, highest priority first.
+#[tracing::instrument(name = "linkdb::category::list-by-topic-id", skip_all, err)]
+pub async fn list_by_topic_id(
+    db: &crate::Db,
+    user_id: &str,
+    topic_id: i64,
+) -> Result<Vec<crate::CategoryRowView>, sqlx::Error> {
+    let query = r#"
+        select
+            cat.id,
+            cat.name,
+            cat.user_id,
+            cat.display_name,
+            cat.description,
+            cat.priority,
+            cat.active,
+            cat.public,
+            cat.created_on,
+            cat.updated_on
+        FROM linknova_category as cat
+        JOIN linknova_topic_category_map as mapping
+            ON cat.id = mapping.category_id
+        WHERE
+            mapping.topic_id = $1 AND
+            cat.user_id = $2 AND
+            cat.active
+        ORDER BY cat.priority DESC, cat.name
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(topic_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+}
@@ -13,7 +13,7 @@ pub struct CatRowI {
     pub user_id: String,
 }
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, Clone, FromRow)]
 pub struct CatRow {
     pub id: i64,
     pub name: String,
@@ -32,6 +32,8 @@ pub struct CatRow {
 pub struct CategoryRowView {
     pub id: i64,
     pub name: String,
+    /// owner, differs from the caller for shared categories
+    pub user_id: String,
     pub display_name: Option<String>,
     pub description: Option<String>,
     pub priority: i32,
@@ -0,0 +1,220 @@
+//! The connection pool of the backend the database url points at: Postgres, or
+//! SQLite when built with the `sqlite` feature.
+//!
+//! Queries are written once with `$N` placeholders, which both backends accept,
+//! and run on either pool through `on_pool!` / `on_tx!`. Both arms have to agree
+//! on a type, so an `execute` maps its result to the rows affected. The few
+//! queries that need backend specific SQL match on `Db` themselves.
+
+use std::str::FromStr;
+
+/// Evaluates `$body` with `$pool` bound to the pool of whichever backend `$db` is.
+macro_rules! on_pool {
+    ($db:expr, |$pool:ident| $body:expr) => {
+        match $db {
+            $crate::Db::Postgres($pool) => $body,
+            #[cfg(feature = "sqlite")]
+            $crate::Db::Sqlite($pool) => $body,
+        }
+    };
+}
+
+/// Same as `on_pool!` for a `Tx`, `$conn` is the backend transaction, used as
+/// `&mut **$conn`.
+macro_rules! on_tx {
+    ($tx:expr, |$conn:ident| $body:expr) => {
+        match $tx {
+            $crate::Tx::Postgres($conn) => $body,
+            #[cfg(feature = "sqlite")]
+            $crate::Tx::Sqlite($conn) => $body,
+        }
+    };
+}
+
+pub(crate) use {on_pool, on_tx};
+
+#[derive(Clone, Debug)]
+pub enum Db {
+    Postgres(sqlx::PgPool),
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlx::SqlitePool),
+}
+
+/// A transaction on either backend, see `Db::begin`.
+#[derive(Debug)]
+pub enum Tx<'c> {
+    Postgres(sqlx::Transaction<'c, sqlx::Postgres>),
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlx::Transaction<'c, sqlx::Sqlite>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Postgres,
+    Sqlite,
+}
+
+/// Connections of the pool at one point in time, see `Db::pool_stats`.
+#[derive(Clone, Copy, Debug)]
+pub struct PoolStats {
+    /// open connections, idle or in use
+    pub size: u32,
+    pub idle: u32,
+    pub max_connections: u32,
+}
+
+/// Sizing of the pool `Db::connect` opens.
+#[derive(Clone, Copy, Debug)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    /// kept open even when idle
+    pub min_connections: u32,
+    /// how long a query waits for a free connection before failing
+    pub acquire_timeout: std::time::Duration,
+}
+
+impl PoolConfig {
+    fn options<DB: sqlx::Database>(&self) -> sqlx::pool::PoolOptions<DB> {
+        sqlx::pool::PoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(self.acquire_timeout)
+    }
+}
+
+impl Backend {
+    /// Backend of a `postgres://`, `postgresql://` or `sqlite:` url.
+    pub fn of_url(url: &str) -> Option<Backend> {
+        let (scheme, _) = url.split_once(':')?;
+        match scheme {
+            "postgres" | "postgresql" => Some(Backend::Postgres),
+            "sqlite" => Some(Backend::Sqlite),
+            _ => None,
+        }
+    }
+}
+
+impl Db {
+    /// Opens a pool on the backend the url scheme names. A SQLite file is created
+    /// when missing and foreign keys are enforced, as they are on Postgres.
+    #[tracing::instrument(name = "linkdb::db::connect", skip_all, err)]
+    pub async fn connect(url: &str, pool: PoolConfig) -> Result<Db, sqlx::Error> {
+        match Backend::of_url(url) {
+            Some(Backend::Postgres) => {
+                let options = sqlx::postgres::PgConnectOptions::from_str(url)?;
+                let pool = pool.options().connect_with(options).await?;
+                Ok(Db::Postgres(pool))
+            }
+            #[cfg(feature = "sqlite")]
+            Some(Backend::Sqlite) => {
+                let options = sqlx::sqlite::SqliteConnectOptions::from_str(url)?
+                    .create_if_missing(true)
+                    .foreign_keys(true)
+                    .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+                    .busy_timeout(std::time::Duration::from_secs(5));
+                let pool = pool.options().connect_with(options).await?;
+                Ok(Db::Sqlite(pool))
+            }
+            #[cfg(not(feature = "sqlite"))]
+            Some(Backend::Sqlite) => Err(sqlx::Error::Configuration(
+                "sqlite urls need a build with the `sqlite` feature".into(),
+            )),
+            None => Err(sqlx::Error::Configuration(
+                "database url has to start with postgres://, postgresql:// or sqlite:".into(),
+            )),
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            Db::Postgres(_) => Backend::Postgres,
+            #[cfg(feature = "sqlite")]
+            Db::Sqlite(_) => Backend::Sqlite,
+        }
+    }
+
+    pub async fn begin(&self) -> Result<Tx<'static>, sqlx::Error> {
+        match self {
+            Db::Postgres(pool) => Ok(Tx::Postgres(pool.begin().await?)),
+            #[cfg(feature = "sqlite")]
+            Db::Sqlite(pool) => Ok(Tx::Sqlite(pool.begin().await?)),
+        }
+    }
+
+    /// A transaction that waits for the others begun with the same `key` to end,
+    /// for a look up followed by an insert the look up decides on. Postgres holds
+    /// an advisory lock on the key, SQLite, with one writer at a time, the write
+    /// lock of the database.
+    pub async fn begin_locked(&self, key: &str) -> Result<Tx<'static>, sqlx::Error> {
+        match self {
+            Db::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                    .bind(key)
+                    .execute(&mut *tx)
+                    .await?;
+                Ok(Tx::Postgres(tx))
+            }
+            #[cfg(feature = "sqlite")]
+            Db::Sqlite(pool) => Ok(Tx::Sqlite(pool.begin_with("BEGIN IMMEDIATE").await?)),
+        }
+    }
+
+    pub fn pool_stats(&self) -> PoolStats {
+        on_pool!(self, |pool| PoolStats {
+            size: pool.size(),
+            idle: pool.num_idle() as u32,
+            max_connections: pool.options().get_max_connections(),
+        })
+    }
+
+    /// Runs a trivial query, for health checks.
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        on_pool!(self, |pool| sqlx::query("SELECT 1")
+            .execute(pool)
+            .await
+            .map(|_| ()))
+    }
+
+    /// Waits for the connections in use to be returned, then closes them all.
+    pub async fn close(&self) {
+        on_pool!(self, |pool| pool.close().await)
+    }
+}
+
+impl Tx<'_> {
+    pub async fn commit(self) -> Result<(), sqlx::Error> {
+        match self {
+            Tx::Postgres(tx) => tx.commit().await,
+            #[cfg(feature = "sqlite")]
+            Tx::Sqlite(tx) => tx.commit().await,
+        }
+    }
+
+    pub async fn rollback(self) -> Result<(), sqlx::Error> {
+        match self {
+            Tx::Postgres(tx) => tx.rollback().await,
+            #[cfg(feature = "sqlite")]
+            Tx::Sqlite(tx) => tx.rollback().await,
+        }
+    }
+}
+
+/// Pushes `(v1, v2, ..)` as the right hand side of `IN`, in place of Postgres'
+/// `= ANY($n)`. An empty list is `(NULL)`, which `IN` never matches.
+pub(crate) fn push_in<'a, DB, T>(qb: &mut sqlx::QueryBuilder<'a, DB>, values: &'a [T])
+where
+    DB: sqlx::Database,
+    &'a T: sqlx::Encode<'a, DB> + sqlx::Type<DB>,
+{
+    if values.is_empty() {
+        qb.push("(NULL)");
+        return;
+    }
+    qb.push("(");
+    let mut list = qb.separated(", ");
+    for value in values {
+        list.push_bind(value);
+    }
+    list.push_unseparated(")");
+}
@@ -0,0 +1,55 @@
+use crate::db::on_tx;
+use sqlx::types::chrono;
+
+/// Records `guid` as seen for the feed, returns `None` if it was already seen.
+#[tracing::instrument(name = "linkdb::feed::entry::insert-new", skip_all, err)]
+pub async fn insert_new(
+    tx: &mut crate::Tx<'_>,
+    feed_id: i64,
+    guid: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<i64>, sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_feed_entry(feed_id, guid, created_on)
+        VALUES($1, $2, $3)
+        ON CONFLICT DO NOTHING
+        RETURNING id
+    "#;
+
+    let id: Option<(i64,)> = on_tx!(tx, |tx| sqlx::query_as(query)
+        .bind(feed_id)
+        .bind(guid)
+        .bind(now)
+        .fetch_optional(&mut **tx)
+        .await)?;
+    Ok(id.map(|(x,)| x))
+}
+
+#[tracing::instrument(name = "linkdb::feed::entry::set-bookmark", skip_all, err)]
+pub async fn set_bookmark(
+    tx: &mut crate::Tx<'_>,
+    entry_id: i64,
+    bookmark_id: i64,
+) -> Result<(), sqlx::Error> {
+    on_tx!(tx, |tx| sqlx::query(
+        "UPDATE linknova_feed_entry SET bookmark_id = $2 WHERE id = $1"
+    )
+    .bind(entry_id)
+    .bind(bookmark_id)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "linkdb::feed::entry::delete-by-feed-id", skip_all, err)]
+pub async fn delete_by_feed_id(tx: &mut crate::Tx<'_>, feed_id: i64) -> Result<(), sqlx::Error> {
+    on_tx!(tx, |tx| sqlx::query(
+        "DELETE FROM linknova_feed_entry WHERE feed_id = $1"
+    )
+    .bind(feed_id)
+    .execute(&mut **tx)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod entry;
+pub mod query;
+pub mod types;
+
+pub use types::{FeedI, FeedPollState, FeedRow};
+
+pub use query::{delete, get_by_id, list_all, list_due, update_poll_state, upsert};
@@ -0,0 +1,150 @@
+use crate::db::{on_pool, on_tx};
+use crate::feed::{FeedI, FeedPollState, FeedRow};
+use sqlx::types::chrono;
+
+const FEED_SELECT: &str = r#"
+    SELECT
+        f.id,
+        f.url,
+        f.user_id,
+        f.title,
+        f.site_url,
+        f.category_id,
+        cat.name AS category,
+        f.etag,
+        f.last_modified,
+        f.last_polled_on,
+        f.last_error,
+        f.active,
+        f.created_on,
+        f.updated_on
+    FROM linknova_feed as f
+    JOIN linknova_category as cat ON f.category_id = cat.id
+"#;
+
+/// Subscribing twice to the same url only moves the feed to the new category.
+#[tracing::instrument(name = "linkdb::feed::upsert", skip_all, err)]
+pub async fn upsert(
+    tx: &mut crate::Tx<'_>,
+    row: FeedI,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<i64, sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_feed(
+            url,
+            user_id,
+            title,
+            site_url,
+            category_id,
+            active,
+            created_on,
+            updated_on
+        ) VALUES($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id, url)
+        DO UPDATE SET
+            title = COALESCE(EXCLUDED.title, linknova_feed.title),
+            category_id = EXCLUDED.category_id,
+            active = EXCLUDED.active,
+            updated_on = EXCLUDED.updated_on
+        RETURNING id
+    "#;
+
+    let (id,): (i64,) = on_tx!(tx, |tx| sqlx::query_as(query)
+        .bind(row.url)
+        .bind(row.user_id)
+        .bind(row.title)
+        .bind(row.site_url)
+        .bind(row.category_id)
+        .bind(row.active)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&mut **tx)
+        .await)?;
+
+    Ok(id)
+}
+
+#[tracing::instrument(name = "linkdb::feed::get-by-id", skip_all, err)]
+pub async fn get_by_id(
+    db: &crate::Db,
+    user_id: &str,
+    id: i64,
+) -> Result<Option<FeedRow>, sqlx::Error> {
+    let query = format!("{FEED_SELECT} WHERE f.id = $1 AND f.user_id = $2");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::feed::list-all", skip_all, err)]
+pub async fn list_all(db: &crate::Db, user_id: &str) -> Result<Vec<FeedRow>, sqlx::Error> {
+    let query = format!("{FEED_SELECT} WHERE f.user_id = $1 ORDER BY cat.name, f.title, f.url");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Active feeds, across all users, which were never polled or polled before `polled_before`.
+#[tracing::instrument(name = "linkdb::feed::list-due", skip_all, err)]
+pub async fn list_due(
+    db: &crate::Db,
+    polled_before: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<FeedRow>, sqlx::Error> {
+    let query = format!(
+        "{FEED_SELECT} WHERE f.active AND (f.last_polled_on IS NULL OR f.last_polled_on < $1) ORDER BY f.last_polled_on NULLS FIRST"
+    );
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(polled_before)
+        .fetch_all(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::feed::update-poll-state", skip_all, err)]
+pub async fn update_poll_state(
+    db: &crate::Db,
+    id: i64,
+    state: FeedPollState,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_feed SET
+            title = COALESCE(title, $2),
+            site_url = COALESCE($3, site_url),
+            etag = COALESCE($4, etag),
+            last_modified = COALESCE($5, last_modified),
+            last_error = $6,
+            last_polled_on = $7,
+            updated_on = $7
+        WHERE id = $1
+    "#;
+
+    on_pool!(db, |pool| sqlx::query(query)
+        .bind(id)
+        .bind(state.title)
+        .bind(state.site_url)
+        .bind(state.etag)
+        .bind(state.last_modified)
+        .bind(state.last_error)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "linkdb::feed::delete", skip_all, err)]
+pub async fn delete(tx: &mut crate::Tx<'_>, user_id: &str, id: i64) -> Result<(), sqlx::Error> {
+    super::entry::delete_by_feed_id(tx, id).await?;
+    let query = r#"
+        DELETE FROM linknova_feed WHERE id = $1 AND user_id = $2 returning id
+    "#;
+    let (_id,): (i64,) = on_tx!(tx, |tx| sqlx::query_as(query)
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await)?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use sqlx::types::chrono;
+
+#[derive(Debug)]
+pub struct FeedI {
+    pub url: String,
+    pub user_id: String,
+    pub title: Option<String>,
+    pub site_url: Option<String>,
+    pub category_id: i64,
+    pub active: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct FeedRow {
+    pub id: i64,
+    pub url: String,
+    pub user_id: String,
+    pub title: Option<String>,
+    pub site_url: Option<String>,
+    pub category_id: i64,
+    pub category: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub last_polled_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    pub active: bool,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
+
+/// Result of a single poll, written back after every fetch attempt.
+#[derive(Debug, Default)]
+pub struct FeedPollState {
+    pub title: Option<String>,
+    pub site_url: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub last_error: Option<String>,
+}
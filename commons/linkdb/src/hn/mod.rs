@@ -0,0 +1,12 @@
+pub mod query;
+pub mod score;
+pub mod search;
+pub mod state;
+pub mod types;
+
+pub use types::{
+    HnCrossedRow, HnItemI, HnItemRow, HnScoreRow, HnSearchQ, HnSearchRow, HnVelocityRow,
+};
+
+pub use query::{get_by_id, upsert};
+pub use search::search;
@@ -0,0 +1,104 @@
+use crate::db::{on_pool, on_tx};
+use crate::hn::{HnItemI, HnItemRow};
+use sqlx::types::chrono;
+
+pub(crate) const ITEM_COLUMNS: &str = r#"
+    i.id,
+    i.item_type,
+    i.by,
+    i.title,
+    i.url,
+    i.text,
+    i.parent,
+    i.score,
+    i.descendants,
+    i.deleted,
+    i.dead,
+    i.posted_on,
+    i.created_on,
+    i.updated_on
+"#;
+
+/// Rows per statement of the multi-row inserts, keeps the bind parameters well
+/// under what Postgres and SQLite allow in one statement.
+pub(crate) const ROWS_PER_INSERT: usize = 1000;
+
+/// Inserts new items and overwrites the ones already stored, HN edits titles
+/// and marks items deleted or dead after the fact.
+#[tracing::instrument(name = "linkdb::hn::upsert", skip_all, err)]
+pub async fn upsert(
+    tx: &mut crate::Tx<'_>,
+    rows: Vec<HnItemI>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    let insert = r#"
+        INSERT INTO linknova_hn_item (
+            id,
+            item_type,
+            by,
+            title,
+            url,
+            text,
+            parent,
+            score,
+            descendants,
+            deleted,
+            dead,
+            posted_on,
+            created_on,
+            updated_on
+        )
+    "#;
+    let conflict = r#"
+        ON CONFLICT (id) DO UPDATE SET
+            item_type = EXCLUDED.item_type,
+            by = EXCLUDED.by,
+            title = EXCLUDED.title,
+            url = EXCLUDED.url,
+            text = EXCLUDED.text,
+            parent = EXCLUDED.parent,
+            score = EXCLUDED.score,
+            descendants = EXCLUDED.descendants,
+            deleted = EXCLUDED.deleted,
+            dead = EXCLUDED.dead,
+            posted_on = EXCLUDED.posted_on,
+            updated_on = EXCLUDED.updated_on
+    "#;
+
+    for chunk in rows.chunks(ROWS_PER_INSERT) {
+        on_tx!(&mut *tx, |tx| {
+            let mut qb = sqlx::QueryBuilder::new(insert);
+            qb.push_values(chunk, |mut b, r| {
+                b.push_bind(r.id)
+                    .push_bind(&r.item_type)
+                    .push_bind(&r.by)
+                    .push_bind(&r.title)
+                    .push_bind(&r.url)
+                    .push_bind(&r.text)
+                    .push_bind(r.parent)
+                    .push_bind(r.score)
+                    .push_bind(r.descendants)
+                    .push_bind(r.deleted)
+                    .push_bind(r.dead)
+                    .push_bind(r.posted_on)
+                    .push_bind(now)
+                    .push_bind(now);
+            });
+            qb.push(conflict);
+            qb.build()
+                .execute(&mut **tx)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "linkdb::hn::get-by-id", skip_all, err)]
+pub async fn get_by_id(db: &crate::Db, id: i64) -> Result<Option<HnItemRow>, sqlx::Error> {
+    let query = format!("SELECT {ITEM_COLUMNS} FROM linknova_hn_item i WHERE i.id = $1");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(id)
+        .fetch_optional(pool)
+        .await)
+}
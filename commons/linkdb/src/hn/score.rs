@@ -0,0 +1,153 @@
+use crate::db::{on_pool, on_tx};
+use crate::hn::query::{ITEM_COLUMNS, ROWS_PER_INSERT};
+use crate::hn::{HnCrossedRow, HnScoreRow, HnVelocityRow};
+use crate::{Backend, Db};
+use sqlx::types::chrono;
+
+/// Records the current score and comment count of `(item_id, score, descendants)`.
+#[tracing::instrument(name = "linkdb::hn::score::insert", skip_all, err)]
+pub async fn insert(
+    tx: &mut crate::Tx<'_>,
+    points: &[(i64, i32, i32)],
+    taken_on: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    for chunk in points.chunks(ROWS_PER_INSERT) {
+        on_tx!(&mut *tx, |tx| {
+            let mut qb = sqlx::QueryBuilder::new(
+                "INSERT INTO linknova_hn_score (item_id, score, descendants, taken_on) ",
+            );
+            qb.push_values(chunk, |mut b, (item_id, score, descendants)| {
+                b.push_bind(*item_id)
+                    .push_bind(*score)
+                    .push_bind(*descendants)
+                    .push_bind(taken_on);
+            });
+            qb.build()
+                .execute(&mut **tx)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "linkdb::hn::score::history", skip_all, err)]
+pub async fn history(
+    db: &crate::Db,
+    item_id: i64,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<HnScoreRow>, sqlx::Error> {
+    let query = r#"
+        SELECT score, descendants, taken_on
+        FROM linknova_hn_score
+        WHERE item_id = $1 AND taken_on >= $2
+        ORDER BY taken_on
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(item_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Items whose first snapshot with at least `points` was taken at or after `since`.
+#[tracing::instrument(name = "linkdb::hn::score::crossed", skip_all, err)]
+pub async fn crossed(
+    db: &crate::Db,
+    points: i32,
+    since: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+) -> Result<Vec<HnCrossedRow>, sqlx::Error> {
+    let query = format!(
+        r#"
+        SELECT {ITEM_COLUMNS}, c.crossed_on
+        FROM (
+            SELECT item_id, min(taken_on) AS crossed_on
+            FROM linknova_hn_score
+            WHERE score >= $1
+            GROUP BY item_id
+            HAVING min(taken_on) >= $2
+        ) c
+        JOIN linknova_hn_item i ON i.id = c.item_id
+        ORDER BY c.crossed_on DESC
+        LIMIT $3
+        "#
+    );
+
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(points)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Items gaining points fastest between their first and last snapshot since `since`,
+/// an item needs at least two snapshots to be ranked.
+#[tracing::instrument(name = "linkdb::hn::score::velocity", skip_all, err)]
+pub async fn velocity(
+    db: &Db,
+    since: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+) -> Result<Vec<HnVelocityRow>, sqlx::Error> {
+    let hours = match db.backend() {
+        Backend::Postgres => "extract(epoch FROM w.last_on - w.first_on) / 3600",
+        Backend::Sqlite => "(julianday(w.last_on) - julianday(w.first_on)) * 24",
+    };
+    let query = format!(
+        r#"
+        WITH w AS (
+            SELECT
+                item_id,
+                min(taken_on) AS first_on,
+                max(taken_on) AS last_on
+            FROM linknova_hn_score
+            WHERE taken_on >= $1
+            GROUP BY item_id
+            HAVING max(taken_on) > min(taken_on)
+        ),
+        s AS (
+            SELECT
+                w.item_id,
+                {hours} AS hours,
+                (SELECT score FROM linknova_hn_score
+                    WHERE item_id = w.item_id AND taken_on = w.first_on LIMIT 1) AS first_score,
+                (SELECT score FROM linknova_hn_score
+                    WHERE item_id = w.item_id AND taken_on = w.last_on LIMIT 1) AS last_score
+            FROM w
+        )
+        SELECT
+            {ITEM_COLUMNS},
+            s.last_score - s.first_score AS gained,
+            CAST(s.last_score - s.first_score AS double precision)
+                / CAST(s.hours AS double precision) AS points_per_hour
+        FROM s
+        JOIN linknova_hn_item i ON i.id = s.item_id
+        ORDER BY points_per_hour DESC, i.id DESC
+        LIMIT $2
+        "#
+    );
+
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Drops snapshots older than `before`, returns how many were removed.
+#[tracing::instrument(name = "linkdb::hn::score::delete-before", skip_all, err)]
+pub async fn delete_before(
+    db: &crate::Db,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Result<u64, sqlx::Error> {
+    let rows = on_pool!(db, |pool| sqlx::query(
+        "DELETE FROM linknova_hn_score WHERE taken_on < $1"
+    )
+    .bind(before)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(rows)
+}
@@ -0,0 +1,205 @@
+use crate::db::on_pool;
+use crate::hn::query::ITEM_COLUMNS;
+use crate::hn::{HnSearchQ, HnSearchRow};
+use crate::{Backend, Db};
+
+/// Text searched over on Postgres, the GIN index `linknova_hn_item_search` is
+/// built on the same expression. SQLite has the FTS5 table of that name instead.
+pub const DOCUMENT: &str = "to_tsvector('english', coalesce(i.title, '') || ' ' || coalesce(i.text, '') || ' ' || coalesce(i.url, ''))";
+
+/// Stored items matching `q.text`, best matches first, or the newest ones when
+/// there is no text. Every row carries the total number of matches.
+///
+/// `q.text` takes the web search syntax of Postgres' `websearch_to_tsquery`:
+/// `"quoted phrases"`, `or` and `-excluded` terms. SQLite stems the terms the
+/// same way but keeps stop words, so `the` has to be in an item to match.
+#[tracing::instrument(name = "linkdb::hn::search", skip_all, err)]
+pub async fn search(db: &Db, q: &HnSearchQ) -> Result<Vec<HnSearchRow>, sqlx::Error> {
+    let text = q.text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let backend = db.backend();
+    let (include, exclude) = match (backend, text) {
+        (Backend::Sqlite, Some(text)) => fts5_query(text),
+        _ => (None, None),
+    };
+
+    on_pool!(db, |pool| {
+        let mut qb = sqlx::QueryBuilder::new(format!("SELECT {ITEM_COLUMNS}, "));
+        match (backend, text) {
+            (Backend::Postgres, Some(text)) => {
+                qb.push(format!(
+                    "ts_rank({DOCUMENT}, websearch_to_tsquery('english', "
+                ));
+                qb.push_bind(text);
+                qb.push(")) AS rank");
+            }
+            // bm25 is lower for better matches
+            (Backend::Sqlite, Some(_)) if include.is_some() => {
+                qb.push("-m.score AS rank");
+            }
+            _ => {
+                qb.push("CAST(0 AS real) AS rank");
+            }
+        }
+        qb.push(", count(*) OVER () AS total FROM linknova_hn_item i");
+
+        if let Some(include) = &include {
+            qb.push(
+                r#"
+                JOIN (
+                    SELECT rowid, bm25(linknova_hn_item_search) AS score
+                    FROM linknova_hn_item_search
+                    WHERE linknova_hn_item_search MATCH "#,
+            );
+            qb.push_bind(include);
+            qb.push(") m ON m.rowid = i.id");
+        }
+        qb.push(" WHERE NOT i.deleted");
+
+        match (backend, text) {
+            (Backend::Postgres, Some(text)) => {
+                qb.push(format!(
+                    " AND {DOCUMENT} @@ websearch_to_tsquery('english', "
+                ));
+                qb.push_bind(text);
+                qb.push(")");
+            }
+            (Backend::Sqlite, Some(_)) => {
+                if let Some(exclude) = &exclude {
+                    qb.push(
+                        r#"
+                        AND i.id NOT IN (
+                            SELECT rowid FROM linknova_hn_item_search
+                            WHERE linknova_hn_item_search MATCH "#,
+                    );
+                    qb.push_bind(exclude);
+                    qb.push(")");
+                }
+                // nothing but punctuation, no item matches that on Postgres either
+                if include.is_none() && exclude.is_none() {
+                    qb.push(" AND false");
+                }
+            }
+            _ => {}
+        }
+        if let Some(item_type) = &q.item_type {
+            qb.push(" AND i.item_type = ");
+            qb.push_bind(item_type);
+        }
+        if let Some(by) = &q.by {
+            qb.push(" AND i.by = ");
+            qb.push_bind(by);
+        }
+        if let Some(from) = q.from {
+            qb.push(" AND i.posted_on >= ");
+            qb.push_bind(from);
+        }
+        if let Some(to) = q.to {
+            qb.push(" AND i.posted_on < ");
+            qb.push_bind(to);
+        }
+        if let Some(min_score) = q.min_score {
+            qb.push(" AND i.score >= ");
+            qb.push_bind(min_score);
+        }
+
+        qb.push(" ORDER BY rank DESC, i.posted_on DESC NULLS LAST, i.id DESC LIMIT ");
+        qb.push_bind(q.limit);
+        qb.push(" OFFSET ");
+        qb.push_bind(q.offset);
+
+        qb.build_query_as().fetch_all(pool).await
+    })
+}
+
+/// Web search syntax as FTS5 queries: the one items have to match, `None` when
+/// there are only excluded terms, and the one they must not match.
+fn fts5_query(text: &str) -> (Option<String>, Option<String>) {
+    let mut include = String::new();
+    let mut exclude = Vec::new();
+    let mut or = false;
+    let mut chars = text.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            break;
+        };
+        let negated = first == '-';
+        if negated {
+            chars.next();
+        }
+        let quoted = chars.next_if_eq(&'"').is_some();
+        let term: String = if quoted {
+            chars.by_ref().take_while(|&c| c != '"').collect()
+        } else {
+            let mut term = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                term.push(c);
+            }
+            term
+        };
+
+        if !quoted && !negated && term.eq_ignore_ascii_case("or") {
+            or = !include.is_empty();
+            continue;
+        }
+        // FTS5 drops punctuation, a term of nothing else would match nothing
+        if !term.chars().any(char::is_alphanumeric) {
+            continue;
+        }
+        let term = format!("\"{}\"", term.replace('"', "\"\""));
+        if negated {
+            exclude.push(term);
+        } else {
+            if !include.is_empty() {
+                include.push_str(if or { " OR " } else { " AND " });
+            }
+            include.push_str(&term);
+        }
+        or = false;
+    }
+
+    let include = (!include.is_empty()).then_some(include);
+    let exclude = (!exclude.is_empty()).then(|| exclude.join(" OR "));
+    (include, exclude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fts5_query;
+
+    fn query(include: Option<&str>, exclude: Option<&str>) -> (Option<String>, Option<String>) {
+        (include.map(str::to_string), exclude.map(str::to_string))
+    }
+
+    #[test]
+    fn web_syntax_becomes_quoted_fts5_terms() {
+        assert_eq!(
+            fts5_query("postgres \"incremental backups\""),
+            query(Some(r#""postgres" AND "incremental backups""#), None)
+        );
+        assert_eq!(
+            fts5_query("rust or go -java -\"c sharp\""),
+            query(Some(r#""rust" OR "go""#), Some(r#""java" OR "c sharp""#))
+        );
+        assert_eq!(fts5_query("-java"), query(None, Some(r#""java""#)));
+        // a leading or, and one quoted, are terms of their own
+        assert_eq!(fts5_query("or rust"), query(Some(r#""rust""#), None));
+        assert_eq!(
+            fts5_query("rust \"or\" go"),
+            query(Some(r#""rust" AND "or" AND "go""#), None)
+        );
+    }
+
+    #[test]
+    fn fts5_syntax_is_taken_as_text() {
+        assert_eq!(
+            fts5_query("NEAR(a b) x*"),
+            query(Some(r#""NEAR(a" AND "b)" AND "x*""#), None)
+        );
+        assert_eq!(fts5_query("say\"what"), query(Some(r#""say""what""#), None));
+        // an unclosed quote runs to the end
+        assert_eq!(fts5_query("\"rust go"), query(Some(r#""rust go""#), None));
+        assert_eq!(fts5_query("\" * - ()"), query(None, None));
+    }
+}
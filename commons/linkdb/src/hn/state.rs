@@ -0,0 +1,38 @@
+use crate::db::{on_pool, on_tx};
+use sqlx::types::chrono;
+
+/// Highest item id ingested by the job `name`, `None` before its first run.
+#[tracing::instrument(name = "linkdb::hn::state::last-item-id", skip_all, err)]
+pub async fn last_item_id(db: &crate::Db, name: &str) -> Result<Option<i64>, sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query_scalar(
+        "SELECT last_item_id FROM linknova_hn_ingest_state WHERE name = $1"
+    )
+    .bind(name)
+    .fetch_optional(pool)
+    .await)
+}
+
+#[tracing::instrument(name = "linkdb::hn::state::set-last-item-id", skip_all, err)]
+pub async fn set_last_item_id(
+    tx: &mut crate::Tx<'_>,
+    name: &str,
+    last_item_id: i64,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_hn_ingest_state (name, last_item_id, updated_on)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (name) DO UPDATE SET
+            last_item_id = EXCLUDED.last_item_id,
+            updated_on = EXCLUDED.updated_on
+    "#;
+
+    on_tx!(tx, |tx| sqlx::query(query)
+        .bind(name)
+        .bind(last_item_id)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(())
+}
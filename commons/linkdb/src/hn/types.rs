@@ -0,0 +1,82 @@
+use sqlx::types::chrono;
+
+#[derive(Debug)]
+pub struct HnItemI {
+    pub id: i64,
+    pub item_type: String,
+    pub by: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub text: Option<String>,
+    pub parent: Option<i64>,
+    pub score: i32,
+    pub descendants: i32,
+    pub deleted: bool,
+    pub dead: bool,
+    pub posted_on: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct HnItemRow {
+    pub id: i64,
+    pub item_type: String,
+    pub by: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub text: Option<String>,
+    pub parent: Option<i64>,
+    pub score: i32,
+    pub descendants: i32,
+    pub deleted: bool,
+    pub dead: bool,
+    pub posted_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct HnScoreRow {
+    pub score: i32,
+    pub descendants: i32,
+    pub taken_on: chrono::DateTime<chrono::Utc>,
+}
+
+/// Item which reached a score threshold, with the first snapshot at or above it.
+#[derive(Debug, sqlx::FromRow)]
+pub struct HnCrossedRow {
+    #[sqlx(flatten)]
+    pub item: HnItemRow,
+    pub crossed_on: chrono::DateTime<chrono::Utc>,
+}
+
+/// Points gained between the first and the last snapshot of a time window.
+#[derive(Debug, sqlx::FromRow)]
+pub struct HnVelocityRow {
+    #[sqlx(flatten)]
+    pub item: HnItemRow,
+    pub gained: i32,
+    pub points_per_hour: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct HnSearchQ {
+    /// web search syntax: quoted phrases, `or`, `-excluded`
+    pub text: Option<String>,
+    pub item_type: Option<String>,
+    pub by: Option<String>,
+    /// on `posted_on`, `from` inclusive and `to` exclusive
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub min_score: Option<i32>,
+    pub offset: i64,
+    pub limit: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct HnSearchRow {
+    #[sqlx(flatten)]
+    pub item: HnItemRow,
+    pub rank: f32,
+    /// matches of the whole search, not only of this page
+    pub total: i64,
+}
@@ -0,0 +1,8 @@
+pub mod query;
+pub mod types;
+
+pub use types::{HnWatchI, HnWatchRow};
+
+pub use query::{
+    clear_category, delete, get_by_username, list_all, list_due, update_poll_state, upsert,
+};
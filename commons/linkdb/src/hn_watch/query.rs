@@ -0,0 +1,149 @@
+use crate::db::{on_pool, on_tx};
+use crate::hn_watch::{HnWatchI, HnWatchRow};
+use sqlx::types::chrono;
+
+const WATCH_SELECT: &str = r#"
+    SELECT
+        w.id,
+        w.user_id,
+        w.username,
+        w.category_id,
+        cat.name AS category,
+        w.last_seen_id,
+        w.last_polled_on,
+        w.last_error,
+        w.created_on,
+        w.updated_on
+    FROM linknova_hn_watch as w
+    LEFT JOIN linknova_category as cat ON w.category_id = cat.id
+"#;
+
+/// Watching the same username twice only changes its auto-save category.
+#[tracing::instrument(name = "linkdb::hn-watch::upsert", skip_all, err)]
+pub async fn upsert(
+    tx: &mut crate::Tx<'_>,
+    row: HnWatchI,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<i64, sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_hn_watch(
+            user_id,
+            username,
+            category_id,
+            created_on,
+            updated_on
+        ) VALUES($1, $2, $3, $4, $4)
+        ON CONFLICT (user_id, username)
+        DO UPDATE SET
+            category_id = EXCLUDED.category_id,
+            updated_on = EXCLUDED.updated_on
+        RETURNING id
+    "#;
+
+    let (id,): (i64,) = on_tx!(tx, |tx| sqlx::query_as(query)
+        .bind(row.user_id)
+        .bind(row.username)
+        .bind(row.category_id)
+        .bind(now)
+        .fetch_one(&mut **tx)
+        .await)?;
+
+    Ok(id)
+}
+
+#[tracing::instrument(name = "linkdb::hn-watch::get-by-username", skip_all, err)]
+pub async fn get_by_username(
+    db: &crate::Db,
+    user_id: &str,
+    username: &str,
+) -> Result<Option<HnWatchRow>, sqlx::Error> {
+    let query = format!("{WATCH_SELECT} WHERE w.user_id = $1 AND w.username = $2");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(username)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::hn-watch::list-all", skip_all, err)]
+pub async fn list_all(db: &crate::Db, user_id: &str) -> Result<Vec<HnWatchRow>, sqlx::Error> {
+    let query = format!("{WATCH_SELECT} WHERE w.user_id = $1 ORDER BY w.username");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Watches, across all users, which were never polled or polled before
+/// `polled_before`, grouped by username.
+#[tracing::instrument(name = "linkdb::hn-watch::list-due", skip_all, err)]
+pub async fn list_due(
+    db: &crate::Db,
+    polled_before: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<HnWatchRow>, sqlx::Error> {
+    let query = format!(
+        "{WATCH_SELECT} WHERE w.last_polled_on IS NULL OR w.last_polled_on < $1 ORDER BY w.username, w.id"
+    );
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(polled_before)
+        .fetch_all(pool)
+        .await)
+}
+
+/// `last_seen_id` only ever moves forward, `None` keeps the current one.
+#[tracing::instrument(name = "linkdb::hn-watch::update-poll-state", skip_all, err)]
+pub async fn update_poll_state(
+    db: &crate::Db,
+    id: i64,
+    last_seen_id: Option<i64>,
+    last_error: Option<String>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_hn_watch SET
+            last_seen_id = CASE
+                WHEN last_seen_id IS NULL OR last_seen_id < $2 THEN $2
+                ELSE last_seen_id
+            END,
+            last_error = $3,
+            last_polled_on = $4
+        WHERE id = $1
+    "#;
+
+    on_pool!(db, |pool| sqlx::query(query)
+        .bind(id)
+        .bind(last_seen_id)
+        .bind(last_error)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+/// Returns whether a watch was removed.
+#[tracing::instrument(name = "linkdb::hn-watch::delete", skip_all, err)]
+pub async fn delete(db: &crate::Db, user_id: &str, username: &str) -> Result<bool, sqlx::Error> {
+    let rows = on_pool!(db, |pool| sqlx::query(
+        "DELETE FROM linknova_hn_watch WHERE user_id = $1 AND username = $2"
+    )
+    .bind(user_id)
+    .bind(username)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(rows > 0)
+}
+
+/// Stops auto-saving into a category which is about to be deleted.
+#[tracing::instrument(name = "linkdb::hn-watch::clear-category", skip_all, err)]
+pub async fn clear_category(db: &crate::Db, category_id: i64) -> Result<(), sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query(
+        "UPDATE linknova_hn_watch SET category_id = NULL WHERE category_id = $1"
+    )
+    .bind(category_id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use sqlx::types::chrono;
+
+#[derive(Debug)]
+pub struct HnWatchI {
+    pub user_id: String,
+    pub username: String,
+    pub category_id: Option<i64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct HnWatchRow {
+    pub id: i64,
+    pub user_id: String,
+    pub username: String,
+    pub category_id: Option<i64>,
+    pub category: Option<String>,
+    pub last_seen_id: Option<i64>,
+    pub last_polled_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
@@ -1,7 +1,18 @@
+pub mod alert;
 pub mod bookmark;
 pub mod category;
+pub mod db;
+pub mod feed;
+pub mod hn;
+pub mod hn_watch;
+pub mod member;
+pub mod migrate;
+pub mod notification;
+pub mod repo;
+pub mod share;
 pub mod topic;
 pub mod topic_cat_map;
 
 pub use category::types::{CatRow, CatRowI, CategoryRowView};
+pub use db::{Backend, Db, PoolConfig, PoolStats, Tx};
 pub use topic::types::{TopicRow, TopicRowI, TopicRowView};
@@ -0,0 +1,35 @@
+pub mod query;
+pub mod types;
+
+pub use types::{InvitationRow, MemberI, MemberRow};
+
+pub use query::{
+    accept, category_roles, delete, delete_by_category_id, delete_by_topic_id, get_by_category,
+    get_by_id, get_by_topic, insert, list_by_category, list_by_topic, list_invitations, topic_role,
+    update_role,
+};
+
+/// Ids of the categories shared with the user bound to `$1`: through an accepted
+/// category membership, or an accepted topic membership for the owner's
+/// categories of that topic.
+pub const SHARED_CATEGORY_IDS: &str = r#"
+    SELECT m.category_id FROM linknova_membership as m
+    WHERE m.user_id = $1 AND m.accepted_on IS NOT NULL AND m.category_id IS NOT NULL
+    UNION
+    SELECT tcm.category_id FROM linknova_membership as m
+    JOIN linknova_topic as t ON t.id = m.topic_id
+    JOIN linknova_topic_category_map as tcm ON tcm.topic_id = t.id
+    JOIN linknova_category as c ON c.id = tcm.category_id AND c.user_id = t.user_id
+    WHERE m.user_id = $1 AND m.accepted_on IS NOT NULL
+"#;
+
+/// Ids of the topics shared with the user bound to `$1`.
+pub const SHARED_TOPIC_IDS: &str = r#"
+    SELECT m.topic_id FROM linknova_membership as m
+    WHERE m.user_id = $1 AND m.accepted_on IS NOT NULL AND m.topic_id IS NOT NULL
+"#;
+
+/// Category name as seen by the user bound to `$1`, other users' categories
+/// are `@owner/name`.
+pub const CATEGORY_LABEL: &str =
+    "CASE WHEN cat.user_id = $1 THEN cat.name ELSE '@' || cat.user_id || '/' || cat.name END";
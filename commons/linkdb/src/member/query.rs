@@ -0,0 +1,265 @@
+use crate::db::on_pool;
+use crate::member::types::{InvitationRow, MemberI, MemberRow};
+use sqlx::types::chrono;
+
+const MEMBER_COLUMNS: &str = r#"
+    id,
+    category_id,
+    topic_id,
+    user_id,
+    role,
+    invited_by,
+    accepted_on,
+    created_on,
+    updated_on
+"#;
+
+#[tracing::instrument(name = "linkdb::member::insert", skip_all, err)]
+pub async fn insert(
+    db: &crate::Db,
+    row: MemberI,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<i64, sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_membership(
+            category_id,
+            topic_id,
+            user_id,
+            role,
+            invited_by,
+            created_on,
+            updated_on
+        ) VALUES($1, $2, $3, $4, $5, $6, $6)
+        RETURNING id
+    "#;
+
+    let (id,): (i64,) = on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(row.category_id)
+        .bind(row.topic_id)
+        .bind(row.user_id)
+        .bind(row.role)
+        .bind(row.invited_by)
+        .bind(now)
+        .fetch_one(pool)
+        .await)?;
+    Ok(id)
+}
+
+#[tracing::instrument(name = "linkdb::member::get-by-id", skip_all, err)]
+pub async fn get_by_id(db: &crate::Db, id: i64) -> Result<Option<MemberRow>, sqlx::Error> {
+    let query = format!("SELECT {MEMBER_COLUMNS} FROM linknova_membership WHERE id = $1");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(id)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::member::get-by-category", skip_all, err)]
+pub async fn get_by_category(
+    db: &crate::Db,
+    category_id: i64,
+    user_id: &str,
+) -> Result<Option<MemberRow>, sqlx::Error> {
+    let query = format!(
+        "SELECT {MEMBER_COLUMNS} FROM linknova_membership WHERE category_id = $1 AND user_id = $2"
+    );
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(category_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::member::get-by-topic", skip_all, err)]
+pub async fn get_by_topic(
+    db: &crate::Db,
+    topic_id: i64,
+    user_id: &str,
+) -> Result<Option<MemberRow>, sqlx::Error> {
+    let query = format!(
+        "SELECT {MEMBER_COLUMNS} FROM linknova_membership WHERE topic_id = $1 AND user_id = $2"
+    );
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(topic_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::member::list-by-category", skip_all, err)]
+pub async fn list_by_category(
+    db: &crate::Db,
+    category_id: i64,
+) -> Result<Vec<MemberRow>, sqlx::Error> {
+    let query = format!(
+        "SELECT {MEMBER_COLUMNS} FROM linknova_membership WHERE category_id = $1 ORDER BY created_on"
+    );
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(category_id)
+        .fetch_all(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::member::list-by-topic", skip_all, err)]
+pub async fn list_by_topic(db: &crate::Db, topic_id: i64) -> Result<Vec<MemberRow>, sqlx::Error> {
+    let query = format!(
+        "SELECT {MEMBER_COLUMNS} FROM linknova_membership WHERE topic_id = $1 ORDER BY created_on"
+    );
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(topic_id)
+        .fetch_all(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::member::update-role", skip_all, err)]
+pub async fn update_role(
+    db: &crate::Db,
+    id: i64,
+    role: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query(
+        "UPDATE linknova_membership SET role = $2, updated_on = $3 WHERE id = $1"
+    )
+    .bind(id)
+    .bind(role)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+/// Accepts a pending invitation of the user, `false` if there is none with that id.
+#[tracing::instrument(name = "linkdb::member::accept", skip_all, err)]
+pub async fn accept(
+    db: &crate::Db,
+    id: i64,
+    user_id: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_membership SET
+            accepted_on = $3,
+            updated_on = $3
+        WHERE id = $1 AND user_id = $2 AND accepted_on IS NULL
+    "#;
+
+    let rows = on_pool!(db, |pool| sqlx::query(query)
+        .bind(id)
+        .bind(user_id)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(rows == 1)
+}
+
+#[tracing::instrument(name = "linkdb::member::delete", skip_all, err)]
+pub async fn delete(db: &crate::Db, id: i64) -> Result<bool, sqlx::Error> {
+    let rows = on_pool!(db, |pool| sqlx::query(
+        "DELETE FROM linknova_membership WHERE id = $1"
+    )
+    .bind(id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(rows == 1)
+}
+
+#[tracing::instrument(name = "linkdb::member::delete-by-category-id", skip_all, err)]
+pub async fn delete_by_category_id(db: &crate::Db, category_id: i64) -> Result<(), sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query(
+        "DELETE FROM linknova_membership WHERE category_id = $1"
+    )
+    .bind(category_id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "linkdb::member::delete-by-topic-id", skip_all, err)]
+pub async fn delete_by_topic_id(db: &crate::Db, topic_id: i64) -> Result<(), sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query(
+        "DELETE FROM linknova_membership WHERE topic_id = $1"
+    )
+    .bind(topic_id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "linkdb::member::list-invitations", skip_all, err)]
+pub async fn list_invitations(
+    db: &crate::Db,
+    user_id: &str,
+) -> Result<Vec<InvitationRow>, sqlx::Error> {
+    let query = r#"
+        SELECT
+            m.id,
+            cat.name as category_name,
+            topic.name as topic_name,
+            COALESCE(cat.user_id, topic.user_id) as owner,
+            m.role,
+            m.invited_by,
+            m.created_on
+        FROM linknova_membership as m
+        LEFT JOIN linknova_category as cat ON cat.id = m.category_id
+        LEFT JOIN linknova_topic as topic ON topic.id = m.topic_id
+        WHERE m.user_id = $1 AND m.accepted_on IS NULL
+        ORDER BY m.created_on DESC
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Roles of the user's accepted memberships which apply to the category: its own
+/// and those of the topics it belongs to.
+#[tracing::instrument(name = "linkdb::member::category-roles", skip_all, err)]
+pub async fn category_roles(
+    db: &crate::Db,
+    category_id: i64,
+    user_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    let query = r#"
+        SELECT m.role FROM linknova_membership as m
+        WHERE m.category_id = $1 AND m.user_id = $2 AND m.accepted_on IS NOT NULL
+        UNION ALL
+        SELECT m.role FROM linknova_membership as m
+        JOIN linknova_topic as t ON t.id = m.topic_id
+        JOIN linknova_topic_category_map as tcm ON tcm.topic_id = t.id
+        JOIN linknova_category as c ON c.id = tcm.category_id AND c.user_id = t.user_id
+        WHERE tcm.category_id = $1 AND m.user_id = $2 AND m.accepted_on IS NOT NULL
+    "#;
+
+    let rows: Vec<(String,)> = on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(category_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)?;
+    Ok(rows.into_iter().map(|(r,)| r).collect())
+}
+
+#[tracing::instrument(name = "linkdb::member::topic-role", skip_all, err)]
+pub async fn topic_role(
+    db: &crate::Db,
+    topic_id: i64,
+    user_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let query = r#"
+        SELECT role FROM linknova_membership
+        WHERE topic_id = $1 AND user_id = $2 AND accepted_on IS NOT NULL
+    "#;
+
+    let row: Option<(String,)> = on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(topic_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)?;
+    Ok(row.map(|(r,)| r))
+}
@@ -0,0 +1,35 @@
+use sqlx::types::chrono;
+
+#[derive(Debug, Clone)]
+pub struct MemberI {
+    pub category_id: Option<i64>,
+    pub topic_id: Option<i64>,
+    pub user_id: String,
+    pub role: String,
+    pub invited_by: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct MemberRow {
+    pub id: i64,
+    pub category_id: Option<i64>,
+    pub topic_id: Option<i64>,
+    pub user_id: String,
+    pub role: String,
+    pub invited_by: String,
+    pub accepted_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
+
+/// Pending membership of a user, with the name and owner of what it is for.
+#[derive(Debug, sqlx::FromRow)]
+pub struct InvitationRow {
+    pub id: i64,
+    pub category_name: Option<String>,
+    pub topic_name: Option<String>,
+    pub owner: String,
+    pub role: String,
+    pub invited_by: String,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,213 @@
+//! Schema of the `linknova_*` tables. Migrations are embedded from
+//! `commons/linkdb/migrations/<backend>` and recorded in `_sqlx_migrations`.
+//! Both backends have the same versions, a migration is added to each.
+
+use crate::Db;
+use crate::db::on_pool;
+use sqlx::migrate::{Migrate, MigrateError, Migration, Migrator};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+#[cfg(feature = "sqlite")]
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// The initial migration holds the schema the Django project had after this one.
+const DJANGO_LAST_MIGRATION: &str = "0010_alertrule";
+const INITIAL_VERSION: i64 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SchemaError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("MigrateError: {0}")]
+    Migrate(#[from] MigrateError),
+    #[error("PendingMigrationsError: {0:?} not applied yet, run `service migrate`")]
+    Pending(Vec<i64>),
+    #[error("UnknownMigrationsError: {0:?} applied by a newer build")]
+    Unknown(Vec<i64>),
+    #[error("DirtyMigrationError: {0} failed part way and has to be fixed by hand")]
+    Dirty(i64),
+    #[error("ModifiedMigrationError: {0} was changed after it was applied")]
+    Modified(i64),
+    #[error(
+        "DjangoSchemaError: apply the Django migrations up to `{0}` before adopting the schema"
+    )]
+    DjangoBehind(&'static str),
+}
+
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct Applied {
+    version: i64,
+    checksum: Vec<u8>,
+    success: bool,
+}
+
+fn migrator(db: &Db) -> &'static Migrator {
+    match db {
+        Db::Postgres(_) => &MIGRATOR,
+        #[cfg(feature = "sqlite")]
+        Db::Sqlite(_) => &SQLITE_MIGRATOR,
+    }
+}
+
+fn up_migrations(db: &Db) -> impl Iterator<Item = &'static Migration> {
+    migrator(db)
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+}
+
+/// Newest version this build knows.
+pub fn latest_version(db: &Db) -> i64 {
+    up_migrations(db).map(|m| m.version).max().unwrap_or(0)
+}
+
+async fn table_exists(db: &Db, name: &str) -> Result<bool, sqlx::Error> {
+    match db {
+        Db::Postgres(pool) => {
+            sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+                .bind(name)
+                .fetch_one(pool)
+                .await
+        }
+        #[cfg(feature = "sqlite")]
+        Db::Sqlite(pool) => {
+            sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = $1)",
+            )
+            .bind(name)
+            .fetch_one(pool)
+            .await
+        }
+    }
+}
+
+async fn applied(db: &Db) -> Result<Vec<Applied>, sqlx::Error> {
+    if !table_exists(db, "_sqlx_migrations").await? {
+        return Ok(Vec::new());
+    }
+    on_pool!(db, |pool| sqlx::query_as(
+        "SELECT version, checksum, success FROM _sqlx_migrations ORDER BY version"
+    )
+    .fetch_all(pool)
+    .await)
+}
+
+/// Every migration of this build, and whether it is applied.
+#[tracing::instrument(name = "linkdb::migrate::status", skip_all, err)]
+pub async fn status(db: &Db) -> Result<Vec<MigrationStatus>, sqlx::Error> {
+    let applied = applied(db).await?;
+    Ok(up_migrations(db)
+        .map(|m| MigrationStatus {
+            version: m.version,
+            description: m.description.to_string(),
+            applied: applied.iter().any(|a| a.version == m.version && a.success),
+        })
+        .collect())
+}
+
+/// Applies the pending migrations, returns their versions.
+#[tracing::instrument(name = "linkdb::migrate::up", skip_all, err)]
+pub async fn up(db: &Db) -> Result<Vec<i64>, SchemaError> {
+    #[allow(irrefutable_let_patterns)]
+    if let Db::Postgres(pool) = db {
+        adopt_django_schema(db, pool).await?;
+    }
+    let pending: Vec<i64> = status(db)
+        .await?
+        .into_iter()
+        .filter(|m| !m.applied)
+        .map(|m| m.version)
+        .collect();
+    on_pool!(db, |pool| migrator(db).run(pool).await)?;
+    Ok(pending)
+}
+
+/// Reverts the last `steps` applied migrations, newest first, returns their versions.
+#[tracing::instrument(name = "linkdb::migrate::down", skip_all, err)]
+pub async fn down(db: &Db, steps: usize) -> Result<Vec<i64>, SchemaError> {
+    let applied: Vec<i64> = applied(db).await?.iter().map(|a| a.version).collect();
+    let reverted: Vec<i64> = applied.iter().rev().take(steps).copied().collect();
+    let target = applied.iter().rev().nth(steps).copied().unwrap_or(0);
+    on_pool!(db, |pool| migrator(db).undo(pool, target).await)?;
+    Ok(reverted)
+}
+
+/// `Ok` only when the database is at exactly the version of this build.
+#[tracing::instrument(name = "linkdb::migrate::check", skip_all, err)]
+pub async fn check(db: &Db) -> Result<(), SchemaError> {
+    let applied = applied(db).await?;
+    if let Some(a) = applied.iter().find(|a| !a.success) {
+        return Err(SchemaError::Dirty(a.version));
+    }
+    let unknown: Vec<i64> = applied
+        .iter()
+        .filter(|a| !up_migrations(db).any(|m| m.version == a.version))
+        .map(|a| a.version)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(SchemaError::Unknown(unknown));
+    }
+    if let Some(a) = applied
+        .iter()
+        .find(|a| up_migrations(db).any(|m| m.version == a.version && *m.checksum != *a.checksum))
+    {
+        return Err(SchemaError::Modified(a.version));
+    }
+    let pending: Vec<i64> = up_migrations(db)
+        .filter(|m| !applied.iter().any(|a| a.version == m.version))
+        .map(|m| m.version)
+        .collect();
+    if !pending.is_empty() {
+        return Err(SchemaError::Pending(pending));
+    }
+    Ok(())
+}
+
+/// A database the Django project created already has the initial schema, the
+/// initial migration is recorded as applied instead of being run. Django only
+/// ever ran on Postgres.
+async fn adopt_django_schema(db: &Db, pool: &sqlx::PgPool) -> Result<(), SchemaError> {
+    if table_exists(db, "_sqlx_migrations").await? || !table_exists(db, "linknova_bookmark").await?
+    {
+        return Ok(());
+    }
+    if table_exists(db, "django_migrations").await? {
+        let done: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM django_migrations WHERE app = 'linknova' AND name = $1)",
+        )
+        .bind(DJANGO_LAST_MIGRATION)
+        .fetch_one(pool)
+        .await?;
+        if !done {
+            return Err(SchemaError::DjangoBehind(DJANGO_LAST_MIGRATION));
+        }
+    }
+
+    let initial = up_migrations(db)
+        .find(|m| m.version == INITIAL_VERSION)
+        .expect("the initial migration is embedded");
+    let mut conn = pool.acquire().await?;
+    conn.lock().await?;
+    conn.ensure_migrations_table().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+        VALUES ($1, $2, TRUE, $3, 0)
+        ON CONFLICT (version) DO NOTHING
+        "#,
+    )
+    .bind(initial.version)
+    .bind(initial.description.as_ref())
+    .bind(initial.checksum.as_ref())
+    .execute(&mut *conn)
+    .await?;
+    conn.unlock().await?;
+    tracing::info!("recorded the schema created by Django as migration {INITIAL_VERSION}");
+    Ok(())
+}
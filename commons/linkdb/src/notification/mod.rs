@@ -0,0 +1,6 @@
+pub mod query;
+pub mod types;
+
+pub use types::{NotificationI, NotificationRow};
+
+pub use query::{count_unread, delete, exists, insert, list, mark_all_read, mark_read};
@@ -0,0 +1,160 @@
+use crate::db::{on_pool, on_tx};
+use crate::notification::{NotificationI, NotificationRow};
+use sqlx::types::chrono;
+
+/// `None` when the user was already notified of the same HN item for the same kind.
+#[tracing::instrument(name = "linkdb::notification::insert", skip_all, err)]
+pub async fn insert(
+    tx: &mut crate::Tx<'_>,
+    row: NotificationI,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<i64>, sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_notification(
+            user_id,
+            kind,
+            title,
+            url,
+            body,
+            hn_id,
+            bookmark_id,
+            created_on
+        ) VALUES($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id, kind, hn_id) DO NOTHING
+        RETURNING id
+    "#;
+
+    on_tx!(tx, |tx| sqlx::query_scalar(query)
+        .bind(row.user_id)
+        .bind(row.kind)
+        .bind(row.title)
+        .bind(row.url)
+        .bind(row.body)
+        .bind(row.hn_id)
+        .bind(row.bookmark_id)
+        .bind(now)
+        .fetch_optional(&mut **tx)
+        .await)
+}
+
+/// Newest first.
+#[tracing::instrument(name = "linkdb::notification::list", skip_all, err)]
+pub async fn list(
+    db: &crate::Db,
+    user_id: &str,
+    unread_only: bool,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<NotificationRow>, sqlx::Error> {
+    let query = r#"
+        SELECT
+            id,
+            user_id,
+            kind,
+            title,
+            url,
+            body,
+            hn_id,
+            bookmark_id,
+            read_on,
+            created_on
+        FROM linknova_notification
+        WHERE user_id = $1 AND (NOT $2 OR read_on IS NULL)
+        ORDER BY created_on DESC, id DESC
+        LIMIT $3 OFFSET $4
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::notification::count-unread", skip_all, err)]
+pub async fn count_unread(db: &crate::Db, user_id: &str) -> Result<i64, sqlx::Error> {
+    on_pool!(db, |pool| sqlx::query_scalar(
+        "SELECT count(*) FROM linknova_notification WHERE user_id = $1 AND read_on IS NULL",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await)
+}
+
+/// Returns whether the notification exists, reading it twice keeps the first time.
+#[tracing::instrument(name = "linkdb::notification::mark-read", skip_all, err)]
+pub async fn mark_read(
+    db: &crate::Db,
+    user_id: &str,
+    id: i64,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_notification SET read_on = COALESCE(read_on, $3)
+        WHERE id = $1 AND user_id = $2
+    "#;
+    let rows = on_pool!(db, |pool| sqlx::query(query)
+        .bind(id)
+        .bind(user_id)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(rows > 0)
+}
+
+/// Returns the number of notifications marked.
+#[tracing::instrument(name = "linkdb::notification::mark-all-read", skip_all, err)]
+pub async fn mark_all_read(
+    db: &crate::Db,
+    user_id: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<u64, sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_notification SET read_on = $2
+        WHERE user_id = $1 AND read_on IS NULL
+    "#;
+    let rows = on_pool!(db, |pool| sqlx::query(query)
+        .bind(user_id)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(rows)
+}
+
+/// Returns whether a notification was removed.
+#[tracing::instrument(name = "linkdb::notification::delete", skip_all, err)]
+pub async fn delete(db: &crate::Db, user_id: &str, id: i64) -> Result<bool, sqlx::Error> {
+    let rows = on_pool!(db, |pool| sqlx::query(
+        "DELETE FROM linknova_notification WHERE id = $1 AND user_id = $2"
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected()))?;
+    Ok(rows > 0)
+}
+
+#[tracing::instrument(name = "linkdb::notification::exists", skip_all, err)]
+pub async fn exists(
+    db: &crate::Db,
+    user_id: &str,
+    kind: &str,
+    hn_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let query = r#"
+        SELECT EXISTS (
+            SELECT 1 FROM linknova_notification WHERE user_id = $1 AND kind = $2 AND hn_id = $3
+        )
+    "#;
+    on_pool!(db, |pool| sqlx::query_scalar(query)
+        .bind(user_id)
+        .bind(kind)
+        .bind(hn_id)
+        .fetch_one(pool)
+        .await)
+}
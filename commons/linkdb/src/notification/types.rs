@@ -0,0 +1,26 @@
+use sqlx::types::chrono;
+
+#[derive(Debug)]
+pub struct NotificationI {
+    pub user_id: String,
+    pub kind: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub body: Option<String>,
+    pub hn_id: Option<i64>,
+    pub bookmark_id: Option<i64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct NotificationRow {
+    pub id: i64,
+    pub user_id: String,
+    pub kind: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub body: Option<String>,
+    pub hn_id: Option<i64>,
+    pub bookmark_id: Option<i64>,
+    pub read_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+}
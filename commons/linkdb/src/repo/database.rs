@@ -0,0 +1,274 @@
+use super::{BookmarkRepo, CategoryRepo, RepoFuture, TopicRepo};
+use crate::bookmark::{BookmarkI, BookmarkRow, BookmarkUpdate};
+use crate::topic::{TopicInfo, TopicRowI};
+use crate::{CatRow, CatRowI, CategoryRowView, Db, TopicRow, TopicRowView};
+use sqlx::types::chrono;
+
+/// The repositories over the linkdb queries, on either backend.
+#[derive(Clone)]
+pub struct Database {
+    db: Db,
+}
+
+impl Database {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+}
+
+impl BookmarkRepo for Database {
+    fn create<'a>(
+        &'a self,
+        row: BookmarkI,
+        categories: Vec<CatRowI>,
+        category_ids: Vec<i64>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'a, i64> {
+        Box::pin(async move {
+            let mut tx = self.db.begin().await?;
+            let mut ids = crate::category::upsert(&mut tx, categories, now).await?;
+            ids.extend(category_ids);
+            let id = crate::bookmark::insert(&mut tx, row).await?;
+            crate::bookmark::cat_map::add_categories(&mut tx, id, &ids).await?;
+            tx.commit().await?;
+            Ok(id)
+        })
+    }
+
+    fn get_visible<'a>(&'a self, user_id: &'a str, id: i64) -> RepoFuture<'a, Option<BookmarkRow>> {
+        Box::pin(crate::bookmark::get_visible(&self.db, user_id, id))
+    }
+
+    fn filter<'a>(
+        &'a self,
+        user_id: &'a str,
+        categories: Option<&'a [String]>,
+        status: &'a Option<String>,
+    ) -> RepoFuture<'a, Vec<BookmarkRow>> {
+        Box::pin(crate::bookmark::filter(
+            &self.db, user_id, categories, status,
+        ))
+    }
+
+    fn filter_by_topic<'a>(
+        &'a self,
+        user_id: &'a str,
+        topic_id: i64,
+        categories: Option<&'a [String]>,
+        status: &'a Option<String>,
+    ) -> RepoFuture<'a, Vec<BookmarkRow>> {
+        Box::pin(crate::bookmark::filter_by_topic(
+            &self.db, user_id, topic_id, categories, status,
+        ))
+    }
+
+    fn update<'a>(
+        &'a self,
+        user_id: &'a str,
+        id: i64,
+        fields: BookmarkUpdate,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'a, bool> {
+        Box::pin(crate::bookmark::update(&self.db, user_id, id, fields, now))
+    }
+
+    fn delete<'a>(&'a self, user_id: &'a str, id: i64) -> RepoFuture<'a, bool> {
+        Box::pin(async move {
+            let mut tx = self.db.begin().await?;
+            if !crate::bookmark::query::delete_by_id(&mut tx, user_id, id).await? {
+                tx.rollback().await?;
+                return Ok(false);
+            }
+            crate::bookmark::cat_map::delete_by_bookmark_id(&mut tx, id).await?;
+            crate::bookmark::hn_meta::delete_by_bookmark_id(&mut tx, id).await?;
+            tx.commit().await?;
+            Ok(true)
+        })
+    }
+
+    fn add_categories<'a>(
+        &'a self,
+        id: i64,
+        categories: Vec<CatRowI>,
+        category_ids: Vec<i64>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'a, ()> {
+        Box::pin(async move {
+            let mut tx = self.db.begin().await?;
+            let mut ids = crate::category::upsert(&mut tx, categories, now).await?;
+            ids.extend(category_ids);
+            crate::bookmark::cat_map::add_categories(&mut tx, id, &ids).await?;
+            tx.commit().await
+        })
+    }
+
+    fn remove_categories<'a>(&'a self, id: i64, category_ids: &'a [i64]) -> RepoFuture<'a, ()> {
+        Box::pin(crate::bookmark::cat_map::remove_categories(
+            &self.db,
+            id,
+            category_ids,
+        ))
+    }
+}
+
+impl CategoryRepo for Database {
+    fn insert(&self, row: CatRowI) -> RepoFuture<'_, (i64, String)> {
+        Box::pin(crate::category::insert(&self.db, row))
+    }
+
+    fn get_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<CatRow>> {
+        Box::pin(crate::category::get_by_name(&self.db, user_id, name))
+    }
+
+    fn get_id_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<i64>> {
+        Box::pin(crate::category::get_id_by_name(&self.db, user_id, name))
+    }
+
+    fn list_all<'a>(&'a self, user_id: &'a str) -> RepoFuture<'a, Vec<CategoryRowView>> {
+        Box::pin(crate::category::list_all(&self.db, user_id))
+    }
+
+    fn list_by_topic_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        topic_names: &'a [String],
+    ) -> RepoFuture<'a, Vec<CategoryRowView>> {
+        Box::pin(crate::category::list_by_topic_name(
+            &self.db,
+            user_id,
+            topic_names,
+        ))
+    }
+
+    fn update<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+        display_name: Option<String>,
+        about: Option<String>,
+        description: Option<String>,
+        public: Option<bool>,
+        priority: Option<i32>,
+    ) -> RepoFuture<'a, ()> {
+        Box::pin(crate::category::update(
+            &self.db,
+            user_id,
+            name,
+            display_name,
+            about,
+            description,
+            public,
+            priority,
+        ))
+    }
+
+    fn delete<'a>(&'a self, user_id: &'a str, name: &'a str) -> RepoFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(id) = crate::category::get_id_by_name(&self.db, user_id, name).await? {
+                crate::member::delete_by_category_id(&self.db, id).await?;
+                crate::hn_watch::clear_category(&self.db, id).await?;
+                crate::alert::clear_category(&self.db, id).await?;
+            }
+            crate::category::delete(&self.db, user_id, name).await
+        })
+    }
+
+    fn roles<'a>(&'a self, category_id: i64, user_id: &'a str) -> RepoFuture<'a, Vec<String>> {
+        Box::pin(crate::member::category_roles(
+            &self.db,
+            category_id,
+            user_id,
+        ))
+    }
+}
+
+impl TopicRepo for Database {
+    fn insert(&self, row: TopicRowI) -> RepoFuture<'_, (i64, String)> {
+        Box::pin(crate::topic::insert(&self.db, row))
+    }
+
+    fn get_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<TopicRow>> {
+        Box::pin(crate::topic::get_by_name(&self.db, user_id, name))
+    }
+
+    fn get_id_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<i64>> {
+        Box::pin(crate::topic::get_id_by_name(&self.db, user_id, name))
+    }
+
+    fn list_all<'a>(&'a self, user_id: &'a str) -> RepoFuture<'a, Vec<TopicRowView>> {
+        Box::pin(crate::topic::list_all(&self.db, user_id))
+    }
+
+    fn list_by_cat_name<'a>(
+        &'a self,
+        cat_names: &'a [String],
+        user_id: &'a str,
+    ) -> RepoFuture<'a, Vec<TopicInfo>> {
+        Box::pin(crate::topic::list_by_cat_name(&self.db, cat_names, user_id))
+    }
+
+    fn delete<'a>(&'a self, user_id: &'a str, name: &'a str) -> RepoFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(id) = crate::topic::get_id_by_name(&self.db, user_id, name).await? {
+                crate::member::delete_by_topic_id(&self.db, id).await?;
+            }
+            crate::topic::delete(&self.db, user_id, name).await
+        })
+    }
+
+    fn role<'a>(&'a self, topic_id: i64, user_id: &'a str) -> RepoFuture<'a, Option<String>> {
+        Box::pin(crate::member::topic_role(&self.db, topic_id, user_id))
+    }
+
+    fn connect(&self, topic_id: i64, category_id: i64) -> RepoFuture<'_, ()> {
+        Box::pin(crate::topic_cat_map::connect(
+            &self.db,
+            topic_id,
+            category_id,
+        ))
+    }
+
+    fn disconnect(&self, topic_id: i64, category_id: i64) -> RepoFuture<'_, ()> {
+        Box::pin(crate::topic_cat_map::delete(
+            &self.db,
+            topic_id,
+            category_id,
+        ))
+    }
+
+    fn add_categories(
+        &self,
+        topic_id: i64,
+        categories: Vec<CatRowI>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'_, ()> {
+        Box::pin(async move {
+            let mut tx = self.db.begin().await?;
+            let ids = crate::category::upsert(&mut tx, categories, now).await?;
+            crate::topic_cat_map::add_categories(&mut tx, topic_id, &ids).await?;
+            tx.commit().await
+        })
+    }
+
+    fn remove_categories<'a>(&'a self, topic_id: i64, names: &'a [String]) -> RepoFuture<'a, ()> {
+        Box::pin(crate::topic_cat_map::remove_categories(
+            &self.db, topic_id, names,
+        ))
+    }
+}
@@ -0,0 +1,845 @@
+//! The repositories kept in memory for tests. Constraint violations of the
+//! Postgres schema surface as the same kinds of `sqlx::Error::Database`, and
+//! listings follow the same visibility rules for shared categories and topics.
+
+use super::{BookmarkRepo, CategoryRepo, RepoFuture, TopicRepo};
+use crate::bookmark::{BookmarkI, BookmarkRow, BookmarkUpdate};
+use crate::member::MemberI;
+use crate::topic::{TopicInfo, TopicRowI};
+use crate::{CatRow, CatRowI, CategoryRowView, TopicRow, TopicRowView};
+use sqlx::error::{DatabaseError, ErrorKind};
+use sqlx::types::chrono;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+type Timestamp = chrono::DateTime<chrono::Utc>;
+
+#[derive(Default)]
+pub struct Memory {
+    state: Mutex<State>,
+}
+
+#[derive(Default, Clone)]
+struct State {
+    last_id: i64,
+    bookmarks: BTreeMap<i64, BookmarkI>,
+    categories: BTreeMap<i64, CatRow>,
+    topics: BTreeMap<i64, Topic>,
+    members: Vec<Member>,
+    /// (bookmark_id, category_id)
+    bookmark_cats: BTreeSet<(i64, i64)>,
+    /// (topic_id, category_id)
+    topic_cats: BTreeSet<(i64, i64)>,
+}
+
+#[derive(Clone)]
+struct Topic {
+    row: TopicRowI,
+    created_on: Timestamp,
+    updated_on: Timestamp,
+}
+
+#[derive(Clone)]
+struct Member {
+    row: MemberI,
+    accepted: bool,
+}
+
+#[derive(Debug)]
+struct MemoryDbError {
+    /// SQLSTATE
+    code: &'static str,
+    message: String,
+    /// named as Postgres names it
+    constraint: Option<String>,
+}
+
+impl std::fmt::Display for MemoryDbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for MemoryDbError {}
+
+impl DatabaseError for MemoryDbError {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn code(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self.code))
+    }
+
+    fn constraint(&self) -> Option<&str> {
+        self.constraint.as_deref()
+    }
+
+    fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> ErrorKind {
+        match self.code {
+            "23505" => ErrorKind::UniqueViolation,
+            "23503" => ErrorKind::ForeignKeyViolation,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+fn db_error(code: &'static str, message: String, constraint: Option<&str>) -> sqlx::Error {
+    sqlx::Error::Database(Box::new(MemoryDbError {
+        code,
+        message,
+        constraint: constraint.map(str::to_string),
+    }))
+}
+
+fn unique_violation(constraint: &str) -> sqlx::Error {
+    db_error(
+        "23505",
+        format!("duplicate key value violates unique constraint \"{constraint}\""),
+        Some(constraint),
+    )
+}
+
+fn foreign_key_violation(table: &str, constraint: &str) -> sqlx::Error {
+    db_error(
+        "23503",
+        format!(
+            "insert or update on table \"{table}\" violates foreign key constraint \"{constraint}\""
+        ),
+        Some(constraint),
+    )
+}
+
+fn still_referenced(table: &str, constraint: &str) -> sqlx::Error {
+    db_error(
+        "23503",
+        format!(
+            "update or delete on table \"{table}\" violates foreign key constraint \"{constraint}\""
+        ),
+        Some(constraint),
+    )
+}
+
+/// Category name as seen by `user_id`, the same as `member::CATEGORY_LABEL`.
+fn label(user_id: &str, cat: &CatRow) -> String {
+    if cat.user_id == user_id {
+        cat.name.clone()
+    } else {
+        format!("@{}/{}", cat.user_id, cat.name)
+    }
+}
+
+fn cat_view(c: &CatRow) -> CategoryRowView {
+    CategoryRowView {
+        id: c.id,
+        name: c.name.clone(),
+        user_id: c.user_id.clone(),
+        display_name: c.display_name.clone(),
+        description: c.description.clone(),
+        priority: c.priority,
+        active: c.active,
+        public: c.public,
+        created_on: c.created_on,
+        updated_on: c.updated_on,
+    }
+}
+
+fn topic_info(id: i64, t: &Topic) -> TopicInfo {
+    TopicInfo {
+        id,
+        name: t.row.name.clone(),
+        display_name: t.row.display_name.clone(),
+        description: t.row.description.clone(),
+        priority: t.row.priority,
+        active: t.row.active,
+        public: t.row.public,
+        created_on: t.created_on,
+        updated_on: t.updated_on,
+    }
+}
+
+impl Memory {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Adds a membership, which only shares anything once `accepted`.
+    pub fn add_member(&self, row: MemberI, accepted: bool) {
+        self.state().members.push(Member { row, accepted });
+    }
+}
+
+impl State {
+    fn next_id(&mut self) -> i64 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    fn category_id(&self, user_id: &str, name: &str) -> Option<i64> {
+        self.categories
+            .values()
+            .find(|c| c.user_id == user_id && c.name == name)
+            .map(|c| c.id)
+    }
+
+    fn topic_id(&self, user_id: &str, name: &str) -> Option<i64> {
+        self.topics
+            .iter()
+            .find(|(_, t)| t.row.user_id == user_id && t.row.name == name)
+            .map(|(id, _)| *id)
+    }
+
+    fn accepted<'a>(&'a self, user_id: &'a str) -> impl Iterator<Item = &'a MemberI> + 'a {
+        self.members
+            .iter()
+            .filter(move |m| m.accepted && m.row.user_id == user_id)
+            .map(|m| &m.row)
+    }
+
+    /// Categories of the topic owned by the topic's owner, the ones a topic
+    /// membership shares.
+    fn owner_categories(&self, topic_id: i64) -> impl Iterator<Item = i64> + '_ {
+        let owner = self.topics.get(&topic_id).map(|t| t.row.user_id.as_str());
+        self.topic_cats
+            .iter()
+            .filter(move |(t, c)| {
+                *t == topic_id && self.categories.get(c).map(|c| c.user_id.as_str()) == owner
+            })
+            .map(|(_, c)| *c)
+    }
+
+    /// Same as `member::SHARED_CATEGORY_IDS` along with the user's own.
+    fn visible_categories(&self, user_id: &str) -> HashSet<i64> {
+        let mut ids: HashSet<i64> = self
+            .categories
+            .values()
+            .filter(|c| c.user_id == user_id)
+            .map(|c| c.id)
+            .collect();
+        for m in self.accepted(user_id) {
+            ids.extend(m.category_id);
+            if let Some(topic_id) = m.topic_id {
+                ids.extend(self.owner_categories(topic_id));
+            }
+        }
+        ids
+    }
+
+    fn bookmark_categories(&self, id: i64) -> impl Iterator<Item = &CatRow> + '_ {
+        self.bookmark_cats
+            .range((id, i64::MIN)..=(id, i64::MAX))
+            .filter_map(|(_, c)| self.categories.get(c))
+    }
+
+    fn bookmark_row(&self, id: i64, b: &BookmarkI, categories: Vec<String>) -> BookmarkRow {
+        BookmarkRow {
+            id,
+            url: b.url.clone(),
+            user_id: b.user_id.clone(),
+            title: b.title.clone(),
+            content: b.content.clone(),
+            referrer: b.referrer.clone(),
+            status: b.status.clone(),
+            created_on: b.created_on,
+            updated_on: b.updated_on,
+            categories,
+        }
+    }
+
+    /// Bookmarks which are the user's or in one of `through`, with the labels of
+    /// their categories in `through`.
+    fn list_bookmarks(
+        &self,
+        user_id: &str,
+        through: &HashSet<i64>,
+        include_own: bool,
+        categories: Option<&[String]>,
+        status: &Option<String>,
+    ) -> Vec<BookmarkRow> {
+        let wanted = categories.filter(|c| !c.is_empty());
+        self.bookmarks
+            .iter()
+            .filter(|(_, b)| status.as_ref().is_none_or(|s| *s == b.status))
+            .filter_map(|(id, b)| {
+                let mut labels: Vec<String> = self
+                    .bookmark_categories(*id)
+                    .filter(|c| through.contains(&c.id))
+                    .map(|c| label(user_id, c))
+                    .collect();
+                if labels.is_empty() && !(include_own && b.user_id == user_id) {
+                    return None;
+                }
+                if wanted.is_some_and(|w| !labels.iter().any(|l| w.contains(l))) {
+                    return None;
+                }
+                labels.sort();
+                Some(self.bookmark_row(*id, b, labels))
+            })
+            .collect()
+    }
+
+    /// Same as `category::upsert`: inserts the missing ones, touches `updated_on`
+    /// of the existing ones, and fails if a name repeats.
+    fn upsert_categories(
+        &mut self,
+        rows: Vec<CatRowI>,
+        now: Timestamp,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let mut seen = HashSet::new();
+        if !rows.iter().all(|r| seen.insert((&r.user_id, &r.name))) {
+            return Err(db_error(
+                "21000",
+                "ON CONFLICT DO UPDATE command cannot affect row a second time".to_string(),
+                None,
+            ));
+        }
+
+        let mut ids = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id = match self.category_id(&row.user_id, &row.name) {
+                Some(id) => {
+                    if let Some(c) = self.categories.get_mut(&id) {
+                        c.updated_on = now;
+                    }
+                    id
+                }
+                None => self.insert_category(row, now),
+            };
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    fn insert_category(&mut self, row: CatRowI, now: Timestamp) -> i64 {
+        let id = self.next_id();
+        self.categories.insert(
+            id,
+            CatRow {
+                id,
+                name: row.name,
+                display_name: row.display_name,
+                description: row.description,
+                about: row.about,
+                priority: row.priority,
+                active: row.active,
+                public: row.public,
+                user_id: row.user_id,
+                created_on: now,
+                updated_on: now,
+            },
+        );
+        id
+    }
+
+    /// Maps the categories, ignoring the existing mappings like `ON CONFLICT DO
+    /// NOTHING`, nothing is mapped if one of them is missing.
+    fn map_bookmark(&mut self, id: i64, category_ids: &[i64]) -> Result<(), sqlx::Error> {
+        if !self.bookmarks.contains_key(&id)
+            || category_ids
+                .iter()
+                .any(|c| !self.categories.contains_key(c))
+        {
+            return Err(foreign_key_violation(
+                "linknova_bookmark_category_map",
+                "linknova_bookmark_category_map_category_id_fkey",
+            ));
+        }
+        self.bookmark_cats
+            .extend(category_ids.iter().map(|c| (id, *c)));
+        Ok(())
+    }
+}
+
+/// Runs `f` on a copy of the state and only keeps it when `f` succeeds, like a
+/// transaction.
+fn atomically<T>(
+    state: &mut State,
+    f: impl FnOnce(&mut State) -> Result<T, sqlx::Error>,
+) -> Result<T, sqlx::Error> {
+    let mut scratch = state.clone();
+    let result = f(&mut scratch)?;
+    *state = scratch;
+    Ok(result)
+}
+
+impl BookmarkRepo for Memory {
+    fn create<'a>(
+        &'a self,
+        row: BookmarkI,
+        categories: Vec<CatRowI>,
+        category_ids: Vec<i64>,
+        now: Timestamp,
+    ) -> RepoFuture<'a, i64> {
+        let result = atomically(&mut self.state(), |state| {
+            let mut ids = state.upsert_categories(categories, now)?;
+            ids.extend(category_ids);
+            let id = state.next_id();
+            state.bookmarks.insert(id, row);
+            state.map_bookmark(id, &ids)?;
+            Ok(id)
+        });
+        Box::pin(async move { result })
+    }
+
+    fn get_visible<'a>(&'a self, user_id: &'a str, id: i64) -> RepoFuture<'a, Option<BookmarkRow>> {
+        let state = self.state();
+        let visible = state.visible_categories(user_id);
+        let row = state
+            .list_bookmarks(user_id, &visible, true, None, &None)
+            .into_iter()
+            .find(|b| b.id == id);
+        Box::pin(async move { Ok(row) })
+    }
+
+    fn filter<'a>(
+        &'a self,
+        user_id: &'a str,
+        categories: Option<&'a [String]>,
+        status: &'a Option<String>,
+    ) -> RepoFuture<'a, Vec<BookmarkRow>> {
+        let state = self.state();
+        let visible = state.visible_categories(user_id);
+        let rows = state.list_bookmarks(user_id, &visible, true, categories, status);
+        Box::pin(async move { Ok(rows) })
+    }
+
+    fn filter_by_topic<'a>(
+        &'a self,
+        user_id: &'a str,
+        topic_id: i64,
+        categories: Option<&'a [String]>,
+        status: &'a Option<String>,
+    ) -> RepoFuture<'a, Vec<BookmarkRow>> {
+        let state = self.state();
+        let mut through = state.visible_categories(user_id);
+        through.retain(|c| state.topic_cats.contains(&(topic_id, *c)));
+        let rows = state.list_bookmarks(user_id, &through, false, categories, status);
+        Box::pin(async move { Ok(rows) })
+    }
+
+    fn update<'a>(
+        &'a self,
+        user_id: &'a str,
+        id: i64,
+        fields: BookmarkUpdate,
+        now: Timestamp,
+    ) -> RepoFuture<'a, bool> {
+        let mut state = self.state();
+        let updated = match state.bookmarks.get_mut(&id) {
+            Some(row) if row.user_id == user_id => {
+                row.updated_on = now;
+                if let Some(title) = fields.title {
+                    row.title = Some(title);
+                }
+                if let Some(url) = fields.url {
+                    row.url = url;
+                }
+                if let Some(content) = fields.content {
+                    row.content = Some(content);
+                }
+                if let Some(referrer) = fields.referrer {
+                    row.referrer = Some(referrer);
+                }
+                if let Some(status) = fields.status {
+                    row.status = status;
+                }
+                true
+            }
+            _ => false,
+        };
+        Box::pin(async move { Ok(updated) })
+    }
+
+    fn delete<'a>(&'a self, user_id: &'a str, id: i64) -> RepoFuture<'a, bool> {
+        let mut state = self.state();
+        let owned = state
+            .bookmarks
+            .get(&id)
+            .is_some_and(|row| row.user_id == user_id);
+        if owned {
+            state.bookmarks.remove(&id);
+            state.bookmark_cats.retain(|(b, _)| *b != id);
+        }
+        Box::pin(async move { Ok(owned) })
+    }
+
+    fn add_categories<'a>(
+        &'a self,
+        id: i64,
+        categories: Vec<CatRowI>,
+        category_ids: Vec<i64>,
+        now: Timestamp,
+    ) -> RepoFuture<'a, ()> {
+        let result = atomically(&mut self.state(), |state| {
+            let mut ids = state.upsert_categories(categories, now)?;
+            ids.extend(category_ids);
+            state.map_bookmark(id, &ids)
+        });
+        Box::pin(async move { result })
+    }
+
+    fn remove_categories<'a>(&'a self, id: i64, category_ids: &'a [i64]) -> RepoFuture<'a, ()> {
+        self.state()
+            .bookmark_cats
+            .retain(|(b, c)| *b != id || !category_ids.contains(c));
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+impl CategoryRepo for Memory {
+    fn insert(&self, row: CatRowI) -> RepoFuture<'_, (i64, String)> {
+        let mut state = self.state();
+        let result = match state.category_id(&row.user_id, &row.name) {
+            Some(_) => Err(unique_violation("linknova_category_user_id_name_key")),
+            None => {
+                let name = row.name.clone();
+                Ok((state.insert_category(row, chrono::Utc::now()), name))
+            }
+        };
+        Box::pin(async move { result })
+    }
+
+    fn get_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<CatRow>> {
+        let state = self.state();
+        let row = state
+            .category_id(user_id, name)
+            .and_then(|id| state.categories.get(&id).cloned());
+        Box::pin(async move { Ok(row) })
+    }
+
+    fn get_id_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<i64>> {
+        let id = self.state().category_id(user_id, name);
+        Box::pin(async move { Ok(id) })
+    }
+
+    fn list_all<'a>(&'a self, user_id: &'a str) -> RepoFuture<'a, Vec<CategoryRowView>> {
+        let state = self.state();
+        let visible = state.visible_categories(user_id);
+        let mut rows: Vec<_> = state
+            .categories
+            .values()
+            .filter(|c| visible.contains(&c.id))
+            .collect();
+        rows.sort_by(|a, b| {
+            (a.user_id != user_id, &a.user_id, &a.name).cmp(&(
+                b.user_id != user_id,
+                &b.user_id,
+                &b.name,
+            ))
+        });
+        let rows = rows.into_iter().map(cat_view).collect();
+        Box::pin(async move { Ok(rows) })
+    }
+
+    fn list_by_topic_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        topic_names: &'a [String],
+    ) -> RepoFuture<'a, Vec<CategoryRowView>> {
+        let state = self.state();
+        let rows = state
+            .topic_cats
+            .iter()
+            .filter(|(t, _)| {
+                state
+                    .topics
+                    .get(t)
+                    .is_some_and(|t| t.row.user_id == user_id && topic_names.contains(&t.row.name))
+            })
+            .filter_map(|(_, c)| state.categories.get(c))
+            .filter(|c| c.user_id == user_id)
+            .map(cat_view)
+            .collect();
+        Box::pin(async move { Ok(rows) })
+    }
+
+    fn update<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+        display_name: Option<String>,
+        about: Option<String>,
+        description: Option<String>,
+        public: Option<bool>,
+        priority: Option<i32>,
+    ) -> RepoFuture<'a, ()> {
+        let mut state = self.state();
+        if let Some(id) = state.category_id(user_id, name) {
+            let c = state.categories.get_mut(&id).expect("category by id");
+            c.display_name = display_name.or(c.display_name.take());
+            c.about = about.or(c.about.take());
+            c.description = description.or(c.description.take());
+            c.public = public.unwrap_or(c.public);
+            c.priority = priority.unwrap_or(c.priority);
+            c.updated_on = chrono::Utc::now();
+        }
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn delete<'a>(&'a self, user_id: &'a str, name: &'a str) -> RepoFuture<'a, ()> {
+        let mut state = self.state();
+        let result = match state.category_id(user_id, name) {
+            None => Err(sqlx::Error::RowNotFound),
+            Some(id) => {
+                state.members.retain(|m| m.row.category_id != Some(id));
+                if state.bookmark_cats.iter().any(|(_, c)| *c == id) {
+                    Err(still_referenced(
+                        "linknova_category",
+                        "linknova_bookmark_category_map_category_id_fkey",
+                    ))
+                } else if state.topic_cats.iter().any(|(_, c)| *c == id) {
+                    Err(still_referenced(
+                        "linknova_category",
+                        "linknova_topic_category_map_category_id_fkey",
+                    ))
+                } else {
+                    state.categories.remove(&id);
+                    Ok(())
+                }
+            }
+        };
+        Box::pin(async move { result })
+    }
+
+    fn roles<'a>(&'a self, category_id: i64, user_id: &'a str) -> RepoFuture<'a, Vec<String>> {
+        let state = self.state();
+        let roles = state
+            .accepted(user_id)
+            .filter(|m| {
+                m.category_id == Some(category_id)
+                    || m.topic_id
+                        .is_some_and(|t| state.owner_categories(t).any(|c| c == category_id))
+            })
+            .map(|m| m.role.clone())
+            .collect();
+        Box::pin(async move { Ok(roles) })
+    }
+}
+
+impl TopicRepo for Memory {
+    fn insert(&self, row: TopicRowI) -> RepoFuture<'_, (i64, String)> {
+        let mut state = self.state();
+        let result = match state.topic_id(&row.user_id, &row.name) {
+            Some(_) => Err(unique_violation("linknova_topic_user_id_name_key")),
+            None => {
+                let id = state.next_id();
+                let name = row.name.clone();
+                let now = chrono::Utc::now();
+                state.topics.insert(
+                    id,
+                    Topic {
+                        row,
+                        created_on: now,
+                        updated_on: now,
+                    },
+                );
+                Ok((id, name))
+            }
+        };
+        Box::pin(async move { result })
+    }
+
+    fn get_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<TopicRow>> {
+        let state = self.state();
+        let row = state.topic_id(user_id, name).map(|id| {
+            let t = &state.topics[&id];
+            TopicRow {
+                id,
+                name: t.row.name.clone(),
+                display_name: t.row.display_name.clone(),
+                description: t.row.description.clone(),
+                about: t.row.about.clone(),
+                priority: t.row.priority,
+                active: t.row.active,
+                public: t.row.public,
+                user_id: t.row.user_id.clone(),
+                created_on: t.created_on,
+                updated_on: t.updated_on,
+                categories: state
+                    .topic_cats
+                    .iter()
+                    .filter(|(t, _)| *t == id)
+                    .filter_map(|(_, c)| state.categories.get(c))
+                    .map(|c| c.name.clone())
+                    .collect(),
+            }
+        });
+        Box::pin(async move { Ok(row) })
+    }
+
+    fn get_id_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<i64>> {
+        let id = self.state().topic_id(user_id, name);
+        Box::pin(async move { Ok(id) })
+    }
+
+    fn list_all<'a>(&'a self, user_id: &'a str) -> RepoFuture<'a, Vec<TopicRowView>> {
+        let state = self.state();
+        let shared: HashSet<i64> = state.accepted(user_id).filter_map(|m| m.topic_id).collect();
+        let mut rows: Vec<_> = state
+            .topics
+            .iter()
+            .filter(|(id, t)| t.row.user_id == user_id || shared.contains(id))
+            .map(|(id, t)| TopicRowView {
+                id: *id,
+                name: t.row.name.clone(),
+                user_id: t.row.user_id.clone(),
+                display_name: t.row.display_name.clone(),
+                description: t.row.description.clone(),
+                priority: t.row.priority,
+                active: t.row.active,
+                public: t.row.public,
+                created_on: t.created_on,
+                updated_on: t.updated_on,
+                categories: state
+                    .owner_categories(*id)
+                    .map(|c| state.categories[&c].name.clone())
+                    .collect(),
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            (a.user_id != user_id, &a.user_id, &a.name).cmp(&(
+                b.user_id != user_id,
+                &b.user_id,
+                &b.name,
+            ))
+        });
+        Box::pin(async move { Ok(rows) })
+    }
+
+    fn list_by_cat_name<'a>(
+        &'a self,
+        cat_names: &'a [String],
+        user_id: &'a str,
+    ) -> RepoFuture<'a, Vec<TopicInfo>> {
+        let state = self.state();
+        let rows = state
+            .topic_cats
+            .iter()
+            .filter(|(_, c)| {
+                state
+                    .categories
+                    .get(c)
+                    .is_some_and(|c| c.user_id == user_id && cat_names.contains(&c.name))
+            })
+            .filter_map(|(t, _)| state.topics.get(t).map(|topic| (*t, topic)))
+            .filter(|(_, t)| t.row.user_id == user_id)
+            .map(|(id, t)| topic_info(id, t))
+            .collect();
+        Box::pin(async move { Ok(rows) })
+    }
+
+    fn delete<'a>(&'a self, user_id: &'a str, name: &'a str) -> RepoFuture<'a, ()> {
+        let mut state = self.state();
+        let result = match state.topic_id(user_id, name) {
+            None => Err(sqlx::Error::RowNotFound),
+            Some(id) => {
+                state.members.retain(|m| m.row.topic_id != Some(id));
+                if state.topic_cats.iter().any(|(t, _)| *t == id) {
+                    Err(still_referenced(
+                        "linknova_topic",
+                        "linknova_topic_category_map_topic_id_fkey",
+                    ))
+                } else {
+                    state.topics.remove(&id);
+                    Ok(())
+                }
+            }
+        };
+        Box::pin(async move { result })
+    }
+
+    fn role<'a>(&'a self, topic_id: i64, user_id: &'a str) -> RepoFuture<'a, Option<String>> {
+        let role = self
+            .state()
+            .accepted(user_id)
+            .find(|m| m.topic_id == Some(topic_id))
+            .map(|m| m.role.clone());
+        Box::pin(async move { Ok(role) })
+    }
+
+    fn connect(&self, topic_id: i64, category_id: i64) -> RepoFuture<'_, ()> {
+        let mut state = self.state();
+        let result = if !state.topics.contains_key(&topic_id)
+            || !state.categories.contains_key(&category_id)
+        {
+            Err(foreign_key_violation(
+                "linknova_topic_category_map",
+                "linknova_topic_category_map_category_id_fkey",
+            ))
+        } else if !state.topic_cats.insert((topic_id, category_id)) {
+            Err(unique_violation(
+                "linknova_topic_category_map_category_id_topic_id_key",
+            ))
+        } else {
+            Ok(())
+        };
+        Box::pin(async move { result })
+    }
+
+    fn disconnect(&self, topic_id: i64, category_id: i64) -> RepoFuture<'_, ()> {
+        self.state().topic_cats.remove(&(topic_id, category_id));
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn add_categories(
+        &self,
+        topic_id: i64,
+        categories: Vec<CatRowI>,
+        now: Timestamp,
+    ) -> RepoFuture<'_, ()> {
+        let result = atomically(&mut self.state(), |state| {
+            let ids = state.upsert_categories(categories, now)?;
+            if !state.topics.contains_key(&topic_id) {
+                return Err(foreign_key_violation(
+                    "linknova_topic_category_map",
+                    "linknova_topic_category_map_topic_id_fkey",
+                ));
+            }
+            state.topic_cats.extend(ids.iter().map(|c| (topic_id, *c)));
+            Ok(())
+        });
+        Box::pin(async move { result })
+    }
+
+    fn remove_categories<'a>(&'a self, topic_id: i64, names: &'a [String]) -> RepoFuture<'a, ()> {
+        let mut state = self.state();
+        let State {
+            categories,
+            topic_cats,
+            ..
+        } = &mut *state;
+        topic_cats.retain(|(t, c)| {
+            *t != topic_id || !categories.get(c).is_some_and(|c| names.contains(&c.name))
+        });
+        Box::pin(async move { Ok(()) })
+    }
+}
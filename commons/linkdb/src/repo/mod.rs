@@ -0,0 +1,183 @@
+//! Bookmarks, categories, topics and the mappings between them behind traits, so
+//! the link services can run against the database or, in tests, an in-memory
+//! store with the same semantics.
+
+pub mod database;
+pub mod memory;
+
+use crate::bookmark::{BookmarkI, BookmarkRow, BookmarkUpdate};
+use crate::topic::{TopicInfo, TopicRowI};
+use crate::{CatRow, CatRowI, CategoryRowView, TopicRow, TopicRowView};
+use sqlx::types::chrono;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub use database::Database;
+pub use memory::Memory;
+
+pub type RepoFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, sqlx::Error>> + Send + 'a>>;
+
+pub trait BookmarkRepo: Send + Sync {
+    /// Inserts the bookmark and files it under `categories`, upserted by name for
+    /// its owner, and the existing `category_ids`, all or nothing.
+    fn create<'a>(
+        &'a self,
+        row: BookmarkI,
+        categories: Vec<CatRowI>,
+        category_ids: Vec<i64>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'a, i64>;
+
+    /// `None` unless the bookmark is the user's or in a category shared with them.
+    fn get_visible<'a>(&'a self, user_id: &'a str, id: i64) -> RepoFuture<'a, Option<BookmarkRow>>;
+
+    fn filter<'a>(
+        &'a self,
+        user_id: &'a str,
+        categories: Option<&'a [String]>,
+        status: &'a Option<String>,
+    ) -> RepoFuture<'a, Vec<BookmarkRow>>;
+
+    fn filter_by_topic<'a>(
+        &'a self,
+        user_id: &'a str,
+        topic_id: i64,
+        categories: Option<&'a [String]>,
+        status: &'a Option<String>,
+    ) -> RepoFuture<'a, Vec<BookmarkRow>>;
+
+    /// `false` unless the user owns a bookmark with that id.
+    fn update<'a>(
+        &'a self,
+        user_id: &'a str,
+        id: i64,
+        fields: BookmarkUpdate,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'a, bool>;
+
+    /// Deletes the bookmark along with its category mappings and HN details,
+    /// `false` unless the user owns a bookmark with that id.
+    fn delete<'a>(&'a self, user_id: &'a str, id: i64) -> RepoFuture<'a, bool>;
+
+    /// Same as `create` for an existing bookmark, mappings it already has are kept.
+    fn add_categories<'a>(
+        &'a self,
+        id: i64,
+        categories: Vec<CatRowI>,
+        category_ids: Vec<i64>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'a, ()>;
+
+    fn remove_categories<'a>(&'a self, id: i64, category_ids: &'a [i64]) -> RepoFuture<'a, ()>;
+}
+
+pub trait CategoryRepo: Send + Sync {
+    fn insert(&self, row: CatRowI) -> RepoFuture<'_, (i64, String)>;
+
+    fn get_by_name<'a>(&'a self, user_id: &'a str, name: &'a str)
+    -> RepoFuture<'a, Option<CatRow>>;
+
+    fn get_id_by_name<'a>(&'a self, user_id: &'a str, name: &'a str)
+    -> RepoFuture<'a, Option<i64>>;
+
+    /// Own categories along with the ones shared with the user.
+    fn list_all<'a>(&'a self, user_id: &'a str) -> RepoFuture<'a, Vec<CategoryRowView>>;
+
+    fn list_by_topic_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        topic_names: &'a [String],
+    ) -> RepoFuture<'a, Vec<CategoryRowView>>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn update<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+        display_name: Option<String>,
+        about: Option<String>,
+        description: Option<String>,
+        public: Option<bool>,
+        priority: Option<i32>,
+    ) -> RepoFuture<'a, ()>;
+
+    /// Deletes the category and its memberships, `RowNotFound` if there is none.
+    /// Fails while bookmarks or topics are still mapped to it.
+    fn delete<'a>(&'a self, user_id: &'a str, name: &'a str) -> RepoFuture<'a, ()>;
+
+    /// Roles of the user's accepted memberships which apply to the category.
+    fn roles<'a>(&'a self, category_id: i64, user_id: &'a str) -> RepoFuture<'a, Vec<String>>;
+}
+
+pub trait TopicRepo: Send + Sync {
+    fn insert(&self, row: TopicRowI) -> RepoFuture<'_, (i64, String)>;
+
+    fn get_by_name<'a>(
+        &'a self,
+        user_id: &'a str,
+        name: &'a str,
+    ) -> RepoFuture<'a, Option<TopicRow>>;
+
+    fn get_id_by_name<'a>(&'a self, user_id: &'a str, name: &'a str)
+    -> RepoFuture<'a, Option<i64>>;
+
+    /// Own topics along with the ones shared with the user.
+    fn list_all<'a>(&'a self, user_id: &'a str) -> RepoFuture<'a, Vec<TopicRowView>>;
+
+    fn list_by_cat_name<'a>(
+        &'a self,
+        cat_names: &'a [String],
+        user_id: &'a str,
+    ) -> RepoFuture<'a, Vec<TopicInfo>>;
+
+    /// Deletes the topic and its memberships, `RowNotFound` if there is none.
+    /// Fails while categories are still mapped to it.
+    fn delete<'a>(&'a self, user_id: &'a str, name: &'a str) -> RepoFuture<'a, ()>;
+
+    fn role<'a>(&'a self, topic_id: i64, user_id: &'a str) -> RepoFuture<'a, Option<String>>;
+
+    /// Maps one category, fails if it already is.
+    fn connect(&self, topic_id: i64, category_id: i64) -> RepoFuture<'_, ()>;
+
+    fn disconnect(&self, topic_id: i64, category_id: i64) -> RepoFuture<'_, ()>;
+
+    /// Upserts `categories` by name and maps them, mappings it already has are kept.
+    fn add_categories(
+        &self,
+        topic_id: i64,
+        categories: Vec<CatRowI>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> RepoFuture<'_, ()>;
+
+    /// Unmaps the categories with these names, whoever owns them.
+    fn remove_categories<'a>(&'a self, topic_id: i64, names: &'a [String]) -> RepoFuture<'a, ()>;
+}
+
+/// The repositories the link services work with.
+#[derive(Clone)]
+pub struct Repos {
+    pub bookmarks: Arc<dyn BookmarkRepo>,
+    pub categories: Arc<dyn CategoryRepo>,
+    pub topics: Arc<dyn TopicRepo>,
+}
+
+impl Repos {
+    pub fn database(db: crate::Db) -> Self {
+        Self::from(Arc::new(Database::new(db)))
+    }
+
+    pub fn memory() -> Self {
+        Self::from(Arc::new(Memory::default()))
+    }
+}
+
+impl<T: BookmarkRepo + CategoryRepo + TopicRepo + 'static> From<Arc<T>> for Repos {
+    fn from(store: Arc<T>) -> Self {
+        Self {
+            bookmarks: store.clone(),
+            categories: store.clone(),
+            topics: store,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::db::on_pool;
+use crate::share::{ShareAccessI, ShareAccessRow};
+use sqlx::types::chrono;
+
+#[tracing::instrument(name = "linkdb::share::access::insert", skip_all, err)]
+pub async fn insert(db: &crate::Db, row: ShareAccessI) -> Result<(), sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_share_access(
+            share_link_id,
+            accessed_on,
+            granted,
+            reason,
+            ip,
+            user_agent
+        ) VALUES($1, $2, $3, $4, $5, $6)
+    "#;
+
+    on_pool!(db, |pool| sqlx::query(query)
+        .bind(row.share_link_id)
+        .bind(chrono::Utc::now())
+        .bind(row.granted)
+        .bind(row.reason)
+        .bind(row.ip)
+        .bind(row.user_agent)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "linkdb::share::access::list-by-link", skip_all, err)]
+pub async fn list_by_link(
+    db: &crate::Db,
+    share_link_id: i64,
+    limit: i64,
+) -> Result<Vec<ShareAccessRow>, sqlx::Error> {
+    let query = r#"
+        SELECT id, accessed_on, granted, reason, ip, user_agent
+        FROM linknova_share_access
+        WHERE share_link_id = $1
+        ORDER BY accessed_on DESC
+        LIMIT $2
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(share_link_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Accesses of the link for `reason` since `since`, e.g. the recent wrong passwords.
+#[tracing::instrument(name = "linkdb::share::access::count-since", skip_all, err)]
+pub async fn count_since(
+    db: &crate::Db,
+    share_link_id: i64,
+    reason: &str,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<i64, sqlx::Error> {
+    let query = r#"
+        SELECT COUNT(*)
+        FROM linknova_share_access
+        WHERE share_link_id = $1 AND reason = $2 AND accessed_on > $3
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_scalar(query)
+        .bind(share_link_id)
+        .bind(reason)
+        .bind(since)
+        .fetch_one(pool)
+        .await)
+}
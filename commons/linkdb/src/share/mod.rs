@@ -0,0 +1,7 @@
+pub mod access;
+pub mod query;
+pub mod types;
+
+pub use types::{ShareAccessI, ShareAccessRow, ShareLinkI, ShareLinkRow};
+
+pub use query::{get_by_id, get_by_token_hash, insert, list_all, record_view, revoke};
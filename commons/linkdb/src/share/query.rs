@@ -0,0 +1,139 @@
+use crate::db::on_pool;
+use crate::share::{ShareLinkI, ShareLinkRow};
+use sqlx::types::chrono;
+
+const SHARE_SELECT: &str = r#"
+    SELECT
+        id,
+        kind,
+        target_id,
+        user_id,
+        token_hash,
+        token_prefix,
+        password_hash,
+        expires_on,
+        max_views,
+        view_count,
+        revoked_on,
+        created_on,
+        updated_on
+    FROM linknova_share_link
+"#;
+
+#[tracing::instrument(name = "linkdb::share::insert", skip_all, err)]
+pub async fn insert(db: &crate::Db, row: ShareLinkI) -> Result<i64, sqlx::Error> {
+    let query = r#"
+        INSERT INTO linknova_share_link(
+            kind,
+            target_id,
+            user_id,
+            token_hash,
+            token_prefix,
+            password_hash,
+            expires_on,
+            max_views,
+            view_count,
+            created_on,
+            updated_on
+        ) VALUES($1, $2, $3, $4, $5, $6, $7, $8, 0, $9, $10)
+        RETURNING id
+    "#;
+
+    let now = chrono::Utc::now();
+    let (id,): (i64,) = on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(row.kind)
+        .bind(row.target_id)
+        .bind(row.user_id)
+        .bind(row.token_hash)
+        .bind(row.token_prefix)
+        .bind(row.password_hash)
+        .bind(row.expires_on)
+        .bind(row.max_views)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await)?;
+    Ok(id)
+}
+
+#[tracing::instrument(name = "linkdb::share::get-by-id", skip_all, err)]
+pub async fn get_by_id(
+    db: &crate::Db,
+    user_id: &str,
+    id: i64,
+) -> Result<Option<ShareLinkRow>, sqlx::Error> {
+    let query = format!("{SHARE_SELECT} WHERE id = $1 AND user_id = $2");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::share::get-by-token-hash", skip_all, err)]
+pub async fn get_by_token_hash(
+    db: &crate::Db,
+    token_hash: &str,
+) -> Result<Option<ShareLinkRow>, sqlx::Error> {
+    let query = format!("{SHARE_SELECT} WHERE token_hash = $1");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await)
+}
+
+#[tracing::instrument(name = "linkdb::share::list-all", skip_all, err)]
+pub async fn list_all(db: &crate::Db, user_id: &str) -> Result<Vec<ShareLinkRow>, sqlx::Error> {
+    let query = format!("{SHARE_SELECT} WHERE user_id = $1 ORDER BY created_on DESC");
+    on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+}
+
+/// Counts a view if the link is still usable, returns `false` if it is revoked,
+/// expired or out of views. Checked and counted in one statement so concurrent
+/// views can't go past `max_views`.
+#[tracing::instrument(name = "linkdb::share::record-view", skip_all, err)]
+pub async fn record_view(
+    db: &crate::Db,
+    id: i64,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_share_link SET
+            view_count = view_count + 1
+        WHERE
+            id = $1
+            AND revoked_on IS NULL
+            AND (expires_on IS NULL OR expires_on > $2)
+            AND (max_views IS NULL OR view_count < max_views)
+    "#;
+
+    let rows = on_pool!(db, |pool| sqlx::query(query)
+        .bind(id)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(rows == 1)
+}
+
+#[tracing::instrument(name = "linkdb::share::revoke", skip_all, err)]
+pub async fn revoke(db: &crate::Db, user_id: &str, id: i64) -> Result<bool, sqlx::Error> {
+    let query = r#"
+        UPDATE linknova_share_link SET
+            revoked_on = COALESCE(revoked_on, $3),
+            updated_on = $3
+        WHERE id = $1 AND user_id = $2
+    "#;
+
+    let rows = on_pool!(db, |pool| sqlx::query(query)
+        .bind(id)
+        .bind(user_id)
+        .bind(chrono::Utc::now())
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected()))?;
+    Ok(rows == 1)
+}
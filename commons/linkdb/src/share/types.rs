@@ -0,0 +1,49 @@
+use sqlx::types::chrono;
+
+#[derive(Debug)]
+pub struct ShareLinkI {
+    pub kind: String,
+    pub target_id: i64,
+    pub user_id: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub password_hash: Option<String>,
+    pub expires_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub max_views: Option<i32>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ShareLinkRow {
+    pub id: i64,
+    pub kind: String,
+    pub target_id: i64,
+    pub user_id: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub password_hash: Option<String>,
+    pub expires_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub max_views: Option<i32>,
+    pub view_count: i32,
+    pub revoked_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug)]
+pub struct ShareAccessI {
+    pub share_link_id: i64,
+    pub granted: bool,
+    pub reason: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ShareAccessRow {
+    pub id: i64,
+    pub accessed_on: chrono::DateTime<chrono::Utc>,
+    pub granted: bool,
+    pub reason: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
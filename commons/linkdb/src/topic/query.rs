@@ -1,12 +1,11 @@
+use crate::db::{on_pool, push_in};
 use crate::topic::types;
 use crate::topic::types::TopicInfo;
 use sqlx::types::chrono;
+use std::collections::HashMap;
 
 #[tracing::instrument(name = "linkdb::topic::insert", skip_all, err)]
-pub async fn insert(
-    pool: &sqlx::PgPool,
-    row: types::TopicRowI,
-) -> Result<(i64, String), sqlx::Error> {
+pub async fn insert(db: &crate::Db, row: types::TopicRowI) -> Result<(i64, String), sqlx::Error> {
     let query = r#"
         INSERT INTO linknova_topic(
             name,
@@ -26,7 +25,7 @@ pub async fn insert(
 
     let now = chrono::Utc::now();
 
-    let (id, name): (i64, String) = sqlx::query_as(query)
+    let (id, name): (i64, String) = on_pool!(db, |pool| sqlx::query_as(query)
         .bind(row.name)
         .bind(row.display_name)
         .bind(row.description)
@@ -38,56 +37,107 @@ pub async fn insert(
         .bind(now)
         .bind(now)
         .fetch_one(pool)
-        .await?;
+        .await)?;
 
     Ok((id, name))
 }
 
-#[tracing::instrument(name = "linkdb::topic::get-by-name", skip_all, err)]
-pub async fn get_by_name(
-    pool: &sqlx::PgPool,
+const TOPIC_SELECT: &str = r#"
+    SELECT
+        t.id,
+        t.name,
+        t.display_name,
+        t.description,
+        t.about,
+        t.priority,
+        t.active,
+        t.public,
+        t.user_id,
+        t.created_on,
+        t.updated_on
+    FROM linknova_topic as t
+"#;
+
+/// Which of a topic's categories its `categories` lists.
+#[derive(Clone, Copy)]
+enum Listed {
+    All,
+    /// Only the topic owner's.
+    Owners,
+    /// Only the public ones, highest priority first.
+    Public,
+}
+
+/// Names of the topics' categories by topic id, read separately rather than
+/// aggregated into an array, which only Postgres has.
+async fn list_categories(
+    db: &crate::Db,
+    topic_ids: &[i64],
+    listed: Listed,
+) -> Result<HashMap<i64, Vec<String>>, sqlx::Error> {
+    if topic_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let names: Vec<(i64, String)> = on_pool!(db, |pool| {
+        let mut qb = sqlx::QueryBuilder::new(
+            r#"
+            SELECT tcm.topic_id, c.name
+            FROM linknova_topic_category_map as tcm
+            JOIN linknova_topic as t ON tcm.topic_id = t.id
+            JOIN linknova_category as c ON tcm.category_id = c.id
+            WHERE tcm.topic_id IN "#,
+        );
+        push_in(&mut qb, topic_ids);
+        qb.push(match listed {
+            Listed::All => " ORDER BY c.id",
+            Listed::Owners => " AND c.user_id = t.user_id ORDER BY c.id",
+            Listed::Public => " AND c.public ORDER BY c.priority DESC, c.name",
+        });
+        qb.build_query_as().fetch_all(pool).await
+    })?;
+
+    let mut by_topic: HashMap<i64, Vec<String>> = HashMap::new();
+    for (id, name) in names {
+        by_topic.entry(id).or_default().push(name);
+    }
+    Ok(by_topic)
+}
+
+async fn get_with_categories(
+    db: &crate::Db,
+    query: &str,
     user_id: &str,
     name: &str,
+    listed: Listed,
 ) -> Result<Option<types::TopicRow>, sqlx::Error> {
-    let query = r#"
-        select
-            t.id,
-            t.name,
-            t.display_name,
-            t.description,
-            t.about,
-            t.priority,
-            t.active,
-            t.public,
-            t.user_id,
-            t.created_on,
-            t.updated_on,
-            COALESCE(
-                array_agg(c.name) FILTER (WHERE c.id IS NOT NULL),
-                '{}'::text[]
-            ) as categories
-        FROM linknova_topic as t
-        LEFT JOIN
-            linknova_topic_category_map tcm ON t.id = tcm.topic_id
-        LEFT JOIN
-            linknova_category as c on tcm.category_id = c.id
-        WHERE t.name = $1 AND t.user_id = $2
-        GROUP BY
-            t.id
-        ORDER BY
-            t.name
-    "#;
-
-    sqlx::query_as(query)
+    let row: Option<types::TopicRow> = on_pool!(db, |pool| sqlx::query_as(query)
         .bind(name)
         .bind(user_id)
         .fetch_optional(pool)
-        .await
+        .await)?;
+
+    let Some(mut row) = row else {
+        return Ok(None);
+    };
+    let mut categories = list_categories(db, &[row.id], listed).await?;
+    row.categories = categories.remove(&row.id).unwrap_or_default();
+    Ok(Some(row))
+}
+
+#[tracing::instrument(name = "linkdb::topic::get-by-name", skip_all, err)]
+pub async fn get_by_name(
+    db: &crate::Db,
+    user_id: &str,
+    name: &str,
+) -> Result<Option<types::TopicRow>, sqlx::Error> {
+    let query = format!("{TOPIC_SELECT} WHERE t.name = $1 AND t.user_id = $2");
+    get_with_categories(db, &query, user_id, name, Listed::All).await
 }
 
 #[tracing::instrument(name = "linkdb::topic::get-id-by-name", skip_all, err)]
 pub async fn get_id_by_name(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     user_id: &str,
     name: &str,
 ) -> Result<Option<i64>, sqlx::Error> {
@@ -96,53 +146,59 @@ pub async fn get_id_by_name(
         WHERE name = $1 AND user_id = $2
     "#;
 
-    let id: Option<(i64,)> = sqlx::query_as(query)
+    let id: Option<(i64,)> = on_pool!(db, |pool| sqlx::query_as(query)
         .bind(name)
         .bind(user_id)
         .fetch_optional(pool)
-        .await?;
+        .await)?;
 
     Ok(id.map(|(x,)| x))
 }
 
+/// Own topics along with the ones shared with the user, `categories` only lists
+/// the owner's categories of a topic.
 #[tracing::instrument(name = "linkdb::topic::list-all", skip_all, err)]
 pub async fn list_all(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     user_id: &str,
 ) -> Result<Vec<types::TopicRowView>, sqlx::Error> {
-    let query = r#"
+    let query = format!(
+        r#"
         select
             t.id,
             t.name,
+            t.user_id,
             t.display_name,
             t.description,
             t.priority,
             t.active,
             t.public,
             t.created_on,
-            t.updated_on,
-            COALESCE(
-                array_agg(c.name) FILTER (WHERE c.id IS NOT NULL),
-                '{}'::text[]
-            ) as categories
+            t.updated_on
         FROM linknova_topic as t
-        LEFT JOIN
-            linknova_topic_category_map tcm ON t.id = tcm.topic_id
-        LEFT JOIN
-            linknova_category as c on tcm.category_id = c.id
         WHERE
-            t.user_id = $1
-        GROUP BY
-            t.id
-        ORDER BY t.name
-    "#;
+            t.user_id = $1 OR t.id IN ({})
+        ORDER BY t.user_id = $1 DESC, t.user_id, t.name
+    "#,
+        crate::member::SHARED_TOPIC_IDS
+    );
+
+    let mut rows: Vec<types::TopicRowView> = on_pool!(db, |pool| sqlx::query_as(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)?;
 
-    sqlx::query_as(query).bind(user_id).fetch_all(pool).await
+    let ids: Vec<i64> = rows.iter().map(|r| r.id).collect();
+    let mut categories = list_categories(db, &ids, Listed::Owners).await?;
+    for row in &mut rows {
+        row.categories = categories.remove(&row.id).unwrap_or_default();
+    }
+    Ok(rows)
 }
 
 #[tracing::instrument(name = "linkdb::topic::list-by-cat-name", skip_all, err)]
 pub async fn list_by_cat_name(
-    pool: &sqlx::PgPool,
+    db: &crate::Db,
     cat_names: &[String],
     user_id: &str,
 ) -> Result<Vec<TopicInfo>, sqlx::Error> {
@@ -162,33 +218,67 @@ pub async fn list_by_cat_name(
             ON topic.id = mapping.topic_id
         JOIN linknova_category as category
             ON mapping.category_id = category.id
-        WHERE
-            topic.user_id = $1 AND
-            category.user_id = $1 AND
-            category.name = ANY($2)
-    "#;
+        WHERE topic.user_id = "#;
 
-    sqlx::query_as(query)
-        .bind(user_id)
-        .bind(cat_names)
-        .fetch_all(pool)
-        .await
+    on_pool!(db, |pool| {
+        let mut qb = sqlx::QueryBuilder::new(query);
+        qb.push_bind(user_id);
+        qb.push(" AND category.user_id = $1 AND category.name IN ");
+        push_in(&mut qb, cat_names);
+        qb.build_query_as().fetch_all(pool).await
+    })
 }
 
 #[tracing::instrument(name = "linkdb::topic::delete", skip_all, err)]
-pub async fn delete(
-    pool: &sqlx::PgPool,
-    user_id: &str,
-    topic_name: &str,
-) -> Result<(), sqlx::Error> {
+pub async fn delete(db: &crate::Db, user_id: &str, topic_name: &str) -> Result<(), sqlx::Error> {
     let query = r#"
         DELETE FROM linknova_topic WHERE name = $1 AND user_id = $2 returning id
     "#;
 
-    let (_id,): (i64,) = sqlx::query_as(query)
+    let (_id,): (i64,) = on_pool!(db, |pool| sqlx::query_as(query)
         .bind(topic_name)
         .bind(user_id)
         .fetch_one(pool)
-        .await?;
+        .await)?;
     Ok(())
 }
+
+/// Same as `get_by_name` but only for a public, active topic, listing only its public categories.
+#[tracing::instrument(name = "linkdb::topic::get-public", skip_all, err)]
+pub async fn get_public(
+    db: &crate::Db,
+    user_id: &str,
+    name: &str,
+) -> Result<Option<types::TopicRow>, sqlx::Error> {
+    let query =
+        format!("{TOPIC_SELECT} WHERE t.name = $1 AND t.user_id = $2 AND t.public AND t.active");
+    get_with_categories(db, &query, user_id, name, Listed::Public).await
+}
+
+#[tracing::instrument(name = "linkdb::topic::get-by-id", skip_all, err)]
+pub async fn get_by_id(
+    db: &crate::Db,
+    user_id: &str,
+    id: i64,
+) -> Result<Option<TopicInfo>, sqlx::Error> {
+    let query = r#"
+        SELECT
+            id,
+            name,
+            display_name,
+            description,
+            priority,
+            active,
+            public,
+            created_on,
+            updated_on
+        FROM linknova_topic
+        WHERE id = $1 AND user_id = $2
+    "#;
+
+    on_pool!(db, |pool| sqlx::query_as(query)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+}
@@ -1,7 +1,7 @@
 use sqlx::FromRow;
 use sqlx::types::chrono;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TopicRowI {
     pub name: String,
     pub display_name: Option<String>,
@@ -26,6 +26,8 @@ pub struct TopicRow {
     pub user_id: String,
     pub created_on: chrono::DateTime<chrono::Utc>,
     pub updated_on: chrono::DateTime<chrono::Utc>,
+    /// Names of its categories, read by a query of their own.
+    #[sqlx(skip)]
     pub categories: Vec<String>,
 }
 
@@ -33,6 +35,8 @@ pub struct TopicRow {
 pub struct TopicRowView {
     pub id: i64,
     pub name: String,
+    /// owner, differs from the caller for shared topics
+    pub user_id: String,
     pub display_name: Option<String>,
     pub description: Option<String>,
     pub priority: i32,
@@ -40,6 +44,8 @@ pub struct TopicRowView {
     pub public: bool,
     pub created_on: chrono::DateTime<chrono::Utc>,
     pub updated_on: chrono::DateTime<chrono::Utc>,
+    /// Names of its categories, read by a query of their own.
+    #[sqlx(skip)]
     pub categories: Vec<String>,
 }
 
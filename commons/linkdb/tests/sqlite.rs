@@ -0,0 +1,304 @@
+//! The SQLite backend on `sqlite::memory:`, run with `--features sqlite`. The
+//! Postgres side is covered by the service's API tests.
+#![cfg(feature = "sqlite")]
+
+use linkdb::bookmark::{BookmarkI, BookmarkUpdate};
+use linkdb::hn::{HnItemI, HnSearchQ};
+use linkdb::member::MemberI;
+use linkdb::repo::Repos;
+use linkdb::{CatRowI, Db, PoolConfig};
+use sqlx::types::chrono;
+
+/// A migrated in-memory database. Every connection opens a database of its
+/// own, so the pool keeps the one.
+async fn db() -> Db {
+    let pool = PoolConfig {
+        max_connections: 1,
+        min_connections: 1,
+        acquire_timeout: std::time::Duration::from_secs(5),
+    };
+    let db = Db::connect("sqlite::memory:", pool)
+        .await
+        .expect("an in-memory database");
+    linkdb::migrate::up(&db)
+        .await
+        .expect("migrations to apply on an empty database");
+    db
+}
+
+fn category(name: &str, user_id: &str) -> CatRowI {
+    CatRowI {
+        name: name.to_string(),
+        display_name: None,
+        description: None,
+        about: None,
+        priority: 0,
+        active: true,
+        public: false,
+        user_id: user_id.to_string(),
+    }
+}
+
+fn bookmark(url: &str, user_id: &str) -> BookmarkI {
+    let now = chrono::Utc::now();
+    BookmarkI {
+        url: url.to_string(),
+        user_id: user_id.to_string(),
+        title: Some(url.to_string()),
+        content: None,
+        referrer: None,
+        status: "UN".to_string(),
+        created_on: now,
+        updated_on: now,
+    }
+}
+
+fn item(id: i64, title: &str, text: Option<&str>) -> HnItemI {
+    HnItemI {
+        id,
+        item_type: "story".to_string(),
+        by: Some("pg".to_string()),
+        title: Some(title.to_string()),
+        url: None,
+        text: text.map(str::to_string),
+        parent: None,
+        score: 10,
+        descendants: 0,
+        deleted: false,
+        dead: false,
+        posted_on: Some(chrono::Utc::now()),
+    }
+}
+
+#[tokio::test]
+async fn migrations_apply_revert_and_apply_again() {
+    let db = db().await;
+    linkdb::migrate::check(&db).await.unwrap();
+    let status = linkdb::migrate::status(&db).await.unwrap();
+    assert!(!status.is_empty());
+    assert!(status.iter().all(|m| m.applied));
+    assert!(linkdb::migrate::up(&db).await.unwrap().is_empty());
+
+    let reverted = linkdb::migrate::down(&db, status.len()).await.unwrap();
+    assert_eq!(reverted.len(), status.len());
+    assert!(linkdb::migrate::check(&db).await.is_err());
+    let applied = linkdb::migrate::up(&db).await.unwrap();
+    assert_eq!(
+        applied,
+        status.iter().map(|m| m.version).collect::<Vec<_>>()
+    );
+    linkdb::migrate::check(&db).await.unwrap();
+}
+
+#[tokio::test]
+async fn bookmarks_round_trip_through_the_placeholders() {
+    let db = db().await;
+    let repos = Repos::database(db.clone());
+    let now = chrono::Utc::now();
+    let id = repos
+        .bookmarks
+        .create(
+            bookmark("https://a.example", "alice"),
+            vec![category("rust", "alice"), category("db", "alice")],
+            Vec::new(),
+            now,
+        )
+        .await
+        .unwrap();
+
+    let row = repos
+        .bookmarks
+        .get_visible("alice", id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.url, "https://a.example");
+    assert_eq!(row.categories, ["db", "rust"]);
+
+    let rust = Some(vec!["rust".to_string()]);
+    let rows = repos
+        .bookmarks
+        .filter("alice", rust.as_deref(), &Some("UN".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(rows.iter().map(|r| r.id).collect::<Vec<_>>(), [id]);
+
+    let fields = BookmarkUpdate {
+        title: Some("A".to_string()),
+        status: Some("RD".to_string()),
+        ..Default::default()
+    };
+    assert!(
+        !repos
+            .bookmarks
+            .update("bob", id, fields, now)
+            .await
+            .unwrap()
+    );
+    let fields = BookmarkUpdate {
+        title: Some("A".to_string()),
+        status: Some("RD".to_string()),
+        ..Default::default()
+    };
+    assert!(
+        repos
+            .bookmarks
+            .update("alice", id, fields, now)
+            .await
+            .unwrap()
+    );
+    let row = repos
+        .bookmarks
+        .get_visible("alice", id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (row.title.as_deref(), row.status.as_str()),
+        (Some("A"), "RD")
+    );
+
+    assert!(!repos.bookmarks.delete("bob", id).await.unwrap());
+    assert!(repos.bookmarks.delete("alice", id).await.unwrap());
+    assert!(
+        repos
+            .bookmarks
+            .get_visible("alice", id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn shared_categories_are_visible_once_accepted() {
+    let db = db().await;
+    let repos = Repos::database(db.clone());
+    let now = chrono::Utc::now();
+    let id = repos
+        .bookmarks
+        .create(
+            bookmark("https://a.example", "alice"),
+            vec![category("rust", "alice"), category("private", "alice")],
+            Vec::new(),
+            now,
+        )
+        .await
+        .unwrap();
+    assert!(
+        repos
+            .bookmarks
+            .get_visible("bob", id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repos
+            .bookmarks
+            .filter("bob", None, &None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let rust = repos
+        .categories
+        .get_id_by_name("alice", "rust")
+        .await
+        .unwrap()
+        .unwrap();
+    let invitation = MemberI {
+        category_id: Some(rust),
+        topic_id: None,
+        user_id: "bob".to_string(),
+        role: "viewer".to_string(),
+        invited_by: "alice".to_string(),
+    };
+    let member = linkdb::member::insert(&db, invitation, now).await.unwrap();
+    // pending invitations share nothing
+    assert!(
+        repos
+            .bookmarks
+            .get_visible("bob", id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    assert!(
+        linkdb::member::accept(&db, member, "bob", now)
+            .await
+            .unwrap()
+    );
+    let row = repos
+        .bookmarks
+        .get_visible("bob", id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.categories, ["@alice/rust"]);
+    let rows = repos.bookmarks.filter("bob", None, &None).await.unwrap();
+    assert_eq!(rows.iter().map(|r| r.id).collect::<Vec<_>>(), [id]);
+    let views = repos.categories.list_all("bob").await.unwrap();
+    assert_eq!(views.iter().map(|c| c.id).collect::<Vec<_>>(), [rust]);
+}
+
+#[tokio::test]
+async fn search_translates_the_web_syntax() {
+    let db = db().await;
+    let mut tx = db.begin().await.unwrap();
+    let items = vec![
+        item(
+            1,
+            "Postgres 17 released",
+            Some("incremental backups at last"),
+        ),
+        item(2, "Rust 1.82 released", None),
+        item(
+            3,
+            "Backups of Postgres on a budget",
+            Some("backups that are incremental"),
+        ),
+        item(
+            4,
+            "Writing a database in Rust",
+            Some("a tour near postgres internals"),
+        ),
+    ];
+    linkdb::hn::upsert(&mut tx, items, chrono::Utc::now())
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    let search = |text: &str| {
+        let q = HnSearchQ {
+            text: Some(text.to_string()),
+            item_type: None,
+            by: None,
+            from: None,
+            to: None,
+            min_score: None,
+            offset: 0,
+            limit: 10,
+        };
+        let db = db.clone();
+        async move {
+            let rows = linkdb::hn::search::search(&db, &q).await.unwrap();
+            let mut ids: Vec<i64> = rows.iter().map(|r| r.item.id).collect();
+            ids.sort_unstable();
+            ids
+        }
+    };
+    assert_eq!(search("postgres").await, [1, 3, 4]);
+    assert_eq!(search("\"incremental backups\"").await, [1]);
+    assert_eq!(search("postgres -rust").await, [1, 3]);
+    assert_eq!(search("-postgres").await, [2]);
+    assert_eq!(search("rust or budget").await, [2, 3, 4]);
+    assert_eq!(search("released rust").await, [2]);
+    // FTS5 syntax of its own is taken as plain text
+    assert_eq!(search("NEAR(postgres").await, [4]);
+    assert_eq!(search("postgres\"").await, [1, 3, 4]);
+    assert_eq!(search("\"").await, Vec::<i64>::new());
+    assert_eq!(search("*").await, Vec::<i64>::new());
+}
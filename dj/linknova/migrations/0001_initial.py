@@ -1,112 +0,0 @@
-# Generated by Django 4.2.1 on 2025-07-25 12:17
-
-from django.db import migrations, models
-import django.db.models.deletion
-
-
-class Migration(migrations.Migration):
-    initial = True
-
-    dependencies = []
-
-    operations = [
-        migrations.CreateModel(
-            name="Category",
-            fields=[
-                (
-                    "id",
-                    models.BigAutoField(
-                        auto_created=True,
-                        primary_key=True,
-                        serialize=False,
-                        verbose_name="ID",
-                    ),
-                ),
-                ("created_on", models.DateTimeField(auto_now_add=True)),
-                ("updated_on", models.DateTimeField(auto_now=True)),
-                ("name", models.CharField(db_index=True, max_length=255)),
-                (
-                    "display_name",
-                    models.CharField(blank=True, max_length=255, null=True),
-                ),
-                (
-                    "description",
-                    models.CharField(blank=True, max_length=1024, null=True),
-                ),
-                ("about", models.TextField(blank=True, null=True)),
-                ("priority", models.IntegerField(default=0)),
-                ("user_id", models.CharField(max_length=255)),
-                ("active", models.BooleanField(default=True)),
-                ("public", models.BooleanField(default=False)),
-            ],
-            options={
-                "db_table": "linknova_category",
-                "unique_together": {("user_id", "name")},
-            },
-        ),
-        migrations.CreateModel(
-            name="Topic",
-            fields=[
-                (
-                    "id",
-                    models.BigAutoField(
-                        auto_created=True,
-                        primary_key=True,
-                        serialize=False,
-                        verbose_name="ID",
-                    ),
-                ),
-                ("created_on", models.DateTimeField(auto_now_add=True)),
-                ("updated_on", models.DateTimeField(auto_now=True)),
-                ("name", models.CharField(db_index=True, max_length=255)),
-                (
-                    "display_name",
-                    models.CharField(blank=True, max_length=512, null=True),
-                ),
-                (
-                    "description",
-                    models.CharField(blank=True, max_length=1024, null=True),
-                ),
-                ("about", models.TextField(blank=True, null=True)),
-                ("priority", models.IntegerField(default=0)),
-                ("active", models.BooleanField(default=True)),
-                ("public", models.BooleanField(default=False)),
-                ("user_id", models.CharField(max_length=255)),
-            ],
-            options={
-                "db_table": "linknova_topic",
-                "unique_together": {("user_id", "name")},
-            },
-        ),
-        migrations.CreateModel(
-            name="TopicCategoryMapping",
-            fields=[
-                (
-                    "id",
-                    models.BigAutoField(
-                        auto_created=True,
-                        primary_key=True,
-                        serialize=False,
-                        verbose_name="ID",
-                    ),
-                ),
-                (
-                    "category",
-                    models.ForeignKey(
-                        on_delete=django.db.models.deletion.CASCADE,
-                        to="linknova.category",
-                    ),
-                ),
-                (
-                    "topic",
-                    models.ForeignKey(
-                        on_delete=django.db.models.deletion.CASCADE, to="linknova.topic"
-                    ),
-                ),
-            ],
-            options={
-                "db_table": "linknova_topic_category_map",
-                "unique_together": {("category", "topic")},
-            },
-        ),
-    ]
@@ -1,4 +1,3 @@
- export PYTHONPATH=${PYTHONPATH}:${PROJDIR}/dj
 # export DIESEL_CONFIG_FILE=${PROJDIR}/diesel.toml
 
 DATABASE_NAME=linknova
@@ -17,35 +16,28 @@ function popd2() {
   unset PUSHED
 }
 
-function manage() {
-  pushd2 /dj
-  python manage.py $*
+function migrate() {
+  pushd2 /
+  cargo run -p service -- migrate $*
   r=$?
   popd2
   return ${r}
 }
 
-function makemigrations() {
-  manage makemigrations $*
-}
-
-function empty_migration() {
-    makemigrations linknova --empty
-    # migrations.RunSQL("insert into linknova_topic(name, is_active, created_on, updated_on) values('default', true, now(), now())"),
-    # migrations.RunSQL("insert into linknova_category(name, topic_id, created_on, updated_on) values('default', (select id from linknova_topic where name = 'default'), now(), now())")
-
-}
-
-function migrate() {
-  manage migrate $*
-}
-
-function djshell() {
-  manage shell
+# new_migration <description>: empty up/down files with the next version, for
+# each backend
+function new_migration() {
+  local dir="$PROJDIR/commons/linkdb/migrations"
+  local last=$(ls "$dir/postgres" | sed -n 's/^\([0-9]*\)_.*/\1/p' | sort -n | tail -1)
+  local next=$(printf "%04d" $((10#${last:-0} + 1)))
+  for backend in postgres sqlite; do
+    touch "$dir/$backend/${next}_$1.up.sql" "$dir/$backend/${next}_$1.down.sql"
+    ls "$dir/$backend/${next}_$1".*
+  done
 }
 
 function dbshell() {
-  manage dbshell
+  psql "$DATABASE_URL"
 }
 
 function recreatedb() {
@@ -57,13 +49,6 @@ function recreatedb() {
   migrate $*
 }
 
-function pyfmt() {
-  pushd2 /dj
-  black .
-  popd2
-}
-
-
 function install_diesel() {
   cargo install diesel_cli --no-default-features --features "postgres"
 }
@@ -77,7 +62,3 @@ function diesel_schema() {
     sed -i -e 's/Varchar/Text/g' $PROJDIR/service/db/src/schema.rs
   fi
 }
-
-function runserver() {
-  manage runserver 0.0.0.0:8001
-}
@@ -0,0 +1,174 @@
+//! `Json`, `Path` and `Query` as axum's, but a request they can't read is
+//! answered with a problem naming the field at fault instead of plain text.
+
+use crate::problem::{FieldError, Problem};
+use axum::extract::path::ErrorKind;
+use axum::extract::rejection::{JsonRejection, PathRejection};
+use axum::extract::{FromRequest, FromRequestParts, RawPathParams, Request};
+use axum::http::request::Parts;
+use axum_extra::extract::QueryRejection;
+use serde::de::DeserializeOwned;
+use std::error::Error;
+
+pub struct Json<T>(pub T);
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(Self(value)),
+            Err(e) => Err(json_problem(&e)),
+        }
+    }
+}
+
+pub struct Path<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Self(value)),
+            Err(e) => {
+                // parameters parsed as primitives or tuples are known by position
+                let keys: Vec<String> = match RawPathParams::from_request_parts(parts, state).await
+                {
+                    Ok(params) => params.iter().map(|(key, _)| key.to_string()).collect(),
+                    Err(_) => Vec::new(),
+                };
+                Err(path_problem(&e, &keys))
+            }
+        }
+    }
+}
+
+pub struct Query<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum_extra::extract::Query::<T>::from_request_parts(parts, state).await {
+            Ok(axum_extra::extract::Query(value)) => Ok(Self(value)),
+            Err(e) => Err(query_problem(&e)),
+        }
+    }
+}
+
+fn json_problem(e: &JsonRejection) -> Problem {
+    tracing::warn!("rejected body: {}", e);
+    let field = match e {
+        JsonRejection::JsonDataError(_) => deserialize_error(e, "body"),
+        JsonRejection::JsonSyntaxError(_) => FieldError::new("body", "is not valid JSON"),
+        JsonRejection::MissingJsonContentType(_) => {
+            FieldError::new("content-type", "must be application/json")
+        }
+        _ => FieldError::new("body", "can't be read"),
+    };
+    Problem::invalid(field)
+}
+
+fn path_problem(e: &PathRejection, keys: &[String]) -> Problem {
+    tracing::warn!("rejected path: {}", e);
+    let PathRejection::FailedToDeserializePathParams(e) = e else {
+        return Problem::internal();
+    };
+    let field = match e.kind() {
+        ErrorKind::ParseErrorAtKey {
+            key, expected_type, ..
+        } => FieldError::new(key, format!("must be {}", expected_type)),
+        ErrorKind::ParseErrorAtIndex {
+            index,
+            expected_type,
+            ..
+        } => FieldError::new(
+            keys.get(*index).map_or("path", String::as_str),
+            format!("must be {}", expected_type),
+        ),
+        ErrorKind::ParseError { expected_type, .. } => FieldError::new(
+            keys.first().map_or("path", String::as_str),
+            format!("must be {}", expected_type),
+        ),
+        ErrorKind::InvalidUtf8InPathParam { key } => FieldError::new(key, "is not valid UTF-8"),
+        ErrorKind::DeserializeError { key, message, .. } => FieldError::new(key, message),
+        ErrorKind::WrongNumberOfParameters { .. } | ErrorKind::UnsupportedType { .. } => {
+            return Problem::internal();
+        }
+        _ => FieldError::new("path", "is invalid"),
+    };
+    Problem::invalid(field)
+}
+
+fn query_problem(e: &QueryRejection) -> Problem {
+    tracing::warn!("rejected query: {}", e);
+    Problem::invalid(deserialize_error(e, "query"))
+}
+
+/// The field serde failed at, as told by the `serde_path_to_error` error the
+/// rejection wraps, or `whole` if it is the input as a whole.
+fn deserialize_error(e: &(dyn Error + 'static), whole: &str) -> FieldError {
+    let mut source = e.source();
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<serde_path_to_error::Error<serde_json::Error>>() {
+            return field_error(&e.path().to_string(), &e.inner().to_string(), whole);
+        }
+        if let Some(e) = e.downcast_ref::<serde_path_to_error::Error<serde::de::value::Error>>() {
+            return field_error(&e.path().to_string(), &e.inner().to_string(), whole);
+        }
+        source = e.source();
+    }
+    FieldError::new(whole, "can't be read")
+}
+
+fn field_error(path: &str, message: &str, whole: &str) -> FieldError {
+    // serde_json tells where in the text it failed, of no use to the caller
+    let message = message.rsplit_once(" at line ").map_or(message, |(m, _)| m);
+    if path != "." {
+        return FieldError::new(path, message);
+    }
+    // a missing or unknown field is reported on the struct holding it
+    let named = ["missing field `", "unknown field `"]
+        .iter()
+        .find_map(|prefix| message.strip_prefix(prefix))
+        .and_then(|rest| rest.split_once('`'));
+    match named {
+        Some((field, _)) => FieldError::new(field, message),
+        None => FieldError::new(whole, message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_named_by_their_path() {
+        let e = field_error(".", "missing field `url` at line 1 column 2", "body");
+        assert_eq!(e, FieldError::new("url", "missing field `url`"));
+
+        let e = field_error(
+            "max_views",
+            "invalid type: string \"x\", expected i64 at line 1 column 17",
+            "body",
+        );
+        assert_eq!(e.field, "max_views");
+        assert_eq!(e.message, "invalid type: string \"x\", expected i64");
+
+        let e = field_error(".", "expected a map", "query");
+        assert_eq!(e.field, "query");
+    }
+}
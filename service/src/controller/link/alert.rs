@@ -0,0 +1,79 @@
+use crate::controller::extract::{Json, Path};
+use crate::controller::link::types;
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link;
+use crate::services::link::alert::types::AlertError;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Extension;
+
+impl From<AlertError> for Problem {
+    fn from(e: AlertError) -> Self {
+        match e {
+            AlertError::NotFound(m) => Problem::not_found("not_found", format!("not found: {}", m)),
+            AlertError::InvalidInput(field) => Problem::invalid(field),
+            AlertError::Conflict(m) => Problem::conflict("alert_exists", m),
+            AlertError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+#[tracing::instrument(name = "controller::alert::create", skip_all)]
+pub async fn create(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<types::AlertReq>,
+) -> Response {
+    match link::alert::create(&ctx, user.user_id.as_str(), request).await {
+        Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::alert::get", skip_all)]
+pub async fn get(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::alert::get(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::alert::list", skip_all)]
+pub async fn list(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
+    match link::alert::list(&ctx, user.user_id.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::alert::update", skip_all)]
+pub async fn update(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    Json(request): Json<types::AlertReq>,
+) -> Response {
+    match link::alert::update(&ctx, user.user_id.as_str(), id, request).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::alert::delete", skip_all)]
+pub async fn delete(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::alert::delete(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
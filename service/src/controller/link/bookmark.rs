@@ -1,34 +1,47 @@
+use crate::controller::extract::{Json, Path, Query};
 use crate::ctx::Ctx;
 use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link::bookmark::types::BookmarkError;
 use crate::{controller::link::types, controller::response, services::link};
-use axum::extract::{Path, State};
+use axum::extract::State;
 use axum::response::Response;
 use axum::Extension;
-use axum_extra::extract::Query;
+
+impl From<BookmarkError> for Problem {
+    fn from(e: BookmarkError) -> Self {
+        match e {
+            BookmarkError::NotFound(m) => {
+                Problem::not_found("not_found", format!("not found: {}", m))
+            }
+            BookmarkError::Forbidden(m) => Problem::forbidden("forbidden", m),
+            BookmarkError::InvalidInput(field) => Problem::invalid(field),
+            BookmarkError::Database(e) => Problem::database(&e),
+        }
+    }
+}
 
 #[tracing::instrument(name = "controller::bookmark::create", skip_all)]
 pub async fn create(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
-    axum::Json(request): axum::Json<types::BmCreateReq>,
+    Json(request): Json<types::BmCreateReq>,
 ) -> Response {
     match link::bookmark::create(&ctx, user.user_id.as_str(), request).await {
         Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
 #[tracing::instrument(name = "controller::bookmark::get", skip_all)]
-pub async fn get(State(ctx): State<Ctx>, Path(id): Path<i64>) -> Response {
-    match link::bookmark::get(&ctx, id).await {
+pub async fn get(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::bookmark::get(&ctx, user.user_id.as_str(), id).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -37,14 +50,11 @@ pub async fn update(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(id): Path<i64>,
-    axum::Json(request): axum::Json<types::BmUpdateReq>,
+    Json(request): Json<types::BmUpdateReq>,
 ) -> Response {
     match link::bookmark::update(&ctx, user.user_id.as_str(), id, request).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -72,10 +82,7 @@ pub async fn list(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -87,10 +94,7 @@ pub async fn delete(
 ) -> Response {
     match link::bookmark::delete(&ctx, user.user_id.as_str(), id).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -99,16 +103,13 @@ pub async fn add_categories(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(id): Path<i64>,
-    axum::Json(req): axum::Json<types::AddCategories>,
+    Json(req): Json<types::AddCategories>,
 ) -> Response {
     match link::bookmark::add_categories(&ctx, user.user_id.as_str(), id, req.categories.as_slice())
         .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -117,7 +118,7 @@ pub async fn remove_categories(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(bm_id): Path<i64>,
-    axum::Json(req): axum::Json<types::AddCategories>,
+    Json(req): Json<types::AddCategories>,
 ) -> Response {
     match link::bookmark::remove_category(
         &ctx,
@@ -128,9 +129,6 @@ pub async fn remove_categories(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
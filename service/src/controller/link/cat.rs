@@ -1,25 +1,34 @@
 use super::types::{CatCreateReq, CatUpdateReq};
+use crate::controller::extract::{Json, Path, Query};
 use crate::controller::response;
 use crate::ctx::Ctx;
 use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
 use crate::services::link;
-use axum::extract::{Path, State};
+use crate::services::link::cat::types::CatError;
+use axum::extract::State;
 use axum::response::Response;
 use axum::Extension;
-use axum_extra::extract::Query;
+
+impl From<CatError> for Problem {
+    fn from(e: CatError) -> Self {
+        match e {
+            CatError::NotFound(m) => Problem::not_found("not_found", format!("not found: {}", m)),
+            CatError::Forbidden(m) => Problem::forbidden("forbidden", m),
+            CatError::Database(e) => Problem::database(&e),
+        }
+    }
+}
 
 #[tracing::instrument(name = "controller::cat::create", skip_all)]
 pub async fn create(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
-    axum::Json(request): axum::Json<CatCreateReq>,
+    Json(request): Json<CatCreateReq>,
 ) -> Response {
     match link::cat::create(&ctx, user.user_id.as_str(), request).await {
         Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -31,10 +40,7 @@ pub async fn get(
 ) -> Response {
     match link::cat::get(&ctx, user.user_id.as_str(), cat_name.as_str()).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -46,10 +52,7 @@ pub async fn list(
 ) -> Response {
     match link::cat::list(&ctx, user.user_id.as_str(), query.topic.as_slice()).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -58,14 +61,11 @@ pub async fn update(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(cat_name): Path<String>,
-    axum::Json(request): axum::Json<CatUpdateReq>,
+    Json(request): Json<CatUpdateReq>,
 ) -> Response {
     match link::cat::update(&ctx, user.user_id.as_str(), cat_name.as_str(), request).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -77,10 +77,7 @@ pub async fn delete(
 ) -> Response {
     match link::cat::delete(&ctx, user.user_id.as_str(), cat_name.as_str()).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -99,10 +96,7 @@ pub async fn add_topics(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -121,10 +115,7 @@ pub async fn remove_topics(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -133,7 +124,7 @@ pub async fn add_topics_bulk(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(cat_name): Path<String>,
-    axum::Json(request): axum::Json<TopicsRequest>,
+    Json(request): Json<TopicsRequest>,
 ) -> Response {
     match link::cat::add_topics_bulk(
         &ctx,
@@ -144,10 +135,7 @@ pub async fn add_topics_bulk(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -156,7 +144,7 @@ pub async fn remove_topics_bulk(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(cat_name): Path<String>,
-    axum::Json(request): axum::Json<TopicsRequest>,
+    Json(request): Json<TopicsRequest>,
 ) -> Response {
     match link::cat::remove_topics_bulk(
         &ctx,
@@ -167,10 +155,7 @@ pub async fn remove_topics_bulk(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
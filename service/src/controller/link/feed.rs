@@ -0,0 +1,135 @@
+use crate::controller::extract::{Json, Path, Query};
+use crate::controller::link::types;
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::{FieldError, Problem};
+use crate::services::link;
+use crate::services::link::feed::types::FeedError;
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+
+impl From<FeedError> for Problem {
+    fn from(e: FeedError) -> Self {
+        match e {
+            FeedError::NotFound(m) => Problem::not_found("not_found", format!("not found: {}", m)),
+            FeedError::InvalidInput(field) => Problem::invalid(field),
+            FeedError::Opml(e) => Problem::invalid(FieldError::new("opml", e.to_string())),
+            FeedError::Http(_) => {
+                Problem::bad_gateway("feed_unreachable", "the feed can't be fetched")
+            }
+            FeedError::UpstreamStatus(status) => Problem::bad_gateway(
+                "feed_upstream_error",
+                format!("the feed server returned {}", status),
+            ),
+            FeedError::TooLarge(max) => Problem::bad_gateway(
+                "feed_too_large",
+                format!("the feed is larger than {} bytes", max),
+            ),
+            FeedError::Parse(_) => Problem::bad_gateway("feed_invalid", "the feed can't be parsed"),
+            FeedError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+#[tracing::instrument(name = "controller::feed::subscribe", skip_all)]
+pub async fn subscribe(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<types::FeedSubscribeReq>,
+) -> Response {
+    match link::feed::subscribe(&ctx, user.user_id.as_str(), request).await {
+        Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::feed::get", skip_all)]
+pub async fn get(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::feed::get(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::feed::list", skip_all)]
+pub async fn list(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
+    match link::feed::list(&ctx, user.user_id.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::feed::delete", skip_all)]
+pub async fn delete(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::feed::delete(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::feed::refresh", skip_all)]
+pub async fn refresh(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::feed::refresh(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct ImportQueryParams {
+    /// category for the feeds which are not inside any folder outline
+    #[serde(default = "default_import_category")]
+    category: String,
+}
+
+fn default_import_category() -> String {
+    "feeds".to_string()
+}
+
+#[tracing::instrument(name = "controller::feed::import-opml", skip_all)]
+pub async fn import_opml(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Query(q): Query<ImportQueryParams>,
+    body: String,
+) -> Response {
+    match link::feed::import_opml(&ctx, user.user_id.as_str(), &body, &q.category).await {
+        Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::feed::export-opml", skip_all)]
+pub async fn export_opml(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
+    match link::feed::export_opml(&ctx, user.user_id.as_str()).await {
+        Ok(xml) => (
+            [
+                (
+                    axum::http::header::CONTENT_TYPE,
+                    "text/x-opml; charset=utf-8",
+                ),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"linknova-feeds.opml\"",
+                ),
+            ],
+            xml,
+        )
+            .into_response(),
+        Err(e) => response::problem(e),
+    }
+}
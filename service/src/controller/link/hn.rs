@@ -0,0 +1,35 @@
+use crate::controller::extract::Path;
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link;
+use crate::services::link::hn::types::HnSaveError;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Extension;
+
+impl From<HnSaveError> for Problem {
+    fn from(e: HnSaveError) -> Self {
+        match e {
+            HnSaveError::NotFound(m) => {
+                Problem::not_found("not_found", format!("not found: {}", m))
+            }
+            HnSaveError::Hn(e) => e.into(),
+            HnSaveError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+#[tracing::instrument(name = "controller::hn::save", skip_all)]
+pub async fn save(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::hn::save(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) if r.created => response::success(axum::http::StatusCode::CREATED, r),
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
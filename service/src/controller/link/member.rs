@@ -0,0 +1,172 @@
+use crate::controller::extract::{Json, Path};
+use crate::controller::link::types;
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link;
+use crate::services::link::member::types::MemberError;
+use crate::services::link::member::Scope;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Extension;
+
+impl From<MemberError> for Problem {
+    fn from(e: MemberError) -> Self {
+        match e {
+            MemberError::NotFound(m) => {
+                Problem::not_found("not_found", format!("not found: {}", m))
+            }
+            MemberError::Forbidden(m) => Problem::forbidden("forbidden", m),
+            MemberError::InvalidInput(field) => Problem::invalid(field),
+            MemberError::Conflict(m) => Problem::conflict("member_exists", m),
+            MemberError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+fn respond<T: serde::Serialize>(
+    result: Result<T, MemberError>,
+    ok: axum::http::StatusCode,
+) -> Response {
+    match result {
+        Ok(r) => response::success(ok, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::member::cat-list", skip_all)]
+pub async fn cat_list(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> Response {
+    let result = link::member::list(&ctx, user.user_id.as_str(), Scope::Category, &name).await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::cat-invite", skip_all)]
+pub async fn cat_invite(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(name): Path<String>,
+    Json(request): Json<types::MemberInviteReq>,
+) -> Response {
+    let result =
+        link::member::invite(&ctx, user.user_id.as_str(), Scope::Category, &name, request).await;
+    respond(result, axum::http::StatusCode::CREATED)
+}
+
+#[tracing::instrument(name = "controller::member::cat-update", skip_all)]
+pub async fn cat_update(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path((name, username)): Path<(String, String)>,
+    Json(request): Json<types::MemberUpdateReq>,
+) -> Response {
+    let result = link::member::update(
+        &ctx,
+        user.user_id.as_str(),
+        Scope::Category,
+        &name,
+        &username,
+        request,
+    )
+    .await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::cat-remove", skip_all)]
+pub async fn cat_remove(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path((name, username)): Path<(String, String)>,
+) -> Response {
+    let result = link::member::remove(
+        &ctx,
+        user.user_id.as_str(),
+        Scope::Category,
+        &name,
+        &username,
+    )
+    .await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::topic-list", skip_all)]
+pub async fn topic_list(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> Response {
+    let result = link::member::list(&ctx, user.user_id.as_str(), Scope::Topic, &name).await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::topic-invite", skip_all)]
+pub async fn topic_invite(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(name): Path<String>,
+    Json(request): Json<types::MemberInviteReq>,
+) -> Response {
+    let result =
+        link::member::invite(&ctx, user.user_id.as_str(), Scope::Topic, &name, request).await;
+    respond(result, axum::http::StatusCode::CREATED)
+}
+
+#[tracing::instrument(name = "controller::member::topic-update", skip_all)]
+pub async fn topic_update(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path((name, username)): Path<(String, String)>,
+    Json(request): Json<types::MemberUpdateReq>,
+) -> Response {
+    let result = link::member::update(
+        &ctx,
+        user.user_id.as_str(),
+        Scope::Topic,
+        &name,
+        &username,
+        request,
+    )
+    .await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::topic-remove", skip_all)]
+pub async fn topic_remove(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path((name, username)): Path<(String, String)>,
+) -> Response {
+    let result =
+        link::member::remove(&ctx, user.user_id.as_str(), Scope::Topic, &name, &username).await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::invitations", skip_all)]
+pub async fn invitations(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
+    let result = link::member::invitations(&ctx, user.user_id.as_str()).await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::accept", skip_all)]
+pub async fn accept(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    let result = link::member::accept(&ctx, user.user_id.as_str(), id).await;
+    respond(result, axum::http::StatusCode::OK)
+}
+
+#[tracing::instrument(name = "controller::member::decline", skip_all)]
+pub async fn decline(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    let result = link::member::decline(&ctx, user.user_id.as_str(), id).await;
+    respond(result, axum::http::StatusCode::OK)
+}
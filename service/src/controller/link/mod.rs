@@ -1,4 +1,12 @@
+pub mod alert;
 pub mod bookmark;
 pub mod cat;
+pub mod feed;
+pub mod hn;
+pub mod member;
+pub mod notification;
+pub mod share;
+pub mod source;
 pub mod topic;
 pub mod types;
+pub mod watch;
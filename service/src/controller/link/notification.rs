@@ -0,0 +1,79 @@
+use crate::controller::extract::{Path, Query};
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link;
+use crate::services::link::notification::types::NotificationError;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Extension;
+
+impl From<NotificationError> for Problem {
+    fn from(e: NotificationError) -> Self {
+        match e {
+            NotificationError::NotFound(m) => {
+                Problem::not_found("not_found", format!("not found: {}", m))
+            }
+            NotificationError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct ListQueryParams {
+    #[serde(default)]
+    unread: bool,
+    #[serde(default)]
+    offset: i64,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    link::notification::types::DEFAULT_PAGE_SIZE
+}
+
+#[tracing::instrument(name = "controller::notification::list", skip_all)]
+pub async fn list(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Query(q): Query<ListQueryParams>,
+) -> Response {
+    match link::notification::list(&ctx, user.user_id.as_str(), q.unread, q.offset, q.limit).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::notification::read", skip_all)]
+pub async fn read(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::notification::read(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::notification::read-all", skip_all)]
+pub async fn read_all(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
+    match link::notification::read_all(&ctx, user.user_id.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::notification::delete", skip_all)]
+pub async fn delete(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::notification::delete(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
@@ -0,0 +1,66 @@
+use crate::controller::extract::{Json, Path};
+use crate::controller::link::types;
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link;
+use crate::services::link::share::types::ShareError;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Extension;
+
+impl From<ShareError> for Problem {
+    fn from(e: ShareError) -> Self {
+        match e {
+            ShareError::NotFound(m) => Problem::not_found("not_found", format!("not found: {}", m)),
+            ShareError::InvalidInput(field) => Problem::invalid(field),
+            ShareError::PasswordHash(_) => Problem::internal(),
+            ShareError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+#[tracing::instrument(name = "controller::share::create", skip_all)]
+pub async fn create(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<types::ShareCreateReq>,
+) -> Response {
+    match link::share::create(&ctx, user.user_id.as_str(), request).await {
+        Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::share::list", skip_all)]
+pub async fn list(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
+    match link::share::list(&ctx, user.user_id.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::share::revoke", skip_all)]
+pub async fn revoke(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::share::revoke(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::share::access-log", skip_all)]
+pub async fn access_log(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> Response {
+    match link::share::access_log(&ctx, user.user_id.as_str(), id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
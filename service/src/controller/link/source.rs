@@ -0,0 +1,121 @@
+use crate::controller::extract::{Path, Query};
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link;
+use crate::services::link::source::types::LinkSourceError;
+use crate::sources::SourceError;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Extension;
+
+impl From<LinkSourceError> for Problem {
+    fn from(e: LinkSourceError) -> Self {
+        match e {
+            LinkSourceError::Source(e @ SourceError::UnknownProvider(_))
+            | LinkSourceError::Source(e @ SourceError::UnknownList(_))
+            | LinkSourceError::Source(e @ SourceError::NotFound(_)) => {
+                Problem::not_found("not_found", e.to_string())
+            }
+            LinkSourceError::Source(SourceError::Http(e)) if e.is_timeout() => Problem::new(
+                axum::http::StatusCode::GATEWAY_TIMEOUT,
+                "source_timeout",
+                "the link source didn't answer in time",
+            ),
+            LinkSourceError::Source(SourceError::Http(_)) => {
+                Problem::bad_gateway("source_unreachable", "the link source can't be reached")
+            }
+            LinkSourceError::Source(SourceError::UpstreamStatus(status)) => Problem::bad_gateway(
+                "source_upstream_error",
+                format!("the link source returned {}", status),
+            ),
+            LinkSourceError::Source(SourceError::Parse(_)) => Problem::bad_gateway(
+                "source_invalid_response",
+                "the link source answer can't be read",
+            ),
+            LinkSourceError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+#[tracing::instrument(name = "controller::source::providers", skip_all)]
+pub async fn providers(State(ctx): State<Ctx>) -> Response {
+    response::success(axum::http::StatusCode::OK, link::source::providers(&ctx))
+}
+
+#[derive(serde::Deserialize)]
+pub struct PageParams {
+    #[serde(default)]
+    offset: usize,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_limit() -> usize {
+    crate::sources::DEFAULT_PAGE_SIZE
+}
+
+#[tracing::instrument(name = "controller::source::list", skip_all)]
+pub async fn list(
+    State(ctx): State<Ctx>,
+    Path((provider, list)): Path<(String, String)>,
+    Query(q): Query<PageParams>,
+) -> Response {
+    match link::source::list(&ctx, &provider, &list, q.offset, q.limit).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::source::item", skip_all)]
+pub async fn item(
+    State(ctx): State<Ctx>,
+    Path((provider, id)): Path<(String, String)>,
+) -> Response {
+    match link::source::item(&ctx, &provider, &id).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct DiscussionParams {
+    #[serde(default = "default_max_depth")]
+    max_depth: usize,
+    #[serde(default = "default_max_nodes")]
+    max_nodes: usize,
+}
+
+fn default_max_depth() -> usize {
+    crate::hn::comments::DEFAULT_MAX_DEPTH
+}
+
+fn default_max_nodes() -> usize {
+    crate::hn::comments::DEFAULT_MAX_NODES
+}
+
+#[tracing::instrument(name = "controller::source::discussion", skip_all)]
+pub async fn discussion(
+    State(ctx): State<Ctx>,
+    Path((provider, id)): Path<(String, String)>,
+    Query(q): Query<DiscussionParams>,
+) -> Response {
+    match link::source::discussion(&ctx, &provider, &id, q.max_depth, q.max_nodes).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::source::save", skip_all)]
+pub async fn save(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path((provider, id)): Path<(String, String)>,
+) -> Response {
+    match link::source::save(&ctx, user.user_id.as_str(), &provider, &id).await {
+        Ok(r) if r.created => response::success(axum::http::StatusCode::CREATED, r),
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
@@ -1,24 +1,34 @@
+use crate::controller::extract::{Json, Path};
 use crate::controller::response;
 use crate::ctx::Ctx;
 use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
 use crate::services::link;
-use axum::extract::{Path, State};
+use crate::services::link::topic::types::TopicError;
+use axum::extract::State;
 use axum::response::Response;
 use axum::Extension;
 
+impl From<TopicError> for Problem {
+    fn from(e: TopicError) -> Self {
+        match e {
+            TopicError::NotFound(m) => Problem::not_found("not_found", format!("not found: {}", m)),
+            TopicError::Forbidden(m) => Problem::forbidden("forbidden", m),
+            TopicError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
 #[tracing::instrument(name = "controller::topic::create", skip_all, parent=None)]
 pub async fn create(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
-    axum::Json(request): axum::Json<super::types::TopicCreateReq>,
+    Json(request): Json<super::types::TopicCreateReq>,
 ) -> Response {
     tracing::info!(msg = "userid", user.user_id);
     match link::topic::create(&ctx, user.user_id.as_str(), request).await {
         Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -30,10 +40,7 @@ pub async fn get(
 ) -> Response {
     match link::topic::get(&ctx, user.user_id.as_str(), topic_name.as_str()).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -41,10 +48,7 @@ pub async fn get(
 pub async fn list(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
     match link::topic::list(&ctx, user.user_id.as_str()).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -53,14 +57,11 @@ pub async fn update(
     State(ctx): State<Ctx>,
     Extension(_user): Extension<AuthUser>,
     Path(topic_name): Path<String>,
-    axum::Json(request): axum::Json<super::types::TopicUpdateReq>,
+    Json(request): Json<super::types::TopicUpdateReq>,
 ) -> Response {
     match link::topic::update(&ctx, topic_name.as_str(), request).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -72,10 +73,7 @@ pub async fn delete(
 ) -> Response {
     match link::topic::delete(&ctx, user.user_id.as_str(), topic_name.as_str()).await {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -89,7 +87,7 @@ pub async fn add_cat(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(topic_name): Path<String>,
-    axum::Json(req): axum::Json<Categories>,
+    Json(req): Json<Categories>,
 ) -> Response {
     match link::topic::add_category(
         &ctx,
@@ -100,10 +98,7 @@ pub async fn add_cat(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
 
@@ -112,7 +107,7 @@ pub async fn remove_cat(
     State(ctx): State<Ctx>,
     Extension(user): Extension<AuthUser>,
     Path(topic_name): Path<String>,
-    axum::Json(req): axum::Json<Categories>,
+    Json(req): Json<Categories>,
 ) -> Response {
     match link::topic::remove_category(
         &ctx,
@@ -123,9 +118,6 @@ pub async fn remove_cat(
     .await
     {
         Ok(r) => response::success(axum::http::StatusCode::OK, r),
-        Err(e) => {
-            tracing::error!("err: {:?}", e);
-            response::error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        }
+        Err(e) => response::problem(e),
     }
 }
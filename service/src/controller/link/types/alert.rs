@@ -0,0 +1,32 @@
+#[derive(serde::Deserialize, Debug)]
+pub struct AlertReq {
+    pub name: String,
+    /// case-insensitive part of the title
+    pub title_contains: Option<String>,
+    /// `github.com` also matches `gist.github.com`
+    pub domain: Option<String>,
+    /// inclusive
+    pub min_score: Option<i32>,
+    /// matches are also saved as bookmarks in this category
+    pub category: Option<String>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct AlertResponse {
+    pub id: i64,
+    pub name: String,
+    pub title_contains: Option<String>,
+    pub domain: Option<String>,
+    pub min_score: Option<i32>,
+    pub category: Option<String>,
+    pub active: bool,
+    pub last_matched_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
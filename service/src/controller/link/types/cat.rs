@@ -23,6 +23,8 @@ pub struct CatGetRes {
     pub created_on: chrono::DateTime<chrono::Utc>,
     pub updated_on: chrono::DateTime<chrono::Utc>,
     pub topics: Vec<String>,
+    pub owner: String,
+    pub role: super::MemberRole,
 }
 
 #[derive(serde::Deserialize)]
@@ -0,0 +1,30 @@
+#[derive(serde::Deserialize, Debug)]
+pub struct FeedSubscribeReq {
+    pub url: String,
+    pub title: Option<String>,
+    pub category: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct FeedResponse {
+    pub id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub site_url: Option<String>,
+    pub category: String,
+    pub active: bool,
+    pub last_polled_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct FeedPollRes {
+    pub new_bookmarks: usize,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct OpmlImportRes {
+    pub feeds: usize,
+}
@@ -0,0 +1,18 @@
+#[derive(serde::Serialize, Debug)]
+pub struct HnMetaResponse {
+    pub id: i64,
+    pub item_type: String,
+    pub by: Option<String>,
+    pub score: i32,
+    pub descendants: i32,
+    pub posted_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub discussion_url: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct HnSaveRes {
+    /// `false` when the item was saved before and only its details were refreshed
+    pub created: bool,
+    pub bookmark: super::BmResponse,
+    pub hn: HnMetaResponse,
+}
@@ -0,0 +1,49 @@
+/// Ordered from least to most privileged.
+#[derive(
+    serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberRole {
+    /// sees the category or topic and its bookmarks
+    Viewer,
+    /// adds bookmarks, removes the ones they added
+    Contributor,
+    /// edits details, removes any bookmark, manages viewers and contributors
+    Editor,
+    /// manages every member
+    Owner,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct MemberInviteReq {
+    pub username: String,
+    pub role: MemberRole,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct MemberUpdateReq {
+    pub role: MemberRole,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct MemberResponse {
+    pub username: String,
+    pub role: MemberRole,
+    /// `None` for the user who created the category or topic
+    pub invited_by: Option<String>,
+    pub accepted_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub pending: bool,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct InvitationResponse {
+    pub id: i64,
+    /// `category` or `topic`
+    pub kind: &'static str,
+    /// `@owner/name`, as it is addressed once accepted
+    pub name: String,
+    pub owner: String,
+    pub role: MemberRole,
+    pub invited_by: String,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+}
@@ -1,7 +1,27 @@
+pub mod alert;
 pub mod bookmark;
 pub mod cat;
+pub mod feed;
+pub mod hn;
+pub mod member;
+pub mod notification;
+pub mod share;
+pub mod source;
 pub mod topic;
+pub mod watch;
 
+pub use alert::{AlertReq, AlertResponse};
 pub use bookmark::{AddCategories, BmCreateReq, BmResponse, BmUpdateReq, RemoveCategories};
 pub use cat::{CatCreateReq, CatGetRes, CatUpdateReq};
+pub use feed::{FeedPollRes, FeedResponse, FeedSubscribeReq, OpmlImportRes};
+pub use hn::{HnMetaResponse, HnSaveRes};
+pub use member::{
+    InvitationResponse, MemberInviteReq, MemberResponse, MemberRole, MemberUpdateReq,
+};
+pub use notification::{
+    NotificationKind, NotificationPage, NotificationReadAllRes, NotificationResponse,
+};
+pub use share::{ShareAccessResponse, ShareCreateReq, ShareCreateRes, ShareKind, ShareResponse};
+pub use source::SourceSaveRes;
 pub use topic::{TopicCreateReq, TopicGetRes, TopicUpdateReq};
+pub use watch::{WatchPollRes, WatchReq, WatchResponse};
@@ -0,0 +1,34 @@
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    HnWatch,
+    HnAlert,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct NotificationResponse {
+    pub id: i64,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub url: Option<String>,
+    /// sanitized HTML
+    pub body: Option<String>,
+    pub hn_id: Option<i64>,
+    /// set when the item was saved automatically
+    pub bookmark_id: Option<i64>,
+    pub read_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct NotificationPage {
+    pub unread: i64,
+    pub offset: i64,
+    pub limit: i64,
+    pub items: Vec<NotificationResponse>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct NotificationReadAllRes {
+    pub marked: u64,
+}
@@ -0,0 +1,51 @@
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareKind {
+    Bookmark,
+    Category,
+    Topic,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ShareCreateReq {
+    pub kind: ShareKind,
+    /// bookmark id, or the category/topic name
+    pub target: String,
+    pub expires_in_secs: Option<i64>,
+    pub password: Option<String>,
+    pub max_views: Option<i32>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct ShareCreateRes {
+    pub id: i64,
+    /// shown only once, only its hash is stored
+    pub token: String,
+    pub path: String,
+    pub expires_on: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct ShareResponse {
+    pub id: i64,
+    pub kind: Option<ShareKind>,
+    pub target_id: i64,
+    pub token_prefix: String,
+    pub password_protected: bool,
+    pub expires_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub max_views: Option<i32>,
+    pub view_count: i32,
+    pub revoked_on: Option<chrono::DateTime<chrono::Utc>>,
+    /// active, revoked, expired or exhausted
+    pub status: &'static str,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct ShareAccessResponse {
+    pub accessed_on: chrono::DateTime<chrono::Utc>,
+    pub granted: bool,
+    pub reason: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
@@ -0,0 +1,6 @@
+#[derive(serde::Serialize, Debug)]
+pub struct SourceSaveRes {
+    /// `false` when the user had already bookmarked the same url
+    pub created: bool,
+    pub bookmark: super::BmResponse,
+}
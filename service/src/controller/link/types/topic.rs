@@ -20,6 +20,8 @@ pub struct TopicGetRes {
     pub created_on: chrono::DateTime<chrono::Utc>,
     pub updated_on: chrono::DateTime<chrono::Utc>,
     pub categories: Vec<String>,
+    pub owner: String,
+    pub role: super::MemberRole,
 }
 
 #[derive(serde::Deserialize)]
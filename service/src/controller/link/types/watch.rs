@@ -0,0 +1,23 @@
+#[derive(serde::Deserialize, Debug)]
+pub struct WatchReq {
+    pub username: String,
+    /// new submissions are also saved as bookmarks in this category
+    pub category: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct WatchResponse {
+    pub username: String,
+    pub category: Option<String>,
+    pub last_seen_id: Option<i64>,
+    pub last_polled_on: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    pub created_on: chrono::DateTime<chrono::Utc>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct WatchPollRes {
+    pub notifications: usize,
+    pub new_bookmarks: usize,
+}
@@ -0,0 +1,79 @@
+use crate::controller::extract::{Json, Path};
+use crate::controller::link::types;
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::middlewares::user::AuthUser;
+use crate::problem::Problem;
+use crate::services::link;
+use crate::services::link::watch::types::WatchError;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Extension;
+
+impl From<WatchError> for Problem {
+    fn from(e: WatchError) -> Self {
+        match e {
+            WatchError::NotFound(m) => Problem::not_found("not_found", format!("not found: {}", m)),
+            WatchError::InvalidInput(field) => Problem::invalid(field),
+            WatchError::Hn(e) => e.into(),
+            WatchError::Save(e) => e.into(),
+            WatchError::Database(e) => Problem::database(&e),
+        }
+    }
+}
+
+#[tracing::instrument(name = "controller::watch::add", skip_all)]
+pub async fn add(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<types::WatchReq>,
+) -> Response {
+    match link::watch::add(&ctx, user.user_id.as_str(), request).await {
+        Ok(r) => response::success(axum::http::StatusCode::CREATED, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::watch::get", skip_all)]
+pub async fn get(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(username): Path<String>,
+) -> Response {
+    match link::watch::get(&ctx, user.user_id.as_str(), username.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::watch::list", skip_all)]
+pub async fn list(State(ctx): State<Ctx>, Extension(user): Extension<AuthUser>) -> Response {
+    match link::watch::list(&ctx, user.user_id.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::watch::remove", skip_all)]
+pub async fn remove(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(username): Path<String>,
+) -> Response {
+    match link::watch::remove(&ctx, user.user_id.as_str(), username.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::watch::refresh", skip_all)]
+pub async fn refresh(
+    State(ctx): State<Ctx>,
+    Extension(user): Extension<AuthUser>,
+    Path(username): Path<String>,
+) -> Response {
+    match link::watch::refresh(&ctx, user.user_id.as_str(), username.as_str()).await {
+        Ok(r) => response::success(axum::http::StatusCode::OK, r),
+        Err(e) => response::problem(e),
+    }
+}
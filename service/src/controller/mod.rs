@@ -1,11 +1,6 @@
-pub mod category;
-pub mod get;
+pub mod extract;
 pub mod link;
+pub mod public;
 pub mod response;
-pub mod save;
-pub mod topic;
 
-pub use get::get_urls;
-pub use save::save_url;
-
-pub use {response::error, response::success};
+pub use {response::problem, response::success};
@@ -0,0 +1,116 @@
+use crate::controller::extract::{Path, Query};
+use crate::controller::response;
+use crate::ctx::Ctx;
+use crate::problem::Problem;
+use crate::services::public;
+use crate::services::public::feed::{FeedUrls, Format};
+use axum::extract::State;
+use axum::http::{header, Extensions, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+#[derive(serde::Deserialize)]
+pub struct FeedQueryParams {
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+const MAX_LIMIT: i64 = 200;
+
+#[tracing::instrument(name = "controller::public::topic-feed", skip_all)]
+pub async fn topic(
+    State(ctx): State<Ctx>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    Path((user_id, topic_name, format)): Path<(String, String, String)>,
+    Query(q): Query<FeedQueryParams>,
+) -> Response {
+    let Some(format) = Format::from_name(&format) else {
+        return response::problem(Problem::not_found(
+            "unknown_feed_format",
+            format!("unknown feed format: {}", format),
+        ));
+    };
+    let limit = q.limit.clamp(1, MAX_LIMIT);
+    match public::topic_listing(&ctx, &user_id, &topic_name, limit, 0).await {
+        Ok(listing) => {
+            let page_url = format!(
+                "/-/ln/u/{}/t/{}",
+                public::segment(&user_id),
+                public::segment(&topic_name)
+            );
+            render(&ctx, &extensions, &headers, &listing, format, &page_url)
+        }
+        Err(e) => response::problem(e),
+    }
+}
+
+#[tracing::instrument(name = "controller::public::cat-feed", skip_all)]
+pub async fn cat(
+    State(ctx): State<Ctx>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    Path((user_id, cat_name, format)): Path<(String, String, String)>,
+    Query(q): Query<FeedQueryParams>,
+) -> Response {
+    let Some(format) = Format::from_name(&format) else {
+        return response::problem(Problem::not_found(
+            "unknown_feed_format",
+            format!("unknown feed format: {}", format),
+        ));
+    };
+    let limit = q.limit.clamp(1, MAX_LIMIT);
+    match public::cat_listing(&ctx, &user_id, &cat_name, limit, 0).await {
+        Ok(listing) => {
+            let page_url = format!(
+                "/-/ln/u/{}/c/{}",
+                public::segment(&user_id),
+                public::segment(&cat_name)
+            );
+            render(&ctx, &extensions, &headers, &listing, format, &page_url)
+        }
+        Err(e) => response::problem(e),
+    }
+}
+
+fn render(
+    ctx: &Ctx,
+    extensions: &Extensions,
+    headers: &HeaderMap,
+    listing: &public::Listing,
+    format: Format,
+    page_path: &str,
+) -> Response {
+    let etag = super::etag(listing, format.name());
+    let last_modified = super::http_date(listing.updated_on);
+    let cache_headers = [
+        (header::CACHE_CONTROL, super::CACHE_CONTROL.to_string()),
+        (header::ETAG, etag.clone()),
+        (header::LAST_MODIFIED, last_modified),
+    ];
+    if super::not_modified(headers, &etag, listing.updated_on) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    let base = super::base_url(super::peer(extensions), headers, &ctx.trusted_proxies);
+    let urls = FeedUrls {
+        feed_url: format!("{}{}/feed/{}", base, page_path, format.name()),
+        page_url: format!("{}{}", base, page_path),
+    };
+    match public::feed::render(format, listing, &urls) {
+        Ok(body) => (
+            StatusCode::OK,
+            cache_headers,
+            [(header::CONTENT_TYPE, format.content_type())],
+            body,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("err: {:?}", e);
+            Problem::internal().into_response()
+        }
+    }
+}
@@ -0,0 +1,110 @@
+pub mod feed;
+pub mod page;
+pub mod share;
+
+use crate::services::public::PublicError;
+use axum::extract::ConnectInfo;
+use axum::http::{Extensions, HeaderMap};
+use std::net::{IpAddr, SocketAddr};
+
+/// Public urls are absolute in feeds and link previews, built from the request's host.
+/// The responses are cached by shared proxies, so `X-Forwarded-Host`/`-Proto` only
+/// count when `peer` is one of the `trusted` proxies.
+pub fn base_url(peer: Option<IpAddr>, headers: &HeaderMap, trusted: &[IpAddr]) -> String {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let forwarded = |name: &str| {
+        peer.filter(|peer| trusted.contains(peer))
+            .and_then(|_| header(name))
+    };
+    let scheme = forwarded("x-forwarded-proto").unwrap_or("http");
+    let host = forwarded("x-forwarded-host")
+        .or_else(|| header(axum::http::header::HOST.as_str()))
+        .unwrap_or("127.0.0.1");
+    format!("{}://{}", scheme, host)
+}
+
+/// Address of the connected socket, absent when the router is driven without
+/// `into_make_service_with_connect_info` (tests)
+pub fn peer(extensions: &Extensions) -> Option<IpAddr> {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+pub fn status_of(e: &PublicError) -> axum::http::StatusCode {
+    match e {
+        PublicError::NotFound(_) => axum::http::StatusCode::NOT_FOUND,
+        PublicError::Database(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl From<PublicError> for crate::problem::Problem {
+    fn from(e: PublicError) -> Self {
+        match e {
+            PublicError::NotFound(m) => Self::not_found("not_found", format!("not found: {}", m)),
+            PublicError::Database(_) => Self::internal(),
+        }
+    }
+}
+
+/// Public pages are cached by browsers and proxies, revalidated through the
+/// `ETag`/`Last-Modified` validators.
+pub const CACHE_CONTROL: &str = "public, max-age=300";
+
+pub fn etag(listing: &crate::services::public::Listing, variant: &str) -> String {
+    format!(
+        "W/\"{}-{}-{}-{}\"",
+        variant,
+        listing.updated_on.timestamp_millis(),
+        listing.bookmarks.len(),
+        listing.bookmarks.first().map(|b| b.id).unwrap_or_default()
+    )
+}
+
+pub fn http_date(t: chrono::DateTime<chrono::Utc>) -> String {
+    t.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// `true` when the client already holds the current version.
+pub fn not_modified(
+    headers: &HeaderMap,
+    etag: &str,
+    updated_on: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    if let Some(inm) = headers
+        .get(axum::http::header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+    {
+        return inm.split(',').any(|t| t.trim() == etag || t.trim() == "*");
+    }
+    headers
+        .get(axum::http::header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok())
+        .is_some_and(|since| updated_on.timestamp() <= since.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_host_counts_only_from_trusted_proxies() {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", "links.example".parse().unwrap());
+        headers.insert("x-forwarded-host", "evil.example".parse().unwrap());
+        headers.insert("x-forwarded-proto", "https".parse().unwrap());
+        let proxy: IpAddr = "10.0.0.2".parse().unwrap();
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+
+        assert_eq!(
+            base_url(Some(client), &headers, &[proxy]),
+            "http://links.example"
+        );
+        assert_eq!(base_url(None, &headers, &[proxy]), "http://links.example");
+        assert_eq!(
+            base_url(Some(proxy), &headers, &[proxy]),
+            "https://evil.example"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::ctx::Ctx;
+use crate::services::public;
+use crate::services::public::page::{Kind, PageInfo};
+use axum::extract::{Path, State};
+use axum::http::{header, Extensions, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum_extra::extract::Query;
+
+const PAGE_SIZE: i64 = 30;
+/// Far beyond any listing, keeps the offset of a made up `page` from overflowing.
+pub const MAX_PAGE: i64 = 10_000;
+
+#[derive(serde::Deserialize)]
+pub struct PageQueryParams {
+    #[serde(default = "default_page")]
+    page: i64,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+#[tracing::instrument(name = "controller::public::topic-page", skip_all)]
+pub async fn topic(
+    State(ctx): State<Ctx>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    Path((user_id, topic_name)): Path<(String, String)>,
+    Query(q): Query<PageQueryParams>,
+) -> Response {
+    let page = q.page.clamp(1, MAX_PAGE);
+    // one extra row tells whether there is a next page
+    let listing = public::topic_listing(
+        &ctx,
+        &user_id,
+        &topic_name,
+        PAGE_SIZE + 1,
+        (page - 1) * PAGE_SIZE,
+    )
+    .await;
+    let path = format!(
+        "/-/ln/u/{}/t/{}",
+        public::segment(&user_id),
+        public::segment(&topic_name)
+    );
+    render(
+        &ctx,
+        &extensions,
+        &headers,
+        listing,
+        Kind::Topic,
+        path,
+        page,
+    )
+}
+
+#[tracing::instrument(name = "controller::public::cat-page", skip_all)]
+pub async fn cat(
+    State(ctx): State<Ctx>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    Path((user_id, cat_name)): Path<(String, String)>,
+    Query(q): Query<PageQueryParams>,
+) -> Response {
+    let page = q.page.clamp(1, MAX_PAGE);
+    let listing = public::cat_listing(
+        &ctx,
+        &user_id,
+        &cat_name,
+        PAGE_SIZE + 1,
+        (page - 1) * PAGE_SIZE,
+    )
+    .await;
+    let path = format!(
+        "/-/ln/u/{}/c/{}",
+        public::segment(&user_id),
+        public::segment(&cat_name)
+    );
+    render(
+        &ctx,
+        &extensions,
+        &headers,
+        listing,
+        Kind::Category,
+        path,
+        page,
+    )
+}
+
+fn render(
+    ctx: &Ctx,
+    extensions: &Extensions,
+    headers: &HeaderMap,
+    listing: Result<public::Listing, public::PublicError>,
+    kind: Kind,
+    path: String,
+    page: i64,
+) -> Response {
+    let mut listing = match listing {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("err: {:?}", e);
+            let status = super::status_of(&e);
+            let message = match e {
+                public::PublicError::NotFound(_) => "Not found",
+                public::PublicError::Database(_) => "Something went wrong",
+            };
+            return (
+                status,
+                Html(format!(
+                    "<!DOCTYPE html><title>{message}</title><h1>{message}</h1>"
+                )),
+            )
+                .into_response();
+        }
+    };
+    let has_next = listing.bookmarks.len() as i64 > PAGE_SIZE;
+    listing.bookmarks.truncate(PAGE_SIZE as usize);
+
+    let etag = super::etag(&listing, &format!("html-{}", page));
+    let cache_headers = [
+        (header::CACHE_CONTROL, super::CACHE_CONTROL.to_string()),
+        (header::ETAG, etag.clone()),
+        (header::LAST_MODIFIED, super::http_date(listing.updated_on)),
+    ];
+    if super::not_modified(headers, &etag, listing.updated_on) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    let info = PageInfo {
+        kind,
+        base_url: super::base_url(super::peer(extensions), headers, &ctx.trusted_proxies),
+        path,
+        page,
+        has_next,
+        shared: false,
+    };
+    (
+        StatusCode::OK,
+        cache_headers,
+        Html(public::page::render(&listing, &info)),
+    )
+        .into_response()
+}
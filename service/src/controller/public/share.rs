@@ -0,0 +1,244 @@
+use crate::ctx::Ctx;
+use crate::services::public;
+use crate::services::public::page::{Kind, PageInfo};
+use crate::services::public::share::{Opened, Visitor};
+use axum::extract::{Form, Path, State};
+use axum::http::{header, Extensions, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum_extra::extract::{CookieJar, Query};
+use std::net::IpAddr;
+
+const PAGE_SIZE: i64 = 30;
+const UNLOCK_COOKIE: &str = "ln-share-unlock";
+const VIEWED_COOKIE: &str = "ln-share-viewed";
+const USER_AGENT_MAX_CHARS: usize = 256;
+
+#[derive(serde::Deserialize)]
+pub struct SharePageParams {
+    #[serde(default = "default_page")]
+    page: i64,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+#[derive(serde::Deserialize)]
+pub struct UnlockForm {
+    password: String,
+}
+
+#[tracing::instrument(name = "controller::public::share-page", skip_all)]
+pub async fn page(
+    State(ctx): State<Ctx>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Path(token): Path<String>,
+    Query(q): Query<SharePageParams>,
+) -> Response {
+    let page = q.page.clamp(1, super::page::MAX_PAGE);
+    let peer = super::peer(&extensions);
+    open(&ctx, peer, &headers, &jar, &token, page, None).await
+}
+
+#[tracing::instrument(name = "controller::public::share-unlock", skip_all)]
+pub async fn unlock(
+    State(ctx): State<Ctx>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Path(token): Path<String>,
+    Form(form): Form<UnlockForm>,
+) -> Response {
+    let peer = super::peer(&extensions);
+    open(&ctx, peer, &headers, &jar, &token, 1, Some(form.password)).await
+}
+
+async fn open(
+    ctx: &Ctx,
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+    jar: &CookieJar,
+    token: &str,
+    page: i64,
+    password: Option<String>,
+) -> Response {
+    let path = format!("/-/ln/s/{}", public::segment(token));
+    let ip = client_ip(peer, headers, &ctx.trusted_proxies);
+    let visitor = Visitor {
+        ip: ip.map(|ip| ip.to_string()),
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.chars().take(USER_AGENT_MAX_CHARS).collect()),
+        password,
+        unlock: jar.get(UNLOCK_COOKIE).map(|c| c.value().to_string()),
+        viewed: jar.get(VIEWED_COOKIE).map(|c| c.value().to_string()),
+    };
+
+    // one extra row tells whether there is a next page
+    let opened =
+        public::share::open(ctx, token, visitor, PAGE_SIZE + 1, (page - 1) * PAGE_SIZE).await;
+    let (status, html, cookies) = match opened {
+        Ok(Opened::Granted {
+            mut listing,
+            kind,
+            unlock,
+            viewed,
+        }) => {
+            let has_next = listing.bookmarks.len() as i64 > PAGE_SIZE;
+            listing.bookmarks.truncate(PAGE_SIZE as usize);
+            let info = PageInfo {
+                kind: match kind {
+                    crate::controller::link::types::ShareKind::Bookmark => Kind::Bookmark,
+                    crate::controller::link::types::ShareKind::Category => Kind::Category,
+                    crate::controller::link::types::ShareKind::Topic => Kind::Topic,
+                },
+                base_url: super::base_url(peer, headers, &ctx.trusted_proxies),
+                path: path.clone(),
+                page,
+                has_next,
+                shared: true,
+            };
+            let cookies = [(UNLOCK_COOKIE, unlock), (VIEWED_COOKIE, viewed)];
+            (
+                StatusCode::OK,
+                public::page::render(&listing, &info),
+                cookies
+                    .into_iter()
+                    .filter_map(|(n, v)| Some((n, v?)))
+                    .collect(),
+            )
+        }
+        Ok(Opened::PasswordRequired { bad_password }) => (
+            StatusCode::UNAUTHORIZED,
+            public::page::render_password(&path, bad_password),
+            Vec::new(),
+        ),
+        Ok(Opened::Locked) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            public::page::render_message(
+                "Link locked",
+                "Too many wrong passwords, try again later.",
+            ),
+            Vec::new(),
+        ),
+        Ok(Opened::Closed(reason)) => {
+            let message = match reason {
+                "revoked" => "This link has been revoked by its owner.",
+                "expired" => "This link has expired.",
+                _ => "This link has reached its view limit.",
+            };
+            (
+                StatusCode::GONE,
+                public::page::render_message("Link unavailable", message),
+                Vec::new(),
+            )
+        }
+        Err(e) => {
+            tracing::error!("err: {:?}", e);
+            let (title, message) = match e {
+                public::PublicError::NotFound(_) => ("Not found", "This link does not exist."),
+                public::PublicError::Database(_) => {
+                    ("Something went wrong", "Please try again later.")
+                }
+            };
+            (
+                super::status_of(&e),
+                public::page::render_message(title, message),
+                Vec::new(),
+            )
+        }
+    };
+
+    let jar = cookies.into_iter().fold(jar.clone(), |jar, (name, value)| {
+        // followed links from elsewhere carry the viewer cookie, not the unlock one
+        let same_site = if name == VIEWED_COOKIE {
+            SameSite::Lax
+        } else {
+            SameSite::Strict
+        };
+        jar.add(
+            Cookie::build((name, value))
+                .path(path.clone())
+                .http_only(true)
+                .same_site(same_site)
+                .build(),
+        )
+    });
+    (
+        status,
+        jar,
+        [
+            (header::CACHE_CONTROL, "private, no-store"),
+            (header::REFERRER_POLICY, "no-referrer"),
+        ],
+        Html(html),
+    )
+        .into_response()
+}
+
+/// The visitor is the connected peer, unless that is a trusted proxy: then the
+/// nearest untrusted hop of `X-Forwarded-For` (read right to left, the left
+/// end is whatever the client sent), or `X-Real-IP`
+fn client_ip(peer: Option<IpAddr>, headers: &HeaderMap, trusted: &[IpAddr]) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted.contains(&peer) {
+        return Some(peer);
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let forwarded = header("x-forwarded-for").and_then(|v| {
+        v.rsplit(',')
+            .map(|hop| hop.trim().parse::<IpAddr>().ok())
+            .find(|hop| hop.is_none_or(|ip| !trusted.contains(&ip)))
+            .flatten()
+    });
+    forwarded
+        .or_else(|| header("x-real-ip").and_then(|v| v.trim().parse().ok()))
+        .or(Some(peer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn forwarded_headers_count_only_from_trusted_proxies() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        let proxy = ip("10.0.0.2");
+        let forged = headers(&[("x-forwarded-for", "1.1.1.1"), ("x-real-ip", "1.1.1.1")]);
+        assert_eq!(
+            client_ip(Some(ip("203.0.113.7")), &forged, &[proxy]),
+            Some(ip("203.0.113.7"))
+        );
+        assert_eq!(client_ip(None, &forged, &[proxy]), None);
+
+        // the client's own X-Forwarded-For is the left end, the proxies append
+        let chain = headers(&[("x-forwarded-for", "1.1.1.1, 203.0.113.7, 10.0.0.3")]);
+        assert_eq!(
+            client_ip(Some(proxy), &chain, &[proxy, ip("10.0.0.3")]),
+            Some(ip("203.0.113.7"))
+        );
+        let garbage = headers(&[("x-forwarded-for", "1.1.1.1, nonsense")]);
+        assert_eq!(client_ip(Some(proxy), &garbage, &[proxy]), Some(proxy));
+        let real = headers(&[("x-real-ip", "203.0.113.7")]);
+        assert_eq!(
+            client_ip(Some(proxy), &real, &[proxy]),
+            Some(ip("203.0.113.7"))
+        );
+        assert_eq!(
+            client_ip(Some(proxy), &HeaderMap::new(), &[proxy]),
+            Some(proxy)
+        );
+    }
+}
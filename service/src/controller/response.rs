@@ -19,21 +19,18 @@ pub fn success<T: serde::Serialize>(
         .into_response()
 }
 
-pub fn error<T: serde::Serialize>(
-    status: axum::http::StatusCode,
-    error: T,
-) -> axum::response::Response {
-    #[derive(serde::Serialize)]
-    struct Error<T> {
-        error: T,
-        success: bool,
+/// Logs `e` and answers with the problem it converts into: errors of ours as
+/// `error`, the caller's as `warn`.
+pub fn problem<E>(e: E) -> axum::response::Response
+where
+    E: Into<crate::problem::Problem> + std::fmt::Debug,
+{
+    let repr = format!("{:?}", e);
+    let problem = e.into();
+    if problem.status.is_server_error() {
+        tracing::error!("err: {}", repr);
+    } else {
+        tracing::warn!("err: {}", repr);
     }
-    (
-        status,
-        axum::Json(Error {
-            error,
-            success: false,
-        }),
-    )
-        .into_response()
+    problem.into_response()
 }
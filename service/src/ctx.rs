@@ -1,11 +1,16 @@
-type CategoryName = String;
-type CategoryID = i64;
-
 #[derive(Clone)]
 pub struct Ctx {
-    pub pg_pool: sqlx::PgPool,
+    pub db: linkdb::Db,
+    pub repos: linkdb::repo::Repos,
     pub secret: String,
+    /// `Domain` of the login cookie
+    pub cookie_domain: String,
+    /// proxies whose forwarding headers name the client
+    pub trusted_proxies: Vec<std::net::IpAddr>,
     pub static_dir: std::path::PathBuf,
-    pub category_map:
-        std::sync::Arc<std::sync::RwLock<std::collections::HashMap<CategoryName, CategoryID>>>,
+    pub feed_client: reqwest::Client,
+    pub hn_client: crate::hn::HnClient,
+    pub sources: crate::sources::Registry,
+    /// of the background workers, reported by readiness
+    pub heartbeats: crate::heartbeat::Heartbeats,
 }
@@ -0,0 +1,98 @@
+//! Background workers beat after every job they do, readiness reports a worker
+//! silent for longer than it allowed itself as down.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Slack a worker is given on top of its own tick, covers the longest single
+/// job: one upstream request with its timeout and the writes that follow.
+pub const JOB_GRACE: Duration = Duration::from_secs(120);
+
+/// The last beat of every registered worker, shared with the health routes.
+#[derive(Clone, Default)]
+pub struct Heartbeats(Arc<Mutex<BTreeMap<&'static str, Beat>>>);
+
+#[derive(Clone, Copy)]
+struct Beat {
+    last: Instant,
+    max_silence: Duration,
+}
+
+/// Handed to a worker by `Heartbeats::register`.
+#[derive(Clone)]
+pub struct Heartbeat {
+    worker: &'static str,
+    beats: Heartbeats,
+}
+
+#[derive(Debug)]
+pub struct WorkerBeat {
+    pub worker: &'static str,
+    pub silent_for: Duration,
+    pub max_silence: Duration,
+}
+
+impl WorkerBeat {
+    pub fn is_alive(&self) -> bool {
+        self.silent_for <= self.max_silence
+    }
+}
+
+impl Heartbeats {
+    /// Registers a worker as beating now, it is stale once silent for longer
+    /// than `max_silence`.
+    pub fn register(&self, worker: &'static str, max_silence: Duration) -> Heartbeat {
+        let beat = Beat {
+            last: Instant::now(),
+            max_silence,
+        };
+        self.0.lock().unwrap().insert(worker, beat);
+        Heartbeat {
+            worker,
+            beats: self.clone(),
+        }
+    }
+
+    pub fn workers(&self) -> Vec<WorkerBeat> {
+        let now = Instant::now();
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(worker, beat)| WorkerBeat {
+                worker,
+                silent_for: now.saturating_duration_since(beat.last),
+                max_silence: beat.max_silence,
+            })
+            .collect()
+    }
+}
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        if let Some(beat) = self.beats.0.lock().unwrap().get_mut(self.worker) {
+            beat.last = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silent_worker_is_stale() {
+        let beats = Heartbeats::default();
+        let feed = beats.register("feed_poller", Duration::from_secs(60));
+        let _ingest = beats.register("hn_ingest", Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(5));
+        feed.beat();
+
+        let workers = beats.workers();
+        assert_eq!(workers[0].worker, "feed_poller");
+        assert!(workers[0].is_alive());
+        assert_eq!(workers[1].worker, "hn_ingest");
+        assert!(!workers[1].is_alive());
+    }
+}
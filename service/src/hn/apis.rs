@@ -1,4 +1,6 @@
-#[derive(serde::Deserialize, serde::Serialize)]
+use super::HnClient;
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct HNItem {
     pub id: i64,
     pub by: Option<String>,
@@ -10,55 +12,106 @@ pub struct HNItem {
     pub url: Option<String>,
     pub score: Option<u64>,
     pub title: Option<String>,
+    /// total comment count of a story or poll
+    pub descendants: Option<u64>,
+    pub deleted: Option<bool>,
+    pub dead: Option<bool>,
 }
 
-pub async fn get_item(item_id: i64) -> Result<HNItem, super::HNError> {
-    let url = format!(
-        "https://hacker-news.firebaseio.com/v0/item/{}.json",
-        item_id
-    );
-    Ok(crate::utils::http::get(&url, &Default::default()).await?)
+/// HN answers `null` for ids it never had, and a stub with `deleted` for
+/// removed items, those are `NotFound` and `Deleted`.
+pub async fn get_item(client: &HnClient, item_id: i64) -> Result<HNItem, super::HNError> {
+    if item_id <= 0 {
+        return Err(super::HNError::InvalidInput(
+            crate::problem::FieldError::new("id", format!("must be positive, got `{}`", item_id)),
+        ));
+    }
+    let item = client
+        .item(item_id)
+        .await?
+        .ok_or_else(|| super::HNError::NotFound(format!("item with id: `{}`", item_id)))?;
+    if item.deleted == Some(true) {
+        return Err(super::HNError::Deleted(format!(
+            "item with id: `{}`",
+            item_id
+        )));
+    }
+    Ok(item)
 }
 
-pub async fn get_items(item_ids: &[i64]) -> Result<Vec<HNItem>, super::HNError> {
-    // call hn to get the item
-    let mut future_apis = vec![];
-    for id in item_ids {
-        future_apis.push(get_item(*id));
-    }
-    let response = futures::future::try_join_all(future_apis).await?;
-    Ok(response)
+pub async fn get_items(client: &HnClient, item_ids: &[i64]) -> Result<Vec<HNItem>, super::HNError> {
+    client.items(item_ids).await
 }
 
-pub async fn max_item_id() -> Result<i64, super::HNError> {
-    Ok(1)
+pub async fn max_item_id(client: &HnClient) -> Result<i64, super::HNError> {
+    client.max_item_id().await
 }
 
-#[derive(serde::Serialize)]
-pub struct TopStory {
-    items: Vec<HNItem>,
+pub const DEFAULT_PAGE_SIZE: usize = 30;
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// The story lists HN publishes, up to 500 ids for top/new/best and 200 for the others.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StoryList {
+    Top,
+    New,
+    Best,
+    Ask,
+    Show,
+    Job,
 }
 
-pub async fn top_stories() -> Result<Vec<TopStory>, super::HNError> {
-    let url = "https://hacker-news.firebaseio.com/v0/newstories.json";
-    let items_ids: Vec<i64> = crate::utils::http::get(&url, &Default::default()).await?;
-    let items = get_items(items_ids.as_slice()).await?;
-    Ok(vec![TopStory { items }])
+impl StoryList {
+    pub fn path(&self) -> &'static str {
+        match self {
+            StoryList::Top => "topstories",
+            StoryList::New => "newstories",
+            StoryList::Best => "beststories",
+            StoryList::Ask => "askstories",
+            StoryList::Show => "showstories",
+            StoryList::Job => "jobstories",
+        }
+    }
 }
 
+/// One page of a story list, only the items of the page are fetched.
 #[derive(serde::Serialize)]
-pub struct AskStory {
-    items: Vec<HNItem>,
+pub struct StoryPage {
+    pub list: StoryList,
+    pub offset: usize,
+    pub limit: usize,
+    /// number of ids in the whole list
+    pub total: usize,
+    /// `None` on the last page
+    pub next_offset: Option<usize>,
+    /// items which couldn't be loaded are left out, so it can be shorter than `limit`
+    pub items: Vec<HNItem>,
 }
 
-pub async fn ask_stories() -> Result<Vec<AskStory>, super::HNError> {
-    let url = "https://hacker-news.firebaseio.com/v0/askstories.json";
-    let items_ids: Vec<i64> = crate::utils::http::get(&url, &Default::default()).await?;
-    let items = get_items(items_ids.as_slice()).await?;
-    Ok(vec![AskStory { items }])
+pub async fn stories(
+    client: &HnClient,
+    list: StoryList,
+    offset: usize,
+    limit: usize,
+) -> Result<StoryPage, super::HNError> {
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let ids = client.story_ids(list.path()).await?;
+    let start = offset.min(ids.len());
+    let end = offset.saturating_add(limit).min(ids.len());
+    let items = get_items(client, &ids[start..end]).await?;
+
+    Ok(StoryPage {
+        list,
+        offset,
+        limit,
+        total: ids.len(),
+        next_offset: (end < ids.len()).then_some(end),
+        items,
+    })
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct HNUserDetails {
     pub about: Option<String>,
     pub created: u64,
@@ -67,11 +120,29 @@ pub struct HNUserDetails {
     pub submitted: Option<Vec<i64>>,
 }
 
-pub async fn user_details(username: &str) -> Result<HNUserDetails, super::HNError> {
-    let url = format!(
-        " https://hacker-news.firebaseio.com/v0/user/{}.json",
-        username.trim()
-    );
-    let user_details: HNUserDetails = crate::utils::http::get(&url, &Default::default()).await?;
-    Ok(user_details)
+/// HN usernames are 2 to 15 letters, digits, `-` or `_`.
+pub fn valid_username(username: &str) -> bool {
+    (2..=15).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub async fn user_details(
+    client: &HnClient,
+    username: &str,
+) -> Result<HNUserDetails, super::HNError> {
+    let username = username.trim();
+    if !valid_username(username) {
+        return Err(super::HNError::InvalidInput(
+            crate::problem::FieldError::new(
+                "username",
+                format!("invalid hn username: `{}`", username),
+            ),
+        ));
+    }
+    client
+        .user(username)
+        .await?
+        .ok_or_else(|| super::HNError::NotFound(format!("user: `{}`", username)))
 }
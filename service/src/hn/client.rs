@@ -0,0 +1,270 @@
+use super::apis::{HNItem, HNUserDetails};
+use super::cache::TtlCache;
+use super::HNError;
+use crate::settings::HnSettings;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Hacker News API client shared by every request: one connection pool, at most
+/// `max_concurrency` requests in flight, retries with backoff and a TTL cache
+/// for items and story lists.
+#[derive(Clone)]
+pub struct HnClient {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    http: reqwest::Client,
+    base_url: String,
+    permits: tokio::sync::Semaphore,
+    max_concurrency: usize,
+    retries: u32,
+    retry_backoff: Duration,
+    items: TtlCache<i64, HNItem>,
+    lists: TtlCache<String, Arc<Vec<i64>>>,
+}
+
+impl HnClient {
+    pub fn new(settings: &HnSettings) -> Result<Self, reqwest::Error> {
+        let max_concurrency = settings.max_concurrency.max(1);
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.request_timeout_secs))
+            .connect_timeout(Duration::from_secs(settings.request_timeout_secs))
+            .pool_max_idle_per_host(max_concurrency)
+            .user_agent(concat!("linknova/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                http,
+                base_url: settings.base_url.trim_end_matches('/').to_string(),
+                permits: tokio::sync::Semaphore::new(max_concurrency),
+                max_concurrency,
+                retries: settings.retries,
+                retry_backoff: Duration::from_millis(settings.retry_backoff_ms),
+                items: TtlCache::new(
+                    Duration::from_secs(settings.item_ttl_secs),
+                    settings.cache_max_items,
+                ),
+                lists: TtlCache::new(Duration::from_secs(settings.list_ttl_secs), 64),
+            }),
+        })
+    }
+
+    /// GET `{base_url}/{path}`, retrying timeouts, connection errors, 429 and 5xx.
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, HNError> {
+        let url = format!("{}/{}", self.inner.base_url, path);
+        let endpoint = endpoint(path);
+        let mut attempt = 0;
+        loop {
+            let result = {
+                let _permit = self
+                    .inner
+                    .permits
+                    .acquire()
+                    .await
+                    .expect("hn client semaphore is never closed");
+                let started = std::time::Instant::now();
+                let result = self
+                    .inner
+                    .http
+                    .get(&url)
+                    .headers(crate::telemetry::outbound_headers())
+                    .send()
+                    .await;
+                let outcome = match &result {
+                    Ok(response) => response.status().as_u16().to_string(),
+                    Err(e) if e.is_timeout() => "timeout".to_string(),
+                    Err(_) => "error".to_string(),
+                };
+                crate::metrics::hn_request(endpoint, outcome, started.elapsed());
+                result
+            };
+            let retryable = match result {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.json::<T>().await?);
+                }
+                Ok(response) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                    HNError::RateLimited(retry_after(&response))
+                }
+                Ok(response) if response.status().is_server_error() => {
+                    HNError::UpstreamStatus(response.status().as_u16())
+                }
+                Ok(response) => return Err(HNError::UpstreamStatus(response.status().as_u16())),
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => e.into(),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= self.inner.retries {
+                return Err(retryable);
+            }
+            attempt += 1;
+            crate::metrics::hn_retry(endpoint);
+            let backoff = self.inner.retry_backoff * 2u32.saturating_pow(attempt - 1);
+            tracing::warn!(%url, attempt, err = %retryable, "retrying hn request");
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// `None` when HN has no such item.
+    #[tracing::instrument(name = "hn::client::item", skip(self))]
+    pub async fn item(&self, id: i64) -> Result<Option<HNItem>, HNError> {
+        let cached = self.inner.items.get(&id);
+        crate::metrics::hn_cache("items", cached.is_some());
+        if let Some(item) = cached {
+            return Ok(Some(item));
+        }
+        let item: Option<HNItem> = self.get_json(&format!("item/{}.json", id)).await?;
+        if let Some(item) = &item {
+            self.inner.items.insert(id, item.clone());
+        }
+        Ok(item)
+    }
+
+    /// Same as `item` but always asks HN, for when current score and comment
+    /// count matter.
+    #[tracing::instrument(name = "hn::client::refresh-item", skip(self))]
+    pub async fn refresh_item(&self, id: i64) -> Result<Option<HNItem>, HNError> {
+        let item: Option<HNItem> = self.get_json(&format!("item/{}.json", id)).await?;
+        if let Some(item) = &item {
+            self.inner.items.insert(id, item.clone());
+        }
+        Ok(item)
+    }
+
+    /// Items in the order of `ids`. Items which fail to load or don't exist are
+    /// left out, only failing every one of them is an error.
+    #[tracing::instrument(name = "hn::client::items", skip_all, fields(count = ids.len()))]
+    pub async fn items(&self, ids: &[i64]) -> Result<Vec<HNItem>, HNError> {
+        match self.collect_items(ids, false).await {
+            (items, _, Some(e)) if items.is_empty() => Err(e),
+            (items, _, _) => Ok(items),
+        }
+    }
+
+    /// Same as `items`, along with the ids which failed to load for a reason
+    /// worth retrying, in order. Items which don't exist, or which HN answers
+    /// with what can't be read, are in neither list. Only failing every one of
+    /// them for a reason worth retrying is an error.
+    #[tracing::instrument(name = "hn::client::items-and-failures", skip_all, fields(count = ids.len()))]
+    pub async fn items_and_failures(
+        &self,
+        ids: &[i64],
+    ) -> Result<(Vec<HNItem>, Vec<i64>), HNError> {
+        match self.collect_items(ids, false).await {
+            (items, _, Some(e)) if items.is_empty() && e.is_retryable() => Err(e),
+            (items, failed, _) => Ok((items, failed)),
+        }
+    }
+
+    /// Same as `items` but bypasses the cache, see `refresh_item`.
+    #[tracing::instrument(name = "hn::client::refresh-items", skip_all, fields(count = ids.len()))]
+    pub async fn refresh_items(&self, ids: &[i64]) -> Result<Vec<HNItem>, HNError> {
+        match self.collect_items(ids, true).await {
+            (items, _, Some(e)) if items.is_empty() => Err(e),
+            (items, _, _) => Ok(items),
+        }
+    }
+
+    /// The items which loaded, the ids which failed to for a reason worth
+    /// retrying, and the last error of any failure.
+    async fn collect_items(
+        &self,
+        ids: &[i64],
+        fresh: bool,
+    ) -> (Vec<HNItem>, Vec<i64>, Option<HNError>) {
+        let results: Vec<_> = futures::stream::iter(ids.iter().copied())
+            .map(|id| async move {
+                let item = if fresh {
+                    self.refresh_item(id).await
+                } else {
+                    self.item(id).await
+                };
+                (id, item)
+            })
+            .buffered(self.inner.max_concurrency)
+            .collect()
+            .await;
+
+        let mut items = Vec::with_capacity(results.len());
+        let mut failed = Vec::new();
+        let mut last_error: Option<HNError> = None;
+        for (id, result) in results {
+            match result {
+                Ok(Some(item)) => items.push(item),
+                Ok(None) => tracing::debug!(id, "hn item not found"),
+                Err(e) if e.is_retryable() => {
+                    tracing::warn!(id, err = %e, "not able to fetch hn item");
+                    failed.push(id);
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    tracing::warn!(id, err = %e, "skipping hn item which can't be loaded");
+                    // a retryable error tells more of why nothing loaded
+                    if !last_error.as_ref().is_some_and(HNError::is_retryable) {
+                        last_error = Some(e);
+                    }
+                }
+            }
+        }
+        (items, failed, last_error)
+    }
+
+    /// Ids of a story list such as `topstories` or `askstories`.
+    #[tracing::instrument(name = "hn::client::story-ids", skip(self))]
+    pub async fn story_ids(&self, list: &str) -> Result<Arc<Vec<i64>>, HNError> {
+        let key = list.to_string();
+        let cached = self.inner.lists.get(&key);
+        crate::metrics::hn_cache("lists", cached.is_some());
+        if let Some(ids) = cached {
+            return Ok(ids);
+        }
+        let ids: Option<Vec<i64>> = self.get_json(&format!("{}.json", list)).await?;
+        let ids = Arc::new(ids.unwrap_or_default());
+        self.inner.lists.insert(key, ids.clone());
+        Ok(ids)
+    }
+
+    /// Id of the newest item, never cached.
+    #[tracing::instrument(name = "hn::client::max-item-id", skip(self))]
+    pub async fn max_item_id(&self) -> Result<i64, HNError> {
+        let id: Option<i64> = self.get_json("maxitem.json").await?;
+        id.ok_or_else(|| HNError::NotFound("maxitem".to_string()))
+    }
+
+    /// `None` when HN has no such user.
+    #[tracing::instrument(name = "hn::client::user", skip(self))]
+    pub async fn user(&self, username: &str) -> Result<Option<HNUserDetails>, HNError> {
+        self.get_json(&format!("user/{}.json", username)).await
+    }
+}
+
+/// Metric label of an API path: `item`, `user`, `maxitem` or the story list,
+/// never an id.
+fn endpoint(path: &str) -> &'static str {
+    match path.split(['/', '.']).next().unwrap_or_default() {
+        "item" => "item",
+        "user" => "user",
+        "maxitem" => "maxitem",
+        "topstories" => "topstories",
+        "newstories" => "newstories",
+        "beststories" => "beststories",
+        "askstories" => "askstories",
+        "showstories" => "showstories",
+        "jobstories" => "jobstories",
+        _ => "other",
+    }
+}
+
+/// Seconds from a `Retry-After` header, the http-date form is ignored.
+fn retry_after(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
@@ -0,0 +1,130 @@
+use super::apis::HNItem;
+use super::{HNError, HnClient};
+use std::collections::HashMap;
+
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+pub const MAX_DEPTH: usize = 32;
+pub const DEFAULT_MAX_NODES: usize = 300;
+pub const MAX_NODES: usize = 2000;
+
+/// Discussion of an item, as much of it as the limits allow.
+#[derive(serde::Serialize)]
+pub struct CommentTree {
+    /// the item itself, its `text` sanitized like the comments
+    pub item: HNItem,
+    pub comments: Vec<CommentNode>,
+    /// comments fetched, deleted and dead ones included
+    pub fetched: usize,
+    /// `true` when the depth or node limit, or a failing request, cut the tree short
+    pub truncated: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct CommentNode {
+    pub id: i64,
+    /// `None` for deleted and dead comments
+    pub by: Option<String>,
+    pub time: Option<u64>,
+    /// sanitized html, `None` for deleted and dead comments
+    pub text: Option<String>,
+    pub deleted: bool,
+    pub dead: bool,
+    pub depth: usize,
+    pub replies: Vec<CommentNode>,
+    /// replies which were not fetched
+    pub more: usize,
+}
+
+/// Fetches the comment tree of `id` breadth first, one level at a time, so when
+/// `max_nodes` runs out the upper levels are complete rather than one deep branch.
+#[tracing::instrument(name = "hn::comments::tree", skip(client))]
+pub async fn tree(
+    client: &HnClient,
+    id: i64,
+    max_depth: usize,
+    max_nodes: usize,
+) -> Result<CommentTree, HNError> {
+    let max_depth = max_depth.clamp(1, MAX_DEPTH);
+    let max_nodes = max_nodes.clamp(1, MAX_NODES);
+    let mut root = super::apis::get_item(client, id).await?;
+    root.text = root.text.as_deref().map(sanitize);
+
+    let mut fetched: HashMap<i64, HNItem> = HashMap::new();
+    let mut level: Vec<i64> = root.kids.clone().unwrap_or_default();
+    let mut depth = 1;
+    let mut truncated = false;
+    while !level.is_empty() {
+        let budget = max_nodes - fetched.len();
+        if depth > max_depth || budget == 0 {
+            truncated = true;
+            break;
+        }
+        if level.len() > budget {
+            level.truncate(budget);
+            truncated = true;
+        }
+
+        let items = match client.items(&level).await {
+            Ok(items) => items,
+            Err(e) => {
+                tracing::warn!(id, depth, err = %e, "not able to fetch hn comments");
+                truncated = true;
+                break;
+            }
+        };
+        truncated |= items.len() < level.len();
+        level = items
+            .iter()
+            .flat_map(|i| i.kids.iter().flatten().copied())
+            .collect();
+        fetched.extend(items.into_iter().map(|i| (i.id, i)));
+        depth += 1;
+    }
+
+    let comments = build(root.kids.as_deref().unwrap_or_default(), &fetched, 1);
+    Ok(CommentTree {
+        item: root,
+        comments,
+        fetched: fetched.len(),
+        truncated,
+    })
+}
+
+fn build(kids: &[i64], fetched: &HashMap<i64, HNItem>, depth: usize) -> Vec<CommentNode> {
+    kids.iter()
+        .filter_map(|id| fetched.get(id))
+        .map(|item| {
+            let kids = item.kids.as_deref().unwrap_or_default();
+            let replies = build(kids, fetched, depth + 1);
+            let deleted = item.deleted.unwrap_or(false);
+            let dead = item.dead.unwrap_or(false);
+            let hidden = deleted || dead;
+            CommentNode {
+                id: item.id,
+                by: item.by.clone().filter(|_| !hidden),
+                time: item.time,
+                text: item.text.as_deref().filter(|_| !hidden).map(sanitize),
+                deleted,
+                dead,
+                depth,
+                more: kids.len() - replies.len(),
+                replies,
+            }
+        })
+        .collect()
+}
+
+static SANITIZER: std::sync::LazyLock<ammonia::Builder<'static>> = std::sync::LazyLock::new(|| {
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .add_tags(["p", "a", "i", "b", "em", "strong", "pre", "code", "br"])
+        .add_tag_attributes("a", ["href"])
+        .add_url_schemes(["http", "https"])
+        .link_rel(Some("nofollow noopener noreferrer"));
+    builder
+});
+
+/// HN comment html limited to the tags HN itself produces, links only to http(s).
+pub fn sanitize(html: &str) -> String {
+    SANITIZER.clean(html).to_string()
+}
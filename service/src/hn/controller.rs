@@ -1,30 +1,264 @@
-use crate::{error, success};
-use axum::extract::Path;
+use crate::controller::extract::{Path, Query};
+use crate::controller::response::problem;
+use crate::ctx::Ctx;
+use crate::hn::apis::StoryList;
+use crate::hn::HNError;
+use crate::problem::Problem;
+use crate::success;
+use axum::extract::State;
+use axum::http::StatusCode;
 
-pub async fn get_item(Path(id): Path<i64>) -> axum::response::Response {
-    match crate::hn::apis::get_item(id).await {
+impl From<HNError> for Problem {
+    fn from(e: HNError) -> Self {
+        let status = match e {
+            HNError::NotFound(_) | HNError::Deleted(_) => StatusCode::NOT_FOUND,
+            HNError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            HNError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            HNError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            HNError::HttpReqwestError(_)
+            | HNError::UpstreamStatus(_)
+            | HNError::InvalidResponse(_) => StatusCode::BAD_GATEWAY,
+        };
+        // the reqwest errors name the upstream urls, kept to the logs
+        let detail = match &e {
+            HNError::HttpReqwestError(_) => "HN can't be reached".to_string(),
+            HNError::Timeout(_) => "HN didn't answer in time".to_string(),
+            HNError::UpstreamStatus(status) => format!("HN returned {}", status),
+            HNError::RateLimited(_) => "too many requests to HN".to_string(),
+            HNError::InvalidResponse(_) => "HN's answer can't be read".to_string(),
+            HNError::NotFound(m) => format!("not found: {}", m),
+            HNError::Deleted(m) => format!("deleted: {}", m),
+            HNError::InvalidInput(field) => field.to_string(),
+        };
+        let mut problem = Problem::new(status, e.code(), detail);
+        match e {
+            HNError::InvalidInput(field) => problem.errors.push(field),
+            HNError::RateLimited(Some(secs)) => {
+                problem = problem.with_header(axum::http::header::RETRY_AFTER, secs.into())
+            }
+            _ => {}
+        }
+        problem
+    }
+}
+
+impl From<crate::hn::trends::TrendError> for Problem {
+    fn from(e: crate::hn::trends::TrendError) -> Self {
+        match e {
+            crate::hn::trends::TrendError::NotFound(m) => {
+                Problem::not_found("not_found", format!("not found: {}", m))
+            }
+            crate::hn::trends::TrendError::Database(_) => Problem::internal(),
+        }
+    }
+}
+
+impl From<crate::hn::search::SearchError> for Problem {
+    fn from(e: crate::hn::search::SearchError) -> Self {
+        match e {
+            crate::hn::search::SearchError::InvalidInput(field) => Problem::invalid(field),
+            crate::hn::search::SearchError::Database(_) => Problem::internal(),
+        }
+    }
+}
+
+fn item_id(id: &str) -> Result<i64, HNError> {
+    id.trim().parse().map_err(|_| {
+        HNError::InvalidInput(crate::problem::FieldError::new(
+            "id",
+            format!("invalid item id: `{}`", id),
+        ))
+    })
+}
+
+pub async fn get_item(State(ctx): State<Ctx>, Path(id): Path<String>) -> axum::response::Response {
+    let id = match item_id(&id) {
+        Ok(id) => id,
+        Err(e) => return problem(e),
+    };
+    match crate::hn::apis::get_item(&ctx.hn_client, id).await {
+        Ok(r) => success(axum::http::StatusCode::OK, r),
+        Err(e) => problem(e),
+    }
+}
+
+pub async fn get_user(
+    State(ctx): State<Ctx>,
+    Path(username): Path<String>,
+) -> axum::response::Response {
+    match crate::hn::apis::user_details(&ctx.hn_client, username.as_str()).await {
+        Ok(r) => success(axum::http::StatusCode::OK, r),
+        Err(e) => problem(e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct CommentParams {
+    #[serde(default = "default_max_depth")]
+    max_depth: usize,
+    #[serde(default = "default_max_nodes")]
+    max_nodes: usize,
+}
+
+fn default_max_depth() -> usize {
+    crate::hn::comments::DEFAULT_MAX_DEPTH
+}
+
+fn default_max_nodes() -> usize {
+    crate::hn::comments::DEFAULT_MAX_NODES
+}
+
+pub async fn get_comments(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+    Query(q): Query<CommentParams>,
+) -> axum::response::Response {
+    let id = match item_id(&id) {
+        Ok(id) => id,
+        Err(e) => return problem(e),
+    };
+    match crate::hn::comments::tree(&ctx.hn_client, id, q.max_depth, q.max_nodes).await {
         Ok(r) => success(axum::http::StatusCode::OK, r),
-        Err(e) => error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        Err(e) => problem(e),
     }
 }
 
-pub async fn get_user(Path(username): Path<String>) -> axum::response::Response {
-    match crate::hn::apis::user_details(username.as_str()).await {
+#[derive(serde::Deserialize)]
+pub struct StoryPageParams {
+    #[serde(default)]
+    offset: usize,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_limit() -> usize {
+    crate::hn::apis::DEFAULT_PAGE_SIZE
+}
+
+async fn stories(ctx: &Ctx, list: StoryList, q: StoryPageParams) -> axum::response::Response {
+    match crate::hn::apis::stories(&ctx.hn_client, list, q.offset, q.limit).await {
         Ok(r) => success(axum::http::StatusCode::OK, r),
-        Err(e) => error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        Err(e) => problem(e),
     }
 }
 
-pub async fn top_stories() -> axum::response::Response {
-    match crate::hn::apis::top_stories().await {
+pub async fn top_stories(
+    State(ctx): State<Ctx>,
+    Query(q): Query<StoryPageParams>,
+) -> axum::response::Response {
+    stories(&ctx, StoryList::Top, q).await
+}
+
+pub async fn new_stories(
+    State(ctx): State<Ctx>,
+    Query(q): Query<StoryPageParams>,
+) -> axum::response::Response {
+    stories(&ctx, StoryList::New, q).await
+}
+
+pub async fn best_stories(
+    State(ctx): State<Ctx>,
+    Query(q): Query<StoryPageParams>,
+) -> axum::response::Response {
+    stories(&ctx, StoryList::Best, q).await
+}
+
+pub async fn ask_stories(
+    State(ctx): State<Ctx>,
+    Query(q): Query<StoryPageParams>,
+) -> axum::response::Response {
+    stories(&ctx, StoryList::Ask, q).await
+}
+
+pub async fn show_stories(
+    State(ctx): State<Ctx>,
+    Query(q): Query<StoryPageParams>,
+) -> axum::response::Response {
+    stories(&ctx, StoryList::Show, q).await
+}
+
+pub async fn job_stories(
+    State(ctx): State<Ctx>,
+    Query(q): Query<StoryPageParams>,
+) -> axum::response::Response {
+    stories(&ctx, StoryList::Job, q).await
+}
+
+#[derive(serde::Deserialize)]
+pub struct CrossedParams {
+    #[serde(default = "default_points")]
+    points: i32,
+    #[serde(default = "default_trend_limit")]
+    limit: i64,
+}
+
+fn default_points() -> i32 {
+    100
+}
+
+fn default_trend_limit() -> i64 {
+    crate::hn::trends::DEFAULT_LIMIT
+}
+
+pub async fn crossed_today(
+    State(ctx): State<Ctx>,
+    Query(q): Query<CrossedParams>,
+) -> axum::response::Response {
+    match crate::hn::trends::crossed_today(&ctx.db, q.points, q.limit).await {
+        Ok(r) => success(axum::http::StatusCode::OK, r),
+        Err(e) => problem(e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct VelocityParams {
+    #[serde(default = "default_window_mins")]
+    window_mins: i64,
+    #[serde(default = "default_trend_limit")]
+    limit: i64,
+}
+
+fn default_window_mins() -> i64 {
+    60
+}
+
+pub async fn velocity(
+    State(ctx): State<Ctx>,
+    Query(q): Query<VelocityParams>,
+) -> axum::response::Response {
+    match crate::hn::trends::velocity(&ctx.db, q.window_mins, q.limit).await {
+        Ok(r) => success(axum::http::StatusCode::OK, r),
+        Err(e) => problem(e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct HistoryParams {
+    #[serde(default = "default_days")]
+    days: i64,
+}
+
+fn default_days() -> i64 {
+    7
+}
+
+pub async fn score_history(
+    State(ctx): State<Ctx>,
+    Path(id): Path<i64>,
+    Query(q): Query<HistoryParams>,
+) -> axum::response::Response {
+    match crate::hn::trends::history(&ctx.db, id, q.days).await {
         Ok(r) => success(axum::http::StatusCode::OK, r),
-        Err(e) => error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        Err(e) => problem(e),
     }
 }
 
-pub async fn ask_stories() -> axum::response::Response {
-    match crate::hn::apis::ask_stories().await {
+pub async fn search(
+    State(ctx): State<Ctx>,
+    Query(q): Query<crate::hn::search::SearchParams>,
+) -> axum::response::Response {
+    match crate::hn::search::search(&ctx.db, q).await {
         Ok(r) => success(axum::http::StatusCode::OK, r),
-        Err(e) => error(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        Err(e) => problem(e),
     }
 }
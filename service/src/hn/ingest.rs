@@ -0,0 +1,208 @@
+use super::apis::HNItem;
+use super::HnClient;
+use crate::heartbeat::{Heartbeat, Heartbeats};
+use crate::settings::HnIngestSettings;
+use crate::shutdown::Shutdown;
+
+#[derive(thiserror::Error, Debug)]
+pub enum IngestError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("HNError: {0}")]
+    Hn(#[from] super::HNError),
+}
+
+/// Name of the incremental item job in `linknova_hn_ingest_state`.
+const NEW_ITEMS_JOB: &str = "items";
+/// New items are fetched and stored in batches of this size.
+const BATCH_SIZE: usize = 200;
+
+/// Background loop snapshotting the configured story lists and pulling every
+/// item posted since the previous round. Returns on shutdown between two steps
+/// of a round, a batch being stored is always committed.
+pub async fn run(
+    db: linkdb::Db,
+    client: HnClient,
+    settings: HnIngestSettings,
+    shutdown: Shutdown,
+    heartbeats: Heartbeats,
+) {
+    let interval = std::time::Duration::from_secs(settings.interval_secs.max(1));
+    let heartbeat = heartbeats.register("hn_ingest", interval + crate::heartbeat::JOB_GRACE);
+    tracing::info!(msg = "hn ingestion started", ?interval, lists = ?settings.lists);
+
+    loop {
+        heartbeat.beat();
+        match snapshot_lists(&db, &client, &settings).await {
+            Ok(count) => {
+                tracing::info!(msg = "hn lists snapshotted", count);
+                crate::metrics::job("hn_ingest", "snapshot", true);
+            }
+            Err(e) => {
+                tracing::error!(msg = "hn list snapshot failed", err = %e);
+                crate::metrics::job("hn_ingest", "snapshot", false);
+            }
+        }
+        if shutdown.is_requested() {
+            break;
+        }
+        heartbeat.beat();
+        match ingest_new_items(&db, &client, settings.max_new_items, &shutdown, &heartbeat).await {
+            Ok(count) => {
+                tracing::info!(msg = "hn new items ingested", count);
+                crate::metrics::job("hn_ingest", "new_items", true);
+                crate::metrics::job_items("hn_ingest", count);
+            }
+            Err(e) => {
+                tracing::error!(msg = "hn new item ingestion failed", err = %e);
+                crate::metrics::job("hn_ingest", "new_items", false);
+            }
+        }
+        if shutdown.is_requested() {
+            break;
+        }
+        if settings.retention_days > 0 {
+            let before =
+                chrono::Utc::now() - chrono::Duration::days(settings.retention_days as i64);
+            let pruned = linkdb::hn::score::delete_before(&db, before).await;
+            if let Err(e) = &pruned {
+                tracing::error!(msg = "hn score pruning failed", err = %e);
+            }
+            crate::metrics::job("hn_ingest", "prune", pruned.is_ok());
+        }
+        if shutdown.sleep(interval).await {
+            break;
+        }
+    }
+    tracing::info!(msg = "hn ingestion stopped");
+}
+
+/// Stores the current state of the head of each list and records a score point
+/// per item, returns the number of items snapshotted.
+#[tracing::instrument(name = "hn::ingest::snapshot-lists", skip_all)]
+pub async fn snapshot_lists(
+    db: &linkdb::Db,
+    client: &HnClient,
+    settings: &HnIngestSettings,
+) -> Result<usize, IngestError> {
+    let mut ids = Vec::new();
+    for list in settings.lists.iter() {
+        let list_ids = client.story_ids(list.path()).await?;
+        ids.extend(list_ids.iter().take(settings.list_size).copied());
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let items = client.refresh_items(&ids).await?;
+    let points: Vec<_> = items
+        .iter()
+        .filter(|i| !i.deleted.unwrap_or(false))
+        .map(|i| (i.id, clamp(i.score), clamp(i.descendants)))
+        .collect();
+
+    let now = chrono::Utc::now();
+    let mut tx = db.begin().await?;
+    linkdb::hn::upsert(&mut tx, items.iter().map(to_row).collect(), now).await?;
+    linkdb::hn::score::insert(&mut tx, &points, now).await?;
+    tx.commit().await?;
+    check_alerts(db, &items).await;
+    Ok(points.len())
+}
+
+/// Fetches the items created since the last round, walking ids up to HN's
+/// `maxitem`. Returns the number of items stored.
+#[tracing::instrument(name = "hn::ingest::new-items", skip_all)]
+pub async fn ingest_new_items(
+    db: &linkdb::Db,
+    client: &HnClient,
+    max_new_items: i64,
+    shutdown: &Shutdown,
+    heartbeat: &Heartbeat,
+) -> Result<usize, IngestError> {
+    let max_id = super::apis::max_item_id(client).await?;
+    let last_id = linkdb::hn::state::last_item_id(db, NEW_ITEMS_JOB).await?;
+    let oldest_allowed = max_id - max_new_items.max(1) + 1;
+    let start = match last_id {
+        Some(last) if last + 1 >= oldest_allowed => last + 1,
+        Some(last) => {
+            tracing::warn!(
+                msg = "hn ingestion fell behind, skipping items",
+                skipped = oldest_allowed - last - 1
+            );
+            oldest_allowed
+        }
+        None => oldest_allowed,
+    }
+    .max(1);
+
+    let ids: Vec<i64> = (start..=max_id).collect();
+    let mut stored = 0;
+    for chunk in ids.chunks(BATCH_SIZE) {
+        // the state row marks where the next round picks up
+        if shutdown.is_requested() {
+            break;
+        }
+        let (mut items, failed) = client.items_and_failures(chunk).await?;
+        // the cursor stops short of the first item which failed to load for a
+        // reason worth retrying, the next round starts over from it. Items which
+        // never load, such as unreadable ones, are skipped
+        let last = match failed.first() {
+            Some(&id) => {
+                items.retain(|i| i.id < id);
+                id - 1
+            }
+            None => *chunk.last().expect("chunks are never empty"),
+        };
+        let now = chrono::Utc::now();
+        let mut tx = db.begin().await?;
+        stored += items.len();
+        linkdb::hn::upsert(&mut tx, items.iter().map(to_row).collect(), now).await?;
+        linkdb::hn::state::set_last_item_id(&mut tx, NEW_ITEMS_JOB, last, now).await?;
+        tx.commit().await?;
+        check_alerts(db, &items).await;
+        heartbeat.beat();
+        if !failed.is_empty() {
+            tracing::warn!(
+                msg = "hn ingestion stopped at an item which failed to load",
+                last
+            );
+            break;
+        }
+    }
+    Ok(stored)
+}
+
+/// Alert rules see every story as it is stored, a failure never stops ingestion.
+async fn check_alerts(db: &linkdb::Db, items: &[HNItem]) {
+    match crate::services::link::alert::evaluate(db, items).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(msg = "hn alerts notified", count),
+        Err(e) => tracing::error!(msg = "hn alert evaluation failed", err = %e),
+    }
+}
+
+fn clamp(v: Option<u64>) -> i32 {
+    v.unwrap_or(0).min(i32::MAX as u64) as i32
+}
+
+fn to_row(item: &HNItem) -> linkdb::hn::HnItemI {
+    linkdb::hn::HnItemI {
+        id: item.id,
+        item_type: item.r#type.clone().unwrap_or_else(|| "story".to_string()),
+        by: item.by.clone(),
+        title: item.title.clone(),
+        url: item.url.clone(),
+        text: item.text.clone(),
+        parent: item.parent,
+        score: clamp(item.score),
+        descendants: clamp(item.descendants),
+        deleted: item.deleted.unwrap_or(false),
+        dead: item.dead.unwrap_or(false),
+        posted_on: item
+            .time
+            .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0)),
+    }
+}
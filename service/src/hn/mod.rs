@@ -1,9 +1,74 @@
 pub mod apis;
+pub mod cache;
+pub mod client;
+pub mod comments;
 pub mod controller;
+pub mod ingest;
 pub mod router;
+pub mod search;
+pub mod trends;
 
+pub use client::HnClient;
+
+/// Failures of the HN API, each with its own status and stable `code` so a
+/// caller can tell a deleted item from HN being down.
 #[derive(thiserror::Error, Debug)]
 pub enum HNError {
     #[error("HttpReqwestError: {}", _0)]
-    HttpReqwestError(#[from] crate::utils::http::ReqwestError),
+    HttpReqwestError(reqwest::Error),
+    #[error("UpstreamTimeoutError: {}", _0)]
+    Timeout(reqwest::Error),
+    #[error("UpstreamStatusError: {}", _0)]
+    UpstreamStatus(u16),
+    #[error("RateLimitedError: too many requests to HN")]
+    /// seconds HN asked to wait, when it said
+    RateLimited(Option<u64>),
+    #[error("InvalidResponseError: {}", _0)]
+    InvalidResponse(String),
+    #[error("NotFoundError: {}", _0)]
+    NotFound(String),
+    #[error("DeletedError: {}", _0)]
+    Deleted(String),
+    #[error("InvalidInputError: {}", _0)]
+    InvalidInput(crate::problem::FieldError),
+}
+
+impl HNError {
+    /// Whether asking again later may succeed: timeouts, connection errors, 429 and 5xx.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            HNError::HttpReqwestError(_) | HNError::Timeout(_) | HNError::RateLimited(_) => true,
+            HNError::UpstreamStatus(status) => *status >= 500,
+            HNError::InvalidResponse(_)
+            | HNError::NotFound(_)
+            | HNError::Deleted(_)
+            | HNError::InvalidInput(_) => false,
+        }
+    }
+
+    /// Machine readable, never changes once published.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HNError::HttpReqwestError(_) => "hn_unreachable",
+            HNError::Timeout(_) => "hn_timeout",
+            HNError::UpstreamStatus(_) => "hn_upstream_error",
+            HNError::RateLimited(_) => "hn_rate_limited",
+            HNError::InvalidResponse(_) => "hn_invalid_response",
+            HNError::NotFound(_) => "hn_not_found",
+            HNError::Deleted(_) => "hn_item_deleted",
+            HNError::InvalidInput(_) => "hn_invalid_input",
+        }
+    }
+}
+
+impl From<reqwest::Error> for HNError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            HNError::Timeout(e)
+        } else if e.is_decode() {
+            HNError::InvalidResponse(e.to_string())
+        } else {
+            HNError::HttpReqwestError(e)
+        }
+    }
 }
@@ -0,0 +1,117 @@
+use super::trends::StoredItem;
+use chrono::NaiveDate;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SearchError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("InvalidInputError: {0}")]
+    InvalidInput(crate::problem::FieldError),
+}
+
+pub const DEFAULT_PAGE_SIZE: i64 = 30;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct SearchParams {
+    /// web search syntax: `"exact phrase"`, `rust or go`, `-excluded`
+    pub q: Option<String>,
+    pub r#type: Option<String>,
+    pub by: Option<String>,
+    /// first day posted, inclusive
+    pub from: Option<NaiveDate>,
+    /// last day posted, inclusive
+    pub to: Option<NaiveDate>,
+    pub min_score: Option<i32>,
+    #[serde(default)]
+    pub offset: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    DEFAULT_PAGE_SIZE
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub item: StoredItem,
+    /// sanitized HTML of comments and text posts
+    pub text: Option<String>,
+    pub rank: f32,
+    pub discussion_url: String,
+    /// `POST` here to save the item as a bookmark
+    pub save_url: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SearchPage {
+    pub offset: i64,
+    pub limit: i64,
+    pub total: i64,
+    /// `None` on the last page
+    pub next_offset: Option<i64>,
+    pub items: Vec<SearchHit>,
+}
+
+/// Full-text search over the items stored by the ingestion job.
+#[tracing::instrument(name = "hn::search", skip(db))]
+pub async fn search(db: &linkdb::Db, params: SearchParams) -> Result<SearchPage, SearchError> {
+    if params.offset < 0 {
+        return Err(SearchError::InvalidInput(crate::problem::FieldError::new(
+            "offset",
+            "can't be negative",
+        )));
+    }
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return Err(SearchError::InvalidInput(crate::problem::FieldError::new(
+                "from",
+                "is after `to`",
+            )));
+        }
+    }
+    let limit = params.limit.clamp(1, MAX_PAGE_SIZE);
+    let q = linkdb::hn::HnSearchQ {
+        text: params.q,
+        item_type: params.r#type,
+        by: params.by,
+        from: params
+            .from
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc()),
+        to: params
+            .to
+            .and_then(|d| d.succ_opt())
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc()),
+        min_score: params.min_score,
+        offset: params.offset,
+        limit,
+    };
+
+    let rows = linkdb::hn::search(db, &q).await?;
+    let total = rows.first().map(|r| r.total).unwrap_or(0);
+    let next = params.offset + rows.len() as i64;
+    Ok(SearchPage {
+        offset: params.offset,
+        limit,
+        total,
+        next_offset: (next < total).then_some(next),
+        items: rows
+            .into_iter()
+            .map(|r| {
+                let id = r.item.id;
+                let text = r.item.text.as_deref().map(super::comments::sanitize);
+                SearchHit {
+                    item: r.item.into(),
+                    text,
+                    rank: r.rank,
+                    discussion_url: crate::services::link::hn::types::discussion_url(id),
+                    save_url: format!("/-/ln/v1/api/bm/hn/{}", id),
+                }
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+
+#[derive(thiserror::Error, Debug)]
+pub enum TrendError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+}
+
+pub const DEFAULT_LIMIT: i64 = 30;
+pub const MAX_LIMIT: i64 = 200;
+/// a week, longer windows are cut to it
+pub const MAX_WINDOW_MINS: i64 = 7 * 24 * 60;
+pub const MAX_HISTORY_DAYS: i64 = 365;
+
+/// An item as stored by the ingestion job.
+#[derive(serde::Serialize, Debug)]
+pub struct StoredItem {
+    pub id: i64,
+    pub r#type: String,
+    pub by: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub score: i32,
+    pub descendants: i32,
+    pub deleted: bool,
+    pub dead: bool,
+    pub posted_on: Option<DateTime<Utc>>,
+    /// last time the job saw the item
+    pub updated_on: DateTime<Utc>,
+}
+
+impl From<linkdb::hn::HnItemRow> for StoredItem {
+    fn from(row: linkdb::hn::HnItemRow) -> Self {
+        Self {
+            id: row.id,
+            r#type: row.item_type,
+            by: row.by,
+            title: row.title,
+            url: row.url,
+            score: row.score,
+            descendants: row.descendants,
+            deleted: row.deleted,
+            dead: row.dead,
+            posted_on: row.posted_on,
+            updated_on: row.updated_on,
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CrossedItem {
+    #[serde(flatten)]
+    pub item: StoredItem,
+    pub crossed_on: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct Crossed {
+    pub points: i32,
+    pub since: DateTime<Utc>,
+    pub items: Vec<CrossedItem>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct VelocityItem {
+    #[serde(flatten)]
+    pub item: StoredItem,
+    /// points gained inside the window
+    pub gained: i32,
+    pub points_per_hour: f64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct Velocity {
+    pub since: DateTime<Utc>,
+    pub items: Vec<VelocityItem>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct ScorePoint {
+    pub score: i32,
+    pub descendants: i32,
+    pub taken_on: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct History {
+    pub item: StoredItem,
+    pub points: Vec<ScorePoint>,
+}
+
+fn clamp_limit(limit: i64) -> i64 {
+    limit.clamp(1, MAX_LIMIT)
+}
+
+/// Stories which reached `points` since midnight UTC. An item counts from the
+/// first snapshot at or above the threshold, so one first seen today already
+/// past it is listed too.
+#[tracing::instrument(name = "hn::trends::crossed-today", skip(db))]
+pub async fn crossed_today(
+    db: &linkdb::Db,
+    points: i32,
+    limit: i64,
+) -> Result<Crossed, TrendError> {
+    let since = Utc::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc();
+    let rows = linkdb::hn::score::crossed(db, points, since, clamp_limit(limit)).await?;
+    Ok(Crossed {
+        points,
+        since,
+        items: rows
+            .into_iter()
+            .map(|r| CrossedItem {
+                item: r.item.into(),
+                crossed_on: r.crossed_on,
+            })
+            .collect(),
+    })
+}
+
+/// Fastest rising items over the last `window_mins` minutes.
+#[tracing::instrument(name = "hn::trends::velocity", skip(db))]
+pub async fn velocity(
+    db: &linkdb::Db,
+    window_mins: i64,
+    limit: i64,
+) -> Result<Velocity, TrendError> {
+    let since = Utc::now() - chrono::Duration::minutes(window_mins.clamp(1, MAX_WINDOW_MINS));
+    let rows = linkdb::hn::score::velocity(db, since, clamp_limit(limit)).await?;
+    Ok(Velocity {
+        since,
+        items: rows
+            .into_iter()
+            .map(|r| VelocityItem {
+                item: r.item.into(),
+                gained: r.gained,
+                points_per_hour: r.points_per_hour,
+            })
+            .collect(),
+    })
+}
+
+/// Score and comment count over the last `days` days, oldest first.
+#[tracing::instrument(name = "hn::trends::history", skip(db))]
+pub async fn history(db: &linkdb::Db, id: i64, days: i64) -> Result<History, TrendError> {
+    let item = linkdb::hn::get_by_id(db, id)
+        .await?
+        .ok_or_else(|| TrendError::NotFound(format!("stored item with id: `{}`", id)))?;
+    let since = Utc::now() - chrono::Duration::days(days.clamp(1, MAX_HISTORY_DAYS));
+    let points = linkdb::hn::score::history(db, id, since).await?;
+    Ok(History {
+        item: item.into(),
+        points: points
+            .into_iter()
+            .map(|p| ScorePoint {
+                score: p.score,
+                descendants: p.descendants,
+                taken_on: p.taken_on,
+            })
+            .collect(),
+    })
+}
@@ -1,12 +1,18 @@
 pub mod controller;
 pub mod ctx;
 pub mod errors;
+pub mod heartbeat;
 pub mod hn;
+pub mod metrics;
 pub mod middlewares;
+pub mod problem;
 pub mod routes;
 pub mod services;
 pub mod settings;
+pub mod shutdown;
+pub mod sources;
+pub mod telemetry;
 pub mod utils;
 use ctx::Ctx;
 
-pub use controller::{error, success};
+pub use controller::{problem, success};
@@ -1,13 +1,24 @@
-use service::controller;
 use service::ctx::Ctx;
+use service::settings::{DatabaseSettings, Settings, SettingsError};
 use std::env::current_dir;
 
+const USAGE: &str = "usage: service [serve | migrate [up | down [STEPS] | status]]";
+
 fn main() {
-    tokio::runtime::Builder::new_multi_thread()
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
-        .unwrap()
-        .block_on(http_main());
+        .unwrap();
+    match args.as_slice() {
+        [] | ["serve"] => runtime.block_on(http_main()),
+        ["migrate", rest @ ..] => runtime.block_on(migrate_main(rest)),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
 }
 
 fn env(name: &str) -> Option<String> {
@@ -17,53 +28,114 @@ fn env(name: &str) -> Option<String> {
     }
 }
 
-fn read_env_with_parse<T: std::str::FromStr<Err = std::num::ParseIntError>>(v: &str) -> T {
-    std::env::var(v)
-        .expect(&format!("Expected env: <{v:?}>"))
-        .parse::<T>()
-        .expect(&format!("<{v:?}> cannot be parsed"))
-}
-
-fn read_env(v: &str) -> String {
-    std::env::var(v).expect(&format!("Expected env: <{v:?}>"))
+/// Installs the subscriber as `[telemetry]` says, exits when the exporter
+/// can't be built.
+fn init_tracing(settings: &Settings) -> service::telemetry::Telemetry {
+    match service::telemetry::init(&settings.telemetry, &settings.service.environment) {
+        Ok(telemetry) => telemetry,
+        Err(e) => {
+            eprintln!("not able to set up telemetry: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
-pub async fn http_main() {
-    // Setting the environment variables
-    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "example_customize_extractor_error=trace".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
+/// Settings of the `PROFILE_NAME` profile, `local` by default, exits listing
+/// every problem when they are not valid.
+fn settings() -> Settings {
     let profile_name = env("PROFILE_NAME").unwrap_or_else(|| "local".to_string());
 
-    tracing::info!("profile: {}", profile_name);
-
     let path = current_dir().unwrap();
-    let settings = service::settings::Settings::new_with_file(
-        path.join("etc/settings").as_path(),
-        profile_name.as_str(),
-    )
-    .expect("settings error");
-
-    println!("settings: {:?}", settings);
+    match Settings::new_with_file(path.join("etc/settings").as_path(), &profile_name) {
+        Ok(settings) => settings,
+        Err(SettingsError::Invalid(problems)) => {
+            eprintln!("invalid settings of profile `{}`:", profile_name);
+            for problem in problems {
+                eprintln!("  - {}", problem);
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("not able to load the settings of `{}`: {}", profile_name, e);
+            std::process::exit(1);
+        }
+    }
+}
 
-    let pool = sqlx::postgres::PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&read_env("DATABASE_URL"))
+/// Postgres or SQLite, whichever the scheme of `database.url` names.
+async fn connect(settings: &DatabaseSettings) -> linkdb::Db {
+    linkdb::Db::connect(&settings.url, settings.pool())
         .await
-        .expect("could not connect to the database");
+        .expect("could not connect to the database")
+}
 
-    let categories = controller::category::categories(&pool)
-        .await
-        .expect("not able to the categories");
+/// `migrate` or `migrate up` applies the pending migrations, `migrate down [STEPS]`
+/// reverts the last STEPS of them, one by default.
+async fn migrate_main(args: &[&str]) {
+    let settings = settings();
+    let telemetry = init_tracing(&settings);
+    let db = connect(&settings.database).await;
+    let result = match args {
+        [] | ["up"] => linkdb::migrate::up(&db).await.map(|applied| {
+            println!("applied: {:?}", applied);
+        }),
+        ["down"] | ["down", _] => {
+            let steps = match args.get(1).map(|n| n.parse::<usize>()) {
+                None => 1,
+                Some(Ok(n)) => n,
+                Some(Err(_)) => {
+                    eprintln!("{USAGE}");
+                    std::process::exit(2);
+                }
+            };
+            linkdb::migrate::down(&db, steps).await.map(|reverted| {
+                println!("reverted: {:?}", reverted);
+            })
+        }
+        ["status"] => linkdb::migrate::status(&db)
+            .await
+            .map(|migrations| {
+                for m in migrations {
+                    let state = if m.applied { "applied" } else { "pending" };
+                    println!("{:>4} {:<8} {}", m.version, state, m.description);
+                }
+            })
+            .map_err(Into::into),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    telemetry.shutdown();
+    if let Err(e) = result {
+        eprintln!("migration failed: {}", e);
+        std::process::exit(1);
+    }
+}
+
+pub async fn http_main() {
+    service::metrics::handle();
+    let settings = settings();
+    let telemetry = init_tracing(&settings);
+    tracing::info!("settings: {:?}", settings);
 
-    let socket_address: std::net::SocketAddr =
-        (std::net::Ipv4Addr::UNSPECIFIED, read_env_with_parse("PORT")).into();
+    let db = connect(&settings.database).await;
+
+    if settings.database.migrate_on_startup {
+        let applied = linkdb::migrate::up(&db)
+            .await
+            .expect("not able to migrate the database");
+        tracing::info!("migrations applied: {:?}", applied);
+    }
+    if let Err(e) = linkdb::migrate::check(&db).await {
+        panic!(
+            "database schema is not at version {}: {}",
+            linkdb::migrate::latest_version(&db),
+            e
+        );
+    }
+
+    let socket_address = settings.service.bind;
     println!(
         "#### Started at: {}:{} ####",
         socket_address.ip(),
@@ -73,23 +145,116 @@ pub async fn http_main() {
         .await
         .expect("cannot bind the address");
 
+    let feed_client = service::services::link::feed::fetch::client(std::time::Duration::from_secs(
+        settings.feed.request_timeout_secs,
+    ))
+    .expect("not able to build the feed http client");
+
+    let (trigger, shutdown) = service::shutdown::channel();
+    tokio::spawn(async move {
+        service::shutdown::signal().await;
+        trigger.fire();
+    });
+    let grace = std::time::Duration::from_secs(settings.service.shutdown_timeout_secs);
+
+    let heartbeats = service::heartbeat::Heartbeats::default();
+    let mut workers = tokio::task::JoinSet::new();
+
+    if settings.feed.poller_enabled {
+        workers.spawn(service::services::link::feed::poller::run(
+            db.clone(),
+            feed_client.clone(),
+            settings.feed,
+            shutdown.clone(),
+            heartbeats.clone(),
+        ));
+    }
+
+    let hn_client =
+        service::hn::HnClient::new(&settings.hn).expect("not able to build the hn http client");
+
+    if settings.hn.watch.poller_enabled {
+        workers.spawn(service::services::link::watch::poller::run(
+            db.clone(),
+            hn_client.clone(),
+            settings.hn.watch,
+            shutdown.clone(),
+            heartbeats.clone(),
+        ));
+    }
+
+    if settings.hn.ingest.enabled {
+        workers.spawn(service::hn::ingest::run(
+            db.clone(),
+            hn_client.clone(),
+            settings.hn.ingest,
+            shutdown.clone(),
+            heartbeats.clone(),
+        ));
+    }
+
+    let sources = service::sources::Registry::from_settings(&settings.sources, hn_client.clone())
+        .expect("not able to build the link source http clients");
+
     let ctx = Ctx {
-        pg_pool: pool,
-        category_map: std::sync::Arc::new(std::sync::RwLock::new(categories)),
+        repos: linkdb::repo::Repos::database(db.clone()),
+        db: db.clone(),
         secret: settings.service.secrets,
+        cookie_domain: settings.service.cookie_domain,
+        trusted_proxies: settings.service.trusted_proxies,
         static_dir: match settings.static_dir {
             Some(p) => std::path::PathBuf::from(p)
                 .canonicalize()
                 .expect("not able to canonicalize static_dir file path or not found"),
             None => current_dir().expect("can't read current-dir"),
         },
+        feed_client,
+        hn_client,
+        sources,
+        heartbeats,
     };
 
     println!("Static DIR to serve files: {}", ctx.static_dir.display());
 
     let app = service::routes::routes(ctx).await;
 
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap()
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown.requested().await;
+        }
+    });
+    // the deadline counts from the signal, requests still running then are cut off
+    let cut_off = {
+        let shutdown = shutdown.clone();
+        async move { tokio::time::sleep_until(shutdown.requested().await + grace).await }
+    };
+    tokio::select! {
+        served = async { server.await } => served.expect("server failed"),
+        () = cut_off => {
+            tracing::warn!(msg = "in-flight requests cut off", timeout_secs = grace.as_secs());
+        }
+    }
+
+    let deadline = shutdown.requested().await + grace;
+    let stopped = tokio::time::timeout_at(deadline, async {
+        while let Some(joined) = workers.join_next().await {
+            if let Err(e) = joined {
+                tracing::error!(msg = "worker failed", err = %e);
+            }
+        }
+    })
+    .await;
+    if stopped.is_err() {
+        tracing::warn!(msg = "workers still busy, aborted", count = workers.len());
+        workers.abort_all();
+    }
+
+    db.close().await;
+    tracing::info!(msg = "shut down");
+    telemetry.shutdown();
 }
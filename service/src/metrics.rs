@@ -0,0 +1,128 @@
+//! Prometheus metrics, rendered by `GET /metrics`.
+//!
+//! - `http_requests_total`, `http_request_duration_seconds` by method, route
+//!   and status, see `middlewares::metrics`
+//! - `db_pool_connections` by state, `db_pool_max_connections`, read on scrape
+//! - `db_query_duration_seconds`, `db_query_errors_total` by `linkdb` function,
+//!   timed from their `tracing::instrument` spans by `DbQueryLayer`
+//! - `job_runs_total` by worker, job and outcome, `job_items_total` by worker
+//! - `hn_upstream_requests_total`, `hn_upstream_request_duration_seconds` by
+//!   endpoint and outcome, `hn_upstream_retries_total`, `hn_cache_lookups_total`
+
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::span;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Seconds, from a fast query to a slow upstream call.
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Installs the process wide recorder on first use, metrics recorded before
+/// that are lost so `main` calls it first thing.
+pub fn handle() -> &'static PrometheusHandle {
+    static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+    HANDLE.get_or_init(|| {
+        PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                Matcher::Suffix("duration_seconds".to_string()),
+                DURATION_BUCKETS,
+            )
+            .expect("buckets are not empty")
+            .install_recorder()
+            .expect("not able to install the metrics recorder")
+    })
+}
+
+/// Renders every metric in the Prometheus text format, with the pool gauges of
+/// `db` as of now.
+pub fn render(db: &linkdb::Db) -> String {
+    let pool = db.pool_stats();
+    let idle = pool.idle.min(pool.size);
+    metrics::gauge!("db_pool_connections", "state" => "idle").set(idle as f64);
+    metrics::gauge!("db_pool_connections", "state" => "in_use").set((pool.size - idle) as f64);
+    metrics::gauge!("db_pool_max_connections").set(pool.max_connections as f64);
+
+    let handle = handle();
+    handle.run_upkeep();
+    handle.render()
+}
+
+/// One job of a background worker: a feed, an HN user, an ingestion step.
+pub fn job(worker: &'static str, job: &'static str, ok: bool) {
+    let outcome = if ok { "ok" } else { "failed" };
+    metrics::counter!("job_runs_total", "worker" => worker, "job" => job, "outcome" => outcome)
+        .increment(1);
+}
+
+/// Bookmarks, notifications or items a worker produced.
+pub fn job_items(worker: &'static str, count: usize) {
+    metrics::counter!("job_items_total", "worker" => worker).increment(count as u64);
+}
+
+/// One attempt at an HN API call, `outcome` is the status code or the kind of
+/// failure.
+pub fn hn_request(endpoint: &'static str, outcome: String, took: Duration) {
+    metrics::counter!("hn_upstream_requests_total", "endpoint" => endpoint, "outcome" => outcome)
+        .increment(1);
+    metrics::histogram!("hn_upstream_request_duration_seconds", "endpoint" => endpoint)
+        .record(took.as_secs_f64());
+}
+
+pub fn hn_retry(endpoint: &'static str) {
+    metrics::counter!("hn_upstream_retries_total", "endpoint" => endpoint).increment(1);
+}
+
+pub fn hn_cache(cache: &'static str, hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    metrics::counter!("hn_cache_lookups_total", "cache" => cache, "result" => result).increment(1);
+}
+
+/// Times the spans of `linkdb` functions, named `linkdb::<module>::<function>`,
+/// from creation to close, and counts the errors their `err` reports.
+pub struct DbQueryLayer;
+
+const DB_SPAN_PREFIX: &str = "linkdb::";
+
+struct Started(Instant);
+
+impl<S> tracing_subscriber::Layer<S> for DbQueryLayer
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if !attrs.metadata().name().starts_with(DB_SPAN_PREFIX) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Started(Instant::now()));
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() != tracing::Level::ERROR {
+            return;
+        }
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let function = span.name();
+        if function.starts_with(DB_SPAN_PREFIX) {
+            metrics::counter!("db_query_errors_total", "function" => function).increment(1);
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let started = span.extensions().get::<Started>().map(|s| s.0);
+        if let Some(started) = started {
+            metrics::histogram!("db_query_duration_seconds", "function" => span.name())
+                .record(started.elapsed().as_secs_f64());
+        }
+    }
+}
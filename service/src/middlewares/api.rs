@@ -1,6 +1,7 @@
-use crate::controller::response;
 use crate::ctx::Ctx;
+use crate::problem::Problem;
 use axum::extract::{Request, State};
+use axum::response::IntoResponse;
 use percent_encoding::percent_decode;
 
 #[tracing::instrument(name = "middleware::verify-secrets", skip_all)]
@@ -11,10 +12,7 @@ pub async fn verify_secrets(
     next: axum::middleware::Next,
 ) -> Result<axum::response::Response, axum::response::Response> {
     if !secrets.api_secrets.eq(ctx.secret.as_str()) {
-        return Err(response::error(
-            axum::http::StatusCode::UNAUTHORIZED,
-            "Secrets are not accepted",
-        ));
+        return Err(Problem::unauthorized("Secrets are not accepted").into_response());
     }
     Ok(next.run(req).await)
 }
@@ -34,22 +32,19 @@ where
         parts: &mut axum::http::request::Parts,
         _: &B,
     ) -> Result<Self, Self::Rejection> {
-        let secrets = match APISecrets::try_from_header(&parts) {
+        let secrets = match APISecrets::try_from_header(parts) {
             Ok(u) => u,
             Err(err) => {
-                return Err(response::error(
-                    axum::http::StatusCode::UNAUTHORIZED,
-                    err.to_string(),
-                ));
+                return Err(Problem::unauthorized(err.to_string()).into_response());
             }
         };
 
         match secrets {
             Some(s) => Ok(Self { api_secrets: s }),
-            None => Err(response::error(
-                axum::http::StatusCode::UNAUTHORIZED,
-                "Expected api-secrets in the header".to_string(),
-            )),
+            None => Err(
+                Problem::unauthorized("Expected api-secrets in the header".to_string())
+                    .into_response(),
+            ),
         }
     }
 }
@@ -1,4 +1,6 @@
 pub mod api;
+pub mod metrics;
+pub mod request_id;
 pub mod user;
 
 #[derive(Debug, thiserror::Error)]
@@ -1,5 +1,6 @@
-use crate::controller::response;
+use crate::problem::Problem;
 use axum::extract::Request;
+use axum::response::IntoResponse;
 
 #[tracing::instrument(name = "middleware::auth-user", skip_all)]
 pub async fn auth_user(
@@ -39,24 +40,18 @@ where
         parts: &mut axum::http::request::Parts,
         _: &B,
     ) -> Result<Self, Self::Rejection> {
-        let user_id = match AuthUser::try_from_header(&parts) {
+        let user_id = match AuthUser::try_from_header(parts) {
             Ok(u) => u,
             Err(err) => {
-                return Err(response::error(
-                    axum::http::StatusCode::UNAUTHORIZED,
-                    err.to_string(),
-                ));
+                return Err(Problem::unauthorized(err.to_string()).into_response());
             }
         };
 
         match user_id {
             Some(u) => Ok(AuthUser { user_id: u }),
-            None => {
-                return Err(response::error(
-                    axum::http::StatusCode::UNAUTHORIZED,
-                    "Expected user-id in the header".to_string(),
-                ));
-            }
+            None => Err(
+                Problem::unauthorized("Expected user-id in the header".to_string()).into_response(),
+            ),
         }
     }
 }
@@ -0,0 +1,342 @@
+//! RFC 7807 problem details, the body of every failed API response.
+//!
+//! Each service error converts into a `Problem` next to the controller using
+//! it. The `code` is machine readable and never changes once published, the
+//! `detail` is for humans and database errors never make it there as they are.
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use std::borrow::Cow;
+
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// One invalid field of a request.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub struct Problem {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub detail: String,
+    pub errors: Vec<FieldError>,
+    headers: HeaderMap,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode, code: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            detail: detail.into(),
+            errors: Vec::new(),
+            headers: HeaderMap::new(),
+        }
+    }
+
+    pub fn not_found(code: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code, detail)
+    }
+
+    pub fn forbidden(code: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, code, detail)
+    }
+
+    pub fn conflict(code: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, code, detail)
+    }
+
+    pub fn unauthorized(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", detail)
+    }
+
+    /// 400 listing `field` as the one at fault.
+    pub fn invalid(field: FieldError) -> Self {
+        let mut problem = Self::new(StatusCode::BAD_REQUEST, "invalid_input", field.to_string());
+        problem.errors.push(field);
+        problem
+    }
+
+    pub fn bad_gateway(code: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_GATEWAY, code, detail)
+    }
+
+    /// Says nothing of what failed, the cause is only logged.
+    pub fn internal() -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            "the request could not be completed, try again later",
+        )
+    }
+
+    /// A failed query. A unique or foreign key violation is a conflict whose
+    /// code is picked by the violated constraint, a missing row is not found,
+    /// anything else is internal.
+    pub fn database(e: &sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                let (code, detail) = constraint(db.as_ref())
+                    .map_or(("conflict", "the resource exists already"), |c| {
+                        unique_conflict(&c)
+                    });
+                Self::conflict(code, detail)
+            }
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                // SQLite names neither the constraint nor the side that failed
+                let (code, detail) = match constraint(db.as_ref()) {
+                    Some(_) if db.message().starts_with("insert or update") => (
+                        "reference_missing",
+                        "a resource it refers to does not exist anymore",
+                    ),
+                    Some(c) => foreign_key_conflict(&c),
+                    None => ("in_use", "the resource is still in use"),
+                };
+                Self::conflict(code, detail)
+            }
+            sqlx::Error::RowNotFound => Self::not_found("not_found", "no such resource"),
+            _ => Self::internal(),
+        }
+    }
+
+    pub fn with_header(mut self, name: header::HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+}
+
+/// The violated constraint as Postgres names it. SQLite only says which
+/// columns collided, e.g. `UNIQUE constraint failed: t.a, t.b` is `t_a_b_key`.
+fn constraint(db: &dyn sqlx::error::DatabaseError) -> Option<Cow<'_, str>> {
+    if let Some(name) = db.constraint() {
+        return Some(Cow::Borrowed(name));
+    }
+    let columns = db.message().strip_prefix("UNIQUE constraint failed: ")?;
+    let mut name = String::new();
+    for (i, column) in columns.split(", ").enumerate() {
+        let (table, column) = column.split_once('.')?;
+        if i == 0 {
+            name.push_str(table);
+        }
+        name.push('_');
+        name.push_str(column);
+    }
+    name.push_str("_key");
+    Some(Cow::Owned(name))
+}
+
+fn unique_conflict(constraint: &str) -> (&'static str, &'static str) {
+    match constraint {
+        "linknova_category_user_id_name_key" => {
+            ("category_exists", "a category of that name exists")
+        }
+        "linknova_topic_user_id_name_key" => ("topic_exists", "a topic of that name exists"),
+        "linknova_topic_category_map_category_id_topic_id_key" => (
+            "topic_category_exists",
+            "the category is in the topic already",
+        ),
+        "linknova_bookmark_category_map_category_id_bookmark_id_key" => (
+            "bookmark_category_exists",
+            "the bookmark is in the category already",
+        ),
+        "linknova_hn_bookmark_user_id_hn_id_key" => {
+            ("bookmark_exists", "the item is saved already")
+        }
+        "linknova_feed_user_id_url_key" => ("feed_exists", "the feed is subscribed already"),
+        "linknova_share_link_token_hash_key" => ("share_exists", "the share link exists"),
+        "linknova_membership_category_id_user_id_key"
+        | "linknova_membership_topic_id_user_id_key" => {
+            ("member_exists", "already a member or invited")
+        }
+        "linknova_hn_watch_user_id_username_key" => {
+            ("watch_exists", "the hn user is watched already")
+        }
+        "linknova_notification_user_id_kind_hn_id_key" => {
+            ("notification_exists", "the notification exists")
+        }
+        "linknova_alert_rule_user_id_name_key" => {
+            ("alert_exists", "an alert rule of that name exists")
+        }
+        _ => ("conflict", "the resource exists already"),
+    }
+}
+
+/// A row still referenced by the constraint `constraint` was deleted.
+fn foreign_key_conflict(constraint: &str) -> (&'static str, &'static str) {
+    if constraint.ends_with("_category_id_fkey") {
+        (
+            "category_in_use",
+            "the category still has bookmarks or topics",
+        )
+    } else if constraint.ends_with("_topic_id_fkey") {
+        ("topic_in_use", "the topic still has categories")
+    } else {
+        ("in_use", "the resource is still in use")
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        #[derive(serde::Serialize)]
+        struct Body {
+            #[serde(rename = "type")]
+            type_: String,
+            title: &'static str,
+            status: u16,
+            detail: String,
+            code: &'static str,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            errors: Vec<FieldError>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            request_id: Option<String>,
+        }
+        let body = Body {
+            type_: format!("urn:linknova:problem:{}", self.code),
+            title: self.status.canonical_reason().unwrap_or("Error"),
+            status: self.status.as_u16(),
+            detail: self.detail,
+            code: self.code,
+            errors: self.errors,
+            request_id: crate::middlewares::request_id::current(),
+        };
+        let body = serde_json::to_vec(&body).expect("problem bodies always serialize");
+
+        let mut response = (self.status, self.headers, body).into_response();
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn database_errors_do_not_leak() {
+        let problem = Problem::database(&sqlx::Error::Protocol(
+            "relation \"linknova_category\" broke".to_string(),
+        ));
+        assert_eq!(problem.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!problem.detail.contains("linknova_category"));
+
+        let problem = Problem::database(&sqlx::Error::RowNotFound);
+        assert_eq!(problem.status, StatusCode::NOT_FOUND);
+    }
+
+    #[derive(Debug)]
+    struct DbError {
+        unique: bool,
+        message: &'static str,
+        constraint: Option<&'static str>,
+    }
+
+    impl std::fmt::Display for DbError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl std::error::Error for DbError {}
+
+    impl sqlx::error::DatabaseError for DbError {
+        fn message(&self) -> &str {
+            self.message
+        }
+
+        fn constraint(&self) -> Option<&str> {
+            self.constraint
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            if self.unique {
+                sqlx::error::ErrorKind::UniqueViolation
+            } else {
+                sqlx::error::ErrorKind::ForeignKeyViolation
+            }
+        }
+    }
+
+    fn violation(unique: bool, message: &'static str, constraint: Option<&'static str>) -> Problem {
+        Problem::database(&sqlx::Error::Database(Box::new(DbError {
+            unique,
+            message,
+            constraint,
+        })))
+    }
+
+    #[test]
+    fn conflicts_are_named_by_the_constraint() {
+        let problem = violation(
+            true,
+            "duplicate key value",
+            Some("linknova_topic_category_map_category_id_topic_id_key"),
+        );
+        assert_eq!(problem.status, StatusCode::CONFLICT);
+        assert_eq!(problem.code, "topic_category_exists");
+
+        let problem = violation(
+            true,
+            "UNIQUE constraint failed: linknova_category.user_id, linknova_category.name",
+            None,
+        );
+        assert_eq!(problem.code, "category_exists");
+
+        let problem = violation(
+            false,
+            "update or delete on table \"linknova_category\" violates foreign key constraint",
+            Some("linknova_bookmark_category_map_category_id_fkey"),
+        );
+        assert_eq!(problem.status, StatusCode::CONFLICT);
+        assert_eq!(problem.code, "category_in_use");
+
+        let problem = violation(
+            false,
+            "insert or update on table \"linknova_topic_category_map\" violates foreign key constraint",
+            Some("linknova_topic_category_map_topic_id_fkey"),
+        );
+        assert_eq!(problem.code, "reference_missing");
+
+        let problem = violation(false, "FOREIGN KEY constraint failed", None);
+        assert_eq!(problem.code, "in_use");
+    }
+
+    #[test]
+    fn invalid_lists_the_field() {
+        let problem = Problem::invalid(FieldError::new("max_views", "must be at least 1"));
+        assert_eq!(problem.status, StatusCode::BAD_REQUEST);
+        assert_eq!(problem.detail, "max_views: must be at least 1");
+        assert_eq!(problem.errors[0].field, "max_views");
+    }
+}
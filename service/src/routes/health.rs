@@ -1,3 +1,135 @@
-pub async fn health() -> impl axum::response::IntoResponse {
-    "linknove is running"
+//! Liveness answers as long as the process serves requests, readiness checks
+//! what a request needs: the database pool, the schema version and the
+//! background workers' heartbeats.
+
+use crate::ctx::Ctx;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::time::{Duration, Instant};
+
+/// A dependency slower than this to answer counts as down.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn routes(ctx: Ctx) -> axum::Router {
+    axum::Router::new()
+        .route("/-/ln/health", axum::routing::get(live))
+        .route("/-/ln/health/live", axum::routing::get(live))
+        .route("/-/ln/health/ready", axum::routing::get(ready))
+        .with_state(ctx)
+}
+
+#[derive(serde::Serialize)]
+struct Build {
+    version: &'static str,
+    git_sha: &'static str,
+}
+
+const BUILD: Build = Build {
+    version: env!("CARGO_PKG_VERSION"),
+    git_sha: env!("LINKNOVA_GIT_SHA"),
+};
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Up,
+    Down,
+}
+
+#[derive(serde::Serialize)]
+struct Report {
+    status: Status,
+    #[serde(flatten)]
+    build: &'static Build,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checks: Vec<Check>,
+}
+
+#[derive(serde::Serialize, Debug)]
+struct Check {
+    component: String,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u128>,
+    /// seconds since a worker's last beat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    silent_for_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Check {
+    /// Times `probe`, down when it fails or takes longer than `CHECK_TIMEOUT`.
+    async fn probe<E: std::fmt::Display>(
+        component: &str,
+        probe: impl std::future::Future<Output = Result<(), E>>,
+    ) -> Check {
+        let started = Instant::now();
+        let error = match tokio::time::timeout(CHECK_TIMEOUT, probe).await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some(format!("no answer within {}s", CHECK_TIMEOUT.as_secs())),
+        };
+        Check {
+            component: component.to_string(),
+            status: if error.is_none() {
+                Status::Up
+            } else {
+                Status::Down
+            },
+            latency_ms: Some(started.elapsed().as_millis()),
+            silent_for_secs: None,
+            error,
+        }
+    }
+}
+
+#[tracing::instrument(name = "routes::health::live", skip_all)]
+async fn live() -> Response {
+    let report = Report {
+        status: Status::Up,
+        build: &BUILD,
+        checks: Vec::new(),
+    };
+    (StatusCode::OK, axum::Json(report)).into_response()
+}
+
+#[tracing::instrument(name = "routes::health::ready", skip_all)]
+async fn ready(State(ctx): State<Ctx>) -> Response {
+    let (database, migrations) = tokio::join!(
+        Check::probe("database", ctx.db.ping()),
+        Check::probe("migrations", linkdb::migrate::check(&ctx.db)),
+    );
+    let mut checks = vec![database, migrations];
+    checks.extend(ctx.heartbeats.workers().into_iter().map(|w| Check {
+        component: format!("worker:{}", w.worker),
+        status: if w.is_alive() {
+            Status::Up
+        } else {
+            Status::Down
+        },
+        latency_ms: None,
+        silent_for_secs: Some(w.silent_for.as_secs()),
+        error: (!w.is_alive()).then(|| format!("no beat for over {}s", w.max_silence.as_secs())),
+    }));
+
+    let status = if checks.iter().all(|c| c.status == Status::Up) {
+        Status::Up
+    } else {
+        for c in checks.iter().filter(|c| c.status == Status::Down) {
+            tracing::warn!(msg = "not ready", component = c.component, err = ?c.error);
+        }
+        Status::Down
+    };
+    let code = match status {
+        Status::Up => StatusCode::OK,
+        Status::Down => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    let report = Report {
+        status,
+        build: &BUILD,
+        checks,
+    };
+    (code, axum::Json(report)).into_response()
 }
@@ -1,9 +1,13 @@
-pub async fn router() -> axum::Router {
+pub async fn router(ctx: crate::Ctx) -> axum::Router {
     axum::Router::new()
         .route(
             "/hn/v1/api/get-item/{id}/",
             axum::routing::get(crate::hn::controller::get_item),
         )
+        .route(
+            "/hn/v1/api/comments/{id}/",
+            axum::routing::get(crate::hn::controller::get_comments),
+        )
         .route(
             "/hn/v1/api/user/{username}/",
             axum::routing::get(crate::hn::controller::get_user),
@@ -12,8 +16,41 @@ pub async fn router() -> axum::Router {
             "/hn/v1/api/top-stories/",
             axum::routing::get(crate::hn::controller::top_stories),
         )
+        .route(
+            "/hn/v1/api/new-stories/",
+            axum::routing::get(crate::hn::controller::new_stories),
+        )
+        .route(
+            "/hn/v1/api/best-stories/",
+            axum::routing::get(crate::hn::controller::best_stories),
+        )
         .route(
             "/hn/v1/api/ask-stories/",
             axum::routing::get(crate::hn::controller::ask_stories),
         )
+        .route(
+            "/hn/v1/api/show-stories/",
+            axum::routing::get(crate::hn::controller::show_stories),
+        )
+        .route(
+            "/hn/v1/api/job-stories/",
+            axum::routing::get(crate::hn::controller::job_stories),
+        )
+        .route(
+            "/hn/v1/api/trends/crossed/",
+            axum::routing::get(crate::hn::controller::crossed_today),
+        )
+        .route(
+            "/hn/v1/api/trends/velocity/",
+            axum::routing::get(crate::hn::controller::velocity),
+        )
+        .route(
+            "/hn/v1/api/history/{id}/",
+            axum::routing::get(crate::hn::controller::score_history),
+        )
+        .route(
+            "/hn/v1/api/search/",
+            axum::routing::get(crate::hn::controller::search),
+        )
+        .with_state(ctx)
 }
@@ -53,6 +53,7 @@ pub async fn router<S>(ctx: crate::ctx::Ctx) -> axum::Router<S> {
                 .route("/bm", routing::get(link::bookmark::list))
                 .route("/bm/{id}", routing::put(link::bookmark::update))
                 .route("/bm/{id}", routing::delete(link::bookmark::delete))
+                .route("/bm/hn/{id}", routing::post(link::hn::save))
                 .route(
                     "/bm/add-cats/{id}",
                     routing::put(link::bookmark::add_categories),
@@ -62,5 +63,105 @@ pub async fn router<S>(ctx: crate::ctx::Ctx) -> axum::Router<S> {
                     routing::delete(link::bookmark::remove_categories),
                 ),
         )
+        .nest(
+            "/-/ln/v1/api/",
+            axum::Router::new()
+                .route("/feed", routing::post(link::feed::subscribe))
+                .route("/feed", routing::get(link::feed::list))
+                .route("/feed/opml", routing::post(link::feed::import_opml))
+                .route("/feed/opml", routing::get(link::feed::export_opml))
+                .route("/feed/{id}", routing::get(link::feed::get))
+                .route("/feed/{id}", routing::delete(link::feed::delete))
+                .route("/feed/{id}/refresh", routing::post(link::feed::refresh))
+                .route("/share", routing::post(link::share::create))
+                .route("/share", routing::get(link::share::list))
+                .route("/share/{id}", routing::delete(link::share::revoke))
+                .route("/share/{id}/access", routing::get(link::share::access_log)),
+        )
+        .nest(
+            "/-/ln/v1/api/",
+            axum::Router::new()
+                .route(
+                    "/cat/{cat-name}/members",
+                    routing::get(link::member::cat_list),
+                )
+                .route(
+                    "/cat/{cat-name}/members",
+                    routing::post(link::member::cat_invite),
+                )
+                .route(
+                    "/cat/{cat-name}/members/{username}",
+                    routing::put(link::member::cat_update),
+                )
+                .route(
+                    "/cat/{cat-name}/members/{username}",
+                    routing::delete(link::member::cat_remove),
+                )
+                .route(
+                    "/topic/{topic-name}/members",
+                    routing::get(link::member::topic_list),
+                )
+                .route(
+                    "/topic/{topic-name}/members",
+                    routing::post(link::member::topic_invite),
+                )
+                .route(
+                    "/topic/{topic-name}/members/{username}",
+                    routing::put(link::member::topic_update),
+                )
+                .route(
+                    "/topic/{topic-name}/members/{username}",
+                    routing::delete(link::member::topic_remove),
+                )
+                .route("/invitations", routing::get(link::member::invitations))
+                .route(
+                    "/invitations/{id}/accept",
+                    routing::post(link::member::accept),
+                )
+                .route("/invitations/{id}", routing::delete(link::member::decline))
+                .route("/hn/watch", routing::post(link::watch::add))
+                .route("/hn/watch", routing::get(link::watch::list))
+                .route("/hn/watch/{username}", routing::get(link::watch::get))
+                .route("/hn/watch/{username}", routing::delete(link::watch::remove))
+                .route(
+                    "/hn/watch/{username}/refresh",
+                    routing::post(link::watch::refresh),
+                )
+                .route("/sources", routing::get(link::source::providers))
+                .route(
+                    "/sources/{provider}/lists/{list}",
+                    routing::get(link::source::list),
+                )
+                .route(
+                    "/sources/{provider}/items/{id}",
+                    routing::get(link::source::item),
+                )
+                .route(
+                    "/sources/{provider}/items/{id}/discussion",
+                    routing::get(link::source::discussion),
+                )
+                .route(
+                    "/sources/{provider}/items/{id}/save",
+                    routing::post(link::source::save),
+                )
+                .route("/alerts", routing::post(link::alert::create))
+                .route("/alerts", routing::get(link::alert::list))
+                .route("/alerts/{id}", routing::get(link::alert::get))
+                .route("/alerts/{id}", routing::put(link::alert::update))
+                .route("/alerts/{id}", routing::delete(link::alert::delete))
+                .route("/notifications", routing::get(link::notification::list))
+                .route(
+                    "/notifications/read-all",
+                    routing::post(link::notification::read_all),
+                )
+                .route(
+                    "/notifications/{id}/read",
+                    routing::post(link::notification::read),
+                )
+                .route(
+                    "/notifications/{id}",
+                    routing::delete(link::notification::delete),
+                ),
+        )
         .with_state(ctx)
 }
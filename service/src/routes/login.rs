@@ -1,10 +1,13 @@
+use crate::ctx::Ctx;
+use axum::extract::State;
 use axum::response::Response;
 use axum::Router;
 
-pub fn routes() -> axum::Router {
+pub fn routes(ctx: Ctx) -> axum::Router {
     Router::new()
         .route("/-/ln/api/login", axum::routing::post(login_json))
         .route("/-/ln/api/login-form", axum::routing::post(login_form))
+        .with_state(ctx)
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -16,25 +19,24 @@ pub struct LoginReq {
 }
 
 // JSON login handler
-pub async fn login_json(axum::Json(req): axum::Json<LoginReq>) -> Response {
-    handle_login(req).await
+pub async fn login_json(State(ctx): State<Ctx>, axum::Json(req): axum::Json<LoginReq>) -> Response {
+    handle_login(&ctx, req).await
 }
 
 // Form login handler
-pub async fn login_form(axum::Form(req): axum::Form<LoginReq>) -> Response {
-    handle_login(req).await
+pub async fn login_form(State(ctx): State<Ctx>, axum::Form(req): axum::Form<LoginReq>) -> Response {
+    handle_login(&ctx, req).await
 }
 
 // Common login handling logic
-async fn handle_login(login_req: LoginReq) -> Response {
-    dbg!(&login_req);
-
+async fn handle_login(ctx: &Ctx, login_req: LoginReq) -> Response {
     // Simple authentication - accept any username/password for now
     // This is where you would add real authentication logic
     let cookie = format!(
-        "X-USER-ID={}; Domain=127.0.0.1; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+        "X-USER-ID={}; Domain={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
         login_req.username, // Use actual username instead of hardcoded value
-        60 * 60 * 24        // 24 hours in seconds
+        ctx.cookie_domain,
+        60 * 60 * 24 // 24 hours in seconds
     );
 
     axum::response::Response::builder()
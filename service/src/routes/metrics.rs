@@ -0,0 +1,17 @@
+use crate::ctx::Ctx;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+
+pub fn routes(ctx: Ctx) -> axum::Router {
+    axum::Router::new()
+        .route("/metrics", axum::routing::get(metrics))
+        .with_state(ctx)
+}
+
+async fn metrics(State(ctx): State<Ctx>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::render(&ctx.db),
+    )
+}
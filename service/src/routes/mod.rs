@@ -2,14 +2,15 @@ mod health;
 mod hn;
 mod link;
 pub mod login;
+mod metrics;
+mod public;
 mod statics;
 
 pub async fn routes(ctx: crate::Ctx) -> axum::Router {
+    crate::metrics::handle();
     let router = axum::Router::new()
-        .route(
-            "/-/ln/health",
-            axum::routing::on(axum::routing::MethodFilter::GET, health::health),
-        )
+        .merge(health::routes(ctx.clone()))
+        .merge(metrics::routes(ctx.clone()))
         .merge(
             link::router(ctx.clone())
                 .await
@@ -20,66 +21,15 @@ pub async fn routes(ctx: crate::Ctx) -> axum::Router {
                     //     crate::middlewares::api::verify_secrets,
                     // )),
         )
-        .merge(login::routes())
+        .merge(login::routes(ctx.clone()))
+        .merge(public::routes(ctx.clone()))
+        .merge(hn::router(ctx.clone()).await)
         .merge(statics::routes(ctx))
-        .merge(hn::router().await);
-
-    // .route(
-    //     "/linknova/v1/api/save/",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::POST,
-    //         crate::controller::save_url,
-    //     ),
-    // )
-    // .route(
-    //     "/linknova/v1/api/list/",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::GET,
-    //         crate::controller::get_urls,
-    //     ),
-    // )
-    // .route(
-    //     "/linknova/v1/api/topic/create/",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::PUT,
-    //         crate::controller::topic::create,
-    //     ),
-    // )
-    // .route(
-    //     "/linknova/v1/api/topic/get/{id}",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::GET,
-    //         crate::controller::topic::get,
-    //     ),
-    // )
-    // .route(
-    //     "/linknova/v1/api/topic/get-by-name/{name}",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::GET,
-    //         crate::controller::topic::get_with_name,
-    //     ),
-    // )
-    // .route(
-    //     "/linknova/v1/api/topic/list/",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::GET,
-    //         crate::controller::topic::list,
-    //     ),
-    // )
-    // .route(
-    //     "/linknova/v1/api/category/create/",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::PUT,
-    //         crate::controller::category::create,
-    //     ),
-    // )
-    // .route(
-    //     "/linknova/v1/api/category/list/",
-    //     axum::routing::on(
-    //         axum::routing::MethodFilter::GET,
-    //         crate::controller::category::list,
-    //     ),
-    // )
-    // .with_state(ctx);
+        .layer(axum::middleware::from_fn(
+            crate::middlewares::metrics::track_http,
+        ))
+        .layer(axum::middleware::from_fn(
+            crate::middlewares::request_id::request_id,
+        ));
     router
 }
@@ -0,0 +1,29 @@
+use crate::controller::public;
+use axum::routing;
+
+/// Unauthenticated routes, everything served here must check the `public` flag
+/// or go through a share link.
+pub fn routes(ctx: crate::Ctx) -> axum::Router {
+    axum::Router::new()
+        .route(
+            "/-/ln/u/{user}/t/{topic-name}",
+            routing::get(public::page::topic),
+        )
+        .route(
+            "/-/ln/u/{user}/c/{cat-name}",
+            routing::get(public::page::cat),
+        )
+        .route(
+            "/-/ln/u/{user}/t/{topic-name}/feed/{format}",
+            routing::get(public::feed::topic),
+        )
+        .route(
+            "/-/ln/u/{user}/c/{cat-name}/feed/{format}",
+            routing::get(public::feed::cat),
+        )
+        .route(
+            "/-/ln/s/{token}",
+            routing::get(public::share::page).post(public::share::unlock),
+        )
+        .with_state(ctx)
+}
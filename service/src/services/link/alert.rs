@@ -0,0 +1,188 @@
+pub mod rules;
+pub mod types;
+
+use crate::controller::link::types::{AlertReq, AlertResponse};
+use crate::ctx::Ctx;
+use crate::hn::apis::HNItem;
+
+#[tracing::instrument(name = "service::alert-create", skip_all)]
+pub async fn create(
+    ctx: &Ctx,
+    user_id: &str,
+    req: AlertReq,
+) -> Result<AlertResponse, types::AlertError> {
+    let name = req.name.trim().to_string();
+    let now = chrono::Utc::now();
+    let mut tx = ctx.db.begin().await?;
+    let row = to_row(&mut tx, user_id, req, now).await?;
+    let id = linkdb::alert::insert(&mut tx, row, now)
+        .await
+        .map_err(|e| types::from_write_error(e, &name))?;
+    tx.commit().await?;
+    get(ctx, user_id, id).await
+}
+
+#[tracing::instrument(name = "service::alert-update", skip_all)]
+pub async fn update(
+    ctx: &Ctx,
+    user_id: &str,
+    id: i64,
+    req: AlertReq,
+) -> Result<AlertResponse, types::AlertError> {
+    let name = req.name.trim().to_string();
+    let now = chrono::Utc::now();
+    let mut tx = ctx.db.begin().await?;
+    let row = to_row(&mut tx, user_id, req, now).await?;
+    let found = linkdb::alert::update(&mut tx, id, row, now)
+        .await
+        .map_err(|e| types::from_write_error(e, &name))?;
+    if !found {
+        return Err(types::AlertError::NotFound(format!(
+            "alert rule with id: `{}`",
+            id
+        )));
+    }
+    tx.commit().await?;
+    get(ctx, user_id, id).await
+}
+
+/// Validates the request and creates its auto-save category.
+async fn to_row(
+    tx: &mut linkdb::Tx<'_>,
+    user_id: &str,
+    req: AlertReq,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<linkdb::alert::AlertRuleI, types::AlertError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(types::AlertError::InvalidInput(
+            crate::problem::FieldError::new("name", "is required"),
+        ));
+    }
+    let title_contains = req
+        .title_contains
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    let domain = match req
+        .domain
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        Some(d) => Some(rules::normalize_domain(d).ok_or_else(|| {
+            types::AlertError::InvalidInput(crate::problem::FieldError::new(
+                "domain",
+                format!("`{}` is not a domain", d),
+            ))
+        })?),
+        None => None,
+    };
+    if title_contains.is_none() && domain.is_none() && req.min_score.is_none() {
+        return Err(types::AlertError::InvalidInput(
+            crate::problem::FieldError::new(
+                "title_contains",
+                "one of title_contains, domain or min_score is required",
+            ),
+        ));
+    }
+    let category_id = match req.category.as_deref().map(str::trim) {
+        Some(category) if !category.is_empty() => {
+            let ids = linkdb::category::upsert(
+                tx,
+                vec![super::cat::types::from_cat_name(category, user_id)],
+                now,
+            )
+            .await?;
+            Some(ids[0])
+        }
+        _ => None,
+    };
+    Ok(linkdb::alert::AlertRuleI {
+        user_id: user_id.to_string(),
+        name: name.to_string(),
+        title_contains,
+        domain,
+        min_score: req.min_score,
+        category_id,
+        active: req.active,
+    })
+}
+
+#[tracing::instrument(name = "service::alert-get", skip_all)]
+pub async fn get(ctx: &Ctx, user_id: &str, id: i64) -> Result<AlertResponse, types::AlertError> {
+    linkdb::alert::get_by_id(&ctx.db, user_id, id)
+        .await?
+        .map(types::from_db_response)
+        .ok_or_else(|| types::AlertError::NotFound(format!("alert rule with id: `{}`", id)))
+}
+
+#[tracing::instrument(name = "service::alert-list", skip_all)]
+pub async fn list(ctx: &Ctx, user_id: &str) -> Result<Vec<AlertResponse>, types::AlertError> {
+    let rows = linkdb::alert::list_all(&ctx.db, user_id).await?;
+    Ok(rows.into_iter().map(types::from_db_response).collect())
+}
+
+#[tracing::instrument(name = "service::alert-delete", skip_all)]
+pub async fn delete(ctx: &Ctx, user_id: &str, id: i64) -> Result<(), types::AlertError> {
+    if !linkdb::alert::delete(&ctx.db, user_id, id).await? {
+        return Err(types::AlertError::NotFound(format!(
+            "alert rule with id: `{}`",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// Runs every active rule against `items`. A user is notified once per story
+/// however many of their rules match it, the first matching rule with a category
+/// also saves it. Returns the number of notifications created.
+#[tracing::instrument(name = "service::alert-evaluate", skip_all, fields(count = items.len()))]
+pub async fn evaluate(db: &linkdb::Db, items: &[HNItem]) -> Result<usize, types::AlertError> {
+    let rows = linkdb::alert::list_active(db).await?;
+    let mut notified = 0;
+    for row in rows.iter() {
+        let rule = rules::Rule::from_row(row);
+        let mut matched = false;
+        for item in items.iter().filter(|i| rule.matches(i)) {
+            matched = true;
+            let kind = super::notification::types::KIND_HN_ALERT;
+            if linkdb::notification::exists(db, &row.user_id, kind, item.id).await? {
+                continue;
+            }
+            let bookmark_id = match row.category.as_deref() {
+                Some(category) => {
+                    match super::hn::store(db, &row.user_id, item, Some(category)).await {
+                        Ok((id, _)) => Some(id),
+                        Err(e) => {
+                            tracing::warn!(msg = "auto-save failed", rule_id = row.id, hn_id = item.id, err = %e);
+                            None
+                        }
+                    }
+                }
+                None => None,
+            };
+            let notification = linkdb::notification::NotificationI {
+                user_id: row.user_id.clone(),
+                kind: kind.to_string(),
+                title: format!(
+                    "{}: {}",
+                    row.name,
+                    item.title.as_deref().unwrap_or_default()
+                ),
+                url: Some(super::hn::types::discussion_url(item.id)),
+                body: item.url.clone(),
+                hn_id: Some(item.id),
+                bookmark_id,
+            };
+            let mut tx = db.begin().await?;
+            let inserted =
+                linkdb::notification::insert(&mut tx, notification, chrono::Utc::now()).await?;
+            tx.commit().await?;
+            notified += inserted.is_some() as usize;
+        }
+        if matched {
+            linkdb::alert::set_last_matched(db, row.id, chrono::Utc::now()).await?;
+        }
+    }
+    Ok(notified)
+}
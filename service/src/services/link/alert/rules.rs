@@ -0,0 +1,160 @@
+use crate::hn::apis::HNItem;
+
+/// Matching part of an alert rule, every condition set must hold.
+#[derive(Debug, Default)]
+pub struct Rule {
+    /// lowercase
+    pub title_contains: Option<String>,
+    /// lowercase host without `www.`
+    pub domain: Option<String>,
+    pub min_score: Option<i32>,
+}
+
+impl Rule {
+    pub fn from_row(row: &linkdb::alert::AlertRuleRow) -> Self {
+        Self {
+            title_contains: row.title_contains.as_deref().map(str::to_lowercase),
+            domain: row.domain.as_deref().and_then(normalize_domain),
+            min_score: row.min_score,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title_contains.is_none() && self.domain.is_none() && self.min_score.is_none()
+    }
+
+    /// Only live stories match: jobs, polls, comments, dead and deleted items never do.
+    pub fn matches(&self, item: &HNItem) -> bool {
+        if self.is_empty()
+            || item.r#type.as_deref().unwrap_or("story") != "story"
+            || item.deleted.unwrap_or(false)
+            || item.dead.unwrap_or(false)
+        {
+            return false;
+        }
+        if let Some(needle) = &self.title_contains {
+            match &item.title {
+                Some(title) if title.to_lowercase().contains(needle.as_str()) => {}
+                _ => return false,
+            }
+        }
+        if let Some(domain) = &self.domain {
+            match item.url.as_deref().and_then(normalize_domain) {
+                Some(host) if host == *domain || host.ends_with(&format!(".{}", domain)) => {}
+                _ => return false,
+            }
+        }
+        if let Some(min_score) = self.min_score {
+            if item.score.unwrap_or(0) < min_score.max(0) as u64 {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Host of `github.com`, `www.github.com` or `https://github.com/rust-lang` alike,
+/// lowercase and without `www.`.
+pub fn normalize_domain(input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    let host = if input.contains("://") {
+        reqwest::Url::parse(input).ok()?.host_str()?.to_string()
+    } else {
+        input.split('/').next()?.to_string()
+    };
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> Vec<HNItem> {
+        serde_json::from_str(include_str!(
+            "../../../../tests/fixtures/hn/new_stories.json"
+        ))
+        .expect("fixture is a list of hn items")
+    }
+
+    fn matching(rule: &Rule) -> Vec<i64> {
+        fixtures()
+            .iter()
+            .filter(|i| rule.matches(i))
+            .map(|i| i.id)
+            .collect()
+    }
+
+    #[test]
+    fn title_contains_is_case_insensitive_and_skips_other_types() {
+        let rule = Rule {
+            title_contains: Some("postgres".to_string()),
+            ..Default::default()
+        };
+        // not the dead story, the job or the comment
+        assert_eq!(matching(&rule), vec![41823001, 41823057, 41823150]);
+    }
+
+    #[test]
+    fn domain_matches_subdomains_but_not_lookalikes() {
+        let rule = Rule {
+            domain: normalize_domain("github.com"),
+            ..Default::default()
+        };
+        assert_eq!(matching(&rule), vec![41823057, 41823199]);
+    }
+
+    #[test]
+    fn www_is_ignored_on_both_sides() {
+        let rule = Rule {
+            domain: normalize_domain("https://www.PostgreSQL.org/docs"),
+            ..Default::default()
+        };
+        assert_eq!(rule.domain.as_deref(), Some("postgresql.org"));
+        assert_eq!(matching(&rule), vec![41823001]);
+    }
+
+    #[test]
+    fn min_score_is_inclusive() {
+        let rule = Rule {
+            min_score: Some(150),
+            ..Default::default()
+        };
+        assert_eq!(matching(&rule), vec![41823001, 41823102, 41823199]);
+    }
+
+    #[test]
+    fn conditions_are_combined() {
+        let rule = Rule {
+            title_contains: Some("postgres".to_string()),
+            min_score: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(matching(&rule), vec![41823001]);
+
+        let rule = Rule {
+            domain: normalize_domain("github.com"),
+            min_score: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(matching(&rule), vec![41823199]);
+    }
+
+    #[test]
+    fn a_rule_without_conditions_matches_nothing() {
+        assert!(matching(&Rule::default()).is_empty());
+    }
+
+    #[test]
+    fn stories_without_url_never_match_a_domain() {
+        let rule = Rule {
+            domain: normalize_domain("news.ycombinator.com"),
+            ..Default::default()
+        };
+        assert!(matching(&rule).is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+use crate::controller::link::types;
+use linkdb::alert::AlertRuleRow;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AlertError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+    #[error("InvalidInputError: {0}")]
+    InvalidInput(crate::problem::FieldError),
+    #[error("ConflictError: {0}")]
+    Conflict(String),
+}
+
+/// Unique violation on `(user_id, name)` becomes a conflict.
+pub fn from_write_error(e: sqlx::Error, name: &str) -> AlertError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AlertError::Conflict(format!("alert rule named: `{}` exists", name))
+        }
+        _ => AlertError::Database(e),
+    }
+}
+
+pub fn from_db_response(row: AlertRuleRow) -> types::AlertResponse {
+    types::AlertResponse {
+        id: row.id,
+        name: row.name,
+        title_contains: row.title_contains,
+        domain: row.domain,
+        min_score: row.min_score,
+        category: row.category,
+        active: row.active,
+        last_matched_on: row.last_matched_on,
+        created_on: row.created_on,
+        updated_on: row.updated_on,
+    }
+}
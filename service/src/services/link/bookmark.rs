@@ -1,36 +1,69 @@
-mod types;
+pub mod types;
 
-use crate::controller::link::types::{BmCreateReq, BmResponse, BmUpdateReq};
+use super::member::types as member_types;
+use crate::controller::link::types::{BmCreateReq, BmResponse, BmUpdateReq, MemberRole};
 use crate::ctx::Ctx;
 
 #[tracing::instrument(name = "service::bookmark-create", skip_all)]
 pub async fn create(
     ctx: &Ctx,
     user_id: &str,
-    req: BmCreateReq,
+    mut req: BmCreateReq,
 ) -> Result<(), types::BookmarkError> {
+    types::check_url(&req.url)?;
     let now = chrono::Utc::now();
+    let categories = std::mem::take(&mut req.categories);
+    let row = types::from_req(req, user_id, now);
+
+    let (own, shared) = categories_of(ctx, user_id, &categories).await?;
+    ctx.repos.bookmarks.create(row, own, shared, now).await?;
+    Ok(())
+}
 
-    // first upsert the category
-    let categories: Vec<_> = req
-        .categories
+/// Categories to file a bookmark under: own categories, which are created on the
+/// fly, and the ids of `@owner/name` ones, which need at least the contributor role.
+async fn categories_of(
+    ctx: &Ctx,
+    user_id: &str,
+    categories: &[String],
+) -> Result<(Vec<linkdb::CatRowI>, Vec<i64>), types::BookmarkError> {
+    let (shared, own): (Vec<&String>, Vec<&String>) = categories
         .iter()
+        .partition(|c| member_types::parse_ref(user_id, c).0 != user_id);
+
+    let own = own
+        .into_iter()
         .map(|c| super::cat::types::from_cat_name(c, user_id))
         .collect();
-    let row = types::from_req(req, user_id, now);
-
-    // todo: create links between category and bookmark
-    let mut tx = ctx.pg_pool.begin().await?;
-    let category_ids = linkdb::category::upsert(&mut tx, categories, now).await?;
-    let bm_id = linkdb::bookmark::insert(&mut tx, row).await?;
-    linkdb::bookmark::cat_map::add_categories(&mut tx, bm_id, category_ids.as_slice()).await?;
-    tx.commit().await?;
-    Ok(())
+    let mut ids = Vec::with_capacity(shared.len());
+    for c in shared {
+        match super::member::category(ctx, user_id, c).await? {
+            Some((cat, role)) if role >= MemberRole::Contributor => ids.push(cat.id),
+            Some(_) => {
+                return Err(types::BookmarkError::Forbidden(format!(
+                    "can't add bookmarks to category: `{}`",
+                    c
+                )))
+            }
+            None => {
+                return Err(types::BookmarkError::NotFound(format!(
+                    "category with name: `{}`",
+                    c
+                )))
+            }
+        }
+    }
+    Ok((own, ids))
 }
 
 #[tracing::instrument(name = "service::bookmark-get", skip_all)]
-pub async fn get(ctx: &Ctx, id: i64) -> Result<BmResponse, types::BookmarkError> {
-    let row = linkdb::bookmark::get_by_id(&ctx.pg_pool, id).await?;
+pub async fn get(ctx: &Ctx, user_id: &str, id: i64) -> Result<BmResponse, types::BookmarkError> {
+    let row = ctx
+        .repos
+        .bookmarks
+        .get_visible(user_id, id)
+        .await?
+        .ok_or_else(|| types::BookmarkError::NotFound(format!("bookmark with id: `{}`", id)))?;
     let result = types::from_db_response(row);
     Ok(result)
 }
@@ -42,50 +75,23 @@ pub async fn update(
     id: i64,
     req: BmUpdateReq,
 ) -> Result<BmResponse, types::BookmarkError> {
-    use sqlx::QueryBuilder;
-
-    let now = chrono::Utc::now();
-
-    // Build dynamic query with only the fields that are provided
-    let mut query_builder = QueryBuilder::new("UPDATE linknova_bookmark SET updated_on = ");
-    query_builder.push_bind(now);
-
-    if let Some(title) = req.title {
-        query_builder.push(", title = ");
-        query_builder.push_bind(title);
-    }
-
-    if let Some(url) = req.url {
-        query_builder.push(", url = ");
-        query_builder.push_bind(url);
-    }
-
-    if let Some(content) = req.content {
-        query_builder.push(", content = ");
-        query_builder.push_bind(content);
+    if let Some(url) = &req.url {
+        types::check_url(url)?;
     }
+    let fields = linkdb::bookmark::BookmarkUpdate {
+        title: req.title,
+        url: req.url,
+        content: req.content,
+        referrer: req.referrer,
+        status: req.status,
+    };
+    let updated = ctx
+        .repos
+        .bookmarks
+        .update(user_id, id, fields, chrono::Utc::now())
+        .await?;
 
-    if let Some(referrer) = req.referrer {
-        query_builder.push(", referrer = ");
-        query_builder.push_bind(referrer);
-    }
-
-    if let Some(status) = req.status {
-        query_builder.push(", status = ");
-        query_builder.push_bind(status);
-    }
-
-    // Add WHERE clause
-    query_builder.push(" WHERE id = ");
-    query_builder.push_bind(id);
-    query_builder.push(" AND user_id = ");
-    query_builder.push_bind(user_id);
-
-    // Execute the update query
-    let query = query_builder.build();
-    let result = query.execute(&ctx.pg_pool).await?;
-
-    if result.rows_affected() == 0 {
+    if !updated {
         return Err(types::BookmarkError::NotFound(format!(
             "Bookmark with id {} not found or not owned by user",
             id
@@ -93,11 +99,11 @@ pub async fn update(
     }
 
     // Return the updated bookmark
-    get(ctx, id).await
+    get(ctx, user_id, id).await
 }
 
 // todo: pagination,
-// list by topic and category
+// list by topic and category, the topic can be `@owner/name` for a shared one
 #[tracing::instrument(name = "service::bookmark-list", skip_all)]
 pub async fn list(
     ctx: &Ctx,
@@ -108,33 +114,38 @@ pub async fn list(
 ) -> Result<Vec<BmResponse>, types::BookmarkError> {
     let rows = match topic_name {
         Some(t) => {
-            linkdb::bookmark::filter_by_topic(
-                &ctx.pg_pool,
-                user_id,
-                t.as_str(),
-                Some(categories),
-                status,
-            )
-            .await?
+            let (topic, _) = super::member::topic(ctx, user_id, t)
+                .await?
+                .ok_or_else(|| {
+                    types::BookmarkError::NotFound(format!("topic with name: `{}`", t))
+                })?;
+            ctx.repos
+                .bookmarks
+                .filter_by_topic(user_id, topic.id, Some(categories), status)
+                .await?
+        }
+        None => {
+            ctx.repos
+                .bookmarks
+                .filter(user_id, Some(categories), status)
+                .await?
         }
-        None => linkdb::bookmark::filter(&ctx.pg_pool, user_id, Some(categories), status).await?,
     };
-    Ok(rows
-        .into_iter()
-        .map(|r| types::from_db_response(r))
-        .collect())
+    Ok(rows.into_iter().map(types::from_db_response).collect())
 }
 
 #[tracing::instrument(name = "service::bookmark-delete", skip_all)]
-pub async fn delete(ctx: &Ctx, _user_id: &str, bm_id: i64) -> Result<(), types::BookmarkError> {
-    let mut tx = ctx.pg_pool.begin().await?;
-
-    linkdb::bookmark::query::delete_by_id(&mut tx, bm_id).await?;
-    linkdb::bookmark::cat_map::delete_by_bookmark_id(&mut tx, bm_id).await?;
-    tx.commit().await?;
+pub async fn delete(ctx: &Ctx, user_id: &str, bm_id: i64) -> Result<(), types::BookmarkError> {
+    if !ctx.repos.bookmarks.delete(user_id, bm_id).await? {
+        return Err(types::BookmarkError::NotFound(format!(
+            "bookmark with id: `{}`",
+            bm_id
+        )));
+    }
     Ok(())
 }
 
+/// Only the owner files a bookmark, or a contributor of a category holding it.
 #[tracing::instrument(name = "service::bookmark-add-categories", skip_all)]
 pub async fn add_categories(
     ctx: &Ctx,
@@ -142,28 +153,354 @@ pub async fn add_categories(
     bm_id: i64,
     categories: &[String],
 ) -> Result<(), types::BookmarkError> {
-    // first upsert the category
-    let categories: Vec<_> = categories
-        .iter()
-        .map(|c| super::cat::types::from_cat_name(c, user_id))
-        .collect();
+    let bookmark = ctx
+        .repos
+        .bookmarks
+        .get_visible(user_id, bm_id)
+        .await?
+        .ok_or_else(|| types::BookmarkError::NotFound(format!("bookmark with id: `{}`", bm_id)))?;
+    if bookmark.user_id != user_id {
+        let mut contributor = false;
+        for c in &bookmark.categories {
+            if let Some((_, role)) = super::member::category(ctx, user_id, c).await? {
+                contributor |= role >= MemberRole::Contributor;
+            }
+        }
+        if !contributor {
+            return Err(types::BookmarkError::Forbidden(format!(
+                "can't add categories to bookmark with id: `{}`",
+                bm_id
+            )));
+        }
+    }
 
-    let mut tx = ctx.pg_pool.begin().await?;
-    let now = chrono::Utc::now();
-    let category_ids = linkdb::category::upsert(&mut tx, categories, now).await?;
-    linkdb::bookmark::cat_map::add_categories(&mut tx, bm_id, category_ids.as_slice()).await?;
-    tx.commit().await?;
+    let (own, shared) = categories_of(ctx, user_id, categories).await?;
+    ctx.repos
+        .bookmarks
+        .add_categories(bm_id, own, shared, chrono::Utc::now())
+        .await?;
 
     Ok(())
 }
 
+/// Editors remove any bookmark from a shared category, contributors only their own.
 #[tracing::instrument(name = "service::bookmark-remove-categories", skip_all)]
 pub async fn remove_category(
     ctx: &Ctx,
-    _user_id: &str,
+    user_id: &str,
     bm_id: i64,
     categories: &[String],
 ) -> Result<(), types::BookmarkError> {
-    linkdb::bookmark::cat_map::remove_categories(&ctx.pg_pool, bm_id, categories).await?;
+    let bookmark = ctx
+        .repos
+        .bookmarks
+        .get_visible(user_id, bm_id)
+        .await?
+        .ok_or_else(|| types::BookmarkError::NotFound(format!("bookmark with id: `{}`", bm_id)))?;
+
+    let mut category_ids = Vec::with_capacity(categories.len());
+    for c in categories {
+        let Some((cat, role)) = super::member::category(ctx, user_id, c).await? else {
+            continue;
+        };
+        let own_bookmark = bookmark.user_id == user_id;
+        if role >= MemberRole::Editor || (role >= MemberRole::Contributor && own_bookmark) {
+            category_ids.push(cat.id);
+        } else {
+            return Err(types::BookmarkError::Forbidden(format!(
+                "can't remove bookmarks from category: `{}`",
+                c
+            )));
+        }
+    }
+    ctx.repos
+        .bookmarks
+        .remove_categories(bm_id, &category_ids)
+        .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::link::testing;
+
+    fn req(url: &str, categories: &[&str]) -> BmCreateReq {
+        BmCreateReq {
+            title: None,
+            url: url.to_string(),
+            content: None,
+            referrer: None,
+            status: None,
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    async fn list_all(ctx: &Ctx, user_id: &str) -> Vec<BmResponse> {
+        list(ctx, user_id, &None, &[], &None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_reuses_own_categories_and_keeps_existing_mappings() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("https://a.example", &["rust"]))
+            .await
+            .unwrap();
+        create(&ctx, "alice", req("https://b.example", &["rust", "db"]))
+            .await
+            .unwrap();
+
+        let cats = ctx.repos.categories.list_all("alice").await.unwrap();
+        let names: Vec<_> = cats.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["db", "rust"]);
+
+        let id = list_all(&ctx, "alice").await[1].id;
+        add_categories(&ctx, "alice", id, &["rust".to_string(), "web".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            get(&ctx, "alice", id).await.unwrap().categories,
+            ["db", "rust", "web"]
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_category_fails_without_creating_the_bookmark() {
+        let (ctx, _) = testing::ctx();
+        let err = create(&ctx, "alice", req("https://a.example", &["rust", "rust"]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::BookmarkError::Database(_)));
+        assert!(list_all(&ctx, "alice").await.is_empty());
+        assert!(ctx
+            .repos
+            .categories
+            .list_all("alice")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn shared_category_needs_a_contributor() {
+        let (ctx, memory) = testing::ctx();
+        create(&ctx, "alice", req("https://a.example", &["rust"]))
+            .await
+            .unwrap();
+        let rust = ctx
+            .repos
+            .categories
+            .get_id_by_name("alice", "rust")
+            .await
+            .unwrap();
+
+        testing::share(&memory, rust, None, "bob", MemberRole::Viewer);
+        let err = create(&ctx, "bob", req("https://b.example", &["@alice/rust"]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::BookmarkError::Forbidden(_)));
+        let err = create(&ctx, "carol", req("https://c.example", &["@alice/rust"]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::BookmarkError::NotFound(_)));
+
+        testing::share(&memory, rust, None, "bob", MemberRole::Contributor);
+        create(
+            &ctx,
+            "bob",
+            req("https://b.example", &["@alice/rust", "mine"]),
+        )
+        .await
+        .unwrap();
+
+        // alice only sees the category she shares, bob sees it as someone else's
+        let alice = list_all(&ctx, "alice").await;
+        assert_eq!(alice.len(), 2);
+        assert_eq!(alice[1].categories, ["rust"]);
+        let bob = list_all(&ctx, "bob").await;
+        assert_eq!(bob[1].categories, ["@alice/rust", "mine"]);
+        assert!(list_all(&ctx, "carol").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn filter_by_category_label_and_status() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("https://a.example", &["rust"]))
+            .await
+            .unwrap();
+        let mut read = req("https://b.example", &["db"]);
+        read.status = Some("RD".to_string());
+        create(&ctx, "alice", read).await.unwrap();
+
+        let rust = list(&ctx, "alice", &None, &["rust".to_string()], &None)
+            .await
+            .unwrap();
+        assert_eq!(rust.len(), 1);
+        assert_eq!(rust[0].url, "https://a.example");
+
+        let read = list(&ctx, "alice", &None, &[], &Some("RD".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].url, "https://b.example");
+    }
+
+    #[tokio::test]
+    async fn list_by_topic_only_shows_its_categories() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("https://a.example", &["rust", "db"]))
+            .await
+            .unwrap();
+        create(&ctx, "alice", req("https://b.example", &["web"]))
+            .await
+            .unwrap();
+        create(&ctx, "alice", req("https://c.example", &[]))
+            .await
+            .unwrap();
+        let topic = linkdb::topic::TopicRowI {
+            name: "code".to_string(),
+            display_name: None,
+            description: None,
+            about: None,
+            priority: 0,
+            active: true,
+            public: false,
+            user_id: "alice".to_string(),
+        };
+        let (topic_id, _) = ctx.repos.topics.insert(topic).await.unwrap();
+        let rust = ctx
+            .repos
+            .categories
+            .get_id_by_name("alice", "rust")
+            .await
+            .unwrap();
+        ctx.repos
+            .topics
+            .connect(topic_id, rust.unwrap())
+            .await
+            .unwrap();
+
+        let rows = list(&ctx, "alice", &Some("code".to_string()), &[], &None)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].url, "https://a.example");
+        assert_eq!(rows[0].categories, ["rust"]);
+    }
+
+    #[tokio::test]
+    async fn update_and_delete_own_bookmarks() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("https://a.example", &["rust"]))
+            .await
+            .unwrap();
+        let id = list_all(&ctx, "alice").await[0].id;
+
+        let change = || BmUpdateReq {
+            title: Some("A".to_string()),
+            url: None,
+            content: None,
+            referrer: None,
+            status: None,
+        };
+        let err = update(&ctx, "bob", id, change()).await.unwrap_err();
+        assert!(matches!(err, types::BookmarkError::NotFound(_)));
+        let updated = update(&ctx, "alice", id, change()).await.unwrap();
+        assert_eq!(updated.title.as_deref(), Some("A"));
+        assert_eq!(updated.url, "https://a.example");
+
+        let err = delete(&ctx, "bob", id).await.unwrap_err();
+        assert!(matches!(err, types::BookmarkError::NotFound(_)));
+        delete(&ctx, "alice", id).await.unwrap();
+        assert!(list_all(&ctx, "alice").await.is_empty());
+        // nothing maps to the category anymore
+        crate::services::link::cat::delete(&ctx, "alice", "rust")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn only_web_urls_are_saved() {
+        let (ctx, _) = testing::ctx();
+        let err = create(&ctx, "alice", req("javascript:alert(1)", &["rust"]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::BookmarkError::InvalidInput(f) if f.field == "url"));
+        assert!(list_all(&ctx, "alice").await.is_empty());
+
+        create(&ctx, "alice", req("https://a.example", &["rust"]))
+            .await
+            .unwrap();
+        let id = list_all(&ctx, "alice").await[0].id;
+        let change = BmUpdateReq {
+            title: None,
+            url: Some("data:text/html,<script>alert(1)</script>".to_string()),
+            content: None,
+            referrer: None,
+            status: None,
+        };
+        let err = update(&ctx, "alice", id, change).await.unwrap_err();
+        assert!(matches!(err, types::BookmarkError::InvalidInput(_)));
+        assert_eq!(
+            get(&ctx, "alice", id).await.unwrap().url,
+            "https://a.example"
+        );
+    }
+
+    #[tokio::test]
+    async fn contributors_only_remove_their_own_bookmarks() {
+        let (ctx, memory) = testing::ctx();
+        create(&ctx, "alice", req("https://a.example", &["rust"]))
+            .await
+            .unwrap();
+        let rust = ctx
+            .repos
+            .categories
+            .get_id_by_name("alice", "rust")
+            .await
+            .unwrap();
+        testing::share(&memory, rust, None, "bob", MemberRole::Contributor);
+        let id = list_all(&ctx, "alice").await[0].id;
+
+        let shared = ["@alice/rust".to_string()];
+        let err = remove_category(&ctx, "bob", id, &shared).await.unwrap_err();
+        assert!(matches!(err, types::BookmarkError::Forbidden(_)));
+
+        testing::share(&memory, rust, None, "bob", MemberRole::Editor);
+        remove_category(&ctx, "bob", id, &shared).await.unwrap();
+        assert!(get(&ctx, "alice", id).await.unwrap().categories.is_empty());
+        let err = get(&ctx, "bob", id).await.unwrap_err();
+        assert!(matches!(err, types::BookmarkError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn viewers_do_not_file_others_bookmarks() {
+        let (ctx, memory) = testing::ctx();
+        create(&ctx, "alice", req("https://a.example", &["rust"]))
+            .await
+            .unwrap();
+        let rust = ctx
+            .repos
+            .categories
+            .get_id_by_name("alice", "rust")
+            .await
+            .unwrap();
+        testing::share(&memory, rust, None, "bob", MemberRole::Viewer);
+        let id = list_all(&ctx, "alice").await[0].id;
+
+        let err = add_categories(&ctx, "bob", id, &["mine".to_string()])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::BookmarkError::Forbidden(_)));
+        assert_eq!(get(&ctx, "alice", id).await.unwrap().categories, ["rust"]);
+
+        testing::share(&memory, rust, None, "bob", MemberRole::Contributor);
+        add_categories(&ctx, "bob", id, &["mine".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            get(&ctx, "bob", id).await.unwrap().categories,
+            ["@alice/rust", "mine"]
+        );
+    }
+}
@@ -7,6 +7,21 @@ pub enum BookmarkError {
     Database(#[from] sqlx::Error),
     #[error("NotFoundError: {0}")]
     NotFound(String),
+    #[error("ForbiddenError: {0}")]
+    Forbidden(String),
+    #[error("InvalidInputError: {0}")]
+    InvalidInput(crate::problem::FieldError),
+}
+
+/// Bookmarks end up as links on public pages and in feeds, only web urls are kept.
+pub fn check_url(url: &str) -> Result<(), BookmarkError> {
+    if crate::utils::is_web_url(url) {
+        Ok(())
+    } else {
+        Err(BookmarkError::InvalidInput(
+            crate::problem::FieldError::new("url", "must be an http or https url"),
+        ))
+    }
 }
 
 pub fn from_req(
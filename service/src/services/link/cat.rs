@@ -1,31 +1,34 @@
 pub mod types;
-use crate::controller::link::types::{CatCreateReq, CatGetRes, CatUpdateReq};
+use super::member::types as member_types;
+use crate::controller::link::types::{CatCreateReq, CatGetRes, CatUpdateReq, MemberRole};
 use crate::ctx::Ctx;
 
 #[tracing::instrument(name = "service::cat-create", skip_all)]
 pub async fn create(ctx: &Ctx, user_id: &str, req: CatCreateReq) -> Result<(), types::CatError> {
     let row = types::from_req(req, user_id);
-    linkdb::category::insert(&ctx.pg_pool, row).await?;
+    ctx.repos.categories.insert(row).await?;
     Ok(())
 }
 
+/// `cat_name` can be `@owner/name` for a category shared with the user.
 #[tracing::instrument(name = "service::cat-get", skip_all)]
 pub async fn get(ctx: &Ctx, user_id: &str, cat_name: &str) -> Result<CatGetRes, types::CatError> {
-    let cat_row = linkdb::category::get_by_name(&ctx.pg_pool, user_id, cat_name)
+    let (cat_row, role) = super::member::category(ctx, user_id, cat_name)
         .await?
-        .ok_or_else(|| {
-            types::CatError::NotFound(format!("cat with name: `{}` not found", cat_name))
-        })?;
+        .ok_or_else(|| types::CatError::NotFound(format!("category with name: `{}`", cat_name)))?;
 
     // Get topics for this category
-    let topics = linkdb::topic::list_by_cat_name(&ctx.pg_pool, &[cat_name.to_string()], user_id)
+    let topics = ctx
+        .repos
+        .topics
+        .list_by_cat_name(std::slice::from_ref(&cat_row.name), &cat_row.user_id)
         .await?
         .into_iter()
-        .map(|topic| topic.name)
+        .map(|topic| member_types::label(user_id, &cat_row.user_id, &topic.name))
         .collect();
 
     Ok(CatGetRes {
-        name: cat_row.name,
+        name: member_types::label(user_id, &cat_row.user_id, &cat_row.name),
         display_name: cat_row.display_name,
         about: cat_row.about,
         description: cat_row.description,
@@ -34,9 +37,12 @@ pub async fn get(ctx: &Ctx, user_id: &str, cat_name: &str) -> Result<CatGetRes,
         created_on: cat_row.created_on,
         updated_on: cat_row.updated_on,
         topics,
+        owner: cat_row.user_id,
+        role,
     })
 }
 
+/// Own categories and, unless filtered by topics, the ones shared with the user.
 #[tracing::instrument(name = "service::cat-list", skip_all)]
 pub async fn list(
     ctx: &Ctx,
@@ -46,22 +52,39 @@ pub async fn list(
     tracing::info!(msg="topics", t=?topics);
 
     let cat_rows = if topics.is_empty() {
-        linkdb::category::list_all(&ctx.pg_pool, user_id).await?
+        ctx.repos.categories.list_all(user_id).await?
     } else {
-        linkdb::category::list_by_topic_name(&ctx.pg_pool, user_id, topics).await?
+        ctx.repos
+            .categories
+            .list_by_topic_name(user_id, topics)
+            .await?
     };
     let mut result = Vec::new();
     for cat_row in cat_rows {
         // Get topics for this category
-        let topics =
-            linkdb::topic::list_by_cat_name(&ctx.pg_pool, &[cat_row.name.clone()], user_id)
+        let topics = ctx
+            .repos
+            .topics
+            .list_by_cat_name(std::slice::from_ref(&cat_row.name), &cat_row.user_id)
+            .await?
+            .into_iter()
+            .map(|topic| member_types::label(user_id, &cat_row.user_id, &topic.name))
+            .collect();
+        let role = if cat_row.user_id == user_id {
+            MemberRole::Owner
+        } else {
+            ctx.repos
+                .categories
+                .roles(cat_row.id, user_id)
                 .await?
-                .into_iter()
-                .map(|topic| topic.name)
-                .collect();
+                .iter()
+                .map(|r| member_types::role_from_code(r))
+                .max()
+                .unwrap_or(MemberRole::Viewer)
+        };
 
         result.push(CatGetRes {
-            name: cat_row.name,
+            name: member_types::label(user_id, &cat_row.user_id, &cat_row.name),
             display_name: cat_row.display_name,
             about: None,
             description: cat_row.description,
@@ -70,11 +93,14 @@ pub async fn list(
             created_on: cat_row.created_on,
             updated_on: cat_row.updated_on,
             topics,
+            owner: cat_row.user_id,
+            role,
         });
     }
     Ok(result)
 }
 
+/// Editors can update a shared category, only owners can change `public`.
 #[tracing::instrument(name = "service::cat-update", skip_all)]
 pub async fn update(
     ctx: &Ctx,
@@ -82,23 +108,34 @@ pub async fn update(
     cat_name: &str,
     req: CatUpdateReq,
 ) -> Result<(), types::CatError> {
-    linkdb::category::update(
-        &ctx.pg_pool,
-        user_id,
-        cat_name,
-        req.display_name,
-        req.about,
-        req.description,
-        req.public,
-        req.priority,
-    )
-    .await?;
+    let (cat_row, role) = super::member::category(ctx, user_id, cat_name)
+        .await?
+        .ok_or_else(|| types::CatError::NotFound(format!("category with name: `{}`", cat_name)))?;
+    if role < MemberRole::Editor || (req.public.is_some() && role < MemberRole::Owner) {
+        return Err(types::CatError::Forbidden(format!(
+            "a {:?} can't update category: `{}`",
+            role, cat_name
+        )));
+    }
+
+    ctx.repos
+        .categories
+        .update(
+            &cat_row.user_id,
+            &cat_row.name,
+            req.display_name,
+            req.about,
+            req.description,
+            req.public,
+            req.priority,
+        )
+        .await?;
     Ok(())
 }
 
 #[tracing::instrument(name = "service::cat-delete", skip_all)]
 pub async fn delete(ctx: &Ctx, user_id: &str, cat_name: &str) -> Result<(), types::CatError> {
-    linkdb::category::delete(&ctx.pg_pool, user_id, cat_name).await?;
+    ctx.repos.categories.delete(user_id, cat_name).await?;
     Ok(())
 }
 
@@ -109,13 +146,19 @@ pub async fn add_topic(
     cat_name: &str,
     topic_name: &str,
 ) -> Result<(), types::CatError> {
-    let topic_id = linkdb::topic::get_id_by_name(&ctx.pg_pool, user_id, topic_name)
+    let topic_id = ctx
+        .repos
+        .topics
+        .get_id_by_name(user_id, topic_name)
         .await?
         .ok_or_else(|| types::CatError::NotFound(format!("topic with name: `{}`", topic_name)))?;
-    let category_id = linkdb::category::get_id_by_name(&ctx.pg_pool, user_id, cat_name)
+    let category_id = ctx
+        .repos
+        .categories
+        .get_id_by_name(user_id, cat_name)
         .await?
         .ok_or_else(|| types::CatError::NotFound(format!("category with name: `{}`", cat_name)))?;
-    linkdb::topic_cat_map::connect(&ctx.pg_pool, topic_id, category_id).await?;
+    ctx.repos.topics.connect(topic_id, category_id).await?;
 
     Ok(())
 }
@@ -127,13 +170,19 @@ pub async fn remove_topic(
     cat_name: &str,
     topic_name: &str,
 ) -> Result<(), types::CatError> {
-    let topic_id = linkdb::topic::get_id_by_name(&ctx.pg_pool, user_id, topic_name)
+    let topic_id = ctx
+        .repos
+        .topics
+        .get_id_by_name(user_id, topic_name)
         .await?
         .ok_or_else(|| types::CatError::NotFound(format!("topic with name: `{}`", topic_name)))?;
-    let category_id = linkdb::category::get_id_by_name(&ctx.pg_pool, user_id, cat_name)
+    let category_id = ctx
+        .repos
+        .categories
+        .get_id_by_name(user_id, cat_name)
         .await?
         .ok_or_else(|| types::CatError::NotFound(format!("category with name: `{}`", cat_name)))?;
-    linkdb::topic_cat_map::delete(&ctx.pg_pool, topic_id, category_id).await?;
+    ctx.repos.topics.disconnect(topic_id, category_id).await?;
     Ok(())
 }
 
@@ -144,16 +193,17 @@ pub async fn add_topics_bulk(
     cat_name: &str,
     topic_names: &[String],
 ) -> Result<(), types::CatError> {
-    let category_id = linkdb::category::get_id_by_name(&ctx.pg_pool, user_id, cat_name)
+    let category_id = ctx
+        .repos
+        .categories
+        .get_id_by_name(user_id, cat_name)
         .await?
         .ok_or_else(|| types::CatError::NotFound(format!("category with name: `{}`", cat_name)))?;
 
     // Get topic IDs for all topics
     let mut topic_ids = Vec::new();
     for topic_name in topic_names {
-        if let Some(topic_id) =
-            linkdb::topic::get_id_by_name(&ctx.pg_pool, user_id, topic_name).await?
-        {
+        if let Some(topic_id) = ctx.repos.topics.get_id_by_name(user_id, topic_name).await? {
             topic_ids.push(topic_id);
         } else {
             return Err(types::CatError::NotFound(format!(
@@ -165,7 +215,7 @@ pub async fn add_topics_bulk(
 
     // Add all topic-category mappings
     for topic_id in topic_ids {
-        linkdb::topic_cat_map::connect(&ctx.pg_pool, topic_id, category_id).await?;
+        ctx.repos.topics.connect(topic_id, category_id).await?;
     }
 
     Ok(())
@@ -178,16 +228,17 @@ pub async fn remove_topics_bulk(
     cat_name: &str,
     topic_names: &[String],
 ) -> Result<(), types::CatError> {
-    let category_id = linkdb::category::get_id_by_name(&ctx.pg_pool, user_id, cat_name)
+    let category_id = ctx
+        .repos
+        .categories
+        .get_id_by_name(user_id, cat_name)
         .await?
         .ok_or_else(|| types::CatError::NotFound(format!("category with name: `{}`", cat_name)))?;
 
     // Get topic IDs for all topics
     let mut topic_ids = Vec::new();
     for topic_name in topic_names {
-        if let Some(topic_id) =
-            linkdb::topic::get_id_by_name(&ctx.pg_pool, user_id, topic_name).await?
-        {
+        if let Some(topic_id) = ctx.repos.topics.get_id_by_name(user_id, topic_name).await? {
             topic_ids.push(topic_id);
         } else {
             return Err(types::CatError::NotFound(format!(
@@ -199,8 +250,139 @@ pub async fn remove_topics_bulk(
 
     // Remove all topic-category mappings
     for topic_id in topic_ids {
-        linkdb::topic_cat_map::delete(&ctx.pg_pool, topic_id, category_id).await?;
+        ctx.repos.topics.disconnect(topic_id, category_id).await?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::link::testing;
+
+    fn req(name: &str) -> CatCreateReq {
+        CatCreateReq {
+            name: name.to_string(),
+            display_name: None,
+            about: None,
+            description: None,
+            public: false,
+            priority: 0,
+        }
+    }
+
+    async fn topic(ctx: &Ctx, user_id: &str, name: &str) -> i64 {
+        let row = linkdb::topic::TopicRowI {
+            name: name.to_string(),
+            display_name: None,
+            description: None,
+            about: None,
+            priority: 0,
+            active: true,
+            public: false,
+            user_id: user_id.to_string(),
+        };
+        ctx.repos.topics.insert(row).await.unwrap().0
+    }
+
+    #[tokio::test]
+    async fn names_are_unique_per_user() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("rust")).await.unwrap();
+        create(&ctx, "bob", req("rust")).await.unwrap();
+        match create(&ctx, "alice", req("rust")).await {
+            Err(types::CatError::Database(e)) => assert!(testing::is_unique_violation(&e)),
+            other => panic!("expected a unique violation, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn topics_connect_once() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("rust")).await.unwrap();
+        topic(&ctx, "alice", "code").await;
+
+        add_topic(&ctx, "alice", "rust", "code").await.unwrap();
+        match add_topic(&ctx, "alice", "rust", "code").await {
+            Err(types::CatError::Database(e)) => assert!(testing::is_unique_violation(&e)),
+            other => panic!("expected a unique violation, got {:?}", other.map(|_| ())),
+        }
+        assert_eq!(get(&ctx, "alice", "rust").await.unwrap().topics, ["code"]);
+
+        let err = add_topic(&ctx, "alice", "rust", "missing")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::CatError::NotFound(_)));
+
+        remove_topic(&ctx, "alice", "rust", "code").await.unwrap();
+        assert!(get(&ctx, "alice", "rust").await.unwrap().topics.is_empty());
+    }
+
+    #[tokio::test]
+    async fn delete_fails_while_mapped() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("rust")).await.unwrap();
+        topic(&ctx, "alice", "code").await;
+        add_topic(&ctx, "alice", "rust", "code").await.unwrap();
+
+        match delete(&ctx, "alice", "rust").await {
+            Err(types::CatError::Database(e)) => assert!(testing::is_foreign_key_violation(&e)),
+            other => panic!("expected a foreign key violation, got {:?}", other),
+        }
+        remove_topic(&ctx, "alice", "rust", "code").await.unwrap();
+        delete(&ctx, "alice", "rust").await.unwrap();
+        assert!(matches!(
+            delete(&ctx, "alice", "rust").await,
+            Err(types::CatError::Database(sqlx::Error::RowNotFound))
+        ));
+    }
+
+    #[tokio::test]
+    async fn list_puts_own_first_and_shares_through_topics() {
+        let (ctx, memory) = testing::ctx();
+        create(&ctx, "alice", req("rust")).await.unwrap();
+        create(&ctx, "alice", req("db")).await.unwrap();
+        create(&ctx, "bob", req("go")).await.unwrap();
+        create(&ctx, "carol", req("zig")).await.unwrap();
+        let code = topic(&ctx, "alice", "code").await;
+        add_topic(&ctx, "alice", "rust", "code").await.unwrap();
+        testing::share(&memory, None, Some(code), "bob", MemberRole::Editor);
+
+        let names = |rows: Vec<CatGetRes>| {
+            rows.into_iter()
+                .map(|c| (c.name, c.role))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(list(&ctx, "bob", &[]).await.unwrap()),
+            [
+                ("go".to_string(), MemberRole::Owner),
+                ("@alice/rust".to_string(), MemberRole::Editor),
+            ]
+        );
+        assert_eq!(
+            names(list(&ctx, "alice", &["code".to_string()]).await.unwrap()),
+            [("rust".to_string(), MemberRole::Owner)]
+        );
+
+        // editors update details, only owners change `public`
+        let change = |public| CatUpdateReq {
+            display_name: Some("Rust".to_string()),
+            about: None,
+            description: None,
+            public,
+            priority: None,
+        };
+        update(&ctx, "bob", "@alice/rust", change(None))
+            .await
+            .unwrap();
+        let err = update(&ctx, "bob", "@alice/rust", change(Some(true)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::CatError::Forbidden(_)));
+        let rust = get(&ctx, "alice", "rust").await.unwrap();
+        assert_eq!(rust.display_name.as_deref(), Some("Rust"));
+        assert!(!rust.public);
+    }
+}
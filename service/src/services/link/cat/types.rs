@@ -7,6 +7,8 @@ pub enum CatError {
     Database(#[from] sqlx::Error),
     #[error("NotFoundError: {0}")]
     NotFound(String),
+    #[error("ForbiddenError: {0}")]
+    Forbidden(String),
 }
 
 pub fn from_cat_name(name: &str, user_id: &str) -> CatRowI {
@@ -28,7 +30,7 @@ pub fn from_req(req: types::CatCreateReq, user_id: &str) -> CatRowI {
         display_name: req.display_name,
         description: req.description,
         about: req.about,
-        priority: 0,
+        priority: req.priority,
         active: true,
         public: req.public,
         user_id: user_id.to_string(),
@@ -0,0 +1,210 @@
+pub mod fetch;
+pub mod opml;
+pub mod poller;
+pub mod types;
+
+use crate::controller::link::types::{FeedPollRes, FeedResponse, FeedSubscribeReq, OpmlImportRes};
+use crate::ctx::Ctx;
+use linkdb::feed::{FeedPollState, FeedRow};
+
+#[tracing::instrument(name = "service::feed-subscribe", skip_all)]
+pub async fn subscribe(
+    ctx: &Ctx,
+    user_id: &str,
+    req: FeedSubscribeReq,
+) -> Result<FeedResponse, types::FeedError> {
+    let url = req.url.trim().to_string();
+    types::check_url(&url)?;
+    let now = chrono::Utc::now();
+    let mut tx = ctx.db.begin().await?;
+    let category_ids = linkdb::category::upsert(
+        &mut tx,
+        vec![super::cat::types::from_cat_name(&req.category, user_id)],
+        now,
+    )
+    .await?;
+    let row = linkdb::feed::FeedI {
+        url,
+        user_id: user_id.to_string(),
+        title: req.title,
+        site_url: None,
+        category_id: category_ids[0],
+        active: true,
+    };
+    let feed_id = linkdb::feed::upsert(&mut tx, row, now).await?;
+    tx.commit().await?;
+
+    // first poll right away, a failure is recorded on the feed and retried by the poller
+    let feed = get_row(ctx, user_id, feed_id).await?;
+    if let Err(e) = poll(&ctx.db, &ctx.feed_client, &feed).await {
+        tracing::warn!(msg = "first poll failed", feed_id, err = %e);
+    }
+    get(ctx, user_id, feed_id).await
+}
+
+#[tracing::instrument(name = "service::feed-get", skip_all)]
+pub async fn get(ctx: &Ctx, user_id: &str, id: i64) -> Result<FeedResponse, types::FeedError> {
+    Ok(types::from_db_response(get_row(ctx, user_id, id).await?))
+}
+
+async fn get_row(ctx: &Ctx, user_id: &str, id: i64) -> Result<FeedRow, types::FeedError> {
+    linkdb::feed::get_by_id(&ctx.db, user_id, id)
+        .await?
+        .ok_or_else(|| types::FeedError::NotFound(format!("feed with id: `{}`", id)))
+}
+
+#[tracing::instrument(name = "service::feed-list", skip_all)]
+pub async fn list(ctx: &Ctx, user_id: &str) -> Result<Vec<FeedResponse>, types::FeedError> {
+    let rows = linkdb::feed::list_all(&ctx.db, user_id).await?;
+    Ok(rows.into_iter().map(types::from_db_response).collect())
+}
+
+#[tracing::instrument(name = "service::feed-delete", skip_all)]
+pub async fn delete(ctx: &Ctx, user_id: &str, id: i64) -> Result<(), types::FeedError> {
+    let mut tx = ctx.db.begin().await?;
+    linkdb::feed::delete(&mut tx, user_id, id).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "service::feed-refresh", skip_all)]
+pub async fn refresh(ctx: &Ctx, user_id: &str, id: i64) -> Result<FeedPollRes, types::FeedError> {
+    let feed = get_row(ctx, user_id, id).await?;
+    let new_bookmarks = poll(&ctx.db, &ctx.feed_client, &feed).await?;
+    Ok(FeedPollRes { new_bookmarks })
+}
+
+/// Feeds in the document are subscribed into the category named by their
+/// enclosing folder, top level feeds go to `default_category`.
+#[tracing::instrument(name = "service::feed-import-opml", skip_all)]
+pub async fn import_opml(
+    ctx: &Ctx,
+    user_id: &str,
+    xml: &str,
+    default_category: &str,
+) -> Result<OpmlImportRes, types::FeedError> {
+    let feeds = opml::parse(xml)?;
+    for feed in feeds.iter() {
+        types::check_url(&feed.url)?;
+    }
+    let now = chrono::Utc::now();
+
+    let mut tx = ctx.db.begin().await?;
+    for feed in feeds.iter() {
+        let category = feed.category.as_deref().unwrap_or(default_category);
+        let category_ids = linkdb::category::upsert(
+            &mut tx,
+            vec![super::cat::types::from_cat_name(category, user_id)],
+            now,
+        )
+        .await?;
+        let row = linkdb::feed::FeedI {
+            url: feed.url.clone(),
+            user_id: user_id.to_string(),
+            title: feed.title.clone(),
+            site_url: feed.site_url.clone(),
+            category_id: category_ids[0],
+            active: true,
+        };
+        linkdb::feed::upsert(&mut tx, row, now).await?;
+    }
+    tx.commit().await?;
+
+    // entries are fetched by the poller, imports can hold hundreds of feeds
+    Ok(OpmlImportRes { feeds: feeds.len() })
+}
+
+#[tracing::instrument(name = "service::feed-export-opml", skip_all)]
+pub async fn export_opml(ctx: &Ctx, user_id: &str) -> Result<String, types::FeedError> {
+    let feeds = linkdb::feed::list_all(&ctx.db, user_id).await?;
+    opml::render(&format!("LinkNova feeds of {}", user_id), feeds.as_slice())
+}
+
+/// Fetches the feed and saves every entry not seen before as an unread
+/// bookmark in the feed's category, returns the number of bookmarks created.
+#[tracing::instrument(name = "service::feed-poll", skip_all, fields(feed_id = feed.id))]
+pub async fn poll(
+    db: &linkdb::Db,
+    client: &reqwest::Client,
+    feed: &FeedRow,
+) -> Result<usize, types::FeedError> {
+    let now = chrono::Utc::now();
+    let outcome = fetch::fetch(
+        client,
+        &feed.url,
+        feed.etag.as_deref(),
+        feed.last_modified.as_deref(),
+    )
+    .await;
+
+    let (state, result) = match outcome {
+        Ok(fetch::FetchOutcome::NotModified) => (FeedPollState::default(), Ok(0)),
+        Ok(fetch::FetchOutcome::Fetched {
+            feed: parsed,
+            etag,
+            last_modified,
+        }) => match save_entries(db, feed, &fetch::entries(&parsed), now).await {
+            Ok(saved) => (
+                FeedPollState {
+                    title: parsed.title.map(|t| t.content.trim().to_string()),
+                    site_url: fetch::entry_link(&parsed.links),
+                    etag,
+                    last_modified,
+                    last_error: None,
+                },
+                Ok(saved),
+            ),
+            // validators are not stored so that the next poll fetches the entries again
+            Err(e) => (
+                FeedPollState {
+                    last_error: Some(e.to_string()),
+                    ..Default::default()
+                },
+                Err(e),
+            ),
+        },
+        Err(e) => (
+            FeedPollState {
+                last_error: Some(e.to_string()),
+                ..Default::default()
+            },
+            Err(e),
+        ),
+    };
+
+    linkdb::feed::update_poll_state(db, feed.id, state, now).await?;
+    result
+}
+
+async fn save_entries(
+    db: &linkdb::Db,
+    feed: &FeedRow,
+    entries: &[fetch::NewEntry],
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<usize, types::FeedError> {
+    let mut tx = db.begin().await?;
+    let mut saved = 0;
+    for entry in entries {
+        let Some(entry_id) =
+            linkdb::feed::entry::insert_new(&mut tx, feed.id, &entry.guid, now).await?
+        else {
+            continue;
+        };
+        let row = linkdb::bookmark::BookmarkI {
+            url: entry.url.clone(),
+            user_id: feed.user_id.clone(),
+            title: entry.title.clone(),
+            content: entry.content.clone(),
+            referrer: Some(feed.url.clone()),
+            status: "UN".to_string(),
+            created_on: now,
+            updated_on: now,
+        };
+        let bm_id = linkdb::bookmark::insert(&mut tx, row).await?;
+        linkdb::bookmark::cat_map::add_categories(&mut tx, bm_id, &[feed.category_id]).await?;
+        linkdb::feed::entry::set_bookmark(&mut tx, entry_id, bm_id).await?;
+        saved += 1;
+    }
+    tx.commit().await?;
+    Ok(saved)
+}
@@ -0,0 +1,119 @@
+use super::types::FeedError;
+
+/// Bodies past this are not read, a feed is never near it.
+pub const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+
+pub enum FetchOutcome {
+    /// Server answered `304 Not Modified` to the conditional request.
+    NotModified,
+    Fetched {
+        feed: Box<feed_rs::model::Feed>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+pub fn client(timeout: std::time::Duration) -> Result<reqwest::Client, FeedError> {
+    Ok(reqwest::Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!("linknova/", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+/// Fetches and parses the feed, sending the validators from the previous poll
+/// as `If-None-Match`/`If-Modified-Since`.
+#[tracing::instrument(name = "service::feed-fetch", skip(client, etag, last_modified))]
+pub async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<FetchOutcome, FeedError> {
+    let mut req = client.get(url);
+    if let Some(etag) = etag {
+        req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        req = req.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = req.send().await?;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(FetchOutcome::NotModified);
+    }
+    if !response.status().is_success() {
+        return Err(FeedError::UpstreamStatus(response.status().as_u16()));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+    let body = read_body(response, MAX_BODY_BYTES).await?;
+    let feed = feed_rs::parser::parse(body.as_slice())?;
+    Ok(FetchOutcome::Fetched {
+        feed: Box::new(feed),
+        etag,
+        last_modified,
+    })
+}
+
+/// Reads the body chunk by chunk, failing as soon as it grows past `max` bytes.
+async fn read_body(mut response: reqwest::Response, max: usize) -> Result<Vec<u8>, FeedError> {
+    if response
+        .content_length()
+        .is_some_and(|len| len > max as u64)
+    {
+        return Err(FeedError::TooLarge(max));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max {
+            return Err(FeedError::TooLarge(max));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+pub struct NewEntry {
+    pub guid: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+}
+
+/// Entries without a web link cannot become bookmarks and are skipped.
+pub fn entries(feed: &feed_rs::model::Feed) -> Vec<NewEntry> {
+    feed.entries
+        .iter()
+        .filter_map(|e| {
+            let url = entry_link(&e.links).filter(|u| crate::utils::is_web_url(u))?;
+            Some(NewEntry {
+                guid: e.id.clone(),
+                url,
+                title: e.title.as_ref().map(|t| t.content.trim().to_string()),
+                content: e
+                    .summary
+                    .as_ref()
+                    .map(|s| s.content.clone())
+                    .or_else(|| e.content.as_ref().and_then(|c| c.body.clone())),
+            })
+        })
+        .collect()
+}
+
+/// Prefers the `alternate` link, which is what the entry points to in both RSS and Atom.
+pub fn entry_link(links: &[feed_rs::model::Link]) -> Option<String> {
+    links
+        .iter()
+        .find(|l| l.rel.as_deref().is_none_or(|r| r == "alternate"))
+        .or_else(|| links.first())
+        .map(|l| l.href.clone())
+}
@@ -0,0 +1,82 @@
+use super::types::FeedError;
+
+pub struct OpmlFeed {
+    pub url: String,
+    pub title: Option<String>,
+    pub site_url: Option<String>,
+    /// Name of the enclosing folder outline, if any.
+    pub category: Option<String>,
+}
+
+/// Flattens the outline tree, every outline with an `xmlUrl` is a feed and
+/// the closest parent outline without one is used as its category.
+pub fn parse(xml: &str) -> Result<Vec<OpmlFeed>, FeedError> {
+    let doc = opml::OPML::from_str(xml)?;
+    let mut feeds = vec![];
+    collect(&doc.body.outlines, None, &mut feeds);
+    Ok(feeds)
+}
+
+fn collect(outlines: &[opml::Outline], folder: Option<&str>, feeds: &mut Vec<OpmlFeed>) {
+    for outline in outlines {
+        match &outline.xml_url {
+            Some(url) => feeds.push(OpmlFeed {
+                url: url.trim().to_string(),
+                title: outline
+                    .title
+                    .clone()
+                    .or_else(|| Some(outline.text.clone()))
+                    .filter(|t| !t.is_empty()),
+                site_url: outline.html_url.clone(),
+                category: folder.map(|f| f.to_string()),
+            }),
+            None => {
+                let name = outline.title.as_deref().unwrap_or(outline.text.as_str());
+                let folder = if name.is_empty() { folder } else { Some(name) };
+                collect(&outline.outlines, folder, feeds);
+            }
+        }
+    }
+}
+
+/// One folder outline per category, holding its feeds.
+pub fn render(title: &str, feeds: &[linkdb::feed::FeedRow]) -> Result<String, FeedError> {
+    let mut doc = opml::OPML {
+        head: Some(opml::Head {
+            title: Some(title.to_string()),
+            date_created: Some(chrono::Utc::now().to_rfc2822()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    for feed in feeds {
+        let folder = match doc
+            .body
+            .outlines
+            .iter_mut()
+            .position(|o| o.text == feed.category)
+        {
+            Some(idx) => &mut doc.body.outlines[idx],
+            None => {
+                doc.body.outlines.push(opml::Outline {
+                    text: feed.category.clone(),
+                    title: Some(feed.category.clone()),
+                    ..Default::default()
+                });
+                doc.body.outlines.last_mut().expect("just pushed")
+            }
+        };
+        let name = feed.title.clone().unwrap_or_else(|| feed.url.clone());
+        folder.outlines.push(opml::Outline {
+            text: name.clone(),
+            title: Some(name),
+            r#type: Some("rss".to_string()),
+            xml_url: Some(feed.url.clone()),
+            html_url: feed.site_url.clone(),
+            ..Default::default()
+        });
+    }
+
+    Ok(doc.to_string()?)
+}
@@ -0,0 +1,62 @@
+use crate::heartbeat::{Heartbeat, Heartbeats};
+use crate::settings::FeedSettings;
+use crate::shutdown::Shutdown;
+
+/// Background loop polling every feed which is due, feeds are fetched one
+/// after another so a large subscription list never floods the network.
+/// Returns on shutdown once the feed at hand is saved.
+pub async fn run(
+    db: linkdb::Db,
+    client: reqwest::Client,
+    settings: FeedSettings,
+    shutdown: Shutdown,
+    heartbeats: Heartbeats,
+) {
+    let interval = std::time::Duration::from_secs(settings.poll_interval_secs);
+    // wake up often enough to pick up feeds which became due since the last round
+    let tick = interval.min(std::time::Duration::from_secs(60));
+    let heartbeat = heartbeats.register("feed_poller", tick + crate::heartbeat::JOB_GRACE);
+    tracing::info!(msg = "feed poller started", ?interval);
+
+    loop {
+        heartbeat.beat();
+        if let Err(e) = poll_due(&db, &client, interval, &shutdown, &heartbeat).await {
+            tracing::error!(msg = "feed poller round failed", err = %e);
+        }
+        if shutdown.is_requested() || shutdown.sleep(tick).await {
+            break;
+        }
+    }
+    tracing::info!(msg = "feed poller stopped");
+}
+
+#[tracing::instrument(name = "service::feed-poll-due", skip_all)]
+async fn poll_due(
+    db: &linkdb::Db,
+    client: &reqwest::Client,
+    interval: std::time::Duration,
+    shutdown: &Shutdown,
+    heartbeat: &Heartbeat,
+) -> Result<(), super::types::FeedError> {
+    let polled_before = chrono::Utc::now()
+        - chrono::Duration::from_std(interval).unwrap_or(chrono::Duration::zero());
+    let feeds = linkdb::feed::list_due(db, polled_before).await?;
+    for feed in feeds.iter() {
+        if shutdown.is_requested() {
+            break;
+        }
+        match super::poll(db, client, feed).await {
+            Ok(saved) => {
+                tracing::info!(msg = "feed polled", feed_id = feed.id, saved);
+                crate::metrics::job("feed_poller", "feed", true);
+                crate::metrics::job_items("feed_poller", saved);
+            }
+            Err(e) => {
+                tracing::warn!(msg = "feed poll failed", feed_id = feed.id, err = %e);
+                crate::metrics::job("feed_poller", "feed", false);
+            }
+        }
+        heartbeat.beat();
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::controller::link::types;
+use linkdb::feed::FeedRow;
+
+#[derive(thiserror::Error, Debug)]
+pub enum FeedError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+    #[error("InvalidInputError: {0}")]
+    InvalidInput(crate::problem::FieldError),
+    #[error("HttpError: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("UpstreamStatusError: feed server returned {0}")]
+    UpstreamStatus(u16),
+    #[error("TooLargeError: feed is larger than {0} bytes")]
+    TooLarge(usize),
+    #[error("FeedParseError: {0}")]
+    Parse(#[from] feed_rs::parser::ParseFeedError),
+    #[error("OpmlError: {0}")]
+    Opml(#[from] opml::Error),
+}
+
+/// The server fetches the url, only the web is reachable through it.
+pub fn check_url(url: &str) -> Result<(), FeedError> {
+    if crate::utils::is_web_url(url) {
+        Ok(())
+    } else {
+        Err(FeedError::InvalidInput(crate::problem::FieldError::new(
+            "url",
+            format!("`{}` must be an http or https url", url),
+        )))
+    }
+}
+
+pub fn from_db_response(row: FeedRow) -> types::FeedResponse {
+    types::FeedResponse {
+        id: row.id,
+        url: row.url,
+        title: row.title,
+        site_url: row.site_url,
+        category: row.category,
+        active: row.active,
+        last_polled_on: row.last_polled_on,
+        last_error: row.last_error,
+        created_on: row.created_on,
+        updated_on: row.updated_on,
+    }
+}
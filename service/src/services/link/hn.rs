@@ -0,0 +1,82 @@
+pub mod types;
+
+use crate::controller::link::types::HnSaveRes;
+use crate::ctx::Ctx;
+use crate::hn::apis::HNItem;
+
+/// Saves an HN item as a bookmark in the `hn` category. Saving it again keeps the
+/// bookmark, as the user may have edited it, and refreshes score and comment count.
+#[tracing::instrument(name = "service::hn-save", skip_all)]
+pub async fn save(ctx: &Ctx, user_id: &str, hn_id: i64) -> Result<HnSaveRes, types::HnSaveError> {
+    let item = ctx
+        .hn_client
+        .refresh_item(hn_id)
+        .await?
+        .ok_or_else(|| types::HnSaveError::NotFound(format!("hn item with id: `{}`", hn_id)))?;
+    if item.deleted == Some(true) {
+        return Err(crate::hn::HNError::Deleted(format!("hn item with id: `{}`", hn_id)).into());
+    }
+
+    let (bookmark_id, created) = store(&ctx.db, user_id, &item, None).await?;
+
+    let bookmark = super::bookmark::get(ctx, user_id, bookmark_id)
+        .await
+        .map_err(|e| match e {
+            super::bookmark::types::BookmarkError::Database(e) => types::HnSaveError::Database(e),
+            e => types::HnSaveError::NotFound(e.to_string()),
+        })?;
+    let meta = linkdb::bookmark::hn_meta::get_by_hn_id(&ctx.db, user_id, hn_id)
+        .await?
+        .ok_or_else(|| types::HnSaveError::NotFound(format!("hn item with id: `{}`", hn_id)))?;
+
+    Ok(HnSaveRes {
+        created,
+        bookmark,
+        hn: types::from_meta_row(meta),
+    })
+}
+
+/// Bookmarks `item` for the user in the `hn` category, and `category` when given,
+/// or refreshes the HN details of the bookmark saved before. Returns the bookmark
+/// id and whether it was created.
+#[tracing::instrument(name = "service::hn-store", skip_all, fields(hn_id = item.id))]
+pub async fn store(
+    db: &linkdb::Db,
+    user_id: &str,
+    item: &HNItem,
+    category: Option<&str>,
+) -> Result<(i64, bool), types::HnSaveError> {
+    let now = chrono::Utc::now();
+    // a second save of the item waits here, then finds the bookmark of the first
+    let mut tx = db
+        .begin_locked(&format!("hn-save:{user_id}:{}", item.id))
+        .await?;
+    let existing = linkdb::bookmark::hn_meta::get_bookmark_id(&mut tx, user_id, item.id).await?;
+    let bookmark_id = match existing {
+        Some(id) => id,
+        None => {
+            let row = linkdb::bookmark::BookmarkI {
+                url: types::bookmark_url(item),
+                user_id: user_id.to_string(),
+                title: Some(types::title(item)),
+                content: item.text.as_deref().map(crate::hn::comments::sanitize),
+                referrer: Some(types::discussion_url(item.id)),
+                status: "UN".to_string(),
+                created_on: now,
+                updated_on: now,
+            };
+            linkdb::bookmark::insert(&mut tx, row).await?
+        }
+    };
+    let categories = std::iter::once(types::HN_CATEGORY)
+        .chain(category.filter(|c| *c != types::HN_CATEGORY))
+        .map(|name| super::cat::types::from_cat_name(name, user_id))
+        .collect();
+    let category_ids = linkdb::category::upsert(&mut tx, categories, now).await?;
+    linkdb::bookmark::cat_map::add_categories(&mut tx, bookmark_id, &category_ids).await?;
+    linkdb::bookmark::hn_meta::upsert(&mut tx, types::meta(item, bookmark_id, user_id), now)
+        .await?;
+    tx.commit().await?;
+
+    Ok((bookmark_id, existing.is_none()))
+}
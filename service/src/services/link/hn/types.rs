@@ -0,0 +1,62 @@
+use crate::controller::link::types;
+use crate::hn::apis::HNItem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum HnSaveError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+    #[error("HNError: {0}")]
+    Hn(#[from] crate::hn::HNError),
+}
+
+/// Category every saved HN item is filed under.
+pub const HN_CATEGORY: &str = "hn";
+
+pub fn discussion_url(id: i64) -> String {
+    format!("https://news.ycombinator.com/item?id={}", id)
+}
+
+/// The story's own link, or the discussion for Ask HN and other text posts.
+pub fn bookmark_url(item: &HNItem) -> String {
+    item.url
+        .clone()
+        .filter(|u| crate::utils::is_web_url(u))
+        .unwrap_or_else(|| discussion_url(item.id))
+}
+
+pub fn title(item: &HNItem) -> String {
+    match (&item.title, &item.by) {
+        (Some(t), _) => t.clone(),
+        (None, Some(by)) => format!("HN comment by {}", by),
+        (None, None) => format!("HN item {}", item.id),
+    }
+}
+
+pub fn meta(item: &HNItem, bookmark_id: i64, user_id: &str) -> linkdb::bookmark::HnMetaI {
+    linkdb::bookmark::HnMetaI {
+        bookmark_id,
+        user_id: user_id.to_string(),
+        hn_id: item.id,
+        item_type: item.r#type.clone().unwrap_or_else(|| "story".to_string()),
+        by: item.by.clone(),
+        score: item.score.unwrap_or(0).min(i32::MAX as u64) as i32,
+        descendants: item.descendants.unwrap_or(0).min(i32::MAX as u64) as i32,
+        posted_on: item
+            .time
+            .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0)),
+    }
+}
+
+pub fn from_meta_row(row: linkdb::bookmark::HnMetaRow) -> types::HnMetaResponse {
+    types::HnMetaResponse {
+        discussion_url: discussion_url(row.hn_id),
+        id: row.hn_id,
+        item_type: row.item_type,
+        by: row.by,
+        score: row.score,
+        descendants: row.descendants,
+        posted_on: row.posted_on,
+    }
+}
@@ -0,0 +1,258 @@
+pub mod types;
+
+use crate::controller::link::types::{
+    InvitationResponse, MemberInviteReq, MemberResponse, MemberRole, MemberUpdateReq,
+};
+use crate::ctx::Ctx;
+
+/// What a membership is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Category,
+    Topic,
+}
+
+struct Target {
+    scope: Scope,
+    id: i64,
+    owner: String,
+}
+
+/// Resolves a category name, or `@owner/name` for someone else's category, along
+/// with the user's role on it. `None` when it doesn't exist or isn't shared with
+/// the user, so a category's existence doesn't leak to non members.
+#[tracing::instrument(name = "service::member-category", skip_all)]
+pub async fn category(
+    ctx: &Ctx,
+    user_id: &str,
+    cat_ref: &str,
+) -> Result<Option<(linkdb::CatRow, MemberRole)>, sqlx::Error> {
+    let (owner, name) = types::parse_ref(user_id, cat_ref);
+    let Some(cat) = ctx.repos.categories.get_by_name(owner, name).await? else {
+        return Ok(None);
+    };
+    if cat.user_id == user_id {
+        return Ok(Some((cat, MemberRole::Owner)));
+    }
+    let role = ctx
+        .repos
+        .categories
+        .roles(cat.id, user_id)
+        .await?
+        .iter()
+        .map(|r| types::role_from_code(r))
+        .max();
+    Ok(role.map(|r| (cat, r)))
+}
+
+/// Same as `category` for topics.
+#[tracing::instrument(name = "service::member-topic", skip_all)]
+pub async fn topic(
+    ctx: &Ctx,
+    user_id: &str,
+    topic_ref: &str,
+) -> Result<Option<(linkdb::TopicRow, MemberRole)>, sqlx::Error> {
+    let (owner, name) = types::parse_ref(user_id, topic_ref);
+    let Some(topic) = ctx.repos.topics.get_by_name(owner, name).await? else {
+        return Ok(None);
+    };
+    if topic.user_id == user_id {
+        return Ok(Some((topic, MemberRole::Owner)));
+    }
+    let role = ctx
+        .repos
+        .topics
+        .role(topic.id, user_id)
+        .await?
+        .map(|r| types::role_from_code(&r));
+    Ok(role.map(|r| (topic, r)))
+}
+
+async fn resolve(
+    ctx: &Ctx,
+    user_id: &str,
+    scope: Scope,
+    item_ref: &str,
+) -> Result<(Target, MemberRole), types::MemberError> {
+    let found = match scope {
+        Scope::Category => category(ctx, user_id, item_ref)
+            .await?
+            .map(|(c, r)| (c.id, c.user_id, r)),
+        Scope::Topic => topic(ctx, user_id, item_ref)
+            .await?
+            .map(|(t, r)| (t.id, t.user_id, r)),
+    };
+    let (id, owner, role) = found.ok_or_else(|| {
+        let kind = match scope {
+            Scope::Category => "category",
+            Scope::Topic => "topic",
+        };
+        types::MemberError::NotFound(format!("{} with name: `{}`", kind, item_ref))
+    })?;
+    Ok((Target { scope, id, owner }, role))
+}
+
+async fn membership(
+    ctx: &Ctx,
+    target: &Target,
+    username: &str,
+) -> Result<Option<linkdb::member::MemberRow>, sqlx::Error> {
+    match target.scope {
+        Scope::Category => linkdb::member::get_by_category(&ctx.db, target.id, username).await,
+        Scope::Topic => linkdb::member::get_by_topic(&ctx.db, target.id, username).await,
+    }
+}
+
+#[tracing::instrument(name = "service::member-list", skip_all)]
+pub async fn list(
+    ctx: &Ctx,
+    user_id: &str,
+    scope: Scope,
+    item_ref: &str,
+) -> Result<Vec<MemberResponse>, types::MemberError> {
+    let (target, _) = resolve(ctx, user_id, scope, item_ref).await?;
+    let rows = match scope {
+        Scope::Category => linkdb::member::list_by_category(&ctx.db, target.id).await?,
+        Scope::Topic => linkdb::member::list_by_topic(&ctx.db, target.id).await?,
+    };
+
+    let creator = MemberResponse {
+        username: target.owner,
+        role: MemberRole::Owner,
+        invited_by: None,
+        accepted_on: None,
+        pending: false,
+    };
+    Ok(std::iter::once(creator)
+        .chain(rows.into_iter().map(types::from_member_row))
+        .collect())
+}
+
+#[tracing::instrument(name = "service::member-invite", skip_all)]
+pub async fn invite(
+    ctx: &Ctx,
+    user_id: &str,
+    scope: Scope,
+    item_ref: &str,
+    req: MemberInviteReq,
+) -> Result<(), types::MemberError> {
+    let (target, actor) = resolve(ctx, user_id, scope, item_ref).await?;
+    if !types::can_manage(actor, req.role) {
+        return Err(types::MemberError::Forbidden(format!(
+            "a {:?} can't invite a {:?}",
+            actor, req.role
+        )));
+    }
+    let username = req.username.trim();
+    if username.is_empty() {
+        return Err(types::MemberError::InvalidInput(
+            crate::problem::FieldError::new("username", "must not be empty"),
+        ));
+    }
+    if username == target.owner || membership(ctx, &target, username).await?.is_some() {
+        return Err(types::MemberError::Conflict(format!(
+            "`{}` is already a member or invited",
+            username
+        )));
+    }
+
+    let row = linkdb::member::MemberI {
+        category_id: (scope == Scope::Category).then_some(target.id),
+        topic_id: (scope == Scope::Topic).then_some(target.id),
+        user_id: username.to_string(),
+        role: types::role_code(req.role).to_string(),
+        invited_by: user_id.to_string(),
+    };
+    linkdb::member::insert(&ctx.db, row, chrono::Utc::now()).await?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "service::member-update", skip_all)]
+pub async fn update(
+    ctx: &Ctx,
+    user_id: &str,
+    scope: Scope,
+    item_ref: &str,
+    username: &str,
+    req: MemberUpdateReq,
+) -> Result<(), types::MemberError> {
+    let (target, actor) = resolve(ctx, user_id, scope, item_ref).await?;
+    let member = membership(ctx, &target, username)
+        .await?
+        .ok_or_else(|| types::MemberError::NotFound(format!("member: `{}`", username)))?;
+    let current = types::role_from_code(&member.role);
+    if !types::can_manage(actor, current) || !types::can_manage(actor, req.role) {
+        return Err(types::MemberError::Forbidden(format!(
+            "a {:?} can't change a {:?} to {:?}",
+            actor, current, req.role
+        )));
+    }
+
+    linkdb::member::update_role(
+        &ctx.db,
+        member.id,
+        types::role_code(req.role),
+        chrono::Utc::now(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Removes a member or cancels an invitation, members can always remove themselves.
+#[tracing::instrument(name = "service::member-remove", skip_all)]
+pub async fn remove(
+    ctx: &Ctx,
+    user_id: &str,
+    scope: Scope,
+    item_ref: &str,
+    username: &str,
+) -> Result<(), types::MemberError> {
+    let (target, actor) = resolve(ctx, user_id, scope, item_ref).await?;
+    let member = membership(ctx, &target, username)
+        .await?
+        .ok_or_else(|| types::MemberError::NotFound(format!("member: `{}`", username)))?;
+    let current = types::role_from_code(&member.role);
+    if username != user_id && !types::can_manage(actor, current) {
+        return Err(types::MemberError::Forbidden(format!(
+            "a {:?} can't remove a {:?}",
+            actor, current
+        )));
+    }
+
+    linkdb::member::delete(&ctx.db, member.id).await?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "service::member-invitations", skip_all)]
+pub async fn invitations(
+    ctx: &Ctx,
+    user_id: &str,
+) -> Result<Vec<InvitationResponse>, types::MemberError> {
+    let rows = linkdb::member::list_invitations(&ctx.db, user_id).await?;
+    Ok(rows.into_iter().map(types::from_invitation_row).collect())
+}
+
+#[tracing::instrument(name = "service::member-accept", skip_all)]
+pub async fn accept(ctx: &Ctx, user_id: &str, id: i64) -> Result<(), types::MemberError> {
+    if !linkdb::member::accept(&ctx.db, id, user_id, chrono::Utc::now()).await? {
+        return Err(types::MemberError::NotFound(format!(
+            "invitation with id: `{}`",
+            id
+        )));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "service::member-decline", skip_all)]
+pub async fn decline(ctx: &Ctx, user_id: &str, id: i64) -> Result<(), types::MemberError> {
+    match linkdb::member::get_by_id(&ctx.db, id).await? {
+        Some(m) if m.user_id == user_id && m.accepted_on.is_none() => {
+            linkdb::member::delete(&ctx.db, m.id).await?;
+            Ok(())
+        }
+        _ => Err(types::MemberError::NotFound(format!(
+            "invitation with id: `{}`",
+            id
+        ))),
+    }
+}
@@ -0,0 +1,90 @@
+use crate::controller::link::types::{self, MemberRole};
+use linkdb::member::{InvitationRow, MemberRow};
+
+#[derive(thiserror::Error, Debug)]
+pub enum MemberError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+    #[error("ForbiddenError: {0}")]
+    Forbidden(String),
+    #[error("InvalidInputError: {0}")]
+    InvalidInput(crate::problem::FieldError),
+    #[error("ConflictError: {0}")]
+    Conflict(String),
+}
+
+pub fn role_code(role: MemberRole) -> &'static str {
+    match role {
+        MemberRole::Viewer => "VW",
+        MemberRole::Contributor => "CB",
+        MemberRole::Editor => "ED",
+        MemberRole::Owner => "OW",
+    }
+}
+
+/// Unknown codes are read as the least privileged role.
+pub fn role_from_code(code: &str) -> MemberRole {
+    match code {
+        "OW" => MemberRole::Owner,
+        "ED" => MemberRole::Editor,
+        "CB" => MemberRole::Contributor,
+        _ => MemberRole::Viewer,
+    }
+}
+
+/// Splits `@owner/name` into its owner and name, a plain name is the caller's own.
+pub fn parse_ref<'a>(user_id: &'a str, item_ref: &'a str) -> (&'a str, &'a str) {
+    item_ref
+        .strip_prefix('@')
+        .and_then(|r| r.split_once('/'))
+        .filter(|(owner, name)| !owner.is_empty() && !name.is_empty())
+        .unwrap_or((user_id, item_ref))
+}
+
+/// Inverse of `parse_ref`, how `user_id` addresses `owner`'s category or topic.
+pub fn label(user_id: &str, owner: &str, name: &str) -> String {
+    if owner == user_id {
+        name.to_string()
+    } else {
+        format!("@{}/{}", owner, name)
+    }
+}
+
+/// Who can grant or take away `role`: editors manage viewers and contributors,
+/// owners manage everyone.
+pub fn can_manage(actor: MemberRole, role: MemberRole) -> bool {
+    match actor {
+        MemberRole::Owner => true,
+        MemberRole::Editor => role <= MemberRole::Contributor,
+        _ => false,
+    }
+}
+
+pub fn from_member_row(row: MemberRow) -> types::MemberResponse {
+    types::MemberResponse {
+        username: row.user_id,
+        role: role_from_code(&row.role),
+        invited_by: Some(row.invited_by),
+        pending: row.accepted_on.is_none(),
+        accepted_on: row.accepted_on,
+    }
+}
+
+pub fn from_invitation_row(row: InvitationRow) -> types::InvitationResponse {
+    let (kind, name) = match (row.category_name, row.topic_name) {
+        (Some(c), _) => ("category", c),
+        (None, Some(t)) => ("topic", t),
+        (None, None) => ("category", String::new()),
+    };
+    types::InvitationResponse {
+        id: row.id,
+        kind,
+        name: format!("@{}/{}", row.owner, name),
+        owner: row.owner,
+        role: role_from_code(&row.role),
+        invited_by: row.invited_by,
+        created_on: row.created_on,
+    }
+}
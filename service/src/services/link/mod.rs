@@ -1,3 +1,14 @@
+pub mod alert;
 pub mod bookmark;
 pub mod cat;
+pub mod feed;
+pub mod hn;
+pub mod member;
+pub mod notification;
+pub mod share;
+pub mod source;
 pub mod topic;
+pub mod watch;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,55 @@
+pub mod types;
+
+use crate::controller::link::types::{NotificationPage, NotificationReadAllRes};
+use crate::ctx::Ctx;
+
+#[tracing::instrument(name = "service::notification-list", skip_all)]
+pub async fn list(
+    ctx: &Ctx,
+    user_id: &str,
+    unread_only: bool,
+    offset: i64,
+    limit: i64,
+) -> Result<NotificationPage, types::NotificationError> {
+    let offset = offset.max(0);
+    let limit = limit.clamp(1, types::MAX_PAGE_SIZE);
+    let rows = linkdb::notification::list(&ctx.db, user_id, unread_only, offset, limit).await?;
+    let unread = linkdb::notification::count_unread(&ctx.db, user_id).await?;
+    Ok(NotificationPage {
+        unread,
+        offset,
+        limit,
+        items: rows.into_iter().map(types::from_db_response).collect(),
+    })
+}
+
+#[tracing::instrument(name = "service::notification-read", skip_all)]
+pub async fn read(ctx: &Ctx, user_id: &str, id: i64) -> Result<(), types::NotificationError> {
+    if !linkdb::notification::mark_read(&ctx.db, user_id, id, chrono::Utc::now()).await? {
+        return Err(types::NotificationError::NotFound(format!(
+            "notification with id: `{}`",
+            id
+        )));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "service::notification-read-all", skip_all)]
+pub async fn read_all(
+    ctx: &Ctx,
+    user_id: &str,
+) -> Result<NotificationReadAllRes, types::NotificationError> {
+    let marked = linkdb::notification::mark_all_read(&ctx.db, user_id, chrono::Utc::now()).await?;
+    Ok(NotificationReadAllRes { marked })
+}
+
+#[tracing::instrument(name = "service::notification-delete", skip_all)]
+pub async fn delete(ctx: &Ctx, user_id: &str, id: i64) -> Result<(), types::NotificationError> {
+    if !linkdb::notification::delete(&ctx.db, user_id, id).await? {
+        return Err(types::NotificationError::NotFound(format!(
+            "notification with id: `{}`",
+            id
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::controller::link::types;
+use linkdb::notification::NotificationRow;
+
+#[derive(thiserror::Error, Debug)]
+pub enum NotificationError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+}
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 200;
+
+pub const KIND_HN_WATCH: &str = "HW";
+pub const KIND_HN_ALERT: &str = "HA";
+
+pub fn kind_from_code(code: &str) -> types::NotificationKind {
+    match code {
+        KIND_HN_WATCH => types::NotificationKind::HnWatch,
+        KIND_HN_ALERT => types::NotificationKind::HnAlert,
+        _ => {
+            tracing::warn!(code, "unknown notification kind");
+            types::NotificationKind::HnWatch
+        }
+    }
+}
+
+pub fn from_db_response(row: NotificationRow) -> types::NotificationResponse {
+    types::NotificationResponse {
+        id: row.id,
+        kind: kind_from_code(&row.kind),
+        title: row.title,
+        url: row.url,
+        body: row.body,
+        hn_id: row.hn_id,
+        bookmark_id: row.bookmark_id,
+        read_on: row.read_on,
+        created_on: row.created_on,
+    }
+}
@@ -0,0 +1,134 @@
+pub mod types;
+
+use crate::controller::link::types::{
+    ShareAccessResponse, ShareCreateReq, ShareCreateRes, ShareKind, ShareResponse,
+};
+use crate::ctx::Ctx;
+
+const ACCESS_LOG_LIMIT: i64 = 500;
+/// A year, longer lived links are better left without an expiry.
+const MAX_EXPIRES_IN_SECS: i64 = 365 * 24 * 60 * 60;
+
+#[tracing::instrument(name = "service::share-create", skip_all)]
+pub async fn create(
+    ctx: &Ctx,
+    user_id: &str,
+    req: ShareCreateReq,
+) -> Result<ShareCreateRes, types::ShareError> {
+    if req.max_views.is_some_and(|m| m < 1) {
+        return Err(types::ShareError::InvalidInput(
+            crate::problem::FieldError::new("max_views", "must be at least 1"),
+        ));
+    }
+    if req.expires_in_secs.is_some_and(|e| e < 1) {
+        return Err(types::ShareError::InvalidInput(
+            crate::problem::FieldError::new("expires_in_secs", "must be positive"),
+        ));
+    }
+    if req.expires_in_secs.is_some_and(|e| e > MAX_EXPIRES_IN_SECS) {
+        return Err(types::ShareError::InvalidInput(
+            crate::problem::FieldError::new(
+                "expires_in_secs",
+                format!("must be at most {}", MAX_EXPIRES_IN_SECS),
+            ),
+        ));
+    }
+    if req.password.as_deref().is_some_and(str::is_empty) {
+        return Err(types::ShareError::InvalidInput(
+            crate::problem::FieldError::new("password", "must not be empty"),
+        ));
+    }
+
+    let target_id = target_id(ctx, user_id, req.kind, &req.target).await?;
+    let token = types::new_token();
+    let expires_on = req
+        .expires_in_secs
+        .map(|secs| chrono::Utc::now() + chrono::Duration::seconds(secs));
+    let password_hash = match req.password.as_deref() {
+        Some(p) => Some(types::hash_password(p)?),
+        None => None,
+    };
+
+    let row = linkdb::share::ShareLinkI {
+        kind: types::kind_code(req.kind).to_string(),
+        target_id,
+        user_id: user_id.to_string(),
+        token_hash: types::sha256_hex(&token),
+        token_prefix: token[..8].to_string(),
+        password_hash,
+        expires_on,
+        max_views: req.max_views,
+    };
+    let id = linkdb::share::insert(&ctx.db, row).await?;
+
+    Ok(ShareCreateRes {
+        id,
+        path: format!("/-/ln/s/{}", token),
+        token,
+        expires_on,
+    })
+}
+
+async fn target_id(
+    ctx: &Ctx,
+    user_id: &str,
+    kind: ShareKind,
+    target: &str,
+) -> Result<i64, types::ShareError> {
+    match kind {
+        ShareKind::Bookmark => {
+            let id: i64 = target.parse().map_err(|_| {
+                types::ShareError::InvalidInput(crate::problem::FieldError::new(
+                    "target",
+                    format!("invalid bookmark id: `{}`", target),
+                ))
+            })?;
+            match linkdb::bookmark::get_by_id(&ctx.db, id).await {
+                Ok(b) if b.user_id == user_id => Ok(b.id),
+                Ok(_) | Err(sqlx::Error::RowNotFound) => Err(types::ShareError::NotFound(format!(
+                    "bookmark with id: `{}`",
+                    id
+                ))),
+                Err(e) => Err(e.into()),
+            }
+        }
+        ShareKind::Category => linkdb::category::get_id_by_name(&ctx.db, user_id, target)
+            .await?
+            .ok_or_else(|| {
+                types::ShareError::NotFound(format!("category with name: `{}`", target))
+            }),
+        ShareKind::Topic => linkdb::topic::get_id_by_name(&ctx.db, user_id, target)
+            .await?
+            .ok_or_else(|| types::ShareError::NotFound(format!("topic with name: `{}`", target))),
+    }
+}
+
+#[tracing::instrument(name = "service::share-list", skip_all)]
+pub async fn list(ctx: &Ctx, user_id: &str) -> Result<Vec<ShareResponse>, types::ShareError> {
+    let rows = linkdb::share::list_all(&ctx.db, user_id).await?;
+    Ok(rows.into_iter().map(types::from_db_response).collect())
+}
+
+#[tracing::instrument(name = "service::share-revoke", skip_all)]
+pub async fn revoke(ctx: &Ctx, user_id: &str, id: i64) -> Result<(), types::ShareError> {
+    if !linkdb::share::revoke(&ctx.db, user_id, id).await? {
+        return Err(types::ShareError::NotFound(format!(
+            "share link with id: `{}`",
+            id
+        )));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "service::share-access-log", skip_all)]
+pub async fn access_log(
+    ctx: &Ctx,
+    user_id: &str,
+    id: i64,
+) -> Result<Vec<ShareAccessResponse>, types::ShareError> {
+    let link = linkdb::share::get_by_id(&ctx.db, user_id, id)
+        .await?
+        .ok_or_else(|| types::ShareError::NotFound(format!("share link with id: `{}`", id)))?;
+    let rows = linkdb::share::access::list_by_link(&ctx.db, link.id, ACCESS_LOG_LIMIT).await?;
+    Ok(rows.into_iter().map(types::from_access_row).collect())
+}
@@ -0,0 +1,114 @@
+use crate::controller::link::types;
+use linkdb::share::{ShareAccessRow, ShareLinkRow};
+use sha2::Digest;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ShareError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+    #[error("InvalidInputError: {0}")]
+    InvalidInput(crate::problem::FieldError),
+    #[error("PasswordHashError: {0}")]
+    PasswordHash(argon2::password_hash::Error),
+}
+
+impl From<argon2::password_hash::Error> for ShareError {
+    fn from(e: argon2::password_hash::Error) -> Self {
+        ShareError::PasswordHash(e)
+    }
+}
+
+pub fn kind_code(kind: types::ShareKind) -> &'static str {
+    match kind {
+        types::ShareKind::Bookmark => "BM",
+        types::ShareKind::Category => "CT",
+        types::ShareKind::Topic => "TP",
+    }
+}
+
+pub fn kind_from_code(code: &str) -> Option<types::ShareKind> {
+    match code {
+        "BM" => Some(types::ShareKind::Bookmark),
+        "CT" => Some(types::ShareKind::Category),
+        "TP" => Some(types::ShareKind::Topic),
+        _ => None,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 256 random bits, hex encoded.
+pub fn new_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    hex(&bytes)
+}
+
+pub fn sha256_hex(value: &str) -> String {
+    hex(&sha2::Sha256::digest(value.as_bytes()))
+}
+
+pub fn hash_password(password: &str) -> Result<String, ShareError> {
+    use argon2::password_hash::{PasswordHasher, SaltString};
+    let salt = SaltString::generate(&mut rand::rngs::OsRng);
+    Ok(argon2::Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    use argon2::password_hash::{PasswordHash, PasswordVerifier};
+    PasswordHash::new(password_hash)
+        .map(|h| {
+            argon2::Argon2::default()
+                .verify_password(password.as_bytes(), &h)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// `None` while the link can still be viewed, otherwise why it can't.
+pub fn closed_reason(
+    row: &ShareLinkRow,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<&'static str> {
+    if row.revoked_on.is_some() {
+        Some("revoked")
+    } else if row.expires_on.is_some_and(|e| e <= now) {
+        Some("expired")
+    } else if row.max_views.is_some_and(|m| row.view_count >= m) {
+        Some("exhausted")
+    } else {
+        None
+    }
+}
+
+pub fn from_db_response(row: ShareLinkRow) -> types::ShareResponse {
+    let status = closed_reason(&row, chrono::Utc::now()).unwrap_or("active");
+    types::ShareResponse {
+        id: row.id,
+        kind: kind_from_code(&row.kind),
+        target_id: row.target_id,
+        token_prefix: row.token_prefix,
+        password_protected: row.password_hash.is_some(),
+        expires_on: row.expires_on,
+        max_views: row.max_views,
+        view_count: row.view_count,
+        revoked_on: row.revoked_on,
+        status,
+        created_on: row.created_on,
+    }
+}
+
+pub fn from_access_row(row: ShareAccessRow) -> types::ShareAccessResponse {
+    types::ShareAccessResponse {
+        accessed_on: row.accessed_on,
+        granted: row.granted,
+        reason: row.reason,
+        ip: row.ip,
+        user_agent: row.user_agent,
+    }
+}
@@ -0,0 +1,96 @@
+pub mod types;
+
+use crate::controller::link::types::SourceSaveRes;
+use crate::ctx::Ctx;
+use crate::sources::{Discussion, ProviderInfo, SourceItem, SourcePage};
+
+pub fn providers(ctx: &Ctx) -> Vec<ProviderInfo> {
+    ctx.sources.providers()
+}
+
+#[tracing::instrument(name = "service::source-list", skip(ctx))]
+pub async fn list(
+    ctx: &Ctx,
+    provider: &str,
+    list: &str,
+    offset: usize,
+    limit: usize,
+) -> Result<SourcePage, types::LinkSourceError> {
+    let source = ctx.sources.get(provider)?;
+    Ok(source.list(list, offset, limit).await?)
+}
+
+#[tracing::instrument(name = "service::source-item", skip(ctx))]
+pub async fn item(
+    ctx: &Ctx,
+    provider: &str,
+    id: &str,
+) -> Result<SourceItem, types::LinkSourceError> {
+    Ok(ctx.sources.get(provider)?.item(id).await?)
+}
+
+#[tracing::instrument(name = "service::source-discussion", skip(ctx))]
+pub async fn discussion(
+    ctx: &Ctx,
+    provider: &str,
+    id: &str,
+    max_depth: usize,
+    max_nodes: usize,
+) -> Result<Discussion, types::LinkSourceError> {
+    let source = ctx.sources.get(provider)?;
+    Ok(source.discussion(id, max_depth, max_nodes).await?)
+}
+
+/// Bookmarks an item in a category named after the provider. A url the user
+/// already bookmarked is only added to that category.
+#[tracing::instrument(name = "service::source-save", skip(ctx, user_id))]
+pub async fn save(
+    ctx: &Ctx,
+    user_id: &str,
+    provider: &str,
+    id: &str,
+) -> Result<SourceSaveRes, types::LinkSourceError> {
+    let source = ctx.sources.get(provider)?;
+    let item = source.item(id).await?;
+    let draft = source.to_bookmark(&item);
+    let now = chrono::Utc::now();
+    // a second save of the url waits here, then finds the bookmark of the first
+    let mut tx = ctx
+        .db
+        .begin_locked(&format!("bookmark-url:{user_id}:{}", draft.url))
+        .await?;
+    let existing = linkdb::bookmark::get_id_by_url(&mut tx, user_id, &draft.url).await?;
+    let bookmark_id = match existing {
+        Some(id) => id,
+        None => {
+            let row = linkdb::bookmark::BookmarkI {
+                url: draft.url,
+                user_id: user_id.to_string(),
+                title: draft.title,
+                content: draft.content,
+                referrer: draft.referrer,
+                status: "UN".to_string(),
+                created_on: now,
+                updated_on: now,
+            };
+            linkdb::bookmark::insert(&mut tx, row).await?
+        }
+    };
+    let category = super::cat::types::from_cat_name(&draft.category, user_id);
+    let category_ids = linkdb::category::upsert(&mut tx, vec![category], now).await?;
+    linkdb::bookmark::cat_map::add_categories(&mut tx, bookmark_id, &category_ids).await?;
+    tx.commit().await?;
+
+    let bookmark = super::bookmark::get(ctx, user_id, bookmark_id)
+        .await
+        .map_err(|e| match e {
+            super::bookmark::types::BookmarkError::Database(e) => e.into(),
+            e => {
+                types::LinkSourceError::Source(crate::sources::SourceError::NotFound(e.to_string()))
+            }
+        })?;
+    Ok(SourceSaveRes {
+        created: existing.is_none(),
+        bookmark,
+    })
+}
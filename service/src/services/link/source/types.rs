@@ -0,0 +1,9 @@
+use crate::sources::SourceError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum LinkSourceError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("{0}")]
+    Source(#[from] SourceError),
+}
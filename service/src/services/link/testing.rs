@@ -0,0 +1,62 @@
+//! `Ctx` over the in-memory repositories, for service tests which don't need Postgres.
+
+use crate::controller::link::types::MemberRole;
+use crate::ctx::Ctx;
+use std::sync::{Arc, OnceLock};
+
+/// Building http clients loads the system's root certificates, so tests share them.
+fn clients() -> &'static (reqwest::Client, crate::hn::HnClient) {
+    static CLIENTS: OnceLock<(reqwest::Client, crate::hn::HnClient)> = OnceLock::new();
+    CLIENTS.get_or_init(|| {
+        let hn = crate::hn::HnClient::new(&Default::default()).expect("hn client");
+        (reqwest::Client::new(), hn)
+    })
+}
+
+pub fn ctx() -> (Ctx, Arc<linkdb::repo::Memory>) {
+    let memory = Arc::new(linkdb::repo::Memory::default());
+    let ctx = Ctx {
+        // never connected, the services under test only go through `repos`
+        db: linkdb::Db::Postgres(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/linknova")
+                .expect("a valid database url"),
+        ),
+        repos: linkdb::repo::Repos::from(memory.clone()),
+        secret: "secret".to_string(),
+        cookie_domain: "127.0.0.1".to_string(),
+        trusted_proxies: Vec::new(),
+        static_dir: std::env::temp_dir(),
+        feed_client: clients().0.clone(),
+        hn_client: clients().1.clone(),
+        sources: crate::sources::Registry::new(Vec::new()),
+        heartbeats: Default::default(),
+    };
+    (ctx, memory)
+}
+
+/// Accepted membership of `user_id` on a category or a topic.
+pub fn share(
+    memory: &linkdb::repo::Memory,
+    category_id: Option<i64>,
+    topic_id: Option<i64>,
+    user_id: &str,
+    role: MemberRole,
+) {
+    let row = linkdb::member::MemberI {
+        category_id,
+        topic_id,
+        user_id: user_id.to_string(),
+        role: super::member::types::role_code(role).to_string(),
+        invited_by: "owner".to_string(),
+    };
+    memory.add_member(row, true);
+}
+
+pub fn is_unique_violation(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Database(db) if db.is_unique_violation())
+}
+
+pub fn is_foreign_key_violation(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Database(db) if db.is_foreign_key_violation())
+}
@@ -1,5 +1,6 @@
 pub mod types;
 
+use super::member::types as member_types;
 use crate::controller::link;
 use crate::ctx::Ctx;
 
@@ -18,23 +19,23 @@ pub async fn create(
         about: req.about,
         priority: req.priority.unwrap_or(0),
         active: true,
-        public: false,
+        public: req.public,
         user_id: user_id.to_string(),
     };
-    linkdb::topic::insert(&ctx.pg_pool, row).await?;
+    ctx.repos.topics.insert(row).await?;
     Ok(())
 }
 
+/// `topic_name` can be `@owner/name` for a topic shared with the user.
 #[tracing::instrument(name = "service::topic-get", skip_all)]
 pub async fn get(
     ctx: &Ctx,
     user_id: &str,
     topic_name: &str,
 ) -> Result<link::types::TopicGetRes, types::TopicError> {
-    let topic_row = linkdb::topic::get_by_name(&ctx.pg_pool, user_id, topic_name).await?;
-    match topic_row {
-        Some(t) => Ok(link::types::TopicGetRes {
-            name: t.name,
+    match super::member::topic(ctx, user_id, topic_name).await? {
+        Some((t, role)) => Ok(link::types::TopicGetRes {
+            name: member_types::label(user_id, &t.user_id, &t.name),
             description: t.description,
             display_name: t.display_name,
             priority: t.priority,
@@ -42,7 +43,13 @@ pub async fn get(
             public: t.public,
             created_on: t.created_on,
             updated_on: t.updated_on,
-            categories: t.categories,
+            categories: t
+                .categories
+                .iter()
+                .map(|c| member_types::label(user_id, &t.user_id, c))
+                .collect(),
+            owner: t.user_id,
+            role,
         }),
         None => Err(types::TopicError::NotFound(format!(
             "topic not found with name: {}",
@@ -56,11 +63,21 @@ pub async fn list(
     ctx: &Ctx,
     user_id: &str,
 ) -> Result<Vec<link::types::TopicGetRes>, types::TopicError> {
-    let rows = linkdb::topic::list_all(&ctx.pg_pool, user_id).await?;
-    Ok(rows
-        .into_iter()
-        .map(|r| link::types::TopicGetRes {
-            name: r.name,
+    let rows = ctx.repos.topics.list_all(user_id).await?;
+    let mut result = Vec::with_capacity(rows.len());
+    for r in rows {
+        let role = if r.user_id == user_id {
+            link::types::MemberRole::Owner
+        } else {
+            ctx.repos
+                .topics
+                .role(r.id, user_id)
+                .await?
+                .map(|r| member_types::role_from_code(&r))
+                .unwrap_or(link::types::MemberRole::Viewer)
+        };
+        result.push(link::types::TopicGetRes {
+            name: member_types::label(user_id, &r.user_id, &r.name),
             description: r.description,
             display_name: r.display_name,
             priority: r.priority,
@@ -68,9 +85,16 @@ pub async fn list(
             public: r.public,
             created_on: r.created_on,
             updated_on: r.updated_on,
-            categories: r.categories,
-        })
-        .collect())
+            categories: r
+                .categories
+                .iter()
+                .map(|c| member_types::label(user_id, &r.user_id, c))
+                .collect(),
+            owner: r.user_id,
+            role,
+        });
+    }
+    Ok(result)
 }
 
 #[tracing::instrument(name = "service::topic-update", skip_all)]
@@ -84,7 +108,7 @@ pub async fn update(
 
 #[tracing::instrument(name = "service::topic-delete", skip_all)]
 pub async fn delete(ctx: &Ctx, user_id: &str, topic_name: &str) -> Result<(), types::TopicError> {
-    linkdb::topic::delete(&ctx.pg_pool, user_id, topic_name).await?;
+    ctx.repos.topics.delete(user_id, topic_name).await?;
     Ok(())
 }
 
@@ -95,7 +119,10 @@ pub async fn add_category(
     topic_name: &str,
     categories: Vec<String>,
 ) -> Result<(), types::TopicError> {
-    let topic_id = linkdb::topic::get_id_by_name(&ctx.pg_pool, user_id, topic_name)
+    let topic_id = ctx
+        .repos
+        .topics
+        .get_id_by_name(user_id, topic_name)
         .await?
         .ok_or_else(|| types::TopicError::NotFound(format!("topic with name: `{}`", topic_name)))?;
 
@@ -105,17 +132,12 @@ pub async fn add_category(
         .map(|c| super::cat::types::from_cat_name(c, user_id))
         .collect();
 
-    let mut tx = ctx.pg_pool.begin().await?;
-    let now = chrono::Utc::now();
-
     tracing::info!(msg = "$$$$$$$$$$$$$$$$$$$$$$$$$$$$$", ?categories);
 
-    let category_ids = linkdb::category::upsert(&mut tx, categories, now).await?;
-
-    tracing::info!(msg = "$$$$$$$$$$$$$$$$$$$$", ?category_ids);
-
-    linkdb::topic_cat_map::add_categories(&mut tx, topic_id, category_ids.as_slice()).await?;
-    tx.commit().await?;
+    ctx.repos
+        .topics
+        .add_categories(topic_id, categories, chrono::Utc::now())
+        .await?;
 
     Ok(())
 }
@@ -127,11 +149,139 @@ pub async fn remove_category(
     topic_name: &str,
     cat_names: Vec<String>,
 ) -> Result<(), types::TopicError> {
-    let topic_id = linkdb::topic::get_id_by_name(&ctx.pg_pool, user_id, topic_name)
+    let topic_id = ctx
+        .repos
+        .topics
+        .get_id_by_name(user_id, topic_name)
         .await?
         .ok_or_else(|| types::TopicError::NotFound(format!("topic with name: `{}`", topic_name)))?;
 
-    linkdb::topic_cat_map::remove_categories(&ctx.pg_pool, topic_id, cat_names.as_slice()).await?;
+    ctx.repos
+        .topics
+        .remove_categories(topic_id, cat_names.as_slice())
+        .await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::link::testing;
+
+    fn req(name: &str) -> link::types::TopicCreateReq {
+        link::types::TopicCreateReq {
+            name: name.to_string(),
+            description: None,
+            display_name: None,
+            priority: None,
+            about: None,
+            public: false,
+        }
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn add_category_upserts_and_ignores_existing_mappings() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("code")).await.unwrap();
+
+        add_category(&ctx, "alice", "code", names(&["rust"]))
+            .await
+            .unwrap();
+        add_category(&ctx, "alice", "code", names(&["rust", "db"]))
+            .await
+            .unwrap();
+        let mut categories = get(&ctx, "alice", "code").await.unwrap().categories;
+        categories.sort();
+        assert_eq!(categories, ["db", "rust"]);
+        assert_eq!(
+            ctx.repos.categories.list_all("alice").await.unwrap().len(),
+            2
+        );
+
+        remove_category(&ctx, "alice", "code", names(&["rust", "missing"]))
+            .await
+            .unwrap();
+        assert_eq!(get(&ctx, "alice", "code").await.unwrap().categories, ["db"]);
+
+        let err = add_category(&ctx, "alice", "missing", names(&["rust"]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, types::TopicError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn delete_fails_while_categories_are_mapped() {
+        let (ctx, _) = testing::ctx();
+        create(&ctx, "alice", req("code")).await.unwrap();
+        add_category(&ctx, "alice", "code", names(&["rust"]))
+            .await
+            .unwrap();
+
+        match delete(&ctx, "alice", "code").await {
+            Err(types::TopicError::Database(e)) => assert!(testing::is_foreign_key_violation(&e)),
+            other => panic!("expected a foreign key violation, got {:?}", other),
+        }
+        remove_category(&ctx, "alice", "code", names(&["rust"]))
+            .await
+            .unwrap();
+        delete(&ctx, "alice", "code").await.unwrap();
+        assert!(matches!(
+            get(&ctx, "alice", "code").await,
+            Err(types::TopicError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn shared_topics_only_list_the_owners_categories() {
+        let (ctx, memory) = testing::ctx();
+        create(&ctx, "alice", req("code")).await.unwrap();
+        create(&ctx, "bob", req("apps")).await.unwrap();
+        add_category(&ctx, "alice", "code", names(&["rust"]))
+            .await
+            .unwrap();
+        let code = ctx
+            .repos
+            .topics
+            .get_id_by_name("alice", "code")
+            .await
+            .unwrap();
+        let go = ctx
+            .repos
+            .categories
+            .insert(crate::services::link::cat::types::from_cat_name(
+                "go", "bob",
+            ))
+            .await
+            .unwrap()
+            .0;
+        ctx.repos.topics.connect(code.unwrap(), go).await.unwrap();
+
+        assert!(matches!(
+            get(&ctx, "bob", "@alice/code").await,
+            Err(types::TopicError::NotFound(_))
+        ));
+        testing::share(&memory, None, code, "bob", link::types::MemberRole::Viewer);
+
+        let topics = list(&ctx, "bob").await.unwrap();
+        let listed: Vec<_> = topics
+            .iter()
+            .map(|t| (t.name.as_str(), t.role, t.categories.clone()))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("apps", link::types::MemberRole::Owner, vec![]),
+                (
+                    "@alice/code",
+                    link::types::MemberRole::Viewer,
+                    names(&["@alice/rust"])
+                ),
+            ]
+        );
+    }
+}
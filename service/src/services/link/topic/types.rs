@@ -4,4 +4,6 @@ pub enum TopicError {
     Database(#[from] sqlx::Error),
     #[error("NotFoundError: {0}")]
     NotFound(String),
+    #[error("ForbiddenError: {0}")]
+    Forbidden(String),
 }
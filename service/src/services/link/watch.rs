@@ -0,0 +1,248 @@
+pub mod poller;
+pub mod types;
+
+use crate::controller::link::types::{WatchPollRes, WatchReq, WatchResponse};
+use crate::ctx::Ctx;
+use crate::hn::apis::HNItem;
+use crate::hn::HnClient;
+use linkdb::hn_watch::HnWatchRow;
+
+/// Follows an HN user. Only submissions made after the watch starts are reported,
+/// watching again just changes the auto-save category.
+#[tracing::instrument(name = "service::watch-add", skip_all)]
+pub async fn add(
+    ctx: &Ctx,
+    user_id: &str,
+    req: WatchReq,
+) -> Result<WatchResponse, types::WatchError> {
+    let username = req.username.trim();
+    if !crate::hn::apis::valid_username(username) {
+        return Err(types::WatchError::InvalidInput(
+            crate::problem::FieldError::new(
+                "username",
+                format!("`{}` is not an HN username", username),
+            ),
+        ));
+    }
+    let details = ctx
+        .hn_client
+        .user(username)
+        .await?
+        .ok_or_else(|| types::WatchError::NotFound(format!("hn user: `{}`", username)))?;
+    let existing = linkdb::hn_watch::get_by_username(&ctx.db, user_id, username).await?;
+
+    let now = chrono::Utc::now();
+    let mut tx = ctx.db.begin().await?;
+    let category_id = match req.category.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            let ids = linkdb::category::upsert(
+                &mut tx,
+                vec![super::cat::types::from_cat_name(name, user_id)],
+                now,
+            )
+            .await?;
+            Some(ids[0])
+        }
+        _ => None,
+    };
+    let row = linkdb::hn_watch::HnWatchI {
+        user_id: user_id.to_string(),
+        username: username.to_string(),
+        category_id,
+    };
+    let id = linkdb::hn_watch::upsert(&mut tx, row, now).await?;
+    tx.commit().await?;
+
+    if existing.is_none() {
+        let newest = details.submitted.unwrap_or_default().into_iter().max();
+        linkdb::hn_watch::update_poll_state(&ctx.db, id, newest, None, now).await?;
+    }
+    get(ctx, user_id, username).await
+}
+
+#[tracing::instrument(name = "service::watch-get", skip_all)]
+pub async fn get(
+    ctx: &Ctx,
+    user_id: &str,
+    username: &str,
+) -> Result<WatchResponse, types::WatchError> {
+    Ok(types::from_db_response(
+        get_row(ctx, user_id, username).await?,
+    ))
+}
+
+async fn get_row(
+    ctx: &Ctx,
+    user_id: &str,
+    username: &str,
+) -> Result<HnWatchRow, types::WatchError> {
+    linkdb::hn_watch::get_by_username(&ctx.db, user_id, username)
+        .await?
+        .ok_or_else(|| types::WatchError::NotFound(format!("watch of: `{}`", username)))
+}
+
+#[tracing::instrument(name = "service::watch-list", skip_all)]
+pub async fn list(ctx: &Ctx, user_id: &str) -> Result<Vec<WatchResponse>, types::WatchError> {
+    let rows = linkdb::hn_watch::list_all(&ctx.db, user_id).await?;
+    Ok(rows.into_iter().map(types::from_db_response).collect())
+}
+
+#[tracing::instrument(name = "service::watch-remove", skip_all)]
+pub async fn remove(ctx: &Ctx, user_id: &str, username: &str) -> Result<(), types::WatchError> {
+    if !linkdb::hn_watch::delete(&ctx.db, user_id, username).await? {
+        return Err(types::WatchError::NotFound(format!(
+            "watch of: `{}`",
+            username
+        )));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "service::watch-refresh", skip_all)]
+pub async fn refresh(
+    ctx: &Ctx,
+    user_id: &str,
+    username: &str,
+) -> Result<WatchPollRes, types::WatchError> {
+    let watch = get_row(ctx, user_id, username).await?;
+    poll(
+        &ctx.db,
+        &ctx.hn_client,
+        std::slice::from_ref(&watch),
+        poller::DEFAULT_MAX_ITEMS,
+    )
+    .await
+}
+
+/// Checks one HN user for every watch of it, `watches` all share the username.
+/// Each watch gets a notification per new submission, at most `max_items` of
+/// the newest, and a bookmark when it has an auto-save category.
+#[tracing::instrument(name = "service::watch-poll", skip_all, fields(username))]
+pub async fn poll(
+    db: &linkdb::Db,
+    client: &HnClient,
+    watches: &[HnWatchRow],
+    max_items: usize,
+) -> Result<WatchPollRes, types::WatchError> {
+    let Some(username) = watches.first().map(|w| w.username.as_str()) else {
+        return Ok(WatchPollRes {
+            notifications: 0,
+            new_bookmarks: 0,
+        });
+    };
+    tracing::Span::current().record("username", username);
+
+    let result = fetch_new(client, username, watches, max_items).await;
+    let now = chrono::Utc::now();
+    let (newest, items, failed) = match result {
+        Ok(r) => r,
+        Err(e) => {
+            for watch in watches.iter() {
+                linkdb::hn_watch::update_poll_state(db, watch.id, None, Some(e.to_string()), now)
+                    .await?;
+            }
+            return Err(e);
+        }
+    };
+
+    let mut res = WatchPollRes {
+        notifications: 0,
+        new_bookmarks: 0,
+    };
+    for watch in watches.iter() {
+        // the watch stops short of the oldest new submission which failed to
+        // load, the next poll starts over from it
+        let unseen = |id: &i64| watch.last_seen_id.is_some_and(|seen| *id > seen);
+        let first_failed = failed.iter().copied().filter(unseen).min();
+        let seen_up_to = first_failed.map_or(newest, |id| Some(id - 1));
+        let mut new_items: Vec<&HNItem> = items
+            .iter()
+            .filter(|i| unseen(&i.id) && first_failed.is_none_or(|failed| i.id < failed))
+            .collect();
+        new_items.sort_by_key(|i| i.id);
+        for item in new_items {
+            let bookmark_id = match watch.category.as_deref() {
+                Some(category) => {
+                    match super::hn::store(db, &watch.user_id, item, Some(category)).await {
+                        Ok((id, created)) => {
+                            res.new_bookmarks += created as usize;
+                            Some(id)
+                        }
+                        Err(e) => {
+                            tracing::warn!(msg = "auto-save failed", hn_id = item.id, err = %e);
+                            None
+                        }
+                    }
+                }
+                None => None,
+            };
+            let mut tx = db.begin().await?;
+            let inserted =
+                linkdb::notification::insert(&mut tx, notification(watch, item, bookmark_id), now)
+                    .await?;
+            tx.commit().await?;
+            res.notifications += inserted.is_some() as usize;
+        }
+        linkdb::hn_watch::update_poll_state(db, watch.id, seen_up_to, None, now).await?;
+    }
+    Ok(res)
+}
+
+/// The newest submission id of the user, the submissions newer than what any
+/// of the watches has seen, deleted and dead ones left out, and the ids of the
+/// ones which failed to load.
+async fn fetch_new(
+    client: &HnClient,
+    username: &str,
+    watches: &[HnWatchRow],
+    max_items: usize,
+) -> Result<(Option<i64>, Vec<HNItem>, Vec<i64>), types::WatchError> {
+    let details = client
+        .user(username)
+        .await?
+        .ok_or_else(|| types::WatchError::NotFound(format!("hn user: `{}`", username)))?;
+    let mut submitted = details.submitted.unwrap_or_default();
+    submitted.sort_unstable_by(|a, b| b.cmp(a));
+    let newest = submitted.first().copied();
+
+    // a watch without `last_seen_id` starts from the newest submission
+    let oldest_seen = watches
+        .iter()
+        .filter_map(|w| w.last_seen_id)
+        .min()
+        .unwrap_or(i64::MAX);
+    let ids: Vec<i64> = submitted
+        .into_iter()
+        .take_while(|id| *id > oldest_seen)
+        .take(max_items)
+        .collect();
+    if ids.is_empty() {
+        return Ok((newest, Vec::new(), Vec::new()));
+    }
+    let (items, failed) = client.items_and_failures(&ids).await?;
+    let items = items
+        .into_iter()
+        .filter(|i| !i.deleted.unwrap_or(false) && !i.dead.unwrap_or(false))
+        .collect();
+    Ok((newest, items, failed))
+}
+
+fn notification(
+    watch: &HnWatchRow,
+    item: &HNItem,
+    bookmark_id: Option<i64>,
+) -> linkdb::notification::NotificationI {
+    let title = match (item.r#type.as_deref(), &item.title) {
+        (Some("comment"), _) | (_, None) => format!("{} commented", watch.username),
+        (_, Some(title)) => format!("{} posted: {}", watch.username, title),
+    };
+    linkdb::notification::NotificationI {
+        user_id: watch.user_id.clone(),
+        kind: super::notification::types::KIND_HN_WATCH.to_string(),
+        title,
+        url: Some(super::hn::types::discussion_url(item.id)),
+        body: item.text.as_deref().map(crate::hn::comments::sanitize),
+        hn_id: Some(item.id),
+        bookmark_id,
+    }
+}
@@ -0,0 +1,73 @@
+use crate::heartbeat::{Heartbeat, Heartbeats};
+use crate::hn::HnClient;
+use crate::settings::HnWatchSettings;
+use crate::shutdown::Shutdown;
+
+/// Submissions reported per watch and poll when polled on demand.
+pub const DEFAULT_MAX_ITEMS: usize = 30;
+
+/// Background loop checking every watched HN user which is due, one username
+/// at a time however many users watch it. Returns on shutdown once the user at
+/// hand is done.
+pub async fn run(
+    db: linkdb::Db,
+    client: HnClient,
+    settings: HnWatchSettings,
+    shutdown: Shutdown,
+    heartbeats: Heartbeats,
+) {
+    let interval = std::time::Duration::from_secs(settings.poll_interval_secs);
+    let tick = interval.min(std::time::Duration::from_secs(60));
+    let heartbeat = heartbeats.register("hn_watch_poller", tick + crate::heartbeat::JOB_GRACE);
+    tracing::info!(msg = "hn watch poller started", ?interval);
+
+    loop {
+        heartbeat.beat();
+        let max_items = settings.max_items_per_poll;
+        if let Err(e) = poll_due(&db, &client, interval, max_items, &shutdown, &heartbeat).await {
+            tracing::error!(msg = "hn watch poller round failed", err = %e);
+        }
+        if shutdown.is_requested() || shutdown.sleep(tick).await {
+            break;
+        }
+    }
+    tracing::info!(msg = "hn watch poller stopped");
+}
+
+#[tracing::instrument(name = "service::watch-poll-due", skip_all)]
+async fn poll_due(
+    db: &linkdb::Db,
+    client: &HnClient,
+    interval: std::time::Duration,
+    max_items: usize,
+    shutdown: &Shutdown,
+    heartbeat: &Heartbeat,
+) -> Result<(), super::types::WatchError> {
+    let polled_before = chrono::Utc::now()
+        - chrono::Duration::from_std(interval).unwrap_or(chrono::Duration::zero());
+    let watches = linkdb::hn_watch::list_due(db, polled_before).await?;
+    for group in watches.chunk_by(|a, b| a.username == b.username) {
+        if shutdown.is_requested() {
+            break;
+        }
+        let username = group[0].username.as_str();
+        match super::poll(db, client, group, max_items).await {
+            Ok(r) => {
+                tracing::info!(
+                    msg = "hn user polled",
+                    username,
+                    notifications = r.notifications,
+                    new_bookmarks = r.new_bookmarks
+                );
+                crate::metrics::job("hn_watch_poller", "user", true);
+                crate::metrics::job_items("hn_watch_poller", r.notifications + r.new_bookmarks);
+            }
+            Err(e) => {
+                tracing::warn!(msg = "hn user poll failed", username, err = %e);
+                crate::metrics::job("hn_watch_poller", "user", false);
+            }
+        }
+        heartbeat.beat();
+    }
+    Ok(())
+}
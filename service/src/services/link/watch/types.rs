@@ -0,0 +1,28 @@
+use crate::controller::link::types;
+use linkdb::hn_watch::HnWatchRow;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WatchError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+    #[error("InvalidInputError: {0}")]
+    InvalidInput(crate::problem::FieldError),
+    #[error("HNError: {0}")]
+    Hn(#[from] crate::hn::HNError),
+    #[error("HnSaveError: {0}")]
+    Save(#[from] crate::services::link::hn::types::HnSaveError),
+}
+
+pub fn from_db_response(row: HnWatchRow) -> types::WatchResponse {
+    types::WatchResponse {
+        username: row.username,
+        category: row.category,
+        last_seen_id: row.last_seen_id,
+        last_polled_on: row.last_polled_on,
+        last_error: row.last_error,
+        created_on: row.created_on,
+        updated_on: row.updated_on,
+    }
+}
@@ -1,2 +1,3 @@
 pub mod link;
+pub mod public;
 pub mod stat_svc;
@@ -0,0 +1,224 @@
+use super::Listing;
+
+const EXCERPT_CHARS: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Atom,
+    Rss,
+    Json,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "atom" => Some(Format::Atom),
+            "rss" => Some(Format::Rss),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Atom => "atom",
+            Format::Rss => "rss",
+            Format::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Atom => "application/atom+xml; charset=utf-8",
+            Format::Rss => "application/rss+xml; charset=utf-8",
+            Format::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/// Absolute urls of the feed document itself and of the html page for the listing.
+pub struct FeedUrls {
+    pub feed_url: String,
+    pub page_url: String,
+}
+
+pub fn render(
+    format: Format,
+    listing: &Listing,
+    urls: &FeedUrls,
+) -> Result<String, serde_json::Error> {
+    match format {
+        Format::Atom => Ok(atom(listing, urls)),
+        Format::Rss => Ok(rss(listing, urls)),
+        Format::Json => json(listing, urls),
+    }
+}
+
+fn entry_id(listing: &Listing, bookmark_id: i64) -> String {
+    format!("urn:linknova:{}:bm:{}", listing.user_id, bookmark_id)
+}
+
+fn entry_title(b: &linkdb::bookmark::BookmarkRow) -> String {
+    b.title.clone().unwrap_or_else(|| b.url.clone())
+}
+
+fn atom(listing: &Listing, urls: &FeedUrls) -> String {
+    let link = |href: &str, rel: &str, mime_type: Option<&str>| atom_syndication::Link {
+        href: href.to_string(),
+        rel: rel.to_string(),
+        mime_type: mime_type.map(|m| m.to_string()),
+        ..Default::default()
+    };
+
+    let feed = atom_syndication::Feed {
+        title: listing.title.clone().into(),
+        id: urls.feed_url.clone(),
+        updated: listing.updated_on.fixed_offset(),
+        subtitle: listing.description.clone().map(Into::into),
+        authors: vec![atom_syndication::Person {
+            name: listing.user_id.clone(),
+            ..Default::default()
+        }],
+        links: vec![
+            link(&urls.feed_url, "self", Some("application/atom+xml")),
+            link(&urls.page_url, "alternate", Some("text/html")),
+        ],
+        entries: listing
+            .bookmarks
+            .iter()
+            .map(|b| atom_syndication::Entry {
+                title: entry_title(b).into(),
+                id: entry_id(listing, b.id),
+                updated: b.updated_on.fixed_offset(),
+                published: Some(b.created_on.fixed_offset()),
+                links: vec![link(&b.url, "alternate", None)],
+                summary: b
+                    .content
+                    .as_deref()
+                    .map(|c| super::excerpt(c, EXCERPT_CHARS).into()),
+                categories: b
+                    .categories
+                    .iter()
+                    .map(|c| atom_syndication::Category {
+                        term: c.clone(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    feed.to_string()
+}
+
+fn rss(listing: &Listing, urls: &FeedUrls) -> String {
+    let channel = rss::Channel {
+        title: listing.title.clone(),
+        link: urls.page_url.clone(),
+        description: listing.description.clone().unwrap_or_default(),
+        last_build_date: Some(listing.updated_on.to_rfc2822()),
+        generator: Some("linknova".to_string()),
+        atom_ext: Some(rss::extension::atom::AtomExtension {
+            links: vec![rss::extension::atom::Link {
+                href: urls.feed_url.clone(),
+                rel: "self".to_string(),
+                mime_type: Some("application/rss+xml".to_string()),
+                ..Default::default()
+            }],
+        }),
+        items: listing
+            .bookmarks
+            .iter()
+            .map(|b| rss::Item {
+                title: Some(entry_title(b)),
+                link: Some(b.url.clone()),
+                description: b
+                    .content
+                    .as_deref()
+                    .map(|c| super::excerpt(c, EXCERPT_CHARS)),
+                guid: Some(rss::Guid {
+                    value: entry_id(listing, b.id),
+                    permalink: false,
+                }),
+                pub_date: Some(b.created_on.to_rfc2822()),
+                categories: b
+                    .categories
+                    .iter()
+                    .map(|c| rss::Category {
+                        name: c.clone(),
+                        domain: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    channel.to_string()
+}
+
+/// JSON Feed version 1.1, https://www.jsonfeed.org/version/1.1/
+fn json(listing: &Listing, urls: &FeedUrls) -> Result<String, serde_json::Error> {
+    #[derive(serde::Serialize)]
+    struct JsonFeed<'a> {
+        version: &'static str,
+        title: &'a str,
+        home_page_url: &'a str,
+        feed_url: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<&'a str>,
+        authors: Vec<Author<'a>>,
+        items: Vec<Item<'a>>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Author<'a> {
+        name: &'a str,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Item<'a> {
+        id: String,
+        url: &'a str,
+        title: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        summary: Option<String>,
+        content_text: String,
+        date_published: String,
+        date_modified: String,
+        tags: &'a [String],
+    }
+
+    let feed = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: &listing.title,
+        home_page_url: &urls.page_url,
+        feed_url: &urls.feed_url,
+        description: listing.description.as_deref(),
+        authors: vec![Author {
+            name: &listing.user_id,
+        }],
+        items: listing
+            .bookmarks
+            .iter()
+            .map(|b| {
+                let summary = b
+                    .content
+                    .as_deref()
+                    .map(|c| super::excerpt(c, EXCERPT_CHARS));
+                Item {
+                    id: entry_id(listing, b.id),
+                    url: &b.url,
+                    title: entry_title(b),
+                    content_text: summary.clone().unwrap_or_else(|| b.url.clone()),
+                    summary,
+                    date_published: b.created_on.to_rfc3339(),
+                    date_modified: b.updated_on.to_rfc3339(),
+                    tags: &b.categories,
+                }
+            })
+            .collect(),
+    };
+    serde_json::to_string(&feed)
+}
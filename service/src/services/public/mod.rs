@@ -0,0 +1,106 @@
+pub mod feed;
+pub mod page;
+pub mod share;
+pub mod types;
+
+use crate::ctx::Ctx;
+pub use types::{Listing, PublicError};
+
+/// Public topic with the recent bookmarks of its public categories.
+#[tracing::instrument(name = "service::public-topic-listing", skip_all)]
+pub async fn topic_listing(
+    ctx: &Ctx,
+    user_id: &str,
+    topic_name: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Listing, PublicError> {
+    let topic = linkdb::topic::get_public(&ctx.db, user_id, topic_name)
+        .await?
+        .ok_or_else(|| PublicError::NotFound(format!("topic with name: `{}`", topic_name)))?;
+    let categories = linkdb::category::list_public_by_topic_id(&ctx.db, user_id, topic.id).await?;
+    let category_ids: Vec<i64> = categories.iter().map(|c| c.id).collect();
+    let bookmarks = if category_ids.is_empty() {
+        vec![]
+    } else {
+        linkdb::bookmark::list_public(&ctx.db, user_id, &category_ids, limit, offset).await?
+    };
+
+    Ok(Listing {
+        user_id: user_id.to_string(),
+        name: topic.name.clone(),
+        title: topic.display_name.unwrap_or(topic.name),
+        description: topic.description,
+        updated_on: types::last_updated(topic.updated_on, &bookmarks),
+        categories: categories.into_iter().map(|c| c.name).collect(),
+        bookmarks,
+    })
+}
+
+/// Public category with its recent bookmarks.
+#[tracing::instrument(name = "service::public-cat-listing", skip_all)]
+pub async fn cat_listing(
+    ctx: &Ctx,
+    user_id: &str,
+    cat_name: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Listing, PublicError> {
+    let cat = linkdb::category::get_public(&ctx.db, user_id, cat_name)
+        .await?
+        .ok_or_else(|| PublicError::NotFound(format!("category with name: `{}`", cat_name)))?;
+    let bookmarks =
+        linkdb::bookmark::list_public(&ctx.db, user_id, &[cat.id], limit, offset).await?;
+
+    Ok(Listing {
+        user_id: user_id.to_string(),
+        name: cat.name.clone(),
+        title: cat.display_name.unwrap_or_else(|| cat.name.clone()),
+        description: cat.description,
+        updated_on: types::last_updated(cat.updated_on, &bookmarks),
+        categories: vec![cat.name],
+        bookmarks,
+    })
+}
+
+/// Plain text prefix of `content` with the html tags removed, at most `max_chars` long.
+pub fn excerpt(content: &str, max_chars: usize) -> String {
+    let mut text = String::with_capacity(content.len().min(max_chars * 4));
+    let mut in_tag = false;
+    for c in content.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let text = words.join(" ");
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", text[..idx].trim_end()),
+        None => text,
+    }
+}
+
+const SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Percent-encodes a user, topic or category name for use as a url path segment.
+pub fn segment(name: &str) -> String {
+    percent_encoding::utf8_percent_encode(name, SEGMENT).to_string()
+}
@@ -0,0 +1,239 @@
+use super::Listing;
+
+const EXCERPT_CHARS: usize = 280;
+
+pub enum Kind {
+    Topic,
+    Category,
+    Bookmark,
+}
+
+/// Everything about the page which is not part of the listing itself.
+pub struct PageInfo {
+    pub kind: Kind,
+    pub base_url: String,
+    /// path of the listing, without the query string
+    pub path: String,
+    /// 1-based page number
+    pub page: i64,
+    pub has_next: bool,
+    /// opened through a share link: not indexed, no feeds, no links to the
+    /// owner's other pages
+    pub shared: bool,
+}
+
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn host_of(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    rest.split(['/', '?', '#']).next().unwrap_or(rest)
+}
+
+fn page_href(path: &str, page: i64) -> String {
+    if page <= 1 {
+        path.to_string()
+    } else {
+        format!("{}?page={}", path, page)
+    }
+}
+
+pub fn render(listing: &Listing, info: &PageInfo) -> String {
+    let title = escape(&listing.title);
+    let description = escape(
+        listing
+            .description
+            .as_deref()
+            .unwrap_or("A reading list shared on LinkNova"),
+    );
+    let url = escape(&format!(
+        "{}{}",
+        info.base_url,
+        page_href(&info.path, info.page)
+    ));
+    let kind = match info.kind {
+        Kind::Topic => "Topic",
+        Kind::Category => "Category",
+        Kind::Bookmark => "Bookmark",
+    };
+
+    let mut html = String::with_capacity(8 * 1024);
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{title} · LinkNova</title>\n"));
+    html.push_str(&format!(
+        "<meta name=\"description\" content=\"{description}\">\n"
+    ));
+    if info.shared {
+        html.push_str("<meta name=\"robots\" content=\"noindex, nofollow\">\n");
+    } else {
+        html.push_str(&format!("<link rel=\"canonical\" href=\"{url}\">\n"));
+    }
+    html.push_str(&format!(
+        "<meta property=\"og:title\" content=\"{title}\">\n\
+         <meta property=\"og:description\" content=\"{description}\">\n\
+         <meta property=\"og:type\" content=\"website\">\n\
+         <meta property=\"og:url\" content=\"{url}\">\n\
+         <meta property=\"og:site_name\" content=\"LinkNova\">\n\
+         <meta name=\"twitter:card\" content=\"summary\">\n\
+         <meta name=\"twitter:title\" content=\"{title}\">\n\
+         <meta name=\"twitter:description\" content=\"{description}\">\n"
+    ));
+    let feeds: &[(&str, &str)] = if info.shared {
+        &[]
+    } else {
+        &[
+            ("atom", "application/atom+xml"),
+            ("rss", "application/rss+xml"),
+            ("json", "application/feed+json"),
+        ]
+    };
+    for (format, mime) in feeds {
+        html.push_str(&format!(
+            "<link rel=\"alternate\" type=\"{mime}\" title=\"{title}\" href=\"{}/feed/{format}\">\n",
+            escape(&info.path)
+        ));
+    }
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n<main>\n<header>\n");
+    html.push_str(&format!(
+        "<p class=\"kind\">{kind} by {}</p>\n<h1>{title}</h1>\n",
+        escape(&listing.user_id)
+    ));
+    if let Some(d) = &listing.description {
+        html.push_str(&format!("<p>{}</p>\n", escape(d)));
+    }
+    if matches!(info.kind, Kind::Topic) && !info.shared && !listing.categories.is_empty() {
+        html.push_str("<nav class=\"categories\">\n");
+        for c in listing.categories.iter() {
+            html.push_str(&format!(
+                "<a href=\"/-/ln/u/{}/c/{}\">{}</a>\n",
+                escape(&super::segment(&listing.user_id)),
+                escape(&super::segment(c)),
+                escape(c)
+            ));
+        }
+        html.push_str("</nav>\n");
+    }
+    html.push_str("</header>\n");
+
+    if listing.bookmarks.is_empty() {
+        html.push_str("<p class=\"empty\">Nothing here yet.</p>\n");
+    } else {
+        html.push_str("<ol class=\"bookmarks\">\n");
+        for b in listing.bookmarks.iter() {
+            let b_title = escape(b.title.as_deref().unwrap_or(&b.url));
+            // saved before urls were checked, shown but not linked
+            let title = if crate::utils::is_web_url(&b.url) {
+                format!(
+                    "<a class=\"title\" href=\"{}\" rel=\"noopener nofollow\">{b_title}</a>",
+                    escape(&b.url)
+                )
+            } else {
+                format!("<span class=\"title\">{b_title}</span>")
+            };
+            html.push_str(&format!(
+                "<li>\n{title}\n<span class=\"meta\">{} · {}</span>\n",
+                escape(host_of(&b.url)),
+                b.created_on.format("%Y-%m-%d")
+            ));
+            if let Some(content) = b.content.as_deref() {
+                let text = super::excerpt(content, EXCERPT_CHARS);
+                if !text.is_empty() {
+                    html.push_str(&format!("<p>{}</p>\n", escape(&text)));
+                }
+            }
+            if !b.categories.is_empty() {
+                html.push_str("<p class=\"tags\">");
+                for c in b.categories.iter() {
+                    html.push_str(&format!("<span>{}</span>", escape(c)));
+                }
+                html.push_str("</p>\n");
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ol>\n");
+    }
+
+    if info.page > 1 || info.has_next {
+        html.push_str("<nav class=\"pages\">\n");
+        if info.page > 1 {
+            html.push_str(&format!(
+                "<a rel=\"prev\" href=\"{}\">← Newer</a>\n",
+                escape(&page_href(&info.path, info.page - 1))
+            ));
+        }
+        if info.has_next {
+            html.push_str(&format!(
+                "<a rel=\"next\" href=\"{}\">Older →</a>\n",
+                escape(&page_href(&info.path, info.page + 1))
+            ));
+        }
+        html.push_str("</nav>\n");
+    }
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+/// Unlock form of a password protected share link, posts back to `path`.
+pub fn render_password(path: &str, bad_password: bool) -> String {
+    let mut html = String::with_capacity(2 * 1024);
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str("<meta name=\"robots\" content=\"noindex, nofollow\">\n");
+    html.push_str("<title>Password required · LinkNova</title>\n");
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n<main>\n<h1>Password required</h1>\n");
+    if bad_password {
+        html.push_str("<p class=\"error\">Wrong password, try again.</p>\n");
+    }
+    html.push_str(&format!(
+        "<form method=\"post\" action=\"{}\">\n\
+         <input type=\"password\" name=\"password\" autofocus required>\n\
+         <button type=\"submit\">Open</button>\n</form>\n",
+        escape(path)
+    ));
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+/// Minimal page for errors and closed share links.
+pub fn render_message(title: &str, message: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"robots\" content=\"noindex, nofollow\">\n\
+         <title>{title} · LinkNova</title>\n{STYLE}</head>\n<body>\n<main>\n\
+         <h1>{title}</h1>\n<p class=\"empty\">{message}</p>\n</main>\n</body>\n</html>\n",
+        title = escape(title),
+        message = escape(message),
+    )
+}
+
+const STYLE: &str = r#"<style>
+body { font-family: system-ui, sans-serif; margin: 0; background: #fafafa; color: #222; }
+main { max-width: 46rem; margin: 0 auto; padding: 1.5rem 1rem; }
+h1 { margin: 0.2rem 0 0.5rem; }
+a { color: #1a56db; }
+.kind, .meta, .empty { color: #666; font-size: 0.9rem; }
+.categories a, .tags span { display: inline-block; margin: 0 0.4rem 0.4rem 0; padding: 0.1rem 0.5rem; border-radius: 0.8rem; background: #e8eefc; font-size: 0.85rem; text-decoration: none; }
+.bookmarks { list-style: none; padding: 0; }
+.bookmarks li { padding: 0.8rem 0; border-bottom: 1px solid #e5e5e5; }
+.bookmarks .title { font-weight: 600; text-decoration: none; display: block; }
+.bookmarks p { margin: 0.3rem 0; }
+.error { color: #b91c1c; }
+form input { padding: 0.4rem; margin-right: 0.4rem; }
+.pages { display: flex; justify-content: space-between; padding-top: 1rem; }
+</style>
+"#;
@@ -0,0 +1,222 @@
+use super::{Listing, PublicError};
+use crate::controller::link::types::ShareKind;
+use crate::ctx::Ctx;
+use crate::services::link::share::types as share;
+
+/// Wrong passwords a link takes within `BAD_PASSWORD_WINDOW_MINS` before it
+/// stops checking them for the rest of the window.
+pub const MAX_BAD_PASSWORDS: i64 = 10;
+pub const BAD_PASSWORD_WINDOW_MINS: i64 = 15;
+
+/// Who is opening a share link, recorded in its access log.
+pub struct Visitor {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// password posted through the unlock form
+    pub password: Option<String>,
+    /// value of the unlock cookie set by an earlier correct password
+    pub unlock: Option<String>,
+    /// value of the viewer cookie set when the visitor's view was counted
+    pub viewed: Option<String>,
+}
+
+pub enum Opened {
+    Granted {
+        listing: Listing,
+        kind: ShareKind,
+        /// set when the visitor just entered the right password
+        unlock: Option<String>,
+        /// set when the visitor's view was just counted
+        viewed: Option<String>,
+    },
+    PasswordRequired {
+        bad_password: bool,
+    },
+    /// too many wrong passwords lately
+    Locked,
+    /// revoked, expired or exhausted
+    Closed(&'static str),
+}
+
+/// Cookie value proving the password was entered, bound to the current
+/// password hash so changing or revoking the link invalidates it.
+pub fn unlock_value(link: &linkdb::share::ShareLinkRow) -> Option<String> {
+    link.password_hash
+        .as_deref()
+        .map(|h| share::sha256_hex(&format!("{}:{}", link.token_hash, h)))
+}
+
+/// Cookie value of a visitor whose view of the link was counted, the pages
+/// after the first and reloads are not counted again. Bound to the server's
+/// secret as the visitor knows everything else.
+pub fn viewed_value(secret: &str, link: &linkdb::share::ShareLinkRow) -> String {
+    share::sha256_hex(&format!("{}:{}:viewed", secret, link.token_hash))
+}
+
+/// Opens a share link, every attempt on an existing link ends up in its access log.
+#[tracing::instrument(name = "service::public-share-open", skip_all)]
+pub async fn open(
+    ctx: &Ctx,
+    token: &str,
+    visitor: Visitor,
+    limit: i64,
+    offset: i64,
+) -> Result<Opened, PublicError> {
+    let link = linkdb::share::get_by_token_hash(&ctx.db, &share::sha256_hex(token))
+        .await?
+        .ok_or_else(|| PublicError::NotFound("share link".to_string()))?;
+    let kind = share::kind_from_code(&link.kind)
+        .ok_or_else(|| PublicError::NotFound("share link".to_string()))?;
+
+    let viewed = viewed_value(&ctx.secret, &link);
+    let counted = visitor.viewed.as_deref() == Some(viewed.as_str());
+    match share::closed_reason(&link, chrono::Utc::now()) {
+        // the views left were taken by others after this visitor's
+        Some("exhausted") if counted => {}
+        Some(reason) => {
+            log(ctx, &link, &visitor, false, reason).await?;
+            return Ok(Opened::Closed(reason));
+        }
+        None => {}
+    }
+
+    let mut unlock = None;
+    if let Some(password_hash) = link.password_hash.as_deref() {
+        let expected = unlock_value(&link);
+        if visitor.unlock.is_some() && visitor.unlock == expected {
+            // unlocked earlier
+        } else if let Some(password) = visitor.password.as_deref() {
+            let since = chrono::Utc::now() - chrono::Duration::minutes(BAD_PASSWORD_WINDOW_MINS);
+            let bad =
+                linkdb::share::access::count_since(&ctx.db, link.id, "bad-password", since).await?;
+            if bad >= MAX_BAD_PASSWORDS {
+                log(ctx, &link, &visitor, false, "locked").await?;
+                return Ok(Opened::Locked);
+            }
+            if !share::verify_password(password, password_hash) {
+                log(ctx, &link, &visitor, false, "bad-password").await?;
+                return Ok(Opened::PasswordRequired { bad_password: true });
+            }
+            unlock = expected;
+        } else {
+            log(ctx, &link, &visitor, false, "password-required").await?;
+            return Ok(Opened::PasswordRequired {
+                bad_password: false,
+            });
+        }
+    }
+
+    // the link may have run out between the check above and now
+    if !counted && !linkdb::share::record_view(&ctx.db, link.id, chrono::Utc::now()).await? {
+        log(ctx, &link, &visitor, false, "exhausted").await?;
+        return Ok(Opened::Closed("exhausted"));
+    }
+
+    let listing = listing(ctx, &link, kind, limit, offset).await?;
+    log(ctx, &link, &visitor, true, "ok").await?;
+    Ok(Opened::Granted {
+        listing,
+        kind,
+        unlock,
+        viewed: (!counted).then_some(viewed),
+    })
+}
+
+async fn listing(
+    ctx: &Ctx,
+    link: &linkdb::share::ShareLinkRow,
+    kind: ShareKind,
+    limit: i64,
+    offset: i64,
+) -> Result<Listing, PublicError> {
+    let user_id = link.user_id.as_str();
+    let not_found = || PublicError::NotFound(format!("shared item with id: `{}`", link.target_id));
+    match kind {
+        ShareKind::Bookmark => {
+            let mut bookmark = match linkdb::bookmark::get_by_id(&ctx.db, link.target_id).await {
+                Ok(b) if b.user_id == user_id => b,
+                Ok(_) | Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+                Err(e) => return Err(e.into()),
+            };
+            // category names of a single bookmark are not part of what was shared
+            bookmark.categories.clear();
+            Ok(Listing {
+                user_id: user_id.to_string(),
+                name: bookmark.id.to_string(),
+                title: bookmark
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| bookmark.url.clone()),
+                description: None,
+                updated_on: bookmark.updated_on,
+                categories: vec![],
+                bookmarks: if offset == 0 { vec![bookmark] } else { vec![] },
+            })
+        }
+        ShareKind::Category => {
+            let cat = linkdb::category::get_by_id(&ctx.db, user_id, link.target_id)
+                .await?
+                .ok_or_else(not_found)?;
+            let bookmarks =
+                linkdb::bookmark::list_by_category_ids(&ctx.db, user_id, &[cat.id], limit, offset)
+                    .await?;
+            Ok(Listing {
+                user_id: user_id.to_string(),
+                name: cat.name.clone(),
+                title: cat.display_name.unwrap_or_else(|| cat.name.clone()),
+                description: cat.description,
+                updated_on: super::types::last_updated(cat.updated_on, &bookmarks),
+                categories: vec![cat.name],
+                bookmarks,
+            })
+        }
+        ShareKind::Topic => {
+            let topic = linkdb::topic::get_by_id(&ctx.db, user_id, link.target_id)
+                .await?
+                .ok_or_else(not_found)?;
+            let categories = linkdb::category::list_by_topic_id(&ctx.db, user_id, topic.id).await?;
+            let category_ids: Vec<i64> = categories.iter().map(|c| c.id).collect();
+            let bookmarks = if category_ids.is_empty() {
+                vec![]
+            } else {
+                linkdb::bookmark::list_by_category_ids(
+                    &ctx.db,
+                    user_id,
+                    &category_ids,
+                    limit,
+                    offset,
+                )
+                .await?
+            };
+            Ok(Listing {
+                user_id: user_id.to_string(),
+                name: topic.name.clone(),
+                title: topic.display_name.unwrap_or(topic.name),
+                description: topic.description,
+                updated_on: super::types::last_updated(topic.updated_on, &bookmarks),
+                categories: categories.into_iter().map(|c| c.name).collect(),
+                bookmarks,
+            })
+        }
+    }
+}
+
+async fn log(
+    ctx: &Ctx,
+    link: &linkdb::share::ShareLinkRow,
+    visitor: &Visitor,
+    granted: bool,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    linkdb::share::access::insert(
+        &ctx.db,
+        linkdb::share::ShareAccessI {
+            share_link_id: link.id,
+            granted,
+            reason: reason.to_string(),
+            ip: visitor.ip.clone(),
+            user_agent: visitor.user_agent.clone(),
+        },
+    )
+    .await
+}
@@ -0,0 +1,32 @@
+use linkdb::bookmark::BookmarkRow;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PublicError {
+    #[error("DatabaseError: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+}
+
+/// A public topic or category along with one page of its bookmarks.
+#[derive(Debug)]
+pub struct Listing {
+    pub user_id: String,
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub updated_on: chrono::DateTime<chrono::Utc>,
+    /// public categories of the topic, or just the category itself
+    pub categories: Vec<String>,
+    pub bookmarks: Vec<BookmarkRow>,
+}
+
+pub fn last_updated(
+    updated_on: chrono::DateTime<chrono::Utc>,
+    bookmarks: &[BookmarkRow],
+) -> chrono::DateTime<chrono::Utc> {
+    bookmarks
+        .iter()
+        .map(|b| b.updated_on)
+        .fold(updated_on, |acc, u| acc.max(u))
+}
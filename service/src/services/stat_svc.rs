@@ -53,7 +53,7 @@ pub async fn handle_static(ctx: &Ctx, path: &str) -> Response {
     // if file exists then canonicalize check and serve
 
     // 1. try serving the path as it is
-    let file_path_variant_1 = ctx.static_dir.join(&path);
+    let file_path_variant_1 = ctx.static_dir.join(path);
     if let Some(response) = check_and_serve(&ctx.static_dir, file_path_variant_1).await {
         println!("file-path 1: {}", path);
 
@@ -63,7 +63,7 @@ pub async fn handle_static(ctx: &Ctx, path: &str) -> Response {
     println!("file-path 2: {}", path);
 
     // 2. try serving the path /path.html
-    let mut file_path_variant_2 = ctx.static_dir.join(&path);
+    let mut file_path_variant_2 = ctx.static_dir.join(path);
     file_path_variant_2.set_extension("html");
     println!("2. trying: {}", file_path_variant_2.display());
     if let Some(response) = check_and_serve(&ctx.static_dir, file_path_variant_2).await {
@@ -71,7 +71,7 @@ pub async fn handle_static(ctx: &Ctx, path: &str) -> Response {
     }
 
     // 3. try serving the path /path/index.html
-    let file_path_variant_3 = ctx.static_dir.join(&path).join("index.html");
+    let file_path_variant_3 = ctx.static_dir.join(path).join("index.html");
     println!("3. trying: {}", file_path_variant_3.display());
     if let Some(response) = check_and_serve(&ctx.static_dir, file_path_variant_3).await {
         return response;
@@ -1,29 +1,793 @@
+//! Typed configuration: `default.toml`, then `settings-{profile}.toml`, then
+//! `LINKNOVA_*` environment variables, `__` separating the levels, e.g.
+//! `LINKNOVA_DATABASE__URL` or `LINKNOVA_HN__INGEST__ENABLED`.
+
 use config::File;
 
+const ENV_PREFIX: &str = "LINKNOVA";
+
+/// Shown instead of secrets when settings are printed.
+const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("LoadError: {0}")]
+    Load(#[from] config::ConfigError),
+    /// every problem found, not only the first one
+    #[error("InvalidError: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct Settings {
+    #[serde(default)]
     pub service: ServiceSettings,
     pub static_dir: Option<String>,
+    #[serde(default)]
+    pub database: DatabaseSettings,
+    #[serde(default)]
+    pub feed: FeedSettings,
+    #[serde(default)]
+    pub hn: HnSettings,
+    #[serde(default)]
+    pub sources: SourcesSettings,
+    #[serde(default)]
+    pub telemetry: TelemetrySettings,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(serde::Deserialize)]
 pub struct ServiceSettings {
+    #[serde(default)]
     pub environment: String,
+    #[serde(default)]
     pub secrets: String,
+    #[serde(default = "default_bind")]
+    pub bind: std::net::SocketAddr,
+    /// `Domain` of the login cookie
+    #[serde(default = "default_cookie_domain")]
+    pub cookie_domain: String,
+    /// Time given on SIGTERM/SIGINT to in-flight requests and the workers'
+    /// current job, keep it below the container's stop timeout
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    /// Reverse proxies whose `X-Forwarded-For`/`X-Real-IP` name the client,
+    /// the headers of anyone else are ignored
+    #[serde(default)]
+    pub trusted_proxies: Vec<std::net::IpAddr>,
+}
+
+impl Default for ServiceSettings {
+    fn default() -> Self {
+        Self {
+            environment: String::new(),
+            secrets: String::new(),
+            bind: default_bind(),
+            cookie_domain: default_cookie_domain(),
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ServiceSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceSettings")
+            .field("environment", &self.environment)
+            .field("secrets", &REDACTED)
+            .field("bind", &self.bind)
+            .field("cookie_domain", &self.cookie_domain)
+            .field("shutdown_timeout_secs", &self.shutdown_timeout_secs)
+            .field("trusted_proxies", &self.trusted_proxies)
+            .finish()
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct DatabaseSettings {
+    /// `postgres://` or `sqlite:`, the scheme picks the backend
+    #[serde(default)]
+    pub url: String,
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+    #[serde(default)]
+    pub min_connections: u32,
+    #[serde(default = "default_acquire_timeout_secs")]
+    pub acquire_timeout_secs: u64,
+    /// Apply pending migrations before serving, otherwise `service migrate` has to.
+    #[serde(default)]
+    pub migrate_on_startup: bool,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            max_connections: default_max_connections(),
+            min_connections: 0,
+            acquire_timeout_secs: default_acquire_timeout_secs(),
+            migrate_on_startup: false,
+        }
+    }
+}
+
+impl DatabaseSettings {
+    pub fn pool(&self) -> linkdb::PoolConfig {
+        linkdb::PoolConfig {
+            max_connections: self.max_connections,
+            min_connections: self.min_connections,
+            acquire_timeout: std::time::Duration::from_secs(self.acquire_timeout_secs),
+        }
+    }
+}
+
+impl std::fmt::Debug for DatabaseSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DatabaseSettings")
+            .field("url", &redact_url(&self.url))
+            .field("max_connections", &self.max_connections)
+            .field("min_connections", &self.min_connections)
+            .field("acquire_timeout_secs", &self.acquire_timeout_secs)
+            .field("migrate_on_startup", &self.migrate_on_startup)
+            .finish()
+    }
+}
+
+/// The url with its password, if any, replaced by `***`, which unlike `REDACTED`
+/// needs no percent-encoding.
+fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) if parsed.password().is_some() => match parsed.set_password(Some("***")) {
+            Ok(()) => parsed.to_string(),
+            Err(()) => REDACTED.to_string(),
+        },
+        Ok(_) => url.to_string(),
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct FeedSettings {
+    #[serde(default = "default_true")]
+    pub poller_enabled: bool,
+    /// A feed is fetched again once this many seconds have passed since its last poll.
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+}
+
+impl Default for FeedSettings {
+    fn default() -> Self {
+        Self {
+            poller_enabled: default_true(),
+            poll_interval_secs: default_poll_interval_secs(),
+            request_timeout_secs: default_request_timeout_secs(),
+        }
+    }
+}
+
+/// Logs and trace export.
+#[derive(Debug, serde::Deserialize)]
+pub struct TelemetrySettings {
+    /// Directives used when `RUST_LOG` is not set
+    #[serde(default = "default_log_filter")]
+    pub log_filter: String,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// Exports spans over OTLP/HTTP when set
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Share of the traces started here which are exported, 0 to 1
+    #[serde(default = "default_trace_sample_ratio")]
+    pub sample_ratio: f64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// one JSON object per line, for log shippers
+    Json,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            log_filter: default_log_filter(),
+            log_format: LogFormat::default(),
+            otlp_endpoint: None,
+            service_name: default_service_name(),
+            sample_ratio: default_trace_sample_ratio(),
+        }
+    }
+}
+
+/// Aggregators other than HN, which has its own `[hn]` settings.
+#[derive(Debug, serde::Deserialize)]
+pub struct SourcesSettings {
+    #[serde(default = "default_hn_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    #[serde(default)]
+    pub lobsters: LobstersSettings,
+    #[serde(default)]
+    pub reddit: RedditSettings,
+}
+
+impl Default for SourcesSettings {
+    fn default() -> Self {
+        Self {
+            request_timeout_secs: default_hn_request_timeout_secs(),
+            lobsters: LobstersSettings::default(),
+            reddit: RedditSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct LobstersSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_lobsters_base_url")]
+    pub base_url: String,
+}
+
+impl Default for LobstersSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            base_url: default_lobsters_base_url(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct RedditSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_reddit_base_url")]
+    pub base_url: String,
+    /// each one is a list of the reddit source
+    #[serde(default = "default_reddit_subreddits")]
+    pub subreddits: Vec<String>,
+}
+
+impl Default for RedditSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            base_url: default_reddit_base_url(),
+            subreddits: default_reddit_subreddits(),
+        }
+    }
+}
+
+fn default_lobsters_base_url() -> String {
+    "https://lobste.rs".to_string()
+}
+
+fn default_reddit_base_url() -> String {
+    "https://www.reddit.com".to_string()
+}
+
+fn default_reddit_subreddits() -> Vec<String> {
+    vec!["programming".to_string(), "rust".to_string()]
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct HnSettings {
+    /// points at a local mock server in tests
+    #[serde(default = "default_hn_base_url")]
+    pub base_url: String,
+    /// requests in flight at once, across all callers
+    #[serde(default = "default_hn_max_concurrency")]
+    pub max_concurrency: usize,
+    #[serde(default = "default_hn_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// extra attempts after a timeout, connection error, 429 or 5xx
+    #[serde(default = "default_hn_retries")]
+    pub retries: u32,
+    /// doubled on every retry
+    #[serde(default = "default_hn_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// 0 disables the item cache
+    #[serde(default = "default_hn_item_ttl_secs")]
+    pub item_ttl_secs: u64,
+    /// 0 disables the story list cache
+    #[serde(default = "default_hn_list_ttl_secs")]
+    pub list_ttl_secs: u64,
+    #[serde(default = "default_hn_cache_max_items")]
+    pub cache_max_items: usize,
+    #[serde(default)]
+    pub ingest: HnIngestSettings,
+    #[serde(default)]
+    pub watch: HnWatchSettings,
+}
+
+/// Background job checking watched HN users for new submissions.
+#[derive(Debug, serde::Deserialize)]
+pub struct HnWatchSettings {
+    #[serde(default = "default_true")]
+    pub poller_enabled: bool,
+    /// a watched user is checked again once this many seconds have passed
+    #[serde(default = "default_hn_watch_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// newest submissions reported per watch and poll, older ones are skipped
+    #[serde(default = "default_hn_watch_max_items_per_poll")]
+    pub max_items_per_poll: usize,
+}
+
+impl Default for HnWatchSettings {
+    fn default() -> Self {
+        Self {
+            poller_enabled: default_true(),
+            poll_interval_secs: default_hn_watch_poll_interval_secs(),
+            max_items_per_poll: default_hn_watch_max_items_per_poll(),
+        }
+    }
+}
+
+/// Background job snapshotting story lists and pulling new items into Postgres.
+#[derive(Debug, serde::Deserialize)]
+pub struct HnIngestSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_hn_ingest_interval_secs")]
+    pub interval_secs: u64,
+    /// story lists whose scores are recorded every round
+    #[serde(default = "default_hn_ingest_lists")]
+    pub lists: Vec<crate::hn::apis::StoryList>,
+    /// only the first ids of each list are snapshotted
+    #[serde(default = "default_hn_ingest_list_size")]
+    pub list_size: usize,
+    /// upper bound of new items fetched per round, older ones are skipped when
+    /// the job falls further behind
+    #[serde(default = "default_hn_ingest_max_new_items")]
+    pub max_new_items: i64,
+    /// 0 keeps score snapshots forever
+    #[serde(default = "default_hn_ingest_retention_days")]
+    pub retention_days: u64,
+}
+
+impl Default for HnIngestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_hn_ingest_interval_secs(),
+            lists: default_hn_ingest_lists(),
+            list_size: default_hn_ingest_list_size(),
+            max_new_items: default_hn_ingest_max_new_items(),
+            retention_days: default_hn_ingest_retention_days(),
+        }
+    }
+}
+
+impl Default for HnSettings {
+    fn default() -> Self {
+        Self {
+            base_url: default_hn_base_url(),
+            max_concurrency: default_hn_max_concurrency(),
+            request_timeout_secs: default_hn_request_timeout_secs(),
+            retries: default_hn_retries(),
+            retry_backoff_ms: default_hn_retry_backoff_ms(),
+            item_ttl_secs: default_hn_item_ttl_secs(),
+            list_ttl_secs: default_hn_list_ttl_secs(),
+            cache_max_items: default_hn_cache_max_items(),
+            ingest: HnIngestSettings::default(),
+            watch: HnWatchSettings::default(),
+        }
+    }
+}
+
+fn default_hn_base_url() -> String {
+    "https://hacker-news.firebaseio.com/v0".to_string()
+}
+
+fn default_hn_max_concurrency() -> usize {
+    16
+}
+
+fn default_hn_request_timeout_secs() -> u64 {
+    10
+}
+
+fn default_hn_retries() -> u32 {
+    2
+}
+
+fn default_hn_retry_backoff_ms() -> u64 {
+    200
+}
+
+fn default_hn_item_ttl_secs() -> u64 {
+    5 * 60
+}
+
+fn default_hn_list_ttl_secs() -> u64 {
+    60
+}
+
+fn default_hn_cache_max_items() -> usize {
+    10_000
+}
+
+fn default_hn_ingest_interval_secs() -> u64 {
+    5 * 60
+}
+
+fn default_hn_ingest_lists() -> Vec<crate::hn::apis::StoryList> {
+    use crate::hn::apis::StoryList;
+    vec![StoryList::Top, StoryList::Best, StoryList::New]
+}
+
+fn default_hn_ingest_list_size() -> usize {
+    100
+}
+
+fn default_hn_ingest_max_new_items() -> i64 {
+    2000
+}
+
+fn default_hn_ingest_retention_days() -> u64 {
+    30
+}
+
+fn default_hn_watch_poll_interval_secs() -> u64 {
+    15 * 60
+}
+
+fn default_hn_watch_max_items_per_poll() -> usize {
+    crate::services::link::watch::poller::DEFAULT_MAX_ITEMS
+}
+
+fn default_bind() -> std::net::SocketAddr {
+    (std::net::Ipv4Addr::UNSPECIFIED, 8000).into()
+}
+
+fn default_cookie_domain() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    20
+}
+
+fn default_log_filter() -> String {
+    "info".to_string()
+}
+
+fn default_service_name() -> String {
+    "linknova".to_string()
+}
+
+fn default_trace_sample_ratio() -> f64 {
+    1.0
+}
+
+fn default_max_connections() -> u32 {
+    5
+}
+
+fn default_acquire_timeout_secs() -> u64 {
+    30
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_poll_interval_secs() -> u64 {
+    30 * 60
+}
+
+fn default_request_timeout_secs() -> u64 {
+    20
 }
 
 impl Settings {
-    pub fn new_with_file(
+    /// Loads the files of `profile` from `path`, with the `LINKNOVA_*` environment
+    /// on top, and validates the result.
+    pub fn new_with_file(path: &std::path::Path, profile: &str) -> Result<Self, SettingsError> {
+        Self::load(path, profile, None)
+    }
+
+    /// `env` stands in for the process environment when set.
+    fn load(
         path: &std::path::Path,
         profile: &str,
-    ) -> Result<Self, config::ConfigError> {
+        env: Option<config::Map<String, String>>,
+    ) -> Result<Self, SettingsError> {
         let settings_file_path = path.join(format!("settings-{}.toml", profile));
         let default_settings_path = path.join("default.toml");
 
-        let settings = config::Config::builder()
+        let environment = config::Environment::with_prefix(ENV_PREFIX)
+            .prefix_separator("_")
+            .separator("__")
+            .list_separator(",")
+            .with_list_parse_key("service.trusted_proxies")
+            .with_list_parse_key("hn.ingest.lists")
+            .with_list_parse_key("sources.reddit.subreddits")
+            .try_parsing(true)
+            .source(env);
+        let settings: Settings = config::Config::builder()
             .add_source(File::from(default_settings_path))
             .add_source(File::from(settings_file_path))
-            .build()?;
-        settings.try_deserialize()
+            .add_source(environment)
+            .build()?
+            .try_deserialize()?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Checks what deserializing can't, reporting every problem at once.
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: String| {
+            if !ok {
+                problems.push(problem);
+            }
+        };
+
+        check(
+            !self.service.environment.is_empty(),
+            "service.environment is not set".to_string(),
+        );
+        check(
+            !self.service.secrets.is_empty(),
+            "service.secrets is not set".to_string(),
+        );
+        check(
+            !self.service.cookie_domain.is_empty(),
+            "service.cookie_domain is empty".to_string(),
+        );
+        if let Some(dir) = self.static_dir.as_deref() {
+            check(
+                std::path::Path::new(dir).is_dir(),
+                format!("static_dir `{}` is not a directory", dir),
+            );
+        }
+
+        let db = &self.database;
+        match linkdb::Backend::of_url(&db.url) {
+            _ if db.url.is_empty() => check(false, "database.url is not set".to_string()),
+            None => check(
+                false,
+                "database.url has to start with postgres://, postgresql:// or sqlite:".to_string(),
+            ),
+            Some(linkdb::Backend::Sqlite) => check(
+                cfg!(feature = "sqlite"),
+                "database.url is a sqlite url, which needs a build with the `sqlite` feature"
+                    .to_string(),
+            ),
+            Some(linkdb::Backend::Postgres) => {}
+        }
+        check(
+            db.max_connections > 0,
+            "database.max_connections has to be at least 1".to_string(),
+        );
+        check(
+            db.min_connections <= db.max_connections,
+            "database.min_connections is above database.max_connections".to_string(),
+        );
+        check(
+            db.acquire_timeout_secs > 0,
+            "database.acquire_timeout_secs has to be positive".to_string(),
+        );
+
+        let feed = &self.feed;
+        check(
+            feed.request_timeout_secs > 0,
+            "feed.request_timeout_secs has to be positive".to_string(),
+        );
+        check(
+            !feed.poller_enabled || feed.poll_interval_secs > 0,
+            "feed.poll_interval_secs has to be positive".to_string(),
+        );
+
+        let hn = &self.hn;
+        check(
+            is_http_url(&hn.base_url),
+            format!("hn.base_url `{}` is not an http(s) url", hn.base_url),
+        );
+        check(
+            hn.max_concurrency > 0,
+            "hn.max_concurrency has to be at least 1".to_string(),
+        );
+        check(
+            hn.request_timeout_secs > 0,
+            "hn.request_timeout_secs has to be positive".to_string(),
+        );
+        if hn.ingest.enabled {
+            check(
+                hn.ingest.interval_secs > 0,
+                "hn.ingest.interval_secs has to be positive".to_string(),
+            );
+            check(
+                !hn.ingest.lists.is_empty() && hn.ingest.list_size > 0,
+                "hn.ingest needs at least one list and a positive list_size".to_string(),
+            );
+            check(
+                hn.ingest.max_new_items > 0,
+                "hn.ingest.max_new_items has to be at least 1".to_string(),
+            );
+        }
+        if hn.watch.poller_enabled {
+            check(
+                hn.watch.poll_interval_secs > 0,
+                "hn.watch.poll_interval_secs has to be positive".to_string(),
+            );
+            check(
+                hn.watch.max_items_per_poll > 0,
+                "hn.watch.max_items_per_poll has to be at least 1".to_string(),
+            );
+        }
+
+        let sources = &self.sources;
+        check(
+            sources.request_timeout_secs > 0,
+            "sources.request_timeout_secs has to be positive".to_string(),
+        );
+        if sources.lobsters.enabled {
+            check(
+                is_http_url(&sources.lobsters.base_url),
+                format!(
+                    "sources.lobsters.base_url `{}` is not an http(s) url",
+                    sources.lobsters.base_url
+                ),
+            );
+        }
+        if sources.reddit.enabled {
+            check(
+                is_http_url(&sources.reddit.base_url),
+                format!(
+                    "sources.reddit.base_url `{}` is not an http(s) url",
+                    sources.reddit.base_url
+                ),
+            );
+            check(
+                !sources.reddit.subreddits.is_empty(),
+                "sources.reddit.subreddits is empty".to_string(),
+            );
+        }
+
+        let telemetry = &self.telemetry;
+        check(
+            tracing_subscriber::EnvFilter::try_new(&telemetry.log_filter).is_ok(),
+            format!(
+                "telemetry.log_filter `{}` is not valid",
+                telemetry.log_filter
+            ),
+        );
+        if let Some(endpoint) = telemetry.otlp_endpoint.as_deref() {
+            check(
+                is_http_url(endpoint),
+                format!(
+                    "telemetry.otlp_endpoint `{}` is not an http(s) url",
+                    endpoint
+                ),
+            );
+        }
+        check(
+            (0.0..=1.0).contains(&telemetry.sample_ratio),
+            "telemetry.sample_ratio has to be between 0 and 1".to_string(),
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(SettingsError::Invalid(problems))
+        }
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir() -> &'static std::path::Path {
+        std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/settings"))
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Option<config::Map<String, String>> {
+        let mut env: config::Map<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        // `./ui/dist` of default.toml is relative to the repository root
+        env.entry("LINKNOVA_STATIC_DIR".to_string())
+            .or_insert_with(|| env!("CARGO_MANIFEST_DIR").to_string());
+        Some(env)
+    }
+
+    #[test]
+    fn env_overrides_the_files() {
+        let settings = Settings::load(
+            dir(),
+            "local",
+            env(&[
+                ("LINKNOVA_DATABASE__URL", "postgres://db.internal/linknova"),
+                ("LINKNOVA_DATABASE__MAX_CONNECTIONS", "12"),
+                ("LINKNOVA_SERVICE__BIND", "127.0.0.1:9000"),
+                ("LINKNOVA_HN__INGEST__ENABLED", "true"),
+                ("LINKNOVA_HN__INGEST__LISTS", "top,ask"),
+                ("LINKNOVA_SOURCES__REDDIT__SUBREDDITS", "rust"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(settings.service.environment, "local");
+        assert_eq!(settings.database.url, "postgres://db.internal/linknova");
+        assert_eq!(settings.database.max_connections, 12);
+        assert!(settings.database.migrate_on_startup);
+        assert_eq!(settings.service.bind.to_string(), "127.0.0.1:9000");
+        assert!(settings.hn.ingest.enabled);
+        assert_eq!(
+            settings.hn.ingest.lists,
+            [
+                crate::hn::apis::StoryList::Top,
+                crate::hn::apis::StoryList::Ask
+            ]
+        );
+        assert_eq!(settings.sources.reddit.subreddits, ["rust"]);
+        // left to default.toml
+        assert_eq!(settings.hn.retries, 2);
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let err = Settings::load(
+            dir(),
+            "prod",
+            env(&[
+                ("LINKNOVA_HN__BASE_URL", "ftp://hn.example"),
+                ("LINKNOVA_DATABASE__MAX_CONNECTIONS", "0"),
+                ("LINKNOVA_TELEMETRY__SAMPLE_RATIO", "2"),
+            ]),
+        )
+        .unwrap_err();
+
+        let SettingsError::Invalid(problems) = err else {
+            panic!("expected invalid settings, got {:?}", err);
+        };
+        assert_eq!(
+            problems,
+            [
+                "service.secrets is not set",
+                "database.url is not set",
+                "database.max_connections has to be at least 1",
+                "hn.base_url `ftp://hn.example` is not an http(s) url",
+                "telemetry.sample_ratio has to be between 0 and 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn secrets_are_not_printed() {
+        let settings = Settings::load(
+            dir(),
+            "prod",
+            env(&[
+                ("LINKNOVA_SERVICE__SECRETS", "hunter2"),
+                (
+                    "LINKNOVA_DATABASE__URL",
+                    "postgres://app:pa55word@db/linknova",
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let printed = format!("{:?}", settings);
+        assert!(!printed.contains("hunter2"), "{printed}");
+        assert!(!printed.contains("pa55word"), "{printed}");
+        assert!(
+            printed.contains("postgres://app:***@db/linknova"),
+            "{printed}"
+        );
     }
 }
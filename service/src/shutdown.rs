@@ -0,0 +1,106 @@
+//! Stopping on SIGTERM/SIGINT: the server stops accepting connections and drains
+//! the ones in flight, background workers finish the job at hand and return.
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// Requests the shutdown, once, for every `Shutdown` of the channel.
+pub struct Trigger(watch::Sender<Option<Instant>>);
+
+/// Cloned into whatever has to stop on shutdown.
+#[derive(Clone)]
+pub struct Shutdown(watch::Receiver<Option<Instant>>);
+
+pub fn channel() -> (Trigger, Shutdown) {
+    let (tx, rx) = watch::channel(None);
+    (Trigger(tx), Shutdown(rx))
+}
+
+impl Trigger {
+    pub fn fire(&self) {
+        self.0.send_if_modified(|requested_on| {
+            let first = requested_on.is_none();
+            requested_on.get_or_insert_with(Instant::now);
+            first
+        });
+    }
+}
+
+impl Shutdown {
+    pub fn is_requested(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+
+    /// Resolves once shutdown is requested, to when it was. Never resolves when
+    /// the trigger is dropped without firing.
+    pub async fn requested(mut self) -> Instant {
+        let requested_on = self.0.wait_for(Option::is_some).await.map(|r| *r);
+        match requested_on {
+            Ok(requested_on) => requested_on.expect("waited for it to be set"),
+            Err(_) => std::future::pending().await,
+        }
+    }
+
+    /// Sleeps between two jobs of a worker, `true` when woken by a shutdown
+    /// rather than by the time passing.
+    pub async fn sleep(&self, duration: std::time::Duration) -> bool {
+        tokio::select! {
+            _ = self.clone().requested() => true,
+            () = tokio::time::sleep(duration) => false,
+        }
+    }
+}
+
+/// Resolves on the first SIGINT (Ctrl-C) or SIGTERM.
+pub async fn signal() {
+    let interrupt = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("not able to listen for ctrl-c");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("not able to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = interrupt => tracing::info!(msg = "SIGINT received, shutting down"),
+        () = terminate => tracing::info!(msg = "SIGTERM received, shutting down"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sleep_is_cut_short() {
+        let (trigger, shutdown) = channel();
+        assert!(!shutdown.sleep(std::time::Duration::from_millis(1)).await);
+        assert!(!shutdown.is_requested());
+
+        let sleeping = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.sleep(std::time::Duration::from_secs(3600)).await }
+        });
+        tokio::task::yield_now().await;
+        trigger.fire();
+        assert!(sleeping.await.unwrap());
+        assert!(shutdown.is_requested());
+    }
+
+    #[tokio::test]
+    async fn requested_on_is_kept_from_the_first_fire() {
+        let (trigger, shutdown) = channel();
+        trigger.fire();
+        let first = shutdown.clone().requested().await;
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        trigger.fire();
+        assert_eq!(shutdown.requested().await, first);
+    }
+}
@@ -0,0 +1,123 @@
+use super::{Comment, Discussion, LinkSource, SourceError, SourceItem, SourcePage};
+use crate::hn::apis::{HNItem, StoryList};
+use crate::hn::comments::CommentNode;
+use crate::hn::HnClient;
+use futures::future::BoxFuture;
+
+const LISTS: [(&str, StoryList); 6] = [
+    ("top", StoryList::Top),
+    ("new", StoryList::New),
+    ("best", StoryList::Best),
+    ("ask", StoryList::Ask),
+    ("show", StoryList::Show),
+    ("job", StoryList::Job),
+];
+
+pub struct Hn {
+    client: HnClient,
+}
+
+impl Hn {
+    pub fn new(client: HnClient) -> Self {
+        Self { client }
+    }
+}
+
+fn parse_id(id: &str) -> Result<i64, SourceError> {
+    id.parse()
+        .map_err(|_| SourceError::NotFound(format!("hn item with id: `{}`", id)))
+}
+
+fn to_item(item: HNItem) -> SourceItem {
+    SourceItem {
+        provider: "hn",
+        id: item.id.to_string(),
+        title: item.title,
+        url: item.url,
+        text: item.text.as_deref().map(crate::hn::comments::sanitize),
+        by: item.by,
+        score: item.score.map(|s| s as i64),
+        comments: item.descendants.map(|d| d as i64),
+        posted_on: item
+            .time
+            .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0)),
+        discussion_url: crate::services::link::hn::types::discussion_url(item.id),
+        tags: item.r#type.into_iter().filter(|t| t != "story").collect(),
+    }
+}
+
+fn to_comment(node: CommentNode) -> Comment {
+    Comment {
+        id: node.id.to_string(),
+        by: node.by,
+        text: node.text,
+        score: None,
+        posted_on: node
+            .time
+            .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0)),
+        depth: node.depth,
+        replies: node.replies.into_iter().map(to_comment).collect(),
+    }
+}
+
+impl LinkSource for Hn {
+    fn provider(&self) -> &'static str {
+        "hn"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hacker News"
+    }
+
+    fn lists(&self) -> Vec<String> {
+        LISTS.iter().map(|(name, _)| name.to_string()).collect()
+    }
+
+    fn list<'a>(
+        &'a self,
+        list: &'a str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<SourcePage, SourceError>> {
+        Box::pin(async move {
+            let story_list = LISTS
+                .iter()
+                .find(|(name, _)| *name == list)
+                .map(|(_, l)| *l)
+                .ok_or_else(|| SourceError::UnknownList(list.to_string()))?;
+            let page = crate::hn::apis::stories(&self.client, story_list, offset, limit).await?;
+            Ok(SourcePage {
+                provider: self.provider(),
+                list: list.to_string(),
+                offset: page.offset,
+                limit: page.limit,
+                next_offset: page.next_offset,
+                items: page.items.into_iter().map(to_item).collect(),
+            })
+        })
+    }
+
+    fn item<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<SourceItem, SourceError>> {
+        Box::pin(async move {
+            let item = crate::hn::apis::get_item(&self.client, parse_id(id)?).await?;
+            Ok(to_item(item))
+        })
+    }
+
+    fn discussion<'a>(
+        &'a self,
+        id: &'a str,
+        max_depth: usize,
+        max_nodes: usize,
+    ) -> BoxFuture<'a, Result<Discussion, SourceError>> {
+        Box::pin(async move {
+            let tree = crate::hn::comments::tree(&self.client, parse_id(id)?, max_depth, max_nodes)
+                .await?;
+            Ok(Discussion {
+                item: to_item(tree.item),
+                comments: tree.comments.into_iter().map(to_comment).collect(),
+                truncated: tree.truncated,
+            })
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use super::SourceError;
+use std::time::Duration;
+
+/// Minimal JSON client for providers without a dedicated one.
+pub struct JsonHttp {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl JsonHttp {
+    pub fn new(base_url: &str, timeout: Duration) -> Result<Self, reqwest::Error> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .connect_timeout(timeout)
+            .user_agent(concat!("linknova/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    /// GET `{base_url}/{path}`, 404 becomes `NotFound(what)`.
+    pub async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        what: &str,
+    ) -> Result<T, SourceError> {
+        let response = self.http.get(self.url(path)).send().await?;
+        match response.status() {
+            s if s.is_success() => response
+                .json::<T>()
+                .await
+                .map_err(|e| SourceError::Parse(e.to_string())),
+            reqwest::StatusCode::NOT_FOUND => Err(SourceError::NotFound(what.to_string())),
+            s => Err(SourceError::UpstreamStatus(s.as_u16())),
+        }
+    }
+}
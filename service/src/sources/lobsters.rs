@@ -0,0 +1,227 @@
+use super::http::JsonHttp;
+use super::{Comment, Discussion, LinkSource, SourceError, SourceItem, SourcePage};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+
+/// Stories per page of every Lobsters list.
+const PAGE_SIZE: usize = 25;
+const LISTS: [&str; 3] = ["hottest", "newest", "active"];
+
+pub struct Lobsters {
+    http: JsonHttp,
+}
+
+impl Lobsters {
+    pub fn new(http: JsonHttp) -> Self {
+        Self { http }
+    }
+
+    fn page_path(list: &str, page: usize) -> String {
+        match (list, page) {
+            ("hottest", 1) => "hottest.json".to_string(),
+            ("hottest", page) => format!("page/{}.json", page),
+            (list, page) => format!("{}/page/{}.json", list, page),
+        }
+    }
+
+    fn to_item(&self, story: Story) -> SourceItem {
+        SourceItem {
+            provider: "lobsters",
+            discussion_url: story
+                .comments_url
+                .clone()
+                .unwrap_or_else(|| self.http.url(&format!("s/{}", story.short_id))),
+            id: story.short_id,
+            title: Some(story.title),
+            url: story.url.filter(|u| !u.is_empty()),
+            text: story
+                .description
+                .filter(|d| !d.is_empty())
+                .as_deref()
+                .map(crate::hn::comments::sanitize),
+            by: username(&story.submitter_user),
+            score: story.score,
+            comments: story.comment_count,
+            posted_on: parse_time(story.created_at.as_deref()),
+            tags: story.tags,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Story {
+    short_id: String,
+    title: String,
+    url: Option<String>,
+    description: Option<String>,
+    score: Option<i64>,
+    comment_count: Option<i64>,
+    created_at: Option<String>,
+    comments_url: Option<String>,
+    /// a username, or an object with one in older versions
+    #[serde(default)]
+    submitter_user: serde_json::Value,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    comments: Vec<StoryComment>,
+}
+
+#[derive(serde::Deserialize)]
+struct StoryComment {
+    short_id: String,
+    comment: Option<String>,
+    score: Option<i64>,
+    created_at: Option<String>,
+    #[serde(default)]
+    is_deleted: bool,
+    #[serde(default)]
+    is_moderated: bool,
+    parent_comment: Option<String>,
+    #[serde(default)]
+    commenting_user: serde_json::Value,
+}
+
+fn username(user: &serde_json::Value) -> Option<String> {
+    match user {
+        serde_json::Value::String(name) => Some(name.clone()),
+        serde_json::Value::Object(o) => o
+            .get("username")
+            .and_then(|u| u.as_str())
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+fn parse_time(t: Option<&str>) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(t?)
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Utc))
+}
+
+/// Lobsters sends comments flat, in thread order, each naming its parent.
+fn thread(comments: Vec<StoryComment>) -> Vec<Comment> {
+    let mut children: HashMap<Option<String>, Vec<StoryComment>> = HashMap::new();
+    for c in comments {
+        children
+            .entry(c.parent_comment.clone())
+            .or_default()
+            .push(c);
+    }
+
+    fn build(
+        parent: Option<String>,
+        depth: usize,
+        children: &mut HashMap<Option<String>, Vec<StoryComment>>,
+    ) -> Vec<Comment> {
+        let Some(list) = children.remove(&parent) else {
+            return Vec::new();
+        };
+        list.into_iter()
+            .map(|c| {
+                let gone = c.is_deleted || c.is_moderated;
+                Comment {
+                    replies: build(Some(c.short_id.clone()), depth + 1, children),
+                    id: c.short_id,
+                    by: (!gone).then(|| username(&c.commenting_user)).flatten(),
+                    text: (!gone)
+                        .then(|| c.comment.as_deref().map(crate::hn::comments::sanitize))
+                        .flatten(),
+                    score: c.score,
+                    posted_on: parse_time(c.created_at.as_deref()),
+                    depth,
+                }
+            })
+            .collect()
+    }
+
+    build(None, 1, &mut children)
+}
+
+impl LinkSource for Lobsters {
+    fn provider(&self) -> &'static str {
+        "lobsters"
+    }
+
+    fn name(&self) -> &'static str {
+        "Lobsters"
+    }
+
+    fn lists(&self) -> Vec<String> {
+        LISTS.iter().map(|l| l.to_string()).collect()
+    }
+
+    /// Lobsters pages hold 25 stories, the pages covering the window are fetched.
+    fn list<'a>(
+        &'a self,
+        list: &'a str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<SourcePage, SourceError>> {
+        Box::pin(async move {
+            if !LISTS.contains(&list) {
+                return Err(SourceError::UnknownList(list.to_string()));
+            }
+            let limit = super::page_limit(limit);
+            let first = offset / PAGE_SIZE + 1;
+            let last = (offset.saturating_add(limit) - 1) / PAGE_SIZE + 1;
+
+            let mut stories = Vec::new();
+            let mut exhausted = false;
+            for page in first..=last {
+                let path = Self::page_path(list, page);
+                let batch: Vec<Story> = self.http.get_json(&path, list).await?;
+                exhausted = batch.len() < PAGE_SIZE;
+                stories.extend(batch);
+                if exhausted {
+                    break;
+                }
+            }
+            let skip = offset - (first - 1) * PAGE_SIZE;
+            let more = stories.len().saturating_sub(skip) > limit || !exhausted;
+            let items: Vec<_> = stories
+                .into_iter()
+                .skip(skip)
+                .take(limit)
+                .map(|s| self.to_item(s))
+                .collect();
+            let next_offset =
+                (more && !items.is_empty()).then_some(offset.saturating_add(items.len()));
+            Ok(SourcePage {
+                provider: self.provider(),
+                list: list.to_string(),
+                offset,
+                limit,
+                next_offset,
+                items,
+            })
+        })
+    }
+
+    fn item<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<SourceItem, SourceError>> {
+        Box::pin(async move {
+            let what = format!("lobsters story with id: `{}`", id);
+            let story: Story = self.http.get_json(&format!("s/{}.json", id), &what).await?;
+            Ok(self.to_item(story))
+        })
+    }
+
+    fn discussion<'a>(
+        &'a self,
+        id: &'a str,
+        max_depth: usize,
+        max_nodes: usize,
+    ) -> BoxFuture<'a, Result<Discussion, SourceError>> {
+        Box::pin(async move {
+            let what = format!("lobsters story with id: `{}`", id);
+            let mut story: Story = self.http.get_json(&format!("s/{}.json", id), &what).await?;
+            let mut comments = thread(std::mem::take(&mut story.comments));
+            let truncated = super::prune(&mut comments, max_depth, max_nodes);
+            Ok(Discussion {
+                item: self.to_item(story),
+                comments,
+                truncated,
+            })
+        })
+    }
+}
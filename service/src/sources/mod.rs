@@ -0,0 +1,247 @@
+//! Link aggregators browsed through one API. Every provider maps its stories and
+//! discussions onto the same types and points at a configurable base URL, so
+//! tests can run against local stand-ins.
+
+pub mod hn;
+pub mod http;
+pub mod lobsters;
+pub mod reddit;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+pub const DEFAULT_PAGE_SIZE: usize = 30;
+pub const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SourceError {
+    #[error("UnknownProviderError: {0}")]
+    UnknownProvider(String),
+    #[error("UnknownListError: {0}")]
+    UnknownList(String),
+    #[error("NotFoundError: {0}")]
+    NotFound(String),
+    #[error("HttpError: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("UpstreamStatusError: {0}")]
+    UpstreamStatus(u16),
+    #[error("ParseError: {0}")]
+    Parse(String),
+}
+
+impl From<crate::hn::HNError> for SourceError {
+    fn from(e: crate::hn::HNError) -> Self {
+        match e {
+            crate::hn::HNError::HttpReqwestError(e) | crate::hn::HNError::Timeout(e) => {
+                SourceError::Http(e)
+            }
+            crate::hn::HNError::UpstreamStatus(status) => SourceError::UpstreamStatus(status),
+            crate::hn::HNError::RateLimited(_) => SourceError::UpstreamStatus(429),
+            crate::hn::HNError::InvalidResponse(e) => SourceError::Parse(e),
+            crate::hn::HNError::NotFound(what) | crate::hn::HNError::Deleted(what) => {
+                SourceError::NotFound(what)
+            }
+            crate::hn::HNError::InvalidInput(field) => SourceError::NotFound(field.to_string()),
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct SourceItem {
+    pub provider: &'static str,
+    /// provider's own id: a number on HN, a short id on Lobsters and Reddit
+    pub id: String,
+    pub title: Option<String>,
+    /// `None` for text posts
+    pub url: Option<String>,
+    /// sanitized html
+    pub text: Option<String>,
+    pub by: Option<String>,
+    pub score: Option<i64>,
+    pub comments: Option<i64>,
+    pub posted_on: Option<DateTime<Utc>>,
+    pub discussion_url: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SourcePage {
+    pub provider: &'static str,
+    pub list: String,
+    pub offset: usize,
+    pub limit: usize,
+    /// `None` on the last page
+    pub next_offset: Option<usize>,
+    pub items: Vec<SourceItem>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct Comment {
+    pub id: String,
+    /// `None` for deleted comments
+    pub by: Option<String>,
+    /// sanitized html, `None` for deleted comments
+    pub text: Option<String>,
+    pub score: Option<i64>,
+    pub posted_on: Option<DateTime<Utc>>,
+    pub depth: usize,
+    pub replies: Vec<Comment>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct Discussion {
+    pub item: SourceItem,
+    pub comments: Vec<Comment>,
+    /// `true` when the depth or node limit cut the tree short
+    pub truncated: bool,
+}
+
+/// What a saved item becomes.
+#[derive(Debug)]
+pub struct BookmarkDraft {
+    pub url: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub referrer: Option<String>,
+    /// own category the bookmark is filed under
+    pub category: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct ProviderInfo {
+    pub provider: &'static str,
+    pub name: &'static str,
+    pub lists: Vec<String>,
+}
+
+pub trait LinkSource: Send + Sync {
+    /// path segment in `/sources/{provider}/...`
+    fn provider(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    /// Lists `list` accepts, the first one is the default.
+    fn lists(&self) -> Vec<String>;
+
+    fn list<'a>(
+        &'a self,
+        list: &'a str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<SourcePage, SourceError>>;
+
+    fn item<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<SourceItem, SourceError>>;
+
+    fn discussion<'a>(
+        &'a self,
+        id: &'a str,
+        max_depth: usize,
+        max_nodes: usize,
+    ) -> BoxFuture<'a, Result<Discussion, SourceError>>;
+
+    /// The item's own link, or its discussion for text posts, in a category named
+    /// after the provider.
+    fn to_bookmark(&self, item: &SourceItem) -> BookmarkDraft {
+        BookmarkDraft {
+            url: item
+                .url
+                .clone()
+                .filter(|u| crate::utils::is_web_url(u))
+                .unwrap_or_else(|| item.discussion_url.clone()),
+            title: item.title.clone(),
+            content: item.text.clone(),
+            referrer: Some(item.discussion_url.clone()),
+            category: self.provider().to_string(),
+        }
+    }
+}
+
+/// Enabled providers, in the order they are listed.
+#[derive(Clone)]
+pub struct Registry {
+    sources: Arc<Vec<Arc<dyn LinkSource>>>,
+}
+
+impl Registry {
+    pub fn new(sources: Vec<Arc<dyn LinkSource>>) -> Self {
+        Self {
+            sources: Arc::new(sources),
+        }
+    }
+
+    /// HN on the shared client plus the providers enabled in `settings`.
+    pub fn from_settings(
+        settings: &crate::settings::SourcesSettings,
+        hn_client: crate::hn::HnClient,
+    ) -> Result<Self, reqwest::Error> {
+        let timeout = std::time::Duration::from_secs(settings.request_timeout_secs);
+        let mut sources: Vec<Arc<dyn LinkSource>> = vec![Arc::new(hn::Hn::new(hn_client))];
+        if settings.lobsters.enabled {
+            sources.push(Arc::new(lobsters::Lobsters::new(http::JsonHttp::new(
+                &settings.lobsters.base_url,
+                timeout,
+            )?)));
+        }
+        if settings.reddit.enabled {
+            sources.push(Arc::new(reddit::Reddit::new(
+                http::JsonHttp::new(&settings.reddit.base_url, timeout)?,
+                settings.reddit.subreddits.clone(),
+            )));
+        }
+        Ok(Self::new(sources))
+    }
+
+    pub fn get(&self, provider: &str) -> Result<&Arc<dyn LinkSource>, SourceError> {
+        self.sources
+            .iter()
+            .find(|s| s.provider() == provider)
+            .ok_or_else(|| SourceError::UnknownProvider(provider.to_string()))
+    }
+
+    pub fn providers(&self) -> Vec<ProviderInfo> {
+        self.sources
+            .iter()
+            .map(|s| ProviderInfo {
+                provider: s.provider(),
+                name: s.name(),
+                lists: s.lists(),
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn page_limit(limit: usize) -> usize {
+    limit.clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Cuts `comments` down to `max_depth` levels and `max_nodes` comments, keeping
+/// upper levels first. Returns whether anything was removed.
+pub(crate) fn prune(comments: &mut Vec<Comment>, max_depth: usize, max_nodes: usize) -> bool {
+    let mut budget = max_nodes;
+    let mut truncated = false;
+    // breadth first: count every level before descending into the next one
+    let mut level: Vec<&mut Vec<Comment>> = vec![comments];
+    let mut depth = 1;
+    while !level.is_empty() {
+        let mut next = Vec::new();
+        for siblings in level {
+            if depth > max_depth {
+                truncated |= !siblings.is_empty();
+                siblings.clear();
+                continue;
+            }
+            if siblings.len() > budget {
+                siblings.truncate(budget);
+                truncated = true;
+            }
+            budget -= siblings.len();
+            for c in siblings.iter_mut() {
+                next.push(&mut c.replies);
+            }
+        }
+        level = next;
+        depth += 1;
+    }
+    truncated
+}
@@ -0,0 +1,232 @@
+use super::http::JsonHttp;
+use super::{Comment, Discussion, LinkSource, SourceError, SourceItem, SourcePage};
+use futures::future::BoxFuture;
+
+/// Reddit returns at most this many posts per request, deeper offsets are not served.
+const MAX_WINDOW: usize = 100;
+
+/// Subreddits through Reddit's public JSON API, each configured subreddit is a
+/// list sorted by `hot`.
+pub struct Reddit {
+    http: JsonHttp,
+    subreddits: Vec<String>,
+}
+
+impl Reddit {
+    pub fn new(http: JsonHttp, subreddits: Vec<String>) -> Self {
+        Self { http, subreddits }
+    }
+
+    fn to_item(&self, post: Post) -> SourceItem {
+        let discussion_url = self.http.url(&post.permalink);
+        SourceItem {
+            provider: "reddit",
+            id: post.id,
+            title: Some(post.title),
+            url: post
+                .url
+                .filter(|u| !post.is_self && !u.is_empty() && *u != discussion_url),
+            text: post
+                .selftext_html
+                .as_deref()
+                .map(crate::hn::comments::sanitize),
+            by: post.author,
+            score: post.score,
+            comments: post.num_comments,
+            posted_on: post
+                .created_utc
+                .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0)),
+            discussion_url,
+            tags: post.subreddit.into_iter().collect(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Listing<T> {
+    data: ListingData<T>,
+}
+
+#[derive(serde::Deserialize)]
+struct ListingData<T> {
+    children: Vec<Thing<T>>,
+}
+
+#[derive(serde::Deserialize)]
+struct Thing<T> {
+    kind: String,
+    data: T,
+}
+
+#[derive(serde::Deserialize)]
+struct Post {
+    id: String,
+    title: String,
+    url: Option<String>,
+    permalink: String,
+    author: Option<String>,
+    score: Option<i64>,
+    num_comments: Option<i64>,
+    created_utc: Option<f64>,
+    selftext_html: Option<String>,
+    #[serde(default)]
+    is_self: bool,
+    subreddit: Option<String>,
+}
+
+/// `replies` is an empty string when there are none.
+#[derive(serde::Deserialize)]
+struct RedditComment {
+    id: Option<String>,
+    author: Option<String>,
+    body_html: Option<String>,
+    score: Option<i64>,
+    created_utc: Option<f64>,
+    #[serde(default)]
+    replies: serde_json::Value,
+}
+
+/// Only `t1` things are comments, `more` placeholders are left out.
+fn comments(value: serde_json::Value, depth: usize) -> Vec<Comment> {
+    let Ok(listing) = serde_json::from_value::<Listing<serde_json::Value>>(value) else {
+        return Vec::new();
+    };
+    listing
+        .data
+        .children
+        .into_iter()
+        .filter(|t| t.kind == "t1")
+        .filter_map(|t| serde_json::from_value::<RedditComment>(t.data).ok())
+        .filter_map(|c| {
+            let deleted = c.author.as_deref() == Some("[deleted]");
+            Some(Comment {
+                id: c.id?,
+                by: c.author.filter(|_| !deleted),
+                text: c
+                    .body_html
+                    .filter(|_| !deleted)
+                    .as_deref()
+                    .map(crate::hn::comments::sanitize),
+                score: c.score,
+                posted_on: c
+                    .created_utc
+                    .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0)),
+                depth,
+                replies: comments(c.replies, depth + 1),
+            })
+        })
+        .collect()
+}
+
+fn valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+impl LinkSource for Reddit {
+    fn provider(&self) -> &'static str {
+        "reddit"
+    }
+
+    fn name(&self) -> &'static str {
+        "Reddit"
+    }
+
+    fn lists(&self) -> Vec<String> {
+        self.subreddits.clone()
+    }
+
+    fn list<'a>(
+        &'a self,
+        list: &'a str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<SourcePage, SourceError>> {
+        Box::pin(async move {
+            if !self.subreddits.iter().any(|s| s == list) {
+                return Err(SourceError::UnknownList(list.to_string()));
+            }
+            let limit = super::page_limit(limit);
+            let window = offset.saturating_add(limit).min(MAX_WINDOW);
+            let path = format!("r/{}/hot.json?limit={}&raw_json=1", list, window);
+            let listing: Listing<Post> = self.http.get_json(&path, list).await?;
+            let fetched = listing.data.children.len();
+            let items: Vec<_> = listing
+                .data
+                .children
+                .into_iter()
+                .filter(|t| t.kind == "t3")
+                .skip(offset)
+                .take(limit)
+                .map(|t| self.to_item(t.data))
+                .collect();
+            let next = offset.saturating_add(items.len());
+            let next_offset =
+                (fetched == window && next < MAX_WINDOW && !items.is_empty()).then_some(next);
+            Ok(SourcePage {
+                provider: self.provider(),
+                list: list.to_string(),
+                offset,
+                limit,
+                next_offset,
+                items,
+            })
+        })
+    }
+
+    fn item<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<SourceItem, SourceError>> {
+        Box::pin(async move {
+            let (post, _) = self.thread(id, "limit=1&depth=1").await?;
+            Ok(self.to_item(post))
+        })
+    }
+
+    fn discussion<'a>(
+        &'a self,
+        id: &'a str,
+        max_depth: usize,
+        max_nodes: usize,
+    ) -> BoxFuture<'a, Result<Discussion, SourceError>> {
+        Box::pin(async move {
+            let depth = max_depth.clamp(1, crate::hn::comments::MAX_DEPTH);
+            let (post, replies) = self.thread(id, &format!("depth={}", depth)).await?;
+            let mut comments = comments(replies, 1);
+            let truncated = super::prune(&mut comments, max_depth, max_nodes);
+            Ok(Discussion {
+                item: self.to_item(post),
+                comments,
+                truncated,
+            })
+        })
+    }
+}
+
+impl Reddit {
+    /// The post and the raw comment listing of `/comments/{id}.json`.
+    async fn thread(
+        &self,
+        id: &str,
+        query: &str,
+    ) -> Result<(Post, serde_json::Value), SourceError> {
+        let what = format!("reddit post with id: `{}`", id);
+        if !valid_id(id) {
+            return Err(SourceError::NotFound(what));
+        }
+        let path = format!("comments/{}.json?raw_json=1&{}", id, query);
+        let mut parts: Vec<serde_json::Value> = self.http.get_json(&path, &what).await?;
+        if parts.len() < 2 {
+            return Err(SourceError::Parse(
+                "expected a post and its comments".to_string(),
+            ));
+        }
+        let replies = parts.pop().unwrap_or_default();
+        let post: Listing<Post> = serde_json::from_value(parts.pop().unwrap_or_default())
+            .map_err(|e| SourceError::Parse(e.to_string()))?;
+        let post = post
+            .data
+            .children
+            .into_iter()
+            .find(|t| t.kind == "t3")
+            .ok_or_else(|| SourceError::NotFound(what))?;
+        Ok((post.data, replies))
+    }
+}
@@ -0,0 +1,182 @@
+//! The tracing subscriber: logs as text or JSON lines, `linkdb` query timings
+//! for the metrics and, when `telemetry.otlp_endpoint` is set, spans exported
+//! over OTLP/HTTP. W3C trace context is read from incoming requests and passed
+//! on to HN.
+
+use crate::settings::{LogFormat, TelemetrySettings};
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("ExporterError: {0}")]
+    Exporter(#[from] opentelemetry_otlp::ExporterBuildError),
+}
+
+/// Flushes the spans not exported yet on `shutdown`.
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+/// `RUST_LOG` when set, `telemetry.log_filter` otherwise.
+fn filter(settings: &TelemetrySettings) -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&settings.log_filter))
+}
+
+/// Installs the global subscriber, `environment` is reported with the spans.
+pub fn init(settings: &TelemetrySettings, environment: &str) -> Result<Telemetry, TelemetryError> {
+    opentelemetry::global::set_text_map_propagator(
+        opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+    );
+
+    let logs = match settings.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .boxed(),
+    }
+    .with_filter(filter(settings));
+    // on its own filter, so query timings don't depend on the log level
+    let db_queries =
+        crate::metrics::DbQueryLayer.with_filter(tracing_subscriber::filter::filter_fn(|m| {
+            m.target().starts_with("linkdb")
+        }));
+
+    let provider = match settings.otlp_endpoint.as_deref() {
+        Some(endpoint) => Some(provider(settings, endpoint, environment)?),
+        None => None,
+    };
+    let traces = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("linknova"))
+            .with_filter(filter(settings))
+    });
+
+    tracing_subscriber::registry()
+        .with(logs)
+        .with(db_queries)
+        .with(traces)
+        .init();
+    Ok(Telemetry { provider })
+}
+
+fn provider(
+    settings: &TelemetrySettings,
+    endpoint: &str,
+    environment: &str,
+) -> Result<SdkTracerProvider, TelemetryError> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+    let resource = opentelemetry_sdk::Resource::builder()
+        .with_service_name(settings.service_name.clone())
+        .with_attributes([
+            opentelemetry::KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+            opentelemetry::KeyValue::new("deployment.environment", environment.to_string()),
+        ])
+        .build();
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        // a trace started upstream keeps the caller's decision
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            settings.sample_ratio,
+        ))))
+        .with_resource(resource)
+        .build())
+}
+
+impl Telemetry {
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("not able to flush the spans: {}", e);
+            }
+        }
+    }
+}
+
+/// Makes `span` a child of the trace an incoming `traceparent` names, if any.
+pub fn set_parent(span: &tracing::Span, headers: &axum::http::HeaderMap) {
+    struct Headers<'a>(&'a axum::http::HeaderMap);
+
+    impl Extractor for Headers<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|v| v.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|k| k.as_str()).collect()
+        }
+    }
+
+    let parent = opentelemetry::global::get_text_map_propagator(|p| p.extract(&Headers(headers)));
+    span.set_parent(parent);
+}
+
+/// `traceparent` of the current span, for an outbound request. Empty while
+/// spans aren't exported, as there is no trace to continue then.
+pub fn outbound_headers() -> reqwest::header::HeaderMap {
+    struct Headers(reqwest::header::HeaderMap);
+
+    impl Injector for Headers {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                reqwest::header::HeaderName::from_bytes(key.as_bytes()),
+                reqwest::header::HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    let context = tracing::Span::current().context();
+    let mut headers = Headers(reqwest::header::HeaderMap::new());
+    opentelemetry::global::get_text_map_propagator(|p| p.inject_context(&context, &mut headers));
+    headers.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceContextExt;
+
+    #[test]
+    fn trace_context_is_passed_on() {
+        opentelemetry::global::set_text_map_propagator(
+            opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+        );
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+            let mut incoming = axum::http::HeaderMap::new();
+            incoming.insert(
+                "traceparent",
+                format!("00-{trace_id}-00f067aa0ba902b7-01")
+                    .parse()
+                    .unwrap(),
+            );
+            let span = tracing::info_span!("request");
+            set_parent(&span, &incoming);
+
+            let outbound = span.in_scope(outbound_headers);
+            let traceparent = outbound["traceparent"].to_str().unwrap();
+            assert!(traceparent.starts_with(&format!("00-{trace_id}-")));
+            assert!(!traceparent.contains("00f067aa0ba902b7"));
+            assert_eq!(
+                span.context().span().span_context().trace_id().to_string(),
+                trace_id
+            );
+        });
+        assert!(outbound_headers().is_empty());
+    }
+}
@@ -14,7 +14,7 @@ pub async fn get<T: serde::de::DeserializeOwned>(
 ) -> Result<T, ReqwestError> {
     let client = reqwest::Client::builder().build()?;
     let mut headers_map = reqwest::header::HeaderMap::<reqwest::header::HeaderValue>::new();
-    for (k, v) in headers.into_iter() {
+    for (k, v) in headers.iter() {
         headers_map.insert(
             reqwest::header::HeaderName::from_bytes(k.as_bytes())?,
             reqwest::header::HeaderValue::from_str(v.as_str())?,
@@ -1 +1,25 @@
 pub mod http;
+
+/// `http` or `https` urls, the only ones linked to or saved as bookmarks, so a
+/// `javascript:` or `data:` url never ends up in an `href`.
+pub fn is_web_url(url: &str) -> bool {
+    let url = url.trim_start();
+    ["http://", "https://"].iter().any(|scheme| {
+        url.get(..scheme.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(scheme))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn only_http_urls_are_web_urls() {
+        assert!(super::is_web_url("https://example.com/a"));
+        assert!(super::is_web_url(" HTTP://example.com"));
+        assert!(!super::is_web_url("javascript:alert(document.cookie)"));
+        assert!(!super::is_web_url("JavaScript:alert(1)"));
+        assert!(!super::is_web_url("data:text/html,<script>"));
+        assert!(!super::is_web_url("//example.com"));
+        assert!(!super::is_web_url("https"));
+    }
+}
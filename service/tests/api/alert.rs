@@ -0,0 +1,139 @@
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use serde_json::json;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn create_get_update_and_delete() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    let alert = alice
+        .post(
+            "/alerts",
+            json!({ "name": "rust", "title_contains": " Rust ", "min_score": 100 }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(alert["name"], "rust");
+    assert_eq!(alert["title_contains"], "Rust");
+    assert_eq!(alert["min_score"], 100);
+    assert_eq!(alert["active"], true);
+    assert!(alert["last_matched_on"].is_null());
+
+    let id = alert["id"].as_i64().unwrap();
+    let alert = alice
+        .put(
+            &format!("/alerts/{id}"),
+            json!({ "name": "rust", "domain": "https://www.GitHub.com/rust-lang", "category": "gh", "active": false }),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(alert["domain"], "github.com");
+    assert_eq!(alert["category"], "gh");
+    assert_eq!(alert["active"], false);
+    assert!(alert["title_contains"].is_null());
+
+    assert_eq!(
+        alice
+            .get(&format!("/alerts/{id}"))
+            .await
+            .expect(StatusCode::OK)["domain"],
+        "github.com"
+    );
+    assert_eq!(
+        alice.get("/alerts").await.expect(StatusCode::OK)[0]["id"],
+        id
+    );
+    let bob = app.login("bob").await;
+    assert_eq!(bob.get("/alerts").await.expect(StatusCode::OK), json!([]));
+    bob.get(&format!("/alerts/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+
+    alice
+        .delete(&format!("/alerts/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    alice
+        .get(&format!("/alerts/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn rules_are_checked() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    alice
+        .post("/alerts", json!({ "name": "empty" }))
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    alice
+        .post("/alerts", json!({ "name": " ", "min_score": 1 }))
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    alice
+        .post("/alerts", json!({ "name": "bad", "domain": "https://" }))
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+
+    alice
+        .post("/alerts", json!({ "name": "popular", "min_score": 500 }))
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .post("/alerts", json!({ "name": "popular", "min_score": 300 }))
+        .await
+        .expect_err(StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn matching_stories_are_notified_and_saved() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post(
+            "/alerts",
+            json!({ "name": "gh", "domain": "github.com", "min_score": 100, "category": "gh" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .post(
+            "/alerts",
+            json!({ "name": "off", "title_contains": "rust", "active": false }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+
+    app.snapshot().await;
+    let page = alice.get("/notifications").await.expect(StatusCode::OK);
+    // the gist, not the lookalike domain nor the low scoring Show HN
+    assert_eq!(page["unread"], 1);
+    let notification = &page["items"][0];
+    assert_eq!(notification["kind"], "hn_alert");
+    assert_eq!(notification["hn_id"], 41823199);
+    let bm = alice
+        .get(&format!("/bm/{}", notification["bookmark_id"]))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(bm["url"], "https://gist.github.com/gist-fan/0a1b2c3d");
+
+    let alerts = alice.get("/alerts").await.expect(StatusCode::OK);
+    let gh = alerts
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["name"] == "gh")
+        .unwrap();
+    assert!(gh["last_matched_on"].is_string());
+
+    // once per story
+    app.snapshot().await;
+    let page = alice.get("/notifications").await.expect(StatusCode::OK);
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+}
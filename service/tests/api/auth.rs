@@ -0,0 +1,36 @@
+use crate::harness::{TestApp, API};
+use axum::http::{header, StatusCode};
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn api_needs_the_login_cookie() {
+    let app = TestApp::spawn().await;
+    let err = app
+        .get(&format!("{API}/bm"))
+        .await
+        .expect_err(StatusCode::UNAUTHORIZED);
+    assert_eq!(err["code"], "unauthorized");
+    assert_eq!(err["detail"], "Expected user-id in the header");
+
+    let alice = app.login("alice").await;
+    assert_eq!(
+        alice.get("/bm").await.expect(StatusCode::OK),
+        serde_json::json!([])
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn login_form_sets_the_same_cookie() {
+    let app = TestApp::spawn().await;
+    let req = axum::http::Request::post("/-/ln/api/login-form")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(axum::body::Body::from("username=alice&password=secret"))
+        .unwrap();
+    let res = app.send(req).await;
+    assert_eq!(res.status, StatusCode::SEE_OTHER);
+    assert_eq!(res.headers[header::LOCATION], "/-/ln");
+    let cookie = res.headers[header::SET_COOKIE].to_str().unwrap();
+    assert!(cookie.starts_with("X-USER-ID=alice;"), "{cookie}");
+    assert!(cookie.contains("HttpOnly"), "{cookie}");
+}
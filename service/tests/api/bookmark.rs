@@ -0,0 +1,157 @@
+use crate::harness::{Session, TestApp};
+use axum::http::{Method, StatusCode};
+use serde_json::{json, Value};
+
+async fn create(user: &Session<'_>, url: &str, categories: &[&str]) {
+    user.post(
+        "/bm",
+        json!({ "url": url, "title": url, "categories": categories }),
+    )
+    .await
+    .expect(StatusCode::CREATED);
+}
+
+fn urls(bookmarks: &Value) -> Vec<&str> {
+    let mut urls: Vec<_> = bookmarks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["url"].as_str().unwrap())
+        .collect();
+    urls.sort_unstable();
+    urls
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn create_get_update_and_delete() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    create(&alice, "https://a.example", &["rust", "db"]).await;
+
+    let all = alice.get("/bm").await.expect(StatusCode::OK);
+    let id = all[0]["id"].as_i64().unwrap();
+    let bm = alice.get(&format!("/bm/{id}")).await.expect(StatusCode::OK);
+    assert_eq!(bm["url"], "https://a.example");
+    assert_eq!(bm["status"], "UN");
+    assert_eq!(bm["categories"], json!(["db", "rust"]));
+    // categories are created on the fly
+    alice.get("/cat/db").await.expect(StatusCode::OK);
+
+    let bm = alice
+        .put(
+            &format!("/bm/{id}"),
+            json!({ "title": "A", "status": "RD" }),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(bm["title"], "A");
+    assert_eq!(bm["status"], "RD");
+    assert_eq!(bm["url"], "https://a.example");
+
+    let bob = app.login("bob").await;
+    bob.delete(&format!("/bm/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .delete(&format!("/bm/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    alice
+        .get(&format!("/bm/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .delete(&format!("/bm/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn filter_by_category_topic_and_status() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    create(&alice, "https://a.example", &["rust"]).await;
+    create(&alice, "https://b.example", &["db"]).await;
+    create(&alice, "https://c.example", &["go"]).await;
+    alice
+        .post("/topic", json!({ "name": "systems" }))
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .put(
+            "/topic/systems/add-cats",
+            json!({ "categories": ["rust", "db"] }),
+        )
+        .await
+        .expect(StatusCode::OK);
+
+    let res = alice.get("/bm?category=rust&category=go").await;
+    assert_eq!(
+        urls(&res.expect(StatusCode::OK)),
+        ["https://a.example", "https://c.example"]
+    );
+    let res = alice.get("/bm?topic=systems").await;
+    assert_eq!(
+        urls(&res.expect(StatusCode::OK)),
+        ["https://a.example", "https://b.example"]
+    );
+
+    let b = alice.get("/bm?category=db").await.expect(StatusCode::OK)[0]["id"].clone();
+    alice
+        .put(&format!("/bm/{b}"), json!({ "status": "RD" }))
+        .await
+        .expect(StatusCode::OK);
+    let res = alice.get("/bm?status=RD").await;
+    assert_eq!(urls(&res.expect(StatusCode::OK)), ["https://b.example"]);
+
+    alice
+        .get("/bm?topic=nope")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    // someone else's bookmarks are neither listed nor readable
+    let bob = app.login("bob").await;
+    assert_eq!(bob.get("/bm").await.expect(StatusCode::OK), json!([]));
+    bob.get(&format!("/bm/{b}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn add_and_remove_categories() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    create(&alice, "https://a.example", &["rust"]).await;
+    let id = alice.get("/bm").await.expect(StatusCode::OK)[0]["id"].clone();
+
+    alice
+        .put(
+            &format!("/bm/add-cats/{id}"),
+            json!({ "categories": ["db", "web"] }),
+        )
+        .await
+        .expect(StatusCode::OK);
+    let bm = alice.get(&format!("/bm/{id}")).await.expect(StatusCode::OK);
+    assert_eq!(bm["categories"], json!(["db", "rust", "web"]));
+
+    alice
+        .send(
+            Method::DELETE,
+            &format!("/bm/remove-cats/{id}"),
+            Some(json!({ "categories": ["rust", "web"] })),
+        )
+        .await
+        .expect(StatusCode::OK);
+    let bm = alice.get(&format!("/bm/{id}")).await.expect(StatusCode::OK);
+    assert_eq!(bm["categories"], json!(["db"]));
+
+    let bob = app.login("bob").await;
+    bob.put(
+        &format!("/bm/add-cats/{id}"),
+        json!({ "categories": ["mine"] }),
+    )
+    .await
+    .expect_err(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,239 @@
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use serde_json::json;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn create_get_update_and_delete() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    alice
+        .post(
+            "/cat",
+            json!({ "name": "rust", "display_name": "Rust", "priority": 2 }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let cat = alice.get("/cat/rust").await.expect(StatusCode::OK);
+    assert_eq!(cat["name"], "rust");
+    assert_eq!(cat["display_name"], "Rust");
+    assert_eq!(cat["priority"], 2);
+    assert_eq!(cat["public"], false);
+    assert_eq!(cat["owner"], "alice");
+    assert_eq!(cat["role"], "owner");
+    assert_eq!(cat["topics"], json!([]));
+
+    alice
+        .put(
+            "/cat/rust",
+            json!({ "about": "The language", "public": true }),
+        )
+        .await
+        .expect(StatusCode::OK);
+    let cat = alice.get("/cat/rust").await.expect(StatusCode::OK);
+    assert_eq!(cat["about"], "The language");
+    assert_eq!(cat["public"], true);
+    // left as it was
+    assert_eq!(cat["display_name"], "Rust");
+
+    alice.delete("/cat/rust").await.expect(StatusCode::OK);
+    alice
+        .get("/cat/rust")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn categories_are_per_user() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    let bob = app.login("bob").await;
+
+    alice
+        .post("/cat", json!({ "name": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+    bob.post("/cat", json!({ "name": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+    bob.post("/cat", json!({ "name": "go" }))
+        .await
+        .expect(StatusCode::CREATED);
+
+    let names = |cats: serde_json::Value| -> Vec<String> {
+        cats.as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(
+        names(alice.get("/cat").await.expect(StatusCode::OK)),
+        ["rust"]
+    );
+    assert_eq!(
+        names(bob.get("/cat").await.expect(StatusCode::OK)),
+        ["go", "rust"]
+    );
+    alice.get("/cat/go").await.expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn add_and_remove_topics() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    for name in ["rust", "go"] {
+        alice
+            .post("/cat", json!({ "name": name }))
+            .await
+            .expect(StatusCode::CREATED);
+    }
+    for name in ["lang", "systems"] {
+        alice
+            .post("/topic", json!({ "name": name }))
+            .await
+            .expect(StatusCode::CREATED);
+    }
+
+    alice
+        .put(
+            "/cat/rust/add-topics",
+            json!({ "topics": ["lang", "systems"] }),
+        )
+        .await
+        .expect(StatusCode::OK);
+    alice
+        .put("/cat/go/add-topics", json!({ "topics": ["lang"] }))
+        .await
+        .expect(StatusCode::OK);
+    let cat = alice.get("/cat/rust").await.expect(StatusCode::OK);
+    assert_eq!(cat["topics"], json!(["lang", "systems"]));
+
+    let in_systems = alice.get("/cat?topic=systems").await.expect(StatusCode::OK);
+    assert_eq!(in_systems.as_array().unwrap().len(), 1);
+    assert_eq!(in_systems[0]["name"], "rust");
+    let in_either = alice
+        .get("/cat?topic=systems&topic=lang")
+        .await
+        .expect(StatusCode::OK);
+    let mut names: Vec<_> = in_either
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names, ["go", "rust"]);
+
+    alice
+        .send(
+            axum::http::Method::DELETE,
+            "/cat/rust/remove-topics",
+            Some(json!({ "topics": ["systems"] })),
+        )
+        .await
+        .expect(StatusCode::OK);
+    let cat = alice.get("/cat/rust").await.expect(StatusCode::OK);
+    assert_eq!(cat["topics"], json!(["lang"]));
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn duplicate_names_conflict() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    alice
+        .post("/cat", json!({ "name": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+    let err = alice
+        .post("/cat", json!({ "name": "rust" }))
+        .await
+        .expect_err(StatusCode::CONFLICT);
+    assert_eq!(err["code"], "category_exists");
+    assert_eq!(err["type"], "urn:linknova:problem:category_exists");
+    assert!(!err["detail"]
+        .as_str()
+        .unwrap()
+        .contains("linknova_category"));
+
+    let err = alice
+        .get("/cat/nope")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    assert_eq!(err["code"], "not_found");
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn conflicts_name_what_collided() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post("/cat", json!({ "name": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .post("/topic", json!({ "name": "lang" }))
+        .await
+        .expect(StatusCode::CREATED);
+
+    alice
+        .put("/cat/rust/add-topics", json!({ "topics": ["lang"] }))
+        .await
+        .expect(StatusCode::OK);
+    let err = alice
+        .put("/cat/rust/add-topics", json!({ "topics": ["lang"] }))
+        .await
+        .expect_err(StatusCode::CONFLICT);
+    assert_eq!(err["code"], "topic_category_exists");
+
+    alice
+        .post(
+            "/bm",
+            json!({ "url": "https://a.example", "categories": ["rust"] }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let err = alice
+        .delete("/cat/rust")
+        .await
+        .expect_err(StatusCode::CONFLICT);
+    assert_eq!(err["code"], "category_in_use");
+    alice.get("/cat/rust").await.expect(StatusCode::OK);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn unreadable_requests_name_the_field() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    let err = alice
+        .post("/cat", json!({ "name": 5 }))
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    assert_eq!(err["code"], "invalid_input");
+    assert_eq!(err["errors"][0]["field"], "name");
+    let err = alice
+        .post("/cat", json!({ "display_name": "Rust" }))
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    assert_eq!(err["errors"][0]["field"], "name");
+
+    let err = alice
+        .get("/bm/abc")
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    assert_eq!(err["errors"][0]["field"], "id");
+    let err = alice
+        .get("/notifications?limit=x")
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    assert_eq!(err["errors"][0]["field"], "limit");
+}
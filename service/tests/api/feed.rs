@@ -0,0 +1,192 @@
+use crate::harness::TestApp;
+use axum::http::{header, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn subscribe_polls_right_away() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    let url = app.upstream.url("/feeds/rust.xml");
+
+    let feed = alice
+        .post("/feed", json!({ "url": url, "category": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(feed["url"], url);
+    assert_eq!(feed["title"], "Rust Blog");
+    assert_eq!(feed["site_url"], "https://blog.rust-lang.org/");
+    assert_eq!(feed["category"], "rust");
+    assert_eq!(feed["last_error"], json!(null));
+    assert!(feed["last_polled_on"].is_string());
+
+    let bookmarks = alice.get("/bm?category=rust").await.expect(StatusCode::OK);
+    let mut titles: Vec<_> = bookmarks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["title"].as_str().unwrap())
+        .collect();
+    titles.sort_unstable();
+    assert_eq!(titles, ["Announcing Rust 1.81.0", "Announcing Rust 1.82.0"]);
+    assert_eq!(bookmarks[0]["referrer"], url);
+
+    let id = feed["id"].as_i64().unwrap();
+    let polled = alice
+        .post(&format!("/feed/{id}/refresh"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(polled["new_bookmarks"], 0);
+
+    assert_eq!(
+        alice
+            .get(&format!("/feed/{id}"))
+            .await
+            .expect(StatusCode::OK)["id"],
+        id
+    );
+    assert_eq!(alice.get("/feed").await.expect(StatusCode::OK)[0]["id"], id);
+    let bob = app.login("bob").await;
+    bob.get(&format!("/feed/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+
+    alice
+        .delete(&format!("/feed/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    alice
+        .get(&format!("/feed/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    // the bookmarks it saved stay
+    let bookmarks = alice.get("/bm?category=rust").await.expect(StatusCode::OK);
+    assert_eq!(bookmarks.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn failed_poll_is_recorded_on_the_feed() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    let url = app.upstream.url("/feeds/missing.xml");
+
+    let feed = alice
+        .post("/feed", json!({ "url": url, "category": "news" }))
+        .await
+        .expect(StatusCode::CREATED);
+    assert!(
+        feed["last_error"].as_str().unwrap().contains("404"),
+        "{feed}"
+    );
+
+    let id = feed["id"].as_i64().unwrap();
+    alice
+        .post(&format!("/feed/{id}/refresh"), json!({}))
+        .await
+        .expect_err(StatusCode::BAD_GATEWAY);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn only_web_feeds_of_a_bounded_size_are_fetched() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    for url in ["file:///etc/passwd", "gopher://a.example/feed", "feed.xml"] {
+        let err = alice
+            .post("/feed", json!({ "url": url, "category": "news" }))
+            .await
+            .expect_err(StatusCode::BAD_REQUEST);
+        assert_eq!(err["errors"][0]["field"], "url", "{url}");
+    }
+    let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <head><title>feeds</title></head>
+  <body><outline type="rss" text="local" xmlUrl="file:///etc/passwd"/></body>
+</opml>"#;
+    let err = alice
+        .post_text("/feed/opml", opml)
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    assert_eq!(err["errors"][0]["field"], "url");
+    assert_eq!(alice.get("/feed").await.expect(StatusCode::OK), json!([]));
+
+    let feed = alice
+        .post(
+            "/feed",
+            json!({ "url": app.upstream.url("/endless"), "category": "news" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert!(
+        feed["last_error"].as_str().unwrap().contains("larger than"),
+        "{feed}"
+    );
+    let id = feed["id"].as_i64().unwrap();
+    let err = alice
+        .post(&format!("/feed/{id}/refresh"), json!({}))
+        .await
+        .expect_err(StatusCode::BAD_GATEWAY);
+    assert_eq!(err["code"], "feed_too_large");
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn opml_import_and_export() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    let rust = app.upstream.url("/feeds/rust.xml");
+    let other = app.upstream.url("/feeds/other.xml");
+    let opml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <head><title>subscriptions</title></head>
+  <body>
+    <outline text="lang">
+      <outline text="Rust Blog" type="rss" xmlUrl="{rust}"/>
+    </outline>
+    <outline text="Other" type="rss" xmlUrl="{other}"/>
+  </body>
+</opml>"#
+    );
+
+    let imported = alice
+        .post_text("/feed/opml?category=misc", &opml)
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(imported["feeds"], 2);
+
+    let feeds = alice.get("/feed").await.expect(StatusCode::OK);
+    let mut subscribed: Vec<_> = feeds
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| (f["category"].as_str().unwrap(), f["url"].as_str().unwrap()))
+        .collect();
+    subscribed.sort_unstable();
+    assert_eq!(
+        subscribed,
+        [("lang", rust.as_str()), ("misc", other.as_str())]
+    );
+    // left to the poller
+    assert!(feeds[0]["last_polled_on"].is_null());
+
+    let res = alice.get("/feed/opml").await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert!(res.headers[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/x-opml"));
+    let exported = res.text();
+    assert!(
+        exported.contains(&format!("xmlUrl=\"{rust}\"")),
+        "{exported}"
+    );
+    assert!(exported.contains("text=\"misc\""), "{exported}");
+
+    alice
+        .post_text("/feed/opml", "not opml")
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,311 @@
+//! The whole router over a Postgres schema of its own, driven in process with
+//! `tower::ServiceExt::oneshot`.
+
+use crate::upstream::Upstream;
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderMap, Method, Request, StatusCode};
+use serde_json::Value;
+use sqlx::Connection;
+use std::str::FromStr;
+use tower::ServiceExt;
+
+/// Root of the routes `Session` calls.
+pub const API: &str = "/-/ln/v1/api";
+
+pub struct TestApp {
+    router: axum::Router,
+    pub ctx: service::ctx::Ctx,
+    pub upstream: Upstream,
+    // last, after everything that uses it
+    _schema: Schema,
+}
+
+/// Dropped along with the app, even when the test panics.
+struct Schema {
+    url: String,
+    name: String,
+}
+
+impl Drop for Schema {
+    fn drop(&mut self) {
+        let (url, name) = (self.url.clone(), self.name.clone());
+        // the test's own runtime can't be blocked on from within it
+        let dropped = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            runtime.block_on(async {
+                let mut conn = sqlx::PgConnection::connect(&url).await?;
+                sqlx::query(&format!("DROP SCHEMA {name} CASCADE"))
+                    .execute(&mut conn)
+                    .await?;
+                conn.close().await
+            })?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        })
+        .join();
+        if let Ok(Err(e)) = dropped {
+            eprintln!("not able to drop schema {}: {}", self.name, e);
+        }
+    }
+}
+
+impl TestApp {
+    /// A migrated schema of its own on the Postgres of `DATABASE_URL`.
+    pub async fn spawn() -> TestApp {
+        let url = std::env::var("DATABASE_URL")
+            .expect("DATABASE_URL to point at a Postgres the tests may create schemas in");
+
+        let name = format!("linknova_test_{:016x}", rand::random::<u64>());
+        let mut conn = sqlx::PgConnection::connect(&url)
+            .await
+            .expect("DATABASE_URL to be a reachable Postgres");
+        sqlx::query(&format!("CREATE SCHEMA {name}"))
+            .execute(&mut conn)
+            .await
+            .expect("a schema of the test");
+        conn.close().await.ok();
+        let schema = Schema {
+            url: url.clone(),
+            name: name.clone(),
+        };
+
+        let options = sqlx::postgres::PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", name.as_str())]);
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .expect("a pool on the test schema");
+        let db = linkdb::Db::Postgres(pool);
+        linkdb::migrate::up(&db)
+            .await
+            .expect("migrations to apply on an empty schema");
+
+        let upstream = Upstream::start().await;
+        let ctx = ctx(&db, &upstream);
+        let router = service::routes::routes(ctx.clone()).await;
+        TestApp {
+            router,
+            ctx,
+            upstream,
+            _schema: schema,
+        }
+    }
+
+    pub async fn send(&self, mut req: Request<Body>) -> Res {
+        // what `into_make_service_with_connect_info` adds in `main`
+        req.extensions_mut()
+            .insert(axum::extract::ConnectInfo(std::net::SocketAddr::from((
+                [127, 0, 0, 1],
+                40000,
+            ))));
+        let res = self
+            .router
+            .clone()
+            .oneshot(req)
+            .await
+            .expect("the router to be infallible");
+        let status = res.status();
+        let headers = res.headers().clone();
+        let body = http_body_util::BodyExt::collect(res.into_body())
+            .await
+            .expect("a readable body")
+            .to_bytes();
+        Res {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    /// Anonymous GET of any route, `path` is the whole path.
+    pub async fn get(&self, path: &str) -> Res {
+        self.send(request(Method::GET, path, None, None)).await
+    }
+
+    /// One round of the score snapshots the ingest job takes every interval,
+    /// which also runs the alert rules.
+    pub async fn snapshot(&self) -> usize {
+        let settings = service::settings::HnIngestSettings::default();
+        service::hn::ingest::snapshot_lists(&self.ctx.db, &self.ctx.hn_client, &settings)
+            .await
+            .expect("a snapshot of the mock lists")
+    }
+
+    /// One round of the new item ingestion, over at most the last
+    /// `max_new_items` ids.
+    pub async fn ingest(&self, max_new_items: i64) -> usize {
+        let (_trigger, shutdown) = service::shutdown::channel();
+        let heartbeat = self
+            .ctx
+            .heartbeats
+            .register("hn_ingest", std::time::Duration::from_secs(60));
+        service::hn::ingest::ingest_new_items(
+            &self.ctx.db,
+            &self.ctx.hn_client,
+            max_new_items,
+            &shutdown,
+            &heartbeat,
+        )
+        .await
+        .expect("the new items of the mock")
+    }
+
+    /// Logs in the way the UI does and keeps the cookie it is given.
+    pub async fn login(&self, username: &str) -> Session<'_> {
+        let body = serde_json::json!({ "username": username, "password": "secret" });
+        let res = self
+            .send(request(Method::POST, "/-/ln/api/login", None, Some(body)))
+            .await;
+        assert_eq!(res.status, StatusCode::SEE_OTHER, "{}", res.text());
+        let cookie = res
+            .headers
+            .get(header::SET_COOKIE)
+            .expect("login to set a cookie")
+            .to_str()
+            .unwrap();
+        let cookie = cookie.split(';').next().unwrap().to_string();
+        Session { app: self, cookie }
+    }
+}
+
+fn ctx(db: &linkdb::Db, upstream: &Upstream) -> service::ctx::Ctx {
+    use service::settings::{HnSettings, LobstersSettings, RedditSettings, SourcesSettings};
+
+    // no retries and no cache, so a test sees what the mock has at the time
+    let hn = HnSettings {
+        base_url: upstream.url("/v0"),
+        retries: 0,
+        item_ttl_secs: 0,
+        list_ttl_secs: 0,
+        ..Default::default()
+    };
+    let hn_client = service::hn::HnClient::new(&hn).expect("hn client");
+    let sources = SourcesSettings {
+        lobsters: LobstersSettings {
+            enabled: false,
+            ..Default::default()
+        },
+        reddit: RedditSettings {
+            enabled: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    service::ctx::Ctx {
+        db: db.clone(),
+        repos: linkdb::repo::Repos::database(db.clone()),
+        secret: "secret".to_string(),
+        cookie_domain: "127.0.0.1".to_string(),
+        trusted_proxies: Vec::new(),
+        static_dir: std::env::temp_dir(),
+        feed_client: service::services::link::feed::fetch::client(std::time::Duration::from_secs(
+            5,
+        ))
+        .expect("feed client"),
+        sources: service::sources::Registry::from_settings(&sources, hn_client.clone())
+            .expect("sources"),
+        hn_client,
+        heartbeats: Default::default(),
+    }
+}
+
+fn request(method: Method, path: &str, cookie: Option<&str>, body: Option<Value>) -> Request<Body> {
+    let mut req = Request::builder().method(method).uri(path);
+    if let Some(cookie) = cookie {
+        req = req.header(header::COOKIE, cookie);
+    }
+    match body {
+        Some(body) => req
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => req.body(Body::empty()),
+    }
+    .unwrap()
+}
+
+/// A logged in user, calling the routes under `API`.
+pub struct Session<'a> {
+    app: &'a TestApp,
+    cookie: String,
+}
+
+impl Session<'_> {
+    pub async fn send(&self, method: Method, path: &str, body: Option<Value>) -> Res {
+        let path = format!("{API}{path}");
+        let req = request(method, &path, Some(&self.cookie), body);
+        self.app.send(req).await
+    }
+
+    pub async fn get(&self, path: &str) -> Res {
+        self.send(Method::GET, path, None).await
+    }
+
+    pub async fn post(&self, path: &str, body: Value) -> Res {
+        self.send(Method::POST, path, Some(body)).await
+    }
+
+    pub async fn put(&self, path: &str, body: Value) -> Res {
+        self.send(Method::PUT, path, Some(body)).await
+    }
+
+    pub async fn delete(&self, path: &str) -> Res {
+        self.send(Method::DELETE, path, None).await
+    }
+
+    /// Raw body, for the routes which don't take JSON.
+    pub async fn post_text(&self, path: &str, body: &str) -> Res {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("{API}{path}"))
+            .header(header::COOKIE, &self.cookie)
+            .header(header::CONTENT_TYPE, "text/xml")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        self.app.send(req).await
+    }
+}
+
+pub struct Res {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl Res {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|e| panic!("not json ({}): {}", e, self.text()))
+    }
+
+    /// `data` of a response of `status`, failing with the body otherwise.
+    #[track_caller]
+    pub fn expect(&self, status: StatusCode) -> Value {
+        assert_eq!(self.status, status, "{}", self.text());
+        let mut body = self.json();
+        body["data"].take()
+    }
+
+    /// Problem details of a failed response of `status`.
+    #[track_caller]
+    pub fn expect_err(&self, status: StatusCode) -> Value {
+        assert_eq!(self.status, status, "{}", self.text());
+        assert_eq!(
+            self.headers[header::CONTENT_TYPE],
+            "application/problem+json",
+            "{}",
+            self.text()
+        );
+        let body = self.json();
+        assert_eq!(body["status"], status.as_u16(), "{}", self.text());
+        assert!(body["code"].is_string(), "{}", self.text());
+        body
+    }
+}
@@ -0,0 +1,62 @@
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use std::time::Duration;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn live_reports_the_build() {
+    let app = TestApp::spawn().await;
+    for path in ["/-/ln/health", "/-/ln/health/live"] {
+        let res = app.get(path).await;
+        assert_eq!(res.status, StatusCode::OK);
+        let body = res.json();
+        assert_eq!(body["status"], "up");
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert!(body["git_sha"].is_string());
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn ready_checks_the_database_and_the_workers() {
+    let app = TestApp::spawn().await;
+    let res = app.get("/-/ln/health/ready").await;
+    assert_eq!(res.status, StatusCode::OK);
+    let body = res.json();
+    assert_eq!(body["status"], "up");
+    assert_eq!(body["checks"][0]["component"], "database");
+    assert_eq!(body["checks"][0]["status"], "up");
+    assert!(body["checks"][0]["latency_ms"].is_u64());
+    assert_eq!(body["checks"][1]["component"], "migrations");
+    assert_eq!(body["checks"][1]["status"], "up");
+
+    let _stuck = app.ctx.heartbeats.register("feed_poller", Duration::ZERO);
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let res = app.get("/-/ln/health/ready").await;
+    assert_eq!(res.status, StatusCode::SERVICE_UNAVAILABLE);
+    let body = res.json();
+    assert_eq!(body["status"], "down");
+    assert_eq!(body["checks"][2]["component"], "worker:feed_poller");
+    assert_eq!(body["checks"][2]["status"], "down");
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn request_id_is_echoed_or_made_up() {
+    let app = TestApp::spawn().await;
+    let res = app.get("/-/ln/health/live").await;
+    let made_up = res.headers["x-request-id"].to_str().unwrap();
+    assert_eq!(made_up.len(), 32);
+
+    let res = app
+        .send(
+            axum::http::Request::get("/hn/v1/api/get-item/abc/")
+                .header("x-request-id", "req-42")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert_eq!(res.headers["x-request-id"], "req-42");
+    assert_eq!(res.json()["request_id"], "req-42");
+}
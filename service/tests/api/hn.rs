@@ -0,0 +1,243 @@
+//! The `/hn/v1/api` routes, open to anyone, and saving HN items as bookmarks.
+
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+fn ids(items: &Value) -> Vec<i64> {
+    items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["id"].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn items_users_and_comments() {
+    let app = TestApp::spawn().await;
+
+    let item = app
+        .get("/hn/v1/api/get-item/41823102/")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(item["title"], "Rust 1.82 is out");
+    assert_eq!(item["score"], 530);
+    app.get("/hn/v1/api/get-item/abc/")
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    app.get("/hn/v1/api/get-item/1/")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+
+    let user = app
+        .get("/hn/v1/api/user/steveklabnik/")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(user["karma"], 70000);
+    assert_eq!(user["submitted"], json!([41823102]));
+
+    let tree = app
+        .get("/hn/v1/api/comments/41823001/")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(tree["item"]["id"], 41823001);
+    assert_eq!(tree["truncated"], false);
+    let first = &tree["comments"][0];
+    assert_eq!(first["by"], "pgfan");
+    assert_eq!(first["replies"][0]["by"], "dba");
+    assert_eq!(
+        first["replies"][0]["depth"],
+        first["depth"].as_i64().unwrap() + 1
+    );
+    let shallow = app
+        .get("/hn/v1/api/comments/41823001/?max_depth=1")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(shallow["comments"][0]["replies"], json!([]));
+    assert_eq!(shallow["comments"][0]["more"], 1);
+
+    let ask = app
+        .get("/hn/v1/api/comments/41823150/")
+        .await
+        .expect(StatusCode::OK);
+    let text = ask["item"]["text"].as_str().unwrap();
+    assert!(text.contains("point-in-time recovery"));
+    assert!(!text.contains("<script>"));
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn story_lists() {
+    let app = TestApp::spawn().await;
+
+    let top = app
+        .get("/hn/v1/api/top-stories/?limit=2")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(ids(&top["items"]), [41823102, 41823001]);
+    assert_eq!(top["next_offset"], 2);
+    let rest = app
+        .get("/hn/v1/api/best-stories/?offset=4&limit=10")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(ids(&rest["items"]), [41823057, 41823150]);
+    assert!(rest["next_offset"].is_null());
+
+    let new = app
+        .get("/hn/v1/api/new-stories/?limit=1")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(ids(&new["items"]), [41823281]);
+    for (list, id) in [("ask", 41823150), ("show", 41823057), ("job", 41823281)] {
+        let page = app
+            .get(&format!("/hn/v1/api/{list}-stories/"))
+            .await
+            .expect(StatusCode::OK);
+        assert_eq!(ids(&page["items"]), [id], "{list}");
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn trends_and_search_over_snapshots() {
+    let app = TestApp::spawn().await;
+    app.get("/hn/v1/api/history/41823102/")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+
+    assert_eq!(app.snapshot().await, 8);
+    let mut item = app
+        .get("/hn/v1/api/get-item/41823199/")
+        .await
+        .expect(StatusCode::OK);
+    item["score"] = json!(450);
+    app.upstream.put_item(item);
+    app.snapshot().await;
+
+    let crossed = app
+        .get("/hn/v1/api/trends/crossed/?points=400")
+        .await
+        .expect(StatusCode::OK);
+    let mut crossed = ids(&crossed["items"]);
+    crossed.sort_unstable();
+    assert_eq!(crossed, [41823102, 41823199]);
+
+    let velocity = app
+        .get("/hn/v1/api/trends/velocity/")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(velocity["items"][0]["id"], 41823199);
+    assert_eq!(velocity["items"][0]["gained"], 300);
+    let widest = app
+        .get(&format!(
+            "/hn/v1/api/trends/velocity/?window_mins={}",
+            i64::MAX
+        ))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(widest["items"][0]["id"], 41823199);
+
+    let history = app
+        .get("/hn/v1/api/history/41823199/")
+        .await
+        .expect(StatusCode::OK);
+    let scores: Vec<_> = history["points"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["score"].as_i64().unwrap())
+        .collect();
+    assert_eq!(scores, [150, 450]);
+    app.get(&format!("/hn/v1/api/history/41823199/?days={}", i64::MAX))
+        .await
+        .expect(StatusCode::OK);
+
+    let found = app
+        .get("/hn/v1/api/search/?q=postgres&min_score=100")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(found["total"], 1);
+    assert_eq!(ids(&found["items"]), [41823001]);
+    let found = app
+        .get("/hn/v1/api/search/?type=job")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(ids(&found["items"]), [41823281]);
+    app.get("/hn/v1/api/search/?from=2024-10-02&to=2024-10-01")
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn ingestion_stops_short_of_an_item_which_failed_to_load() {
+    let app = TestApp::spawn().await;
+    let cursor = || linkdb::hn::state::last_item_id(&app.ctx.db, "items");
+
+    app.upstream.fail_item(41823300, true);
+    // 41822923..=41823422, in batches of 200
+    assert_eq!(app.ingest(500).await, 8);
+    assert_eq!(cursor().await.unwrap(), Some(41823299));
+
+    app.upstream.fail_item(41823300, false);
+    assert_eq!(app.ingest(500).await, 4);
+    assert_eq!(cursor().await.unwrap(), Some(41823422));
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn ingestion_skips_an_item_which_never_loads() {
+    let app = TestApp::spawn().await;
+    let cursor = || linkdb::hn::state::last_item_id(&app.ctx.db, "items");
+
+    app.upstream.garble_item(41823300);
+    assert_eq!(app.ingest(500).await, 11);
+    assert_eq!(cursor().await.unwrap(), Some(41823422));
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn save_as_bookmark() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    let saved = alice
+        .post("/bm/hn/41823102", json!({}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(saved["created"], true);
+    assert_eq!(
+        saved["bookmark"]["url"],
+        "https://blog.rust-lang.org/2024/10/17/Rust-1.82.0.html"
+    );
+    assert_eq!(saved["hn"]["score"], 530);
+    assert_eq!(
+        saved["hn"]["discussion_url"],
+        "https://news.ycombinator.com/item?id=41823102"
+    );
+
+    let again = alice
+        .post("/bm/hn/41823102", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(again["created"], false);
+    assert_eq!(again["bookmark"]["id"], saved["bookmark"]["id"]);
+
+    // saved twice at once, still one bookmark
+    let (a, b) = tokio::join!(
+        alice.post("/bm/hn/41823001", json!({})),
+        alice.post("/bm/hn/41823001", json!({}))
+    );
+    assert_ne!(a.status, b.status);
+    assert_eq!(
+        a.json()["data"]["bookmark"]["id"],
+        b.json()["data"]["bookmark"]["id"]
+    );
+
+    alice
+        .post("/bm/hn/1", json!({}))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,30 @@
+//! End to end tests of the `/-/ln/v1/api/` routes: `routes::routes` as `main`
+//! builds it, on a Postgres schema created for each test and dropped after it,
+//! with HN and feeds served by a local mock.
+//!
+//! `DATABASE_URL` has to point at a Postgres the tests may create schemas in.
+//! They are ignored unless asked for, so a run without one lists them as such
+//! rather than as passed:
+//!
+//! ```sh
+//! DATABASE_URL=postgres://localhost/linknova cargo test -p service --test api -- --ignored
+//! ```
+
+mod harness;
+mod upstream;
+
+mod alert;
+mod auth;
+mod bookmark;
+mod cat;
+mod feed;
+mod health;
+mod hn;
+mod member;
+mod metrics;
+mod notification;
+mod public;
+mod share;
+mod source;
+mod topic;
+mod watch;
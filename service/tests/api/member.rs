@@ -0,0 +1,182 @@
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+fn members(list: &Value) -> Vec<(&str, &str, bool)> {
+    list.as_array()
+        .unwrap()
+        .iter()
+        .map(|m| {
+            (
+                m["username"].as_str().unwrap(),
+                m["role"].as_str().unwrap(),
+                m["pending"].as_bool().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn invited_member_sees_the_category_once_accepted() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    let bob = app.login("bob").await;
+    alice
+        .post("/cat", json!({ "name": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+
+    alice
+        .post(
+            "/cat/rust/members",
+            json!({ "username": "bob", "role": "viewer" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .post(
+            "/cat/rust/members",
+            json!({ "username": "bob", "role": "editor" }),
+        )
+        .await
+        .expect_err(StatusCode::CONFLICT);
+    let list = alice.get("/cat/rust/members").await.expect(StatusCode::OK);
+    assert_eq!(
+        members(&list),
+        [("alice", "owner", false), ("bob", "viewer", true)]
+    );
+
+    // not until accepted
+    bob.get("/cat/%40alice%2Frust")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    let invitations = bob.get("/invitations").await.expect(StatusCode::OK);
+    assert_eq!(invitations[0]["kind"], "category");
+    assert_eq!(invitations[0]["name"], "@alice/rust");
+    assert_eq!(invitations[0]["invited_by"], "alice");
+    let id = invitations[0]["id"].as_i64().unwrap();
+    alice
+        .post(&format!("/invitations/{id}/accept"), json!({}))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    bob.post(&format!("/invitations/{id}/accept"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(
+        bob.get("/invitations").await.expect(StatusCode::OK),
+        json!([])
+    );
+
+    let cat = bob.get("/cat/%40alice%2Frust").await.expect(StatusCode::OK);
+    assert_eq!(cat["owner"], "alice");
+    assert_eq!(cat["role"], "viewer");
+    // a viewer manages nobody
+    bob.post(
+        "/cat/%40alice%2Frust/members",
+        json!({ "username": "carol", "role": "viewer" }),
+    )
+    .await
+    .expect_err(StatusCode::FORBIDDEN);
+
+    alice
+        .put("/cat/rust/members/bob", json!({ "role": "editor" }))
+        .await
+        .expect(StatusCode::OK);
+    bob.post(
+        "/cat/%40alice%2Frust/members",
+        json!({ "username": "carol", "role": "contributor" }),
+    )
+    .await
+    .expect(StatusCode::CREATED);
+    bob.post(
+        "/cat/%40alice%2Frust/members",
+        json!({ "username": "dave", "role": "editor" }),
+    )
+    .await
+    .expect_err(StatusCode::FORBIDDEN);
+    let list = alice.get("/cat/rust/members").await.expect(StatusCode::OK);
+    assert_eq!(
+        members(&list),
+        [
+            ("alice", "owner", false),
+            ("bob", "editor", false),
+            ("carol", "contributor", true)
+        ]
+    );
+
+    alice
+        .delete("/cat/rust/members/bob")
+        .await
+        .expect(StatusCode::OK);
+    bob.get("/cat/%40alice%2Frust")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .delete("/cat/rust/members/bob")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn topic_invitation_can_be_declined() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    let bob = app.login("bob").await;
+    alice
+        .post("/topic", json!({ "name": "lang" }))
+        .await
+        .expect(StatusCode::CREATED);
+
+    alice
+        .post(
+            "/topic/lang/members",
+            json!({ "username": " ", "role": "viewer" }),
+        )
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    alice
+        .post(
+            "/topic/lang/members",
+            json!({ "username": "bob", "role": "contributor" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .put("/topic/lang/members/bob", json!({ "role": "viewer" }))
+        .await
+        .expect(StatusCode::OK);
+    let list = alice
+        .get("/topic/lang/members")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(
+        members(&list),
+        [("alice", "owner", false), ("bob", "viewer", true)]
+    );
+
+    let invitations = bob.get("/invitations").await.expect(StatusCode::OK);
+    assert_eq!(invitations[0]["kind"], "topic");
+    assert_eq!(invitations[0]["name"], "@alice/lang");
+    let id = invitations[0]["id"].as_i64().unwrap();
+    bob.delete(&format!("/invitations/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    bob.delete(&format!("/invitations/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    let list = alice
+        .get("/topic/lang/members")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(members(&list), [("alice", "owner", false)]);
+
+    alice
+        .delete("/topic/lang/members/bob")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    bob.get("/topic/%40alice%2Flang/members")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
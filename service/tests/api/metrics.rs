@@ -0,0 +1,27 @@
+use crate::harness::TestApp;
+use axum::http::{header, StatusCode};
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn requests_and_the_pool_are_exposed() {
+    let app = TestApp::spawn().await;
+    app.get("/hn/v1/api/get-item/41823102/")
+        .await
+        .expect(StatusCode::OK);
+
+    let res = app.get("/metrics").await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert!(res.headers[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let text = res.text();
+    // the route template, not the id
+    assert!(text.contains(
+        r#"http_requests_total{method="GET",route="/hn/v1/api/get-item/{id}/",status="200"}"#
+    ));
+    assert!(text.contains("http_request_duration_seconds_bucket"));
+    assert!(text.contains(r#"hn_upstream_requests_total{endpoint="item",outcome="200"}"#));
+    assert!(text.contains(r#"db_pool_connections{state="idle"}"#));
+    assert!(text.contains("db_pool_max_connections"));
+}
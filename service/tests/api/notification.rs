@@ -0,0 +1,128 @@
+use crate::harness::{Session, TestApp};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+/// Has dang submit `ids` after `alice` started watching, and has it noticed.
+async fn notify(app: &TestApp, alice: &Session<'_>, ids: &[i64]) {
+    alice
+        .post("/hn/watch", json!({ "username": "dang" }))
+        .await
+        .expect(StatusCode::CREATED);
+    for id in ids {
+        app.upstream.put_item(json!({
+            "by": "dang",
+            "id": id,
+            "score": 1,
+            "time": 1728990000,
+            "title": format!("Post {id}"),
+            "type": "story",
+            "url": format!("https://dang.example/{id}")
+        }));
+    }
+    let mut submitted = ids.to_vec();
+    submitted.push(41823057);
+    app.upstream
+        .put_user(json!({ "created": 1160000000, "id": "dang", "submitted": submitted }));
+    alice
+        .post("/hn/watch/dang/refresh", json!({}))
+        .await
+        .expect(StatusCode::OK);
+}
+
+fn hn_ids(page: &Value) -> Vec<i64> {
+    let mut ids: Vec<_> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["hn_id"].as_i64().unwrap())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn read_and_delete() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    notify(&app, &alice, &[41900001, 41900002, 41900003]).await;
+
+    let page = alice.get("/notifications").await.expect(StatusCode::OK);
+    assert_eq!(page["unread"], 3);
+    assert_eq!(hn_ids(&page), [41900001, 41900002, 41900003]);
+    assert!(page["items"][0]["read_on"].is_null());
+    assert!(page["items"][0]["bookmark_id"].is_null());
+
+    let first = page["items"][0]["id"].as_i64().unwrap();
+    alice
+        .post(&format!("/notifications/{first}/read"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    let unread = alice
+        .get("/notifications?unread=true")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(unread["unread"], 2);
+    assert_eq!(unread["items"].as_array().unwrap().len(), 2);
+
+    let marked = alice
+        .post("/notifications/read-all", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(marked["marked"], 2);
+    let page = alice.get("/notifications").await.expect(StatusCode::OK);
+    assert_eq!(page["unread"], 0);
+    assert_eq!(page["items"].as_array().unwrap().len(), 3);
+
+    alice
+        .delete(&format!("/notifications/{first}"))
+        .await
+        .expect(StatusCode::OK);
+    alice
+        .delete(&format!("/notifications/{first}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .post(&format!("/notifications/{first}/read"), json!({}))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn paged_and_per_user() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    notify(&app, &alice, &[41900001, 41900002, 41900003]).await;
+
+    let first = alice
+        .get("/notifications?limit=2")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(first["limit"], 2);
+    assert_eq!(first["items"].as_array().unwrap().len(), 2);
+    let rest = alice
+        .get("/notifications?limit=2&offset=2")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(rest["offset"], 2);
+    let mut seen = hn_ids(&first);
+    seen.extend(hn_ids(&rest));
+    seen.sort_unstable();
+    assert_eq!(seen, [41900001, 41900002, 41900003]);
+
+    let bob = app.login("bob").await;
+    let page = bob.get("/notifications").await.expect(StatusCode::OK);
+    assert_eq!(page["unread"], 0);
+    assert_eq!(page["items"], json!([]));
+    let id = first["items"][0]["id"].as_i64().unwrap();
+    bob.delete(&format!("/notifications/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    assert_eq!(
+        bob.post("/notifications/read-all", json!({}))
+            .await
+            .expect(StatusCode::OK)["marked"],
+        0
+    );
+}
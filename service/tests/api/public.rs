@@ -0,0 +1,68 @@
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use serde_json::json;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn any_page_number_is_served() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post(
+            "/bm",
+            json!({ "url": "https://a.example", "title": "Public A", "categories": ["rust"] }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .put("/cat/rust", json!({ "public": true }))
+        .await
+        .expect(StatusCode::OK);
+
+    let page = app.get("/-/ln/u/alice/c/rust").await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.text().contains("href=\"https://a.example\""));
+
+    for n in [i64::MAX, i64::MIN, 0] {
+        let page = app.get(&format!("/-/ln/u/alice/c/rust?page={n}")).await;
+        assert_eq!(page.status, StatusCode::OK, "page={n}");
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn forwarded_host_of_an_untrusted_peer_is_ignored() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post(
+            "/bm",
+            json!({ "url": "https://a.example", "categories": ["rust"] }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .put("/cat/rust", json!({ "public": true }))
+        .await
+        .expect(StatusCode::OK);
+
+    for path in ["/-/ln/u/alice/c/rust", "/-/ln/u/alice/c/rust/feed/json"] {
+        let res = app
+            .send(
+                axum::http::Request::get(path)
+                    .header("host", "links.example")
+                    .header("x-forwarded-host", "evil.example")
+                    .header("x-forwarded-proto", "https")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(res.status, StatusCode::OK, "{path}");
+        assert!(!res.text().contains("evil.example"), "{path}");
+        assert!(
+            res.text()
+                .contains("http://links.example/-/ln/u/alice/c/rust"),
+            "{path}"
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use crate::harness::TestApp;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+
+fn reasons(log: &Value) -> Vec<&str> {
+    let mut reasons: Vec<_> = log
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["reason"].as_str().unwrap())
+        .collect();
+    reasons.sort_unstable();
+    reasons
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn shared_category_is_readable_until_revoked() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post(
+            "/bm",
+            json!({ "url": "https://a.example", "title": "Shared A", "categories": ["rust"] }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+
+    let share = alice
+        .post("/share", json!({ "kind": "category", "target": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+    let path = share["path"].as_str().unwrap().to_string();
+    assert_eq!(
+        path,
+        format!("/-/ln/s/{}", share["token"].as_str().unwrap())
+    );
+
+    // not from a trusted proxy, so the header is ignored
+    let forged = Request::get(&path)
+        .header("x-forwarded-for", "1.1.1.1")
+        .body(Body::empty())
+        .unwrap();
+    let page = app.send(forged).await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert_eq!(page.headers[header::CACHE_CONTROL], "private, no-store");
+    assert!(page.text().contains("Shared A"));
+    let last = app.get(&format!("{path}?page={}", i64::MAX)).await;
+    assert_eq!(last.status, StatusCode::OK);
+
+    let id = share["id"].as_i64().unwrap();
+    let links = alice.get("/share").await.expect(StatusCode::OK);
+    assert_eq!(links[0]["id"], id);
+    assert_eq!(links[0]["kind"], "category");
+    assert_eq!(links[0]["status"], "active");
+    assert_eq!(links[0]["view_count"], 2);
+    assert_eq!(links[0]["password_protected"], false);
+
+    alice
+        .delete(&format!("/share/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(app.get(&path).await.status, StatusCode::GONE);
+    assert_eq!(
+        alice.get("/share").await.expect(StatusCode::OK)[0]["status"],
+        "revoked"
+    );
+
+    let log = alice
+        .get(&format!("/share/{id}/access"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(reasons(&log), ["ok", "ok", "revoked"]);
+    assert!(log
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|a| a["ip"] == "127.0.0.1"));
+
+    // neither visible nor revocable by anyone else
+    let bob = app.login("bob").await;
+    assert_eq!(bob.get("/share").await.expect(StatusCode::OK), json!([]));
+    bob.delete(&format!("/share/{id}"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    bob.get(&format!("/share/{id}/access"))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    assert_eq!(
+        app.get("/-/ln/s/not-a-token").await.status,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn password_and_view_limit() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post(
+            "/bm",
+            json!({ "url": "https://a.example", "title": "Secret A", "categories": ["rust"] }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let bm = alice.get("/bm").await.expect(StatusCode::OK)[0]["id"].to_string();
+
+    let share = alice
+        .post(
+            "/share",
+            json!({ "kind": "bookmark", "target": bm, "password": "hunter2", "max_views": 1 }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let path = share["path"].as_str().unwrap().to_string();
+
+    let page = app.get(&path).await;
+    assert_eq!(page.status, StatusCode::UNAUTHORIZED);
+    assert!(!page.text().contains("Secret A"));
+
+    let unlock = |password: &str| {
+        Request::post(&path)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(format!("password={password}")))
+            .unwrap()
+    };
+    assert_eq!(
+        app.send(unlock("wrong")).await.status,
+        StatusCode::UNAUTHORIZED
+    );
+    let page = app.send(unlock("hunter2")).await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.text().contains("Secret A"));
+    assert!(page.headers.contains_key(header::SET_COOKIE));
+
+    // the one view is used up
+    assert_eq!(app.send(unlock("hunter2")).await.status, StatusCode::GONE);
+    let links = alice.get("/share").await.expect(StatusCode::OK);
+    assert_eq!(links[0]["status"], "exhausted");
+    assert_eq!(links[0]["password_protected"], true);
+
+    let id = share["id"].as_i64().unwrap();
+    let log = alice
+        .get(&format!("/share/{id}/access"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(
+        reasons(&log),
+        ["bad-password", "exhausted", "ok", "password-required"]
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn a_visitor_is_counted_once() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post(
+            "/bm",
+            json!({ "url": "https://a.example", "title": "Shared A", "categories": ["rust"] }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let share = alice
+        .post(
+            "/share",
+            json!({ "kind": "category", "target": "rust", "max_views": 1 }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let path = share["path"].as_str().unwrap().to_string();
+
+    let page = app.get(&path).await;
+    assert_eq!(page.status, StatusCode::OK);
+    let viewed = page
+        .headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|c| c.to_str().ok())
+        .find(|c| c.starts_with("ln-share-viewed="))
+        .and_then(|c| c.split(';').next())
+        .expect("a viewer cookie")
+        .to_string();
+    for page in [2, 1] {
+        let again = Request::get(format!("{path}?page={page}"))
+            .header(header::COOKIE, &viewed)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app.send(again).await.status, StatusCode::OK, "page={page}");
+    }
+    let links = alice.get("/share").await.expect(StatusCode::OK);
+    assert_eq!(links[0]["view_count"], 1);
+
+    // the one view is the first visitor's
+    assert_eq!(app.get(&path).await.status, StatusCode::GONE);
+    let forged = Request::get(&path)
+        .header(header::COOKIE, "ln-share-viewed=0000")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(app.send(forged).await.status, StatusCode::GONE);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn wrong_passwords_lock_the_link() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post(
+            "/bm",
+            json!({ "url": "https://a.example", "title": "Secret A", "categories": ["rust"] }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let share = alice
+        .post(
+            "/share",
+            json!({ "kind": "category", "target": "rust", "password": "hunter2" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let path = share["path"].as_str().unwrap().to_string();
+    let unlock = |password: &str| {
+        Request::post(&path)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(format!("password={password}")))
+            .unwrap()
+    };
+
+    for _ in 0..service::services::public::share::MAX_BAD_PASSWORDS {
+        assert_eq!(
+            app.send(unlock("wrong")).await.status,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+    // not even the right one is checked anymore
+    let page = app.send(unlock("hunter2")).await;
+    assert_eq!(page.status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(!page.text().contains("Secret A"));
+
+    let id = share["id"].as_i64().unwrap();
+    let log = alice
+        .get(&format!("/share/{id}/access"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(log[0]["reason"], "locked");
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn create_checks_its_input() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    alice
+        .post("/share", json!({ "kind": "topic", "target": "nope" }))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .post("/share", json!({ "kind": "bookmark", "target": "abc" }))
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    alice
+        .post("/topic", json!({ "name": "lang" }))
+        .await
+        .expect(StatusCode::CREATED);
+    let err = alice
+        .post(
+            "/share",
+            json!({ "kind": "topic", "target": "lang", "max_views": 0 }),
+        )
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    assert_eq!(err["code"], "invalid_input");
+    assert_eq!(
+        err["errors"],
+        json!([{ "field": "max_views", "message": "must be at least 1" }])
+    );
+    let err = alice
+        .post(
+            "/share",
+            json!({ "kind": "topic", "target": "lang", "expires_in_secs": i64::MAX }),
+        )
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    assert_eq!(err["errors"][0]["field"], "expires_in_secs");
+    let share = alice
+        .post(
+            "/share",
+            json!({ "kind": "topic", "target": "lang", "expires_in_secs": 3600 }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert!(share["expires_on"].is_string());
+}
@@ -0,0 +1,102 @@
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use serde_json::json;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn only_enabled_providers_are_listed() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    let providers = alice.get("/sources").await.expect(StatusCode::OK);
+    assert_eq!(providers.as_array().unwrap().len(), 1);
+    assert_eq!(providers[0]["provider"], "hn");
+    assert_eq!(
+        providers[0]["lists"],
+        json!(["top", "new", "best", "ask", "show", "job"])
+    );
+    alice
+        .get("/sources/lobsters/lists/hottest")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .get("/sources/hn/lists/hottest")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn browse_an_hn_list() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    let page = alice
+        .get("/sources/hn/lists/top?offset=1&limit=2")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(page["provider"], "hn");
+    assert_eq!(page["next_offset"], 3);
+    let ids: Vec<_> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["41823001", "41823199"]);
+
+    let item = alice
+        .get("/sources/hn/items/41823281")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(item["tags"], json!(["job"]));
+    alice
+        .get("/sources/hn/items/abc")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+
+    let discussion = alice
+        .get("/sources/hn/items/41823001/discussion")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(discussion["item"]["comments"], 41);
+    assert_eq!(discussion["comments"][0]["id"], "41823310");
+    assert_eq!(discussion["comments"][0]["replies"][0]["by"], "dba");
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn save_an_item() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    let saved = alice
+        .post("/sources/hn/items/41823199/save", json!({}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(saved["created"], true);
+    assert_eq!(
+        saved["bookmark"]["url"],
+        "https://gist.github.com/gist-fan/0a1b2c3d"
+    );
+    let id = saved["bookmark"]["id"].clone();
+
+    let again = alice
+        .post("/sources/hn/items/41823199/save", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(again["created"], false);
+    assert_eq!(again["bookmark"]["id"], id);
+    assert_eq!(alice.get("/bm").await.expect(StatusCode::OK)[0]["id"], id);
+
+    // saved twice at once, still one bookmark
+    let (a, b) = tokio::join!(
+        alice.post("/sources/hn/items/41823001/save", json!({})),
+        alice.post("/sources/hn/items/41823001/save", json!({}))
+    );
+    assert_ne!(a.status, b.status);
+    assert_eq!(
+        a.json()["data"]["bookmark"]["id"],
+        b.json()["data"]["bookmark"]["id"]
+    );
+}
@@ -0,0 +1,99 @@
+use crate::harness::TestApp;
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn create_get_list_and_delete() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    alice
+        .post(
+            "/topic",
+            json!({ "name": "lang", "display_name": "Languages", "public": true }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .post("/topic", json!({ "name": "db" }))
+        .await
+        .expect(StatusCode::CREATED);
+
+    let topic = alice.get("/topic/lang").await.expect(StatusCode::OK);
+    assert_eq!(topic["name"], "lang");
+    assert_eq!(topic["display_name"], "Languages");
+    assert_eq!(topic["public"], true);
+    assert_eq!(topic["owner"], "alice");
+    assert_eq!(topic["role"], "owner");
+    assert_eq!(topic["categories"], json!([]));
+
+    alice
+        .put("/topic/lang", json!({ "priority": 3 }))
+        .await
+        .expect(StatusCode::OK);
+
+    let topics = alice.get("/topic").await.expect(StatusCode::OK);
+    let mut names: Vec<_> = topics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, ["db", "lang"]);
+
+    alice.delete("/topic/db").await.expect(StatusCode::OK);
+    alice
+        .get("/topic/db")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    let bob = app.login("bob").await;
+    bob.get("/topic/lang")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn add_cats_creates_missing_categories() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+    alice
+        .post("/cat", json!({ "name": "rust" }))
+        .await
+        .expect(StatusCode::CREATED);
+    alice
+        .post("/topic", json!({ "name": "lang" }))
+        .await
+        .expect(StatusCode::CREATED);
+
+    alice
+        .put(
+            "/topic/lang/add-cats",
+            json!({ "categories": ["rust", "go"] }),
+        )
+        .await
+        .expect(StatusCode::OK);
+    let topic = alice.get("/topic/lang").await.expect(StatusCode::OK);
+    assert_eq!(topic["categories"], json!(["rust", "go"]));
+    alice.get("/cat/go").await.expect(StatusCode::OK);
+
+    alice
+        .send(
+            Method::DELETE,
+            "/topic/lang/remove-cats",
+            Some(json!({ "categories": ["rust"] })),
+        )
+        .await
+        .expect(StatusCode::OK);
+    let topic = alice.get("/topic/lang").await.expect(StatusCode::OK);
+    assert_eq!(topic["categories"], json!(["go"]));
+    // only unmapped, not deleted
+    alice.get("/cat/rust").await.expect(StatusCode::OK);
+
+    alice
+        .put("/topic/nope/add-cats", json!({ "categories": ["rust"] }))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,187 @@
+//! Local stand-in for the servers the service calls: the HN API under `/v0`,
+//! answering from `tests/fixtures/hn`, the feeds of `tests/fixtures/feeds`
+//! under `/feeds`, and a body past any size limit under `/endless`.
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[derive(Default)]
+struct Hn {
+    items: HashMap<i64, Value>,
+    users: HashMap<String, Value>,
+    /// `topstories`, `newstories`, ..
+    lists: HashMap<String, Vec<i64>>,
+    /// items answered with a 500
+    failing: HashSet<i64>,
+    /// items answered with what isn't JSON
+    garbled: HashSet<i64>,
+}
+
+#[derive(Clone)]
+pub struct Upstream {
+    base_url: String,
+    hn: Arc<Mutex<Hn>>,
+}
+
+fn fixture(path: &str) -> Vec<Value> {
+    let text = std::fs::read_to_string(format!("{FIXTURES}/{path}")).expect("fixture to exist");
+    serde_json::from_str(&text).expect("fixture to be a list")
+}
+
+impl Upstream {
+    /// Serves on a free port of 127.0.0.1 until the test's runtime stops.
+    pub async fn start() -> Upstream {
+        let mut hn = Hn::default();
+        let stories = fixture("hn/new_stories.json");
+        let ids = |keep: &dyn Fn(&Value) -> bool| -> Vec<i64> {
+            stories
+                .iter()
+                .filter(|i| keep(i))
+                .map(|i| i["id"].as_i64().unwrap())
+                .collect()
+        };
+        let title =
+            |i: &Value, prefix: &str| i["title"].as_str().is_some_and(|t| t.starts_with(prefix));
+        let story = |i: &Value| i["type"] == "story" && i["dead"] != true;
+
+        let mut top = ids(&|i| story(i));
+        top.sort_by_key(|id| {
+            let item = stories.iter().find(|i| i["id"] == *id).unwrap();
+            -item["score"].as_i64().unwrap_or(0)
+        });
+        hn.lists.insert("beststories".to_string(), top.clone());
+        hn.lists.insert("topstories".to_string(), top);
+        let mut new = ids(&|i| i["type"] != "comment");
+        new.reverse();
+        hn.lists.insert("newstories".to_string(), new);
+        hn.lists
+            .insert("askstories".to_string(), ids(&|i| title(i, "Ask HN")));
+        hn.lists
+            .insert("showstories".to_string(), ids(&|i| title(i, "Show HN")));
+        hn.lists
+            .insert("jobstories".to_string(), ids(&|i| i["type"] == "job"));
+
+        for item in stories.into_iter().chain(fixture("hn/comments.json")) {
+            hn.items.insert(item["id"].as_i64().unwrap(), item);
+        }
+        for user in fixture("hn/users.json") {
+            hn.users
+                .insert(user["id"].as_str().unwrap().to_string(), user);
+        }
+
+        let hn = Arc::new(Mutex::new(hn));
+        let router = axum::Router::new()
+            .route("/v0/item/{id}", axum::routing::get(item))
+            .route("/v0/user/{username}", axum::routing::get(user))
+            .route("/v0/{list}", axum::routing::get(list))
+            .route("/feeds/{file}", axum::routing::get(feed))
+            .route("/endless", axum::routing::get(endless))
+            .with_state(hn.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("a free port");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        Upstream { base_url, hn }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Adds or replaces an item, as HN does when it is edited or voted on.
+    pub fn put_item(&self, item: Value) {
+        let id = item["id"].as_i64().expect("item to have an id");
+        self.hn.lock().unwrap().items.insert(id, item);
+    }
+
+    /// Makes the item fail to load, or load again.
+    pub fn fail_item(&self, id: i64, failing: bool) {
+        let mut hn = self.hn.lock().unwrap();
+        if failing {
+            hn.failing.insert(id);
+        } else {
+            hn.failing.remove(&id);
+        }
+    }
+
+    /// Makes HN answer the item with what can't be read, however often asked.
+    pub fn garble_item(&self, id: i64) {
+        self.hn.lock().unwrap().garbled.insert(id);
+    }
+
+    pub fn put_user(&self, user: Value) {
+        let id = user["id"].as_str().expect("user to have an id").to_string();
+        self.hn.lock().unwrap().users.insert(id, user);
+    }
+}
+
+/// HN answers `null` for what it doesn't know.
+async fn item(State(hn): State<Arc<Mutex<Hn>>>, Path(id): Path<String>) -> Response {
+    let Some(id) = id
+        .strip_suffix(".json")
+        .and_then(|id| id.parse::<i64>().ok())
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let hn = hn.lock().unwrap();
+    if hn.failing.contains(&id) {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    if hn.garbled.contains(&id) {
+        return "{\"id\": ".into_response();
+    }
+    let item = hn.items.get(&id).cloned();
+    axum::Json(item.unwrap_or(Value::Null)).into_response()
+}
+
+async fn user(State(hn): State<Arc<Mutex<Hn>>>, Path(username): Path<String>) -> Response {
+    let Some(username) = username.strip_suffix(".json") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let user = hn.lock().unwrap().users.get(username).cloned();
+    axum::Json(user.unwrap_or(Value::Null)).into_response()
+}
+
+async fn list(State(hn): State<Arc<Mutex<Hn>>>, Path(list): Path<String>) -> Response {
+    let hn = hn.lock().unwrap();
+    match list.strip_suffix(".json") {
+        Some("maxitem") => axum::Json(hn.items.keys().max().copied()).into_response(),
+        Some(name) => match hn.lists.get(name) {
+            Some(ids) => axum::Json(ids.clone()).into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        },
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn feed(Path(file): Path<String>) -> Response {
+    if file.contains('/') || file.starts_with('.') {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    match std::fs::read_to_string(format!("{FIXTURES}/feeds/{file}")) {
+        Ok(xml) => (
+            [(axum::http::header::CONTENT_TYPE, "application/rss+xml")],
+            xml,
+        )
+            .into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Streamed without a `Content-Length`, one chunk more than a feed may have.
+async fn endless() -> Response {
+    const CHUNK: usize = 64 * 1024;
+    let chunks = service::services::link::feed::fetch::MAX_BODY_BYTES / CHUNK + 1;
+    let body = futures::stream::iter(
+        (0..chunks).map(|_| Ok::<_, std::convert::Infallible>(vec![b' '; CHUNK])),
+    );
+    axum::body::Body::from_stream(body).into_response()
+}
@@ -0,0 +1,179 @@
+use crate::harness::TestApp;
+use axum::http::StatusCode;
+use serde_json::json;
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn new_submissions_are_notified_and_saved() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    let watch = alice
+        .post(
+            "/hn/watch",
+            json!({ "username": "steveklabnik", "category": "steve" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(watch["username"], "steveklabnik");
+    assert_eq!(watch["category"], "steve");
+    // what was submitted before the watch isn't news
+    assert_eq!(watch["last_seen_id"], 41823102);
+
+    let polled = alice
+        .post("/hn/watch/steveklabnik/refresh", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(polled, json!({ "notifications": 0, "new_bookmarks": 0 }));
+
+    app.upstream.put_item(json!({
+        "by": "steveklabnik",
+        "descendants": 0,
+        "id": 41900001,
+        "score": 12,
+        "time": 1728990000,
+        "title": "Writing a database in Rust",
+        "type": "story",
+        "url": "https://steveklabnik.example/db"
+    }));
+    app.upstream.put_user(json!({
+        "created": 1180000000,
+        "id": "steveklabnik",
+        "karma": 70001,
+        "submitted": [41900001, 41823102]
+    }));
+    let polled = alice
+        .post("/hn/watch/steveklabnik/refresh", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(polled, json!({ "notifications": 1, "new_bookmarks": 1 }));
+
+    let page = alice.get("/notifications").await.expect(StatusCode::OK);
+    let notification = &page["items"][0];
+    assert_eq!(notification["kind"], "hn_watch");
+    assert_eq!(
+        notification["title"],
+        "steveklabnik posted: Writing a database in Rust"
+    );
+    assert_eq!(notification["hn_id"], 41900001);
+    let bm = alice
+        .get(&format!("/bm/{}", notification["bookmark_id"]))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(bm["url"], "https://steveklabnik.example/db");
+    assert_eq!(bm["categories"], json!(["hn", "steve"]));
+
+    let watch = alice
+        .get("/hn/watch/steveklabnik")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(watch["last_seen_id"], 41900001);
+    assert!(watch["last_polled_on"].is_string());
+
+    // already seen
+    let polled = alice
+        .post("/hn/watch/steveklabnik/refresh", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(polled["notifications"], 0);
+
+    // a submission which fails to load holds back the ones after it
+    for id in [41900002, 41900003] {
+        app.upstream.put_item(json!({
+            "by": "steveklabnik",
+            "id": id,
+            "time": 1728990000,
+            "title": format!("Post {id}"),
+            "type": "story",
+            "url": format!("https://steveklabnik.example/{id}")
+        }));
+    }
+    app.upstream.put_user(json!({
+        "created": 1180000000,
+        "id": "steveklabnik",
+        "karma": 70001,
+        "submitted": [41900003, 41900002, 41900001, 41823102]
+    }));
+    app.upstream.fail_item(41900002, true);
+    let polled = alice
+        .post("/hn/watch/steveklabnik/refresh", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(polled, json!({ "notifications": 0, "new_bookmarks": 0 }));
+    let watch = alice
+        .get("/hn/watch/steveklabnik")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(watch["last_seen_id"], 41900001);
+
+    app.upstream.fail_item(41900002, false);
+    let polled = alice
+        .post("/hn/watch/steveklabnik/refresh", json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(polled, json!({ "notifications": 2, "new_bookmarks": 2 }));
+    let watch = alice
+        .get("/hn/watch/steveklabnik")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(watch["last_seen_id"], 41900003);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres, see main.rs"]
+async fn add_list_and_remove() {
+    let app = TestApp::spawn().await;
+    let alice = app.login("alice").await;
+
+    alice
+        .post("/hn/watch", json!({ "username": "not a user" }))
+        .await
+        .expect_err(StatusCode::BAD_REQUEST);
+    alice
+        .post("/hn/watch", json!({ "username": "nobody" }))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+
+    for username in ["dang", "steveklabnik"] {
+        alice
+            .post("/hn/watch", json!({ "username": username }))
+            .await
+            .expect(StatusCode::CREATED);
+    }
+    // watching again only changes the category
+    let watch = alice
+        .post(
+            "/hn/watch",
+            json!({ "username": "dang", "category": "mod" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(watch["category"], "mod");
+    assert_eq!(watch["last_seen_id"], 41823057);
+
+    let watches = alice.get("/hn/watch").await.expect(StatusCode::OK);
+    let mut usernames: Vec<_> = watches
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["username"].as_str().unwrap())
+        .collect();
+    usernames.sort_unstable();
+    assert_eq!(usernames, ["dang", "steveklabnik"]);
+    let bob = app.login("bob").await;
+    assert_eq!(bob.get("/hn/watch").await.expect(StatusCode::OK), json!([]));
+
+    alice.delete("/hn/watch/dang").await.expect(StatusCode::OK);
+    alice
+        .get("/hn/watch/dang")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .delete("/hn/watch/dang")
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+    alice
+        .post("/hn/watch/dang/refresh", json!({}))
+        .await
+        .expect_err(StatusCode::NOT_FOUND);
+}
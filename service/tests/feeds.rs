@@ -0,0 +1,129 @@
+//! Feed and OPML parsing against the documents in `fixtures/feeds`.
+
+use service::services::link::feed::{fetch, opml};
+
+fn parse(name: &str) -> feed_rs::model::Feed {
+    let path = format!(
+        "{}/tests/fixtures/feeds/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let xml = std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    feed_rs::parser::parse(xml.as_slice()).unwrap()
+}
+
+#[test]
+fn rss_entries_skip_the_ones_without_a_web_link() {
+    let entries = fetch::entries(&parse("entries.rss.xml"));
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.guid, "twir-570");
+    assert_eq!(
+        entry.url,
+        "https://this-week-in-rust.org/blog/2024/10/16/this-week-in-rust-570/"
+    );
+    assert_eq!(entry.title.as_deref(), Some("This Week in Rust 570"));
+    assert_eq!(
+        entry.content.as_deref(),
+        Some("Hello and welcome to another issue.")
+    );
+}
+
+#[test]
+fn atom_entries_link_to_the_alternate() {
+    let entries = fetch::entries(&parse("entries.atom.xml"));
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.guid, "tag:blog.rust-lang.org,2024:compiler-meeting");
+    assert_eq!(
+        entry.url,
+        "https://blog.rust-lang.org/inside-rust/2024/10/10/compiler-meeting.html"
+    );
+    assert_eq!(entry.title.as_deref(), Some("Compiler team meeting"));
+    assert!(entry
+        .content
+        .as_deref()
+        .unwrap()
+        .contains("Notes of the meeting."));
+}
+
+fn opml_fixture() -> String {
+    let path = format!(
+        "{}/tests/fixtures/feeds/subscriptions.opml",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn opml_folders_become_categories() {
+    let feeds = opml::parse(&opml_fixture()).unwrap();
+    let summary: Vec<_> = feeds
+        .iter()
+        .map(|f| (f.url.as_str(), f.title.as_deref(), f.category.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("https://example.com/top.xml", Some("Top level"), None),
+            (
+                "https://this-week-in-rust.org/rss.xml",
+                Some("This Week in Rust"),
+                Some("Rust")
+            ),
+            ("https://example.com/nested.xml", None, Some("Nested")),
+        ]
+    );
+    assert_eq!(
+        feeds[1].site_url.as_deref(),
+        Some("https://this-week-in-rust.org/")
+    );
+}
+
+#[test]
+fn opml_export_reads_back() {
+    let now = chrono::Utc::now();
+    let row = |id: i64, url: &str, title: Option<&str>, category: &str| linkdb::feed::FeedRow {
+        id,
+        url: url.to_string(),
+        user_id: "alice".to_string(),
+        title: title.map(str::to_string),
+        site_url: None,
+        category_id: 1,
+        category: category.to_string(),
+        etag: None,
+        last_modified: None,
+        last_polled_on: None,
+        last_error: None,
+        active: true,
+        created_on: now,
+        updated_on: now,
+    };
+    let xml = opml::render(
+        "alice's feeds",
+        &[
+            row(1, "https://a.example/feed", Some("A"), "rust"),
+            row(2, "https://b.example/feed", None, "news"),
+            row(3, "https://c.example/feed", Some("C"), "rust"),
+        ],
+    )
+    .unwrap();
+
+    let feeds = opml::parse(&xml).unwrap();
+    let summary: Vec<_> = feeds
+        .iter()
+        .map(|f| (f.url.as_str(), f.title.as_deref(), f.category.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("https://a.example/feed", Some("A"), Some("rust")),
+            ("https://c.example/feed", Some("C"), Some("rust")),
+            (
+                "https://b.example/feed",
+                Some("https://b.example/feed"),
+                Some("news")
+            ),
+        ]
+    );
+}
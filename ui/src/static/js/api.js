@@ -46,7 +46,8 @@ const API = {
             const contentType = response.headers.get('content-type');
             let data;
 
-            if (contentType && contentType.includes('application/json')) {
+            // errors come as application/problem+json
+            if (contentType && /application\/(problem\+)?json/.test(contentType)) {
                 data = await response.json();
             } else {
                 data = await response.text();
@@ -54,7 +55,7 @@ const API = {
 
             if (!response.ok) {
                 throw new APIError(
-                    data.message || `HTTP ${response.status}: ${response.statusText}`,
+                    data.detail || data.message || `HTTP ${response.status}: ${response.statusText}`,
                     response.status,
                     data
                 );